@@ -18,5 +18,8 @@ let binop_can_fail (binop : binop) : bool =
   | Gt
   | CheckedAdd
   | CheckedSub
-  | CheckedMul -> false
+  | CheckedMul
+  | WrappingAdd
+  | WrappingSub
+  | WrappingMul -> false
   | Div | Rem | Add | Sub | Mul | Shl | Shr -> true
@@ -31,12 +31,28 @@ type 'body gfun_decl = {
 }
 [@@deriving show]
 
+(** The level of MIR the bodies were extracted from. *)
+type mir_level =
+  | MirBuilt  (** Original MIR, directly translated from HIR. *)
+  | MirPromoted
+  | MirOptimized  (** MIR after optimization passes. The last one before codegen. *)
+[@@deriving show]
+
 (* Hand-written because the rust equivalent isn't generic *)
 
 (** A crate *)
 type 'fun_body gcrate = {
   name : string;
   options : cli_options;
+  cfg : string list;
+      (** The `cfg` flags that were active when compiling the crate, in the `--cfg` syntax and
+          sorted, e.g. `feature="std"` or `target_os="linux"`. *)
+  crate_version : string option;
+      (** The version of the crate, as declared in its manifest. This is `None` when Charon isn't
+          called through cargo. *)
+  rustc_commit_hash : string;
+      (** The commit hash of the rustc that compiled the crate. *)
+  mir_level : mir_level;  (** The level of MIR the bodies were extracted from. *)
   declarations : declaration_group list;
   type_decls : type_decl TypeDeclId.Map.t;
   fun_decls : 'fun_body gfun_decl FunDeclId.Map.t;
@@ -79,6 +79,14 @@ and id_to_file_of_json (js : json) : (of_json_ctx, string) result =
      in
      Ok (FileId.Map.of_list files_with_ids))
 
+and mir_level_of_json (_ : of_json_ctx) (js : json) : (mir_level, string) result =
+  combine_error_msgs js __FUNCTION__
+    (match js with
+    | `String "Built" -> Ok MirBuilt
+    | `String "Promoted" -> Ok MirPromoted
+    | `String "Optimized" -> Ok MirOptimized
+    | _ -> Error "")
+
 (* This is written by hand because the corresponding rust type is not type-generic. *)
 and gtranslated_crate_of_json
     (body_of_json : of_json_ctx -> json -> ('body gexpr_body, string) result)
@@ -89,6 +97,10 @@ and gtranslated_crate_of_json
         [
           ("crate_name", name);
           ("options", options);
+          ("cfg", cfg);
+          ("crate_version", crate_version);
+          ("rustc_commit_hash", rustc_commit_hash);
+          ("mir_level", mir_level);
           ("all_ids", _);
           ("item_names", _);
           ("files", files);
@@ -102,6 +114,10 @@ and gtranslated_crate_of_json
         let* ctx = id_to_file_of_json files in
         let* name = string_of_json ctx name in
         let* options = cli_options_of_json ctx options in
+        let* cfg = list_of_json string_of_json ctx cfg in
+        let* crate_version = option_of_json string_of_json ctx crate_version in
+        let* rustc_commit_hash = string_of_json ctx rustc_commit_hash in
+        let* mir_level = mir_level_of_json ctx mir_level in
 
         let* declarations =
           list_of_json declaration_group_of_json ctx declarations
@@ -152,6 +168,10 @@ and gtranslated_crate_of_json
           {
             name;
             options;
+            cfg;
+            crate_version;
+            rustc_commit_hash;
+            mir_level;
             declarations;
             type_decls;
             fun_decls;
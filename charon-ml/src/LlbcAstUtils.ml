@@ -25,6 +25,7 @@ let statement_has_loops (st : statement) : bool =
     object
       inherit [_] iter_statement
       method! visit_Loop _ _ = raise Found
+      method! visit_While _ _ _ _ = raise Found
     end
   in
   try
@@ -42,7 +43,7 @@ let fun_decl_has_loops (fd : fun_decl) : bool =
 let mk_sequence (st1 : statement) (st2 : statement) : statement =
   let span = MetaUtils.safe_combine_span st1.span st2.span in
   let content = Sequence (st1, st2) in
-  { span; content; comments_before = [] }
+  { span; content; comments_before = []; annotations = [] }
 
 (** Chain two statements into a sequence, by pushing the second statement
     at the end of the first one (diving into sequences, switches, etc.).
@@ -55,7 +56,12 @@ let rec chain_statements (st1 : statement) (st2 : statement) : statement =
   | Assign _
   | FakeRead _
   | Drop _
+  | LetElse _
+  | IfLetChain _
   | Loop _
+  | While _
+  | EnterUnsafe
+  | ExitUnsafe
   | Error _ ->
       (* Simply create a sequence *)
       mk_sequence st1 st2
@@ -66,7 +72,12 @@ let rec chain_statements (st1 : statement) (st2 : statement) : statement =
       (* Insert inside the switch *)
       let span = MetaUtils.safe_combine_span st1.span st2.span in
       let content = Switch (chain_statements_in_switch switch st2) in
-      { span; content; comments_before = st1.comments_before }
+      {
+        span;
+        content;
+        comments_before = st1.comments_before;
+        annotations = st1.annotations;
+      }
   | Sequence (st3, st4) ->
       (* Insert at the end of the statement *)
       mk_sequence st3 (chain_statements st4 st2)
@@ -75,12 +86,18 @@ and chain_statements_in_switch (switch : switch) (st : statement) : switch =
   match switch with
   | If (op, st0, st1) ->
       If (op, chain_statements st0 st, chain_statements st1 st)
-  | SwitchInt (op, int_ty, branches, otherwise) ->
+  | SwitchInt (op, lit_ty, branches, otherwise) ->
       let branches =
         List.map (fun (svl, br) -> (svl, chain_statements br st)) branches
       in
       let otherwise = chain_statements otherwise st in
-      SwitchInt (op, int_ty, branches, otherwise)
+      SwitchInt (op, lit_ty, branches, otherwise)
+  | SwitchRange (op, lit_ty, branches, otherwise) ->
+      let branches =
+        List.map (fun (range, br) -> (range, chain_statements br st)) branches
+      in
+      let otherwise = chain_statements otherwise st in
+      SwitchRange (op, lit_ty, branches, otherwise)
   | Match (op, branches, otherwise) ->
       let branches =
         List.map (fun (svl, br) -> (svl, chain_statements br st)) branches
@@ -244,6 +261,10 @@ class ['self] iter_crate_with_span =
       let {
         name;
         options;
+        cfg;
+        crate_version;
+        rustc_commit_hash;
+        mir_level = _;
         declarations;
         type_decls;
         fun_decls;
@@ -255,6 +276,9 @@ class ['self] iter_crate_with_span =
       in
       self#visit_string decl_span_info name;
       self#visit_cli_options decl_span_info options;
+      List.iter (self#visit_string decl_span_info) cfg;
+      Option.iter (self#visit_string decl_span_info) crate_version;
+      self#visit_string decl_span_info rustc_commit_hash;
       List.iter (self#visit_declaration_group decl_span_info) declarations;
       TypeDeclId.Map.iter
         (fun _ -> self#visit_type_decl decl_span_info)
@@ -499,6 +499,44 @@ and match_pattern_with_type_id (ctx : 'fun_body ctx) (c : match_config)
               PIdent ("Box", pgenerics);
             ] ) ) -> match_generic_args ctx c m pgenerics generics
       | TStr, [ PIdent ("str", []) ] -> generics = TypesUtils.empty_generic_args
+      | ( TUnsafeCell,
+          [
+            PIdent ("core", []);
+            PIdent ("cell", []);
+            PIdent ("UnsafeCell", pgenerics);
+          ] ) -> match_generic_args ctx c m pgenerics generics
+      | ( TVecDeque,
+          [
+            PIdent ("alloc", []);
+            PIdent ("collections", []);
+            PIdent ("vec_deque", []);
+            PIdent ("VecDeque", pgenerics);
+          ] )
+      | ( TBTreeMap,
+          [
+            PIdent ("alloc", []);
+            PIdent ("collections", []);
+            PIdent ("btree", []);
+            PIdent ("map", []);
+            PIdent ("BTreeMap", pgenerics);
+          ] )
+      | ( THashMap,
+          [
+            PIdent ("std", []);
+            PIdent ("collections", []);
+            PIdent ("hash", []);
+            PIdent ("map", []);
+            PIdent ("HashMap", pgenerics);
+          ] )
+      | ( THashSet,
+          [
+            PIdent ("std", []);
+            PIdent ("collections", []);
+            PIdent ("hash", []);
+            PIdent ("set", []);
+            PIdent ("HashSet", pgenerics);
+          ] ) ->
+          match_generic_args ctx c m pgenerics generics
       | _ -> false)
 
 and match_pattern_with_literal_type (pty : pattern) (ty : T.literal_type) : bool
@@ -525,7 +563,7 @@ and match_expr_with_ty (ctx : 'fun_body ctx) (c : match_config) (m : maps)
       && match_expr_with_ty ctx c m pty ty
       && match_ref_kind prk rk
   | EVar v, _ -> opt_update_tmap c m v ty
-  | EComp pid, TTraitType (trait_ref, type_name) ->
+  | EComp pid, TTraitType (trait_ref, type_name, _) ->
       match_trait_type ctx c m pid trait_ref type_name
   | EArrow (pinputs, pout), TArrow binder -> begin
       (* Push a region group in the map, if necessary - TODO: make this more precise *)
@@ -669,6 +707,54 @@ let builtin_fun_id_to_string (fid : E.builtin_fun_id) : string =
       let op = if is_range then "SubSlice" else "Index" in
       let mutability = PrintTypes.ref_kind_to_string mutability in
       ty ^ op ^ mutability
+  | PtrOffset { ptr_mutability; offset_kind; wrapping } ->
+      let wrapping = if wrapping then "Wrapping" else "" in
+      let op =
+        match offset_kind with
+        | PtrOpOffset -> "Offset"
+        | PtrOpAdd -> "Add"
+        | PtrOpSub -> "Sub"
+      in
+      let mutability = PrintTypes.ref_kind_to_string ptr_mutability in
+      "Ptr" ^ wrapping ^ op ^ mutability
+  | MemSwap -> "MemSwap"
+  | MemReplace -> "MemReplace"
+  | MemTake -> "MemTake"
+  | DerivedCmp -> "DerivedCmp"
+  | DerivedPartialCmp -> "DerivedPartialCmp"
+  | DerivedHash -> "DerivedHash"
+  | HasherWrite None -> "HasherWrite"
+  | HasherWrite (Some ty) ->
+      "HasherWrite_" ^ PrintValues.integer_type_to_string ty
+  | HasherFinish -> "HasherFinish"
+  | FormatArgs -> "FormatArgs"
+  | FmtArgDisplay -> "FmtArgDisplay"
+  | FmtArgDebug -> "FmtArgDebug"
+  | FmtDisplay -> "FmtDisplay"
+  | FmtDebug -> "FmtDebug"
+
+(** The path of the function a builtin function comes from, to match it against
+    patterns. *)
+let builtin_fun_id_to_path (fid : E.builtin_fun_id) : string list =
+  match fid with
+  | MemSwap -> [ "core"; "mem"; "swap" ]
+  | MemReplace -> [ "core"; "mem"; "replace" ]
+  | MemTake -> [ "core"; "mem"; "take" ]
+  | DerivedCmp -> [ "core"; "cmp"; "Ord"; "cmp" ]
+  | DerivedPartialCmp -> [ "core"; "cmp"; "PartialOrd"; "partial_cmp" ]
+  | DerivedHash -> [ "core"; "hash"; "Hash"; "hash" ]
+  | HasherWrite None -> [ "core"; "hash"; "Hasher"; "write" ]
+  | HasherWrite (Some ty) ->
+      [
+        "core";
+        "hash";
+        "Hasher";
+        "write_" ^ PrintValues.integer_type_to_string ty;
+      ]
+  | HasherFinish -> [ "core"; "hash"; "Hasher"; "finish" ]
+  | FmtDisplay -> [ "core"; "fmt"; "Display"; "fmt" ]
+  | FmtDebug -> [ "core"; "fmt"; "Debug"; "fmt" ]
+  | _ -> [ builtin_fun_id_to_string fid ]
 
 let match_fn_ptr (ctx : 'fun_body ctx) (c : match_config) (p : pattern)
     (func : E.fn_ptr) : bool =
@@ -704,8 +790,8 @@ let match_fn_ptr (ctx : 'fun_body ctx) (c : match_config) (p : pattern)
               | _ -> false)
           | _ -> false)
       | _ ->
-          let name = builtin_fun_id_to_string fid in
-          match_name_with_generics ctx c p (to_name [ name ]) func.generics)
+          let path = builtin_fun_id_to_path fid in
+          match_name_with_generics ctx c p (to_name path) func.generics)
   | FunId (FRegular fid) ->
       let d = Types.FunDeclId.Map.find fid ctx.crate.fun_decls in
       (* Match the pattern on the name of the function. *)
@@ -956,7 +1042,49 @@ and ty_to_pattern_aux (ctx : 'fun_body ctx) (c : to_pat_config)
       | TBuiltin TArray -> EPrimAdt (TArray, generics)
       | TBuiltin TSlice -> EPrimAdt (TSlice, generics)
       | TBuiltin TBox -> EComp [ PIdent ("Box", generics) ]
-      | TBuiltin TStr -> EComp [ PIdent ("str", generics) ])
+      | TBuiltin TStr -> EComp [ PIdent ("str", generics) ]
+      | TBuiltin TUnsafeCell ->
+          EComp
+            [
+              PIdent ("core", []);
+              PIdent ("cell", []);
+              PIdent ("UnsafeCell", generics);
+            ]
+      | TBuiltin TVecDeque ->
+          EComp
+            [
+              PIdent ("alloc", []);
+              PIdent ("collections", []);
+              PIdent ("vec_deque", []);
+              PIdent ("VecDeque", generics);
+            ]
+      | TBuiltin TBTreeMap ->
+          EComp
+            [
+              PIdent ("alloc", []);
+              PIdent ("collections", []);
+              PIdent ("btree", []);
+              PIdent ("map", []);
+              PIdent ("BTreeMap", generics);
+            ]
+      | TBuiltin THashMap ->
+          EComp
+            [
+              PIdent ("std", []);
+              PIdent ("collections", []);
+              PIdent ("hash", []);
+              PIdent ("map", []);
+              PIdent ("HashMap", generics);
+            ]
+      | TBuiltin THashSet ->
+          EComp
+            [
+              PIdent ("std", []);
+              PIdent ("collections", []);
+              PIdent ("hash", []);
+              PIdent ("set", []);
+              PIdent ("HashSet", generics);
+            ])
   | TVar v -> EVar (type_var_to_pattern m v)
   | TLiteral lit -> literal_type_to_pattern c lit
   | TRef (r, ty, rk) ->
@@ -964,10 +1092,10 @@ and ty_to_pattern_aux (ctx : 'fun_body ctx) (c : to_pat_config)
         ( region_to_pattern m r,
           ty_to_pattern_aux ctx c m ty,
           ref_kind_to_pattern rk )
-  | TTraitType (trait_ref, type_name) ->
+  | TTraitType (trait_ref, type_name, generics) ->
       let name =
         trait_ref_item_with_generics_to_pattern ctx c m trait_ref type_name
-          TypesUtils.empty_generic_args
+          generics
       in
       EComp name
   | TArrow binder ->
@@ -1113,9 +1241,12 @@ let fn_ptr_to_pattern (ctx : 'fun_body ctx) (c : to_pat_config)
               PImpl (EComp box_impl);
               PIdent ("new", args);
             ]
-        | _ ->
-            let fid = builtin_fun_id_to_string fid in
-            [ PIdent (fid, args) ])
+        | _ -> (
+            match List.rev (builtin_fun_id_to_path fid) with
+            | last :: prefix ->
+                List.rev_map (fun s -> PIdent (s, [])) prefix
+                @ [ PIdent (last, args) ]
+            | [] -> raise (Failure "Unreachable")))
     | FunId (FRegular fid) ->
         let d = Types.FunDeclId.Map.find fid ctx.crate.fun_decls in
         name_with_generic_args_to_pattern_aux ctx c d.item_meta.name (Some args)
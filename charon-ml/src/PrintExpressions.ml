@@ -55,6 +55,10 @@ let cast_kind_to_string (env : 'a fmt_env) (cast : cast_kind) : string =
       "cast<" ^ ty_to_string env src ^ "," ^ ty_to_string env tgt ^ ">"
   | CastUnsize (src, tgt) ->
       "unsize<" ^ ty_to_string env src ^ "," ^ ty_to_string env tgt ^ ">"
+  | CastDynUpcast (src, tgt, _, tgt_trait) ->
+      "upcast<" ^ ty_to_string env src ^ "," ^ ty_to_string env tgt ^ ">("
+      ^ trait_decl_ref_to_string env tgt_trait
+      ^ ")"
 
 let nullop_to_string (env : 'a fmt_env) (op : nullop) : string =
   match op with
@@ -88,6 +92,9 @@ let binop_to_string (binop : binop) : string =
   | CheckedAdd -> "checked.+"
   | CheckedSub -> "checked.-"
   | CheckedMul -> "checked.*"
+  | WrappingAdd -> "wrapping.+"
+  | WrappingSub -> "wrapping.-"
+  | WrappingMul -> "wrapping.*"
   | Shl -> "<<"
   | Shr -> ">>"
 
@@ -102,6 +109,31 @@ let builtin_fun_id_to_string (aid : builtin_fun_id) : string =
       let op = if is_range then "SubSlice" else "Index" in
       let mutability = ref_kind_to_string mutability in
       "@" ^ ty ^ op ^ mutability
+  | PtrOffset { ptr_mutability; offset_kind; wrapping } ->
+      let wrapping = if wrapping then "Wrapping" else "" in
+      let op =
+        match offset_kind with
+        | PtrOpOffset -> "Offset"
+        | PtrOpAdd -> "Add"
+        | PtrOpSub -> "Sub"
+      in
+      let mutability = ref_kind_to_string ptr_mutability in
+      "@Ptr" ^ wrapping ^ op ^ mutability
+  | MemSwap -> "core::mem::swap"
+  | MemReplace -> "core::mem::replace"
+  | MemTake -> "core::mem::take"
+  | DerivedCmp -> "core::cmp::Ord::cmp"
+  | DerivedPartialCmp -> "core::cmp::PartialOrd::partial_cmp"
+  | DerivedHash -> "core::hash::Hash::hash"
+  | HasherWrite None -> "core::hash::Hasher::write"
+  | HasherWrite (Some ty) ->
+      "core::hash::Hasher::write_" ^ PrintValues.integer_type_to_string ty
+  | HasherFinish -> "core::hash::Hasher::finish"
+  | FormatArgs -> "core::fmt::Arguments::new"
+  | FmtArgDisplay -> "core::fmt::rt::Argument::new_display"
+  | FmtArgDebug -> "core::fmt::rt::Argument::new_debug"
+  | FmtDisplay -> "core::fmt::Display::fmt"
+  | FmtDebug -> "core::fmt::Debug::fmt"
 
 let fun_id_to_string (env : 'a fmt_env) (fid : fun_id) : string =
   match fid with
@@ -220,11 +252,34 @@ let rvalue_to_string (env : 'a fmt_env) (rv : rvalue) : string =
                     let fields = String.concat " " fields in
                     "{ " ^ fields ^ " }"
               in
-              variant_name ^ " " ^ fields
-          | TBuiltin _ -> raise (Failure "Unreachable"))
+              if adt_ctor_kind env def_id opt_variant_id = Some Unit then
+                variant_name
+              else variant_name ^ " " ^ fields
+          | TBuiltin _ ->
+              type_id_to_string env type_id ^ "(" ^ String.concat ", " ops ^ ")")
       | AggregatedArray (_ty, _cg) -> "[" ^ String.concat ", " ops ^ "]"
       | AggregatedClosure (fid, generics) ->
           "{"
           ^ fun_decl_id_to_string env fid
           ^ generic_args_to_string env generics
           ^ "}" ^ " {" ^ String.concat ", " ops ^ "}")
+  | StructUpdate (akind, fields, base) -> (
+      match akind with
+      | AggregatedAdt (TAdtId def_id, opt_variant_id, _, _generics) ->
+          let adt_name = type_decl_id_to_string env def_id in
+          let field_names = adt_field_names env def_id opt_variant_id in
+          let fields =
+            List.map
+              (fun (field_id, op) ->
+                let field =
+                  match field_names with
+                  | None -> FieldId.to_string field_id
+                  | Some field_names ->
+                      List.nth field_names (FieldId.to_int field_id)
+                in
+                field ^ " = " ^ operand_to_string env op ^ ";")
+              fields
+          in
+          let fields = fields @ [ ".." ^ place_to_string env base ] in
+          adt_name ^ " { " ^ String.concat " " fields ^ " }"
+      | _ -> raise (Failure "Unreachable"))
@@ -19,19 +19,52 @@ let fn_operand_to_string (env : 'a fmt_env) (op : fn_operand) : string =
   match op with
   | FnOpRegular func -> fn_ptr_to_string env func
   | FnOpMove p -> "move " ^ place_to_string env p
+  | FnOpDynClosure (kind, _, _) ->
+      let kind =
+        match kind with
+        | Fn -> "Fn"
+        | FnMut -> "FnMut"
+        | FnOnce -> "FnOnce"
+      in
+      "@Dyn" ^ kind ^ "::call"
 
 let call_to_string (env : 'a fmt_env) (indent : string) (call : call) : string =
   let func = fn_operand_to_string env call.func in
   let args = List.map (operand_to_string env) call.args in
-  let args = "(" ^ String.concat ", " args ^ ")" in
   let dest = place_to_string env call.dest in
-  indent ^ dest ^ " := move " ^ func ^ args
+  match (call.func, call.is_method_call, args) with
+  | FnOpDynClosure (_, closure, args), _, _ ->
+      let closure = operand_to_string env closure in
+      let args = operand_to_string env args in
+      indent ^ dest ^ " := " ^ func ^ "(" ^ closure ^ ", " ^ args ^ ")"
+  | _, true, receiver :: args ->
+      let args = "(" ^ String.concat ", " args ^ ")" in
+      indent ^ dest ^ " := (" ^ receiver ^ ")." ^ func ^ args
+  | _, _, _ ->
+      let args = "(" ^ String.concat ", " args ^ ")" in
+      indent ^ dest ^ " := move " ^ func ^ args
 
 let assertion_to_string (env : 'a fmt_env) (indent : string) (a : assertion) :
     string =
-  let cond = operand_to_string env a.cond in
-  if a.expected then indent ^ "assert(" ^ cond ^ ")"
-  else indent ^ "assert(¬" ^ cond ^ ")"
+  match a.comparison with
+  | Some cmp ->
+      let macro_name = if cmp.op = Eq then "assert_eq" else "assert_ne" in
+      indent ^ macro_name ^ "(" ^ operand_to_string env cmp.left ^ ", "
+      ^ operand_to_string env cmp.right
+      ^ ")"
+  | None ->
+      let cond = operand_to_string env a.cond in
+      let message =
+        match a.message with
+        | None -> ""
+        | Some msg ->
+            let args =
+              List.map (fun arg -> ", " ^ operand_to_string env arg) msg.args
+            in
+            ", \"" ^ String.escaped msg.format ^ "\"" ^ String.concat "" args
+      in
+      if a.expected then indent ^ "assert(" ^ cond ^ message ^ ")"
+      else indent ^ "assert(¬" ^ cond ^ message ^ ")"
 
 (** Small helper *)
 let fun_sig_with_name_to_string (env : 'a fmt_env) (indent : string)
@@ -36,6 +36,8 @@ module Ast = struct
     | Break i -> indent ^ "break " ^ string_of_int i
     | Continue i -> indent ^ "continue " ^ string_of_int i
     | Nop -> indent ^ "nop"
+    | EnterUnsafe -> indent ^ "@enter_unsafe"
+    | ExitUnsafe -> indent ^ "@exit_unsafe"
     | Sequence (st1, st2) ->
         statement_to_string env indent indent_incr st1
         ^ ";\n"
@@ -63,7 +65,7 @@ module Ast = struct
               List.map
                 (fun (svl, be) ->
                   let svl =
-                    List.map (fun sv -> "| " ^ scalar_value_to_string sv) svl
+                    List.map (fun sv -> "| " ^ literal_to_string sv) svl
                   in
                   let svl = String.concat " " svl in
                   indent ^ svl ^ " => {\n" ^ inner_to_string2 be ^ "\n"
@@ -76,6 +78,26 @@ module Ast = struct
               ^ inner_to_string2 otherwise ^ "\n" ^ indent1 ^ "}"
             in
             indent ^ "switch (" ^ op ^ ") {\n" ^ branches ^ "\n" ^ indent ^ "}"
+        | SwitchRange (op, _ty, branches, otherwise) ->
+            let op = operand_to_string env op in
+            let indent1 = indent ^ indent_incr in
+            let indent2 = indent1 ^ indent_incr in
+            let inner_to_string2 =
+              statement_to_string env indent2 indent_incr
+            in
+            let branches =
+              List.map
+                (fun (range, be) ->
+                  indent ^ "| " ^ literal_range_to_string range ^ " => {\n"
+                  ^ inner_to_string2 be ^ "\n" ^ indent1 ^ "}")
+                branches
+            in
+            let branches = String.concat "\n" branches in
+            let branches =
+              branches ^ "\n" ^ indent1 ^ "_ => {\n"
+              ^ inner_to_string2 otherwise ^ "\n" ^ indent1 ^ "}"
+            in
+            indent ^ "switch (" ^ op ^ ") {\n" ^ branches ^ "\n" ^ indent ^ "}"
         | Match (p, branches, otherwise) ->
             let p = place_to_string env p in
             let indent1 = indent ^ indent_incr in
@@ -104,10 +126,52 @@ module Ast = struct
             in
             let branches = branches ^ otherwise in
             indent ^ "match (" ^ p ^ ") {\n" ^ branches ^ "\n" ^ indent ^ "}")
+    | LetElse (p, variants, else_block) ->
+        let p = place_to_string env p in
+        let variants = List.map VariantId.to_string variants in
+        indent ^ "let "
+        ^ String.concat " | " variants
+        ^ " = " ^ p ^ " else {\n"
+        ^ statement_to_string env (indent ^ indent_incr) indent_incr else_block
+        ^ "\n" ^ indent ^ "}"
+    | IfLetChain (lets, cond, then_block, else_block) ->
+        let indent1 = indent ^ indent_incr in
+        let lets =
+          List.map
+            (fun (binding : let_chain_binding) ->
+              let variants = List.map VariantId.to_string binding.variants in
+              "let "
+              ^ String.concat " | " variants
+              ^ " = "
+              ^ place_to_string env binding.scrutinee
+              ^ " {\n"
+              ^ statement_to_string env indent1 indent_incr binding.block
+              ^ "\n" ^ indent ^ "}")
+            lets
+        in
+        let cond =
+          match cond with
+          | None -> []
+          | Some cond -> [ operand_to_string env cond ]
+        in
+        indent ^ "if "
+        ^ String.concat " && " (lets @ cond)
+        ^ " {\n"
+        ^ statement_to_string env indent1 indent_incr then_block
+        ^ "\n" ^ indent ^ "}\n" ^ indent ^ "else {\n"
+        ^ statement_to_string env indent1 indent_incr else_block
+        ^ "\n" ^ indent ^ "}"
     | Loop loop_st ->
         indent ^ "loop {\n"
         ^ statement_to_string env (indent ^ indent_incr) indent_incr loop_st
         ^ "\n" ^ indent ^ "}"
+    | While (cond_block, cond, body) ->
+        let indent1 = indent ^ indent_incr in
+        indent ^ "while {\n"
+        ^ statement_to_string env indent1 indent_incr cond_block
+        ^ "\n" ^ indent1 ^ operand_to_string env cond ^ "\n" ^ indent ^ "} {\n"
+        ^ statement_to_string env indent1 indent_incr body
+        ^ "\n" ^ indent ^ "}"
     | Error s -> indent ^ "ERROR(' " ^ s ^ "')"
 
   let fun_sig_to_string (env : fmt_env) (indent : string) (indent_incr : string)
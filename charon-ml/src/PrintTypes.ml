@@ -173,7 +173,12 @@ let rec type_id_to_string (env : 'a fmt_env) (id : type_id) : string =
       | TBox -> "alloc::boxed::Box"
       | TStr -> "str"
       | TArray -> "@Array"
-      | TSlice -> "@Slice")
+      | TSlice -> "@Slice"
+      | TUnsafeCell -> "core::cell::UnsafeCell"
+      | TVecDeque -> "alloc::collections::vec_deque::VecDeque"
+      | TBTreeMap -> "alloc::collections::btree::map::BTreeMap"
+      | THashMap -> "std::collections::hash::map::HashMap"
+      | THashSet -> "std::collections::hash::set::HashSet")
 
 and type_decl_id_to_string env def_id =
   (* We don't want the printing functions to crash if the crate is partial *)
@@ -226,9 +231,9 @@ and ty_to_string (env : 'a fmt_env) (ty : ty) : string =
   | TVar tv -> type_db_var_to_string env tv
   | TNever -> "!"
   | TLiteral lit_ty -> literal_type_to_string lit_ty
-  | TTraitType (trait_ref, type_name) ->
+  | TTraitType (trait_ref, type_name, generics) ->
       let trait_ref = trait_ref_to_string env trait_ref in
-      trait_ref ^ "::" ^ type_name
+      trait_ref ^ "::" ^ type_name ^ generic_args_to_string env generics
   | TRef (r, rty, ref_kind) -> (
       match ref_kind with
       | RMut ->
@@ -478,6 +483,11 @@ let type_decl_to_string (env : 'a fmt_env) (def : type_decl) : string =
         in
         "union " ^ name ^ params ^ clauses ^ "\n{" ^ fields ^ "\n}"
       else "union " ^ name ^ params ^ clauses ^ "{}"
+  | Simd (elem_ty, lanes) ->
+      "simd type " ^ name ^ params ^ clauses ^ " = simd<"
+      ^ ty_to_string env elem_ty ^ "; "
+      ^ const_generic_to_string env lanes
+      ^ ">"
   | Alias ty -> "type " ^ name ^ params ^ clauses ^ " = " ^ ty_to_string env ty
   | Opaque -> "opaque type " ^ name ^ params ^ clauses
   | TDeclError err -> "error(\"" ^ err ^ "\")"
@@ -513,6 +523,12 @@ let adt_field_names (env : 'a fmt_env) (def_id : TypeDeclId.id)
         Some fields
       else None
 
+let adt_ctor_kind (env : 'a fmt_env) (def_id : TypeDeclId.id)
+    (opt_variant_id : VariantId.id option) : ctor_kind option =
+  match TypeDeclId.Map.find_opt def_id env.crate.type_decls with
+  | None -> None
+  | Some def -> type_decl_get_ctor_kind def opt_variant_id
+
 let adt_field_to_string (env : 'a fmt_env) (def_id : TypeDeclId.id)
     (opt_variant_id : VariantId.id option) (field_id : FieldId.id) :
     string option =
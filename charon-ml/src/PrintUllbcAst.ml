@@ -34,6 +34,8 @@ module Ast = struct
     | Assert a -> assertion_to_string env indent a
     | Drop p -> indent ^ "drop " ^ place_to_string env p
     | Nop -> "nop"
+    | EnterUnsafe -> indent ^ "@enter_unsafe"
+    | ExitUnsafe -> indent ^ "@exit_unsafe"
     | Call call -> call_to_string env indent call
 
   let switch_to_string (indent : string) (tgt : switch) : string =
@@ -46,7 +48,18 @@ module Ast = struct
         let branches =
           List.map
             (fun (sv, bid) ->
-              scalar_value_to_string sv ^ " -> " ^ block_id_to_string bid ^ "; ")
+              literal_to_string sv ^ " -> " ^ block_id_to_string bid ^ "; ")
+            branches
+        in
+        let branches = String.concat "" branches in
+        let otherwise = "_ -> " ^ block_id_to_string otherwise in
+        indent ^ "[" ^ branches ^ otherwise ^ "]"
+    | SwitchRange (_ty, branches, otherwise) ->
+        let branches =
+          List.map
+            (fun (range, bid) ->
+              literal_range_to_string range
+              ^ " -> " ^ block_id_to_string bid ^ "; ")
             branches
         in
         let branches = String.concat "" branches in
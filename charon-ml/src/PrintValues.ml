@@ -46,3 +46,15 @@ let literal_to_string (lit : literal) : string =
   | VChar c -> String.make 1 c
   | VStr s -> "\"" ^ s ^ "\""
   | VByteStr bs -> "[" ^ String.concat ", " (List.map string_of_int bs) ^ "]"
+
+let literal_range_to_string (range : literal_range) : string =
+  let start =
+    match range.range_start with
+    | Some start -> literal_to_string start
+    | None -> ""
+  in
+  match range.range_end with
+  | Some end_ when range.range_end_inclusive ->
+      start ^ "..=" ^ literal_to_string end_
+  | Some end_ -> start ^ ".." ^ literal_to_string end_
+  | None -> start ^ ".."
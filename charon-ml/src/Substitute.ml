@@ -361,7 +361,7 @@ let type_decl_get_instantiated_variants_fields_types (def : type_decl)
     | Enum variants ->
         List.mapi (fun i v -> (Some (VariantId.of_int i), v.fields)) variants
     | Struct fields | Union fields -> [ (None, fields) ]
-    | Alias _ | Opaque | TDeclError _ ->
+    | Simd _ | Alias _ | Opaque | TDeclError _ ->
         raise
           (Failure
              ("Can't retrieve the variants of non-adt type: "
@@ -516,7 +516,7 @@ let bound_identity_args (params : generic_params) : generic_args =
         params.regions;
     types =
       List.map
-        (fun (var : _ indexed_var) -> s.ty_sb_subst var.index)
+        (fun (var : type_var) -> s.ty_sb_subst var.index)
         params.types;
     const_generics =
       List.map
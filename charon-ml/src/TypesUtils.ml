@@ -31,6 +31,16 @@ let type_decl_is_opaque (d : type_decl) : bool =
 
     Raises [Invalid_argument] if the arguments are incorrect.
  *)
+(** The syntax used to build values of the given struct, or of the given variant
+    of an enum. *)
+let type_decl_get_ctor_kind (def : type_decl)
+    (opt_variant_id : VariantId.id option) : ctor_kind option =
+  match (def.kind, opt_variant_id) with
+  | Enum variants, Some variant_id ->
+      Some (VariantId.nth variants variant_id).ctor_kind
+  | Struct _, None -> def.ctor_kind
+  | _ -> None
+
 let type_decl_get_fields (def : type_decl)
     (opt_variant_id : VariantId.id option) : field list =
   match (def.kind, opt_variant_id) with
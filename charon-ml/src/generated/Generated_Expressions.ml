@@ -104,6 +104,12 @@ and cast_kind =
 
           The special case of `&[T; N]` -> `&[T]` coercion is caught by `UnOp::ArrayToSlice`.
        *)
+  | CastDynUpcast of ty * ty * trait_decl_ref * trait_decl_ref
+      (** [Trait upcasting coercion](https://doc.rust-lang.org/reference/type-coercions.html#unsized-coercions),
+          e.g. `&dyn Sub` -> `&dyn Super` where `Super` is a supertrait of `Sub`, behind a pointer
+          like for [CastKind::Unsize]. The trait refs are the principal traits of the source and
+          target trait objects, with the trait object type itself as `Self`.
+       *)
   | CastTransmute of ty * ty
       (** Reinterprets the bits of a value of one type as another type, i.e. exactly what
           [`std::mem::transmute`] does.
@@ -134,6 +140,12 @@ and binop =
        *)
   | CheckedSub  (** Like `CheckedAdd`. *)
   | CheckedMul  (** Like `CheckedAdd`. *)
+  | WrappingAdd
+      (** Wraps around on overflow. This operation does not fail.
+          Not present in MIR; introduced with `--overflow wrapping`.
+       *)
+  | WrappingSub  (** Like `WrappingAdd`. *)
+  | WrappingMul  (** Like `WrappingAdd`. *)
   | Shl  (** Fails if the shift is bigger than the bit-size of the type. *)
   | Shr  (** Fails if the shift is bigger than the bit-size of the type. *)
 
@@ -184,6 +196,68 @@ and builtin_fun_id =
           - `fn SliceSubSliceMut<T>(&mut [T], usize, usize) -> &mut [T]`
           - etc
        *)
+  | PtrOffset of builtin_ptr_offset_op
+      (** Pointer arithmetic: the `offset`, `add` and `sub` methods on `*const T` and `*mut T`, and
+          their `wrapping_` counterparts. The generics contain the pointee type `T`. The signature
+          looks like:
+          - `fn PtrAddShared<T>(*const T, usize) -> *const T`
+          - `fn PtrWrappingOffsetMut<T>(*mut T, isize) -> *mut T`
+          - etc
+       *)
+  | MemSwap
+      (** `core::mem::swap<T>(&mut T, &mut T)`. The generics contain the type `T`. *)
+  | MemReplace
+      (** `core::mem::replace<T>(&mut T, T) -> T`. The generics contain the type `T`. *)
+  | MemTake
+      (** `core::mem::take<T>(&mut T) -> T`. The generics contain the type `T` and its `Default`
+          impl.
+       *)
+  | DerivedCmp
+      (** `core::cmp::Ord::cmp<T>(&T, &T) -> Ordering`, when the impl for `T` is derived. The
+          generics contain the type `T`. Only emitted with `--derived-cmp-builtins`.
+       *)
+  | DerivedPartialCmp
+      (** `core::cmp::PartialOrd::partial_cmp<T, T>(&T, &T) -> Option<Ordering>`, when the impl for
+          `T` is derived. The generics contain the type `T` twice, as `Self` and `Rhs`. Only emitted
+          with `--derived-cmp-builtins`.
+       *)
+  | DerivedHash
+      (** `core::hash::Hash::hash<T, H>(&T, &mut H)`, when the impl for `T` is derived. The generics
+          contain `T`, the hasher type `H` and its `Hasher` impl. Only emitted with
+          `--hash-builtins`.
+       *)
+  | HasherWrite of integer_type option
+      (** The `write` methods of `core::hash::Hasher`: `write<H>(&mut H, &[u8])` when there is no
+          integer type, `write_u32<H>(&mut H, u32)` etc. otherwise. The generics contain the hasher
+          type `H`. Only emitted with `--hash-builtins`.
+       *)
+  | HasherFinish
+      (** `core::hash::Hasher::finish<H>(&H) -> u64`. The generics contain the hasher type `H`. Only
+          emitted with `--hash-builtins`.
+       *)
+  | FormatArgs
+      (** The constructors of `core::fmt::Arguments` that `format_args!` expands to: `new_const`,
+          `new_v1` and `new_v1_formatted`. The arguments are those of the original constructor. Only
+          emitted with `--abstract-formatting`.
+       *)
+  | FmtArgDisplay
+      (** `core::fmt::rt::Argument::new_display<T>(&T) -> Argument`, which `format_args!` uses for the
+          `{}` placeholders. The generics contain `T` and its `Display` impl. Only emitted with
+          `--abstract-formatting`.
+       *)
+  | FmtArgDebug
+      (** `core::fmt::rt::Argument::new_debug<T>(&T) -> Argument`, which `format_args!` uses for the
+          `{:?}` placeholders. The generics contain `T` and its `Debug` impl. Only emitted with
+          `--abstract-formatting`.
+       *)
+  | FmtDisplay
+      (** `core::fmt::Display::fmt<T>(&T, &mut Formatter) -> Result`. The generics contain `T`. Only
+          emitted with `--abstract-formatting`.
+       *)
+  | FmtDebug
+      (** `core::fmt::Debug::fmt<T>(&T, &mut Formatter) -> Result`. The generics contain `T`. Only
+          emitted with `--abstract-formatting`.
+       *)
 
 (** One of 8 built-in indexing operations. *)
 and builtin_index_op = {
@@ -199,6 +273,23 @@ and builtin_index_op = {
      *)
 }
 
+(** One of the built-in pointer offset operations. *)
+and builtin_ptr_offset_op = {
+  ptr_mutability : ref_kind;
+      (** Whether this operates on a `*mut T` or a `*const T`. *)
+  offset_kind : ptr_offset_kind;
+      (** Which method this is. Determines the type of the count argument. *)
+  wrapping : bool;
+      (** Whether this is a `wrapping_` method. These are safe and wrap around the address space
+        instead of requiring the result to stay within the same allocation.
+     *)
+}
+
+and ptr_offset_kind =
+  | PtrOpOffset  (** `offset(count: isize)` *)
+  | PtrOpAdd  (** `add(count: usize)` *)
+  | PtrOpSub  (** `sub(count: usize)` *)
+
 and fun_id_or_trait_method_ref =
   | FunId of fun_id
   | TraitMethod of trait_ref * trait_item_name * fun_decl_id
@@ -304,6 +395,15 @@ and rvalue =
           Remark: in case of closures, the aggregated value groups the closure id
           together with its state.
        *)
+  | StructUpdate of aggregate_kind * (field_id * operand) list * place
+      (** Builds a struct using struct-update syntax:
+          ```text
+          l = Foo { x: op, ..base };
+          ```
+          The explicitly-given fields are listed with their id; all the other fields are copied (or
+          moved) out of the base place.
+          Not present in MIR; introduced in [reconstruct_struct_updates.rs].
+       *)
   | Global of global_decl_ref
       (** Copy the value of the referenced global.
           Not present in MIR; introduced in [simplify_constants.rs].
@@ -34,8 +34,22 @@ type var = {
         through desugaring.
      *)
   var_ty : ty;  (** The variable type *)
+  binding_mode : binding_mode option;
+      (** How the variable was bound, if it was introduced by the user (in a `let`, a `match` arm, a
+        function parameter, etc.). For instance, in `match &opt { Some(x) => .. }` the default
+        binding mode makes `x` a reference, hence [BindingMode::ByRef].
+     *)
 }
 
+(** The binding mode of a variable introduced by a pattern. *)
+and binding_mode =
+  | ByMove
+      (** `x`: the variable is bound to (a move or copy of) the matched value. *)
+  | ByRef
+      (** `ref x`: the variable is bound to a shared borrow of the matched value. *)
+  | ByMutRef
+      (** `ref mut x`: the variable is bound to a mutable borrow of the matched value. *)
+
 (** The local variables of a body. *)
 and locals = {
   arg_count : int;
@@ -103,15 +117,68 @@ and fn_operand =
       (** Regular case: call to a top-level function, trait method, etc. *)
   | FnOpMove of place
       (** Use of a function pointer stored in a local variable *)
+  | FnOpDynClosure of closure_kind * operand * operand
+      (** Call through a closure trait object (`dyn Fn`, `dyn FnMut` or `dyn FnOnce`, possibly behind
+          a reference or a `Box`): the function to call is only known at runtime. The arguments are
+          given here as a tuple, hence the `args` of the [Call] are empty.
+       *)
 
-and call = { func : fn_operand; args : operand list; dest : place }
+and call = {
+  func : fn_operand;
+  args : operand list;
+  dest : place;
+  is_method_call : bool;
+      (** `true` if this is a call to a method whose first argument is the receiver, in which case we
+        print it with the method-call syntax `x.method(y)`. Computed in
+        [crate::transform::reconstruct_method_calls].
+     *)
+}
 
 (** Asserts are special constructs introduced by Rust to perform dynamic
     checks, to detect out-of-bounds accesses or divisions by zero for
     instance. We eliminate the assertions in [crate::remove_dynamic_checks],
     then introduce other dynamic checks in [crate::reconstruct_asserts].
  *)
-and assertion = { cond : operand; expected : bool }
+and assertion = {
+  cond : operand;
+  expected : bool;
+  comparison : assert_comparison option;
+      (** Set when the assertion comes from an `assert_eq!` or `assert_ne!`. See
+          [crate::reconstruct_assert_eq].
+       *)
+  message : assert_message option;
+      (** The message of an `assert!(cond, "...", args..)`. See [crate::reconstruct_asserts]. *)
+}
+
+(** The comparison checked by an `assert_eq!(left, right)` or `assert_ne!(left, right)`. `cond` is
+    then the result of comparing the two operands, and `expected` is `true` for `assert_eq!` and
+    `false` for `assert_ne!`.
+ *)
+and assert_comparison = {
+  op : binop;  (** Either [BinOp::Eq] or [BinOp::Ne]. *)
+  left : operand;
+  right : operand;
+}
+
+(** The message of a failed `assert!(cond, "bad: {}", x)`. The arguments are evaluated before the
+    assertion, whether it fails or not.
+ *)
+and assert_message = {
+  format : string;  (** The format string, e.g. `"bad: {}"`. *)
+  args : operand list;
+      (** The interpolated arguments, in the order of the `{}`s of the format string. *)
+}
+
+(** A verification directive written as a whole-line `// charon: <directive>` comment inside a
+    function body (the prefix is configurable with `--annotation-prefix`). The conditions are kept
+    as source text: we don't parse them. Annotations are attached to the statement that follows
+    them by the `recover_body_comments` pass.
+ *)
+and annotation =
+  | AnnotAssume of string
+      (** `assume <cond>`: the condition can be assumed to hold at this point. *)
+  | AnnotAssert of string
+      (** `assert <cond>`: the condition must be proven to hold at this point. *)
 
 and closure_kind = Fn | FnMut | FnOnce
 
@@ -146,6 +213,14 @@ and fun_sig = {
           (the function in which the closure is defined)
         - the region variables are local to the closure
      *)
+  has_self_param : bool;
+      (** `true` if the function is an associated function which takes a `self` parameter, i.e. a
+        method which can be called with the `x.method(..)` syntax.
+     *)
+  abi : string option;
+      (** The ABI of the function if it isn't the default Rust ABI, e.g. `"C"` for `extern "C" fn`
+        and for the functions of an `extern "C" { .. }` block.
+     *)
   closure_info : closure_info option;
       (** Additional information if this is the signature of a closure. *)
   generics : generic_params;
@@ -173,14 +248,30 @@ and fun_sig = {
         nude = true (* Don't inherit VisitorsRuntime *);
       }]
 
+(** The kind of a global declaration. *)
+type global_kind =
+  | Static  (** A `static` item. *)
+  | StaticMut
+      (** A `static mut` item. Accessing it is unsafe: reads and writes go through the raw pointer
+          given by [Rvalue::GlobalRef] with [RefKind::Mut], never through a copy of the value.
+       *)
+  | NamedConst  (** A `const` item, at the top level or in an impl/trait block. *)
+  | ThreadLocal
+      (** A `#[thread_local]` static, as generated by the `thread_local!` macro. Each thread has its
+          own copy of the value, initialized with `init`. Accesses to it are translated to
+          [Rvalue::GlobalRef].
+       *)
+
 (** A global variable definition (constant or static). *)
-type global_decl = {
+and global_decl = {
   def_id : global_decl_id;
   item_meta : item_meta;  (** The meta data associated with the declaration. *)
   generics : generic_params;
   ty : ty;
   kind : item_kind;
       (** The global kind: "regular" function, trait const declaration, etc. *)
+  global_kind : global_kind;
+      (** Whether this is a `static`, a `static mut`, a `const` or a thread-local static. *)
   body : fun_decl_id;
       (** The initializer function used to compute the initial value for this constant/static. It
         uses the same generic parameters as the global.
@@ -262,6 +353,10 @@ type trait_decl = {
       (** The associated constants declared in the trait, along with their type. *)
   types : trait_item_name list;
       (** The associated types declared in the trait. *)
+  generic_types : (trait_item_name * generic_params) list;
+      (** The generic associated types declared in the trait, along with their own generic
+        parameters. Only lifetime parameters are supported for now.
+     *)
   methods : (trait_item_name * fun_decl_ref binder) list;
       (** The methods declared by the trait. The signature of the methods can be found in each
         corresponding `FunDecl`. These `FunDecl` may have a body if the trait provided a default
@@ -318,6 +413,10 @@ type trait_impl = {
       (** The associated constants declared in the trait. *)
   types : (trait_item_name * ty) list;
       (** The associated types declared in the trait. *)
+  generic_types : (trait_item_name * ty binder) list;
+      (** The values of the generic associated types declared in the trait. The binder binds the
+        parameters of the associated type, in the same order as in the `TraitDecl`.
+     *)
   methods : (trait_item_name * fun_decl_ref binder) list;
       (** The implemented methods *)
 }
@@ -365,6 +464,32 @@ and declaration_group =
   | MixedGroup of any_decl_id g_declaration_group
       (** Anything that doesn't fit into these categories. *)
 
+(** The format in which diagnostics are emitted. *)
+and error_format =
+  | Human  (** Rendered with source snippets, for humans. *)
+  | Json  (** Newline-delimited JSON, for tools. *)
+
+(** How the overflow of the arithmetic operations `+`, `-` and `*` is modeled.
+
+    Which overflow checks rustc inserts depends on the MIR level: in built and promoted MIR they
+    are present in the bodies that are compiled with overflow checks (which is the case of
+    constants), while optimized MIR may already have removed or folded some of them. This option
+    only decides what we do with the checks and operations that are left.
+ *)
+and overflow_mode =
+  | OverflowPanic
+      (** The operations fail on overflow: the checks inserted by rustc are merged into them, and
+          folding an overflowing operation is an error.
+       *)
+  | OverflowWrapping
+      (** The operations wrap around on overflow: the checks inserted by rustc are removed, and the
+          operations become [crate::ast::BinOp::WrappingAdd] etc.
+       *)
+  | OverflowChecked
+      (** The checks inserted by rustc are kept as a [crate::ast::BinOp::CheckedAdd] (etc.) followed
+          by an `assert`.
+       *)
+
 and cli_options = {
   ullbc : bool;
       (** Extract the unstructured LLBC (i.e., don't reconstruct the control-flow) *)
@@ -380,6 +505,10 @@ and cli_options = {
      *)
   read_llbc : path_buf option;
       (** Read an llbc file and pretty-print it. This is a terrible API, we should use subcommands. *)
+  print_depth : int option;
+      (** When pretty-printing with `--read-llbc`, elide the statements nested more than this many
+        blocks deep. This avoids overflowing the stack on pathological bodies.
+     *)
   dest_dir : path_buf option;
       (** The destination directory. Files will be generated as `<dest_dir>/<crate_name>.{u}llbc`,
         unless `dest_file` is set. `dest_dir` defaults to the current directory.
@@ -388,6 +517,21 @@ and cli_options = {
       (** The destination file. By default `<dest_dir>/<crate_name>.llbc`. If this is set we ignore
         `dest_dir`.
      *)
+  split_by_kind : path_buf option;
+      (** Instead of a single file, write the translated items to this directory, split by kind:
+        `types.llbc`, `funs.llbc`, `globals.llbc` and `traits.llbc`, plus an `index.llbc` that
+        contains the item names and the declaration order. Item ids are the same across files so
+        cross-references are preserved. If this is set we ignore `dest_dir` and `dest_file`.
+     *)
+  group_by_module : bool;
+      (** Add to the output a `modules` tree that nests the ids of the items under the module that
+        defines them, so that consumers don't have to parse the item names.
+     *)
+  output_stdout : bool;
+      (** Write the serialized crate to stdout instead of a file, e.g. to pipe it into another
+        tool. Logs and diagnostics still go to stderr. Can't be combined with the options that
+        choose an output file, nor with the `--print-*` options.
+     *)
   use_polonius : bool;
       (** If activated, use Polonius' non-lexical lifetimes (NLL) analysis.
         Otherwise, use the standard borrow checker.
@@ -409,6 +553,7 @@ and cli_options = {
       (** Blacklist of items to keep opaque. These use the name-matcher syntax. *)
   exclude : string list;
       (** Blacklist of items to not translate at all. These use the name-matcher syntax. *)
+  opaque_file : path_buf option;
   remove_associated_types : string list;
       (** List of traits for which we transform associated types to type parameters. *)
   hide_marker_traits : bool;
@@ -417,17 +562,127 @@ and cli_options = {
      *)
   no_cargo : bool;  (** Do not run cargo; instead, run the driver directly. *)
   rustc_args : string list;  (** Extra flags to pass to rustc. *)
+  extern_args : string list;
+      (** Extra `--extern <name>=<path>` flags to pass to rustc, e.g. for a dependency built outside
+        of cargo. These take precedence over the `--extern` flags for the same crate that cargo
+        computed.
+     *)
+  library_paths : string list;
+      (** Extra library search paths to pass to rustc as `-L <path>`. *)
   cargo_args : string list;
       (** Extra flags to pass to cargo. Incompatible with `--no-cargo`. *)
+  edition : string option;
+      (** The Rust edition to compile the crate with. Defaults to the edition of the `Cargo.toml`
+        manifest: cargo passes it when using `charon cargo`, and `charon rustc` reads it from
+        the closest `Cargo.toml` above the input file if there is one.
+     *)
+  crate_name : string option;
+      (** Override the name of the crate, which appears in the names of its items and in the name of
+        the output file `<dest_dir>/<crate_name>.llbc`. This is passed to rustc as `--crate-name`.
+     *)
+  version_check : bool;
+      (** Check that the rustc toolchain Charon will use matches the one Charon was built with, then
+        exit without translating anything.
+     *)
+  ignore_toolchain_mismatch : bool;
+      (** Only warn (instead of erroring) when the rustc toolchain Charon will use doesn't match the
+        one Charon was built with.
+     *)
   abort_on_error : bool;
       (** Panic on the first error. This is useful for debugging. *)
   error_on_warnings : bool;  (** Print the errors as warnings *)
+  error_format : error_format;
+      (** How to emit diagnostics. `human` prints rendered diagnostics on stderr; `json` prints one
+        JSON object per line, of the shape `{ level, message, span, code }`.
+     *)
+  error_output : path_buf option;
+      (** Write the `json` diagnostics to this file instead of stderr. Use `/dev/fd/<n>` to write
+        them to an already-open file descriptor.
+     *)
+  max_errors : int option;
+      (** Stop the translation once this many items failed to translate, listing them. By default we
+        translate as much as we can whatever the number of failures.
+     *)
+  max_parallel : int option;
+      (** Run the passes that transform each function body on its own on up to this many threads. The
+        output is the same whatever the number of threads. By default we use a single thread.
+     *)
+  on_unsupported : string list;
+  emit_opaque_manifest : path_buf option;
+      (** Write to this file the list of the items whose contents we didn't translate (opaque types
+        and functions without a body), grouped by crate, along with the reason why.
+     *)
+  emit_deps_graphviz : path_buf option;
+      (** Write the dependency graph between items to this file, in the graphviz `.dot` format.
+        Groups of mutually recursive items are drawn as clusters.
+     *)
+  emit_symbol_table : path_buf option;
+      (** Write to this file a JSON table that maps each translated function and type to its name,
+        the rustc `DefId` it comes from and its source span. Useful to correlate the output with
+        other tools such as rustdoc.
+     *)
+  emit_type_sizes : path_buf option;
+      (** Write to this file a JSON table that maps the index of each translated type to its size and
+        alignment in bytes, as computed by rustc. The types rustc can't lay out, such as the
+        generic types, are omitted.
+     *)
+  emit_dependencies : path_buf option;
+      (** Write to this file a JSON list of the external crates whose items appear in the output,
+        with their versions when they are listed in the `Cargo.lock` of the translated crate.
+     *)
+  models : path_buf option;
+      (** Read models for the functions we don't have the body of from this JSON file, and use them
+        as the bodies of these functions. Each model gives a name pattern, an unstructured body and
+        optionally a signature; models that don't fit the signature of the function are rejected.
+     *)
+  emit_ullbc : bool;
+      (** Also write the ULLBC, as it is just before control-flow reconstruction, to a `.ullbc` file
+        next to the `.llbc` one. The two files share the same item ids. Has no effect with
+        `--ullbc`.
+     *)
   no_serialize : bool;
+  check : bool;
+      (** Run the whole translation without writing the output file, and exit with an error code if
+        any item failed to translate. Unless `--abort-on-error` is set, the items that failed are
+        listed. This is a quick way to check that Charon supports a given crate.
+     *)
+  validate : bool;
+      (** Check some invariants of the translated crate before exporting it: that the items referred
+        to exist, that the locals used in bodies are declared, and that the control-flow targets
+        exist. Violations are reported as errors; they indicate a bug in Charon.
+     *)
   print_original_ullbc : bool;
   print_ullbc : bool;
   print_built_llbc : bool;
   print_llbc : bool;
   no_merge_goto_chains : bool;
+  single_return : bool;
+  cse : bool;
+  normalize_if_polarity : bool;
+  reconstruct_let_chains : bool;
+  reconstruct_let_else : bool;
+  reconstruct_while_loops : bool;
+  reconstruct_range_matches : bool;
+  reconstruct_deref : bool;
+  reconstruct_struct_updates : bool;
+  reconstruct_method_calls : bool;
+  overflow : overflow_mode;
+  fold_constants : bool;
+  normalize_assoc_types : bool;
+  no_mem_builtins : bool;
+  mark_unsafe : bool;
+  derived_cmp_builtins : bool;
+  inline_derived_eq : bool;
+  hash_builtins : bool;
+  abstract_formatting : bool;
+  defunctionalize : bool;
+  stable_ids : bool;
+      (** Allocate the item ids in the order of the item names instead of the order in which we
+        encounter the items, so that the output doesn't depend on how rustc explores the crate.
+        See `transform::stable_ids` for the exact ordering key.
+     *)
+  annotation_prefix : string option;
+  remap_path_prefix : string list;
 }
 
 (** A (group of) top-level declaration(s), properly reordered.
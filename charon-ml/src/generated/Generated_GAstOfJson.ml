@@ -129,6 +129,12 @@ and cast_kind_of_json (ctx : of_json_ctx) (js : json) :
         let* x_0 = ty_of_json ctx x_0 in
         let* x_1 = ty_of_json ctx x_1 in
         Ok (CastUnsize (x_0, x_1))
+    | `Assoc [ ("DynUpcast", `List [ x_0; x_1; x_2; x_3 ]) ] ->
+        let* x_0 = ty_of_json ctx x_0 in
+        let* x_1 = ty_of_json ctx x_1 in
+        let* x_2 = trait_decl_ref_of_json ctx x_2 in
+        let* x_3 = trait_decl_ref_of_json ctx x_3 in
+        Ok (CastDynUpcast (x_0, x_1, x_2, x_3))
     | `Assoc [ ("Transmute", `List [ x_0; x_1 ]) ] ->
         let* x_0 = ty_of_json ctx x_0 in
         let* x_1 = ty_of_json ctx x_1 in
@@ -155,6 +161,9 @@ and binop_of_json (ctx : of_json_ctx) (js : json) : (binop, string) result =
     | `String "CheckedAdd" -> Ok CheckedAdd
     | `String "CheckedSub" -> Ok CheckedSub
     | `String "CheckedMul" -> Ok CheckedMul
+    | `String "WrappingAdd" -> Ok WrappingAdd
+    | `String "WrappingSub" -> Ok WrappingSub
+    | `String "WrappingMul" -> Ok WrappingMul
     | `String "Shl" -> Ok Shl
     | `String "Shr" -> Ok Shr
     | _ -> Error "")
@@ -195,6 +204,26 @@ and builtin_fun_id_of_json (ctx : of_json_ctx) (js : json) :
     | `Assoc [ ("Index", index) ] ->
         let* index = builtin_index_op_of_json ctx index in
         Ok (Index index)
+    | `Assoc [ ("PtrOffset", ptr_offset) ] ->
+        let* ptr_offset = builtin_ptr_offset_op_of_json ctx ptr_offset in
+        Ok (PtrOffset ptr_offset)
+    | `String "MemSwap" -> Ok MemSwap
+    | `String "MemReplace" -> Ok MemReplace
+    | `String "MemTake" -> Ok MemTake
+    | `String "DerivedCmp" -> Ok DerivedCmp
+    | `String "DerivedPartialCmp" -> Ok DerivedPartialCmp
+    | `String "DerivedHash" -> Ok DerivedHash
+    | `Assoc [ ("HasherWrite", hasher_write) ] ->
+        let* hasher_write =
+          option_of_json integer_type_of_json ctx hasher_write
+        in
+        Ok (HasherWrite hasher_write)
+    | `String "HasherFinish" -> Ok HasherFinish
+    | `String "FormatArgs" -> Ok FormatArgs
+    | `String "FmtArgDisplay" -> Ok FmtArgDisplay
+    | `String "FmtArgDebug" -> Ok FmtArgDebug
+    | `String "FmtDisplay" -> Ok FmtDisplay
+    | `String "FmtDebug" -> Ok FmtDebug
     | _ -> Error "")
 
 and builtin_index_op_of_json (ctx : of_json_ctx) (js : json) :
@@ -213,6 +242,28 @@ and builtin_index_op_of_json (ctx : of_json_ctx) (js : json) :
         Ok ({ is_array; mutability; is_range } : builtin_index_op)
     | _ -> Error "")
 
+and builtin_ptr_offset_op_of_json (ctx : of_json_ctx) (js : json) :
+    (builtin_ptr_offset_op, string) result =
+  combine_error_msgs js __FUNCTION__
+    (match js with
+    | `Assoc
+        [ ("mutability", mutability); ("kind", kind); ("wrapping", wrapping) ]
+      ->
+        let* ptr_mutability = ref_kind_of_json ctx mutability in
+        let* offset_kind = ptr_offset_kind_of_json ctx kind in
+        let* wrapping = bool_of_json ctx wrapping in
+        Ok ({ ptr_mutability; offset_kind; wrapping } : builtin_ptr_offset_op)
+    | _ -> Error "")
+
+and ptr_offset_kind_of_json (ctx : of_json_ctx) (js : json) :
+    (ptr_offset_kind, string) result =
+  combine_error_msgs js __FUNCTION__
+    (match js with
+    | `String "Offset" -> Ok PtrOpOffset
+    | `String "Add" -> Ok PtrOpAdd
+    | `String "Sub" -> Ok PtrOpSub
+    | _ -> Error "")
+
 and fun_id_or_trait_method_ref_of_json (ctx : of_json_ctx) (js : json) :
     (fun_id_or_trait_method_ref, string) result =
   combine_error_msgs js __FUNCTION__
@@ -303,6 +354,13 @@ and rvalue_of_json (ctx : of_json_ctx) (js : json) : (rvalue, string) result =
         let* x_0 = aggregate_kind_of_json ctx x_0 in
         let* x_1 = list_of_json operand_of_json ctx x_1 in
         Ok (Aggregate (x_0, x_1))
+    | `Assoc [ ("StructUpdate", `List [ x_0; x_1; x_2 ]) ] ->
+        let* x_0 = aggregate_kind_of_json ctx x_0 in
+        let* x_1 =
+          list_of_json (pair_of_json field_id_of_json operand_of_json) ctx x_1
+        in
+        let* x_2 = place_of_json ctx x_2 in
+        Ok (StructUpdate (x_0, x_1, x_2))
     | `Assoc [ ("Global", global) ] ->
         let* global = global_decl_ref_of_json ctx global in
         Ok (Global global)
@@ -340,11 +398,29 @@ and aggregate_kind_of_json (ctx : of_json_ctx) (js : json) :
 and var_of_json (ctx : of_json_ctx) (js : json) : (var, string) result =
   combine_error_msgs js __FUNCTION__
     (match js with
-    | `Assoc [ ("index", index); ("name", name); ("ty", ty) ] ->
+    | `Assoc
+        [
+          ("index", index);
+          ("name", name);
+          ("ty", ty);
+          ("binding_mode", binding_mode);
+        ] ->
         let* index = var_id_of_json ctx index in
         let* name = option_of_json string_of_json ctx name in
         let* var_ty = ty_of_json ctx ty in
-        Ok ({ index; name; var_ty } : var)
+        let* binding_mode =
+          option_of_json binding_mode_of_json ctx binding_mode
+        in
+        Ok ({ index; name; var_ty; binding_mode } : var)
+    | _ -> Error "")
+
+and binding_mode_of_json (ctx : of_json_ctx) (js : json) :
+    (binding_mode, string) result =
+  combine_error_msgs js __FUNCTION__
+    (match js with
+    | `String "ByMove" -> Ok ByMove
+    | `String "ByRef" -> Ok ByRef
+    | `String "ByMutRef" -> Ok ByMutRef
     | _ -> Error "")
 
 and locals_of_json (ctx : of_json_ctx) (js : json) : (locals, string) result =
@@ -421,6 +497,16 @@ and fun_decl_ref_of_json (ctx : of_json_ctx) (js : json) :
         Ok ({ fun_id; fun_generics } : fun_decl_ref)
     | _ -> Error "")
 
+and global_kind_of_json (ctx : of_json_ctx) (js : json) :
+    (global_kind, string) result =
+  combine_error_msgs js __FUNCTION__
+    (match js with
+    | `String "Static" -> Ok Static
+    | `String "StaticMut" -> Ok StaticMut
+    | `String "NamedConst" -> Ok NamedConst
+    | `String "ThreadLocal" -> Ok ThreadLocal
+    | _ -> Error "")
+
 and global_decl_of_json (ctx : of_json_ctx) (js : json) :
     (global_decl, string) result =
   combine_error_msgs js __FUNCTION__
@@ -432,6 +518,7 @@ and global_decl_of_json (ctx : of_json_ctx) (js : json) :
           ("generics", generics);
           ("ty", ty);
           ("kind", kind);
+          ("global_kind", global_kind);
           ("init", init);
         ] ->
         let* def_id = global_decl_id_of_json ctx def_id in
@@ -439,8 +526,11 @@ and global_decl_of_json (ctx : of_json_ctx) (js : json) :
         let* generics = generic_params_of_json ctx generics in
         let* ty = ty_of_json ctx ty in
         let* kind = item_kind_of_json ctx kind in
+        let* global_kind = global_kind_of_json ctx global_kind in
         let* body = fun_decl_id_of_json ctx init in
-        Ok ({ def_id; item_meta; generics; ty; kind; body } : global_decl)
+        Ok
+          ({ def_id; item_meta; generics; ty; kind; global_kind; body }
+            : global_decl)
     | _ -> Error "")
 
 and global_decl_ref_of_json (ctx : of_json_ctx) (js : json) :
@@ -473,6 +563,7 @@ and trait_decl_of_json (ctx : of_json_ctx) (js : json) :
           ("consts", consts);
           ("const_defaults", _);
           ("types", types);
+          ("generic_types", generic_types);
           ("type_defaults", _);
           ("type_clauses", _);
           ("methods", methods);
@@ -490,6 +581,11 @@ and trait_decl_of_json (ctx : of_json_ctx) (js : json) :
             ctx consts
         in
         let* types = list_of_json trait_item_name_of_json ctx types in
+        let* generic_types =
+          list_of_json
+            (pair_of_json trait_item_name_of_json generic_params_of_json)
+            ctx generic_types
+        in
         let* methods =
           list_of_json
             (pair_of_json trait_item_name_of_json
@@ -504,6 +600,7 @@ and trait_decl_of_json (ctx : of_json_ctx) (js : json) :
              parent_clauses;
              consts;
              types;
+             generic_types;
              methods;
            }
             : trait_decl)
@@ -522,6 +619,7 @@ and trait_impl_of_json (ctx : of_json_ctx) (js : json) :
           ("parent_trait_refs", parent_trait_refs);
           ("consts", consts);
           ("types", types);
+          ("generic_types", generic_types);
           ("type_clauses", _);
           ("methods", methods);
         ] ->
@@ -543,6 +641,11 @@ and trait_impl_of_json (ctx : of_json_ctx) (js : json) :
             (pair_of_json trait_item_name_of_json ty_of_json)
             ctx types
         in
+        let* generic_types =
+          list_of_json
+            (pair_of_json trait_item_name_of_json (binder_of_json ty_of_json))
+            ctx generic_types
+        in
         let* methods =
           list_of_json
             (pair_of_json trait_item_name_of_json
@@ -558,6 +661,7 @@ and trait_impl_of_json (ctx : of_json_ctx) (js : json) :
              parent_trait_refs;
              consts;
              types;
+             generic_types;
              methods;
            }
             : trait_impl)
@@ -573,36 +677,107 @@ and fn_operand_of_json (ctx : of_json_ctx) (js : json) :
     | `Assoc [ ("Move", move) ] ->
         let* move = place_of_json ctx move in
         Ok (FnOpMove move)
+    | `Assoc
+        [
+          ( "DynClosure",
+            `Assoc [ ("kind", kind); ("closure", closure); ("args", args) ] );
+        ] ->
+        let* kind = closure_kind_of_json ctx kind in
+        let* closure = operand_of_json ctx closure in
+        let* args = operand_of_json ctx args in
+        Ok (FnOpDynClosure (kind, closure, args))
     | _ -> Error "")
 
 and call_of_json (ctx : of_json_ctx) (js : json) : (call, string) result =
   combine_error_msgs js __FUNCTION__
     (match js with
-    | `Assoc [ ("func", func); ("args", args); ("dest", dest) ] ->
+    | `Assoc
+        [
+          ("func", func);
+          ("args", args);
+          ("dest", dest);
+          ("is_method_call", is_method_call);
+        ] ->
         let* func = fn_operand_of_json ctx func in
         let* args = list_of_json operand_of_json ctx args in
         let* dest = place_of_json ctx dest in
-        Ok ({ func; args; dest } : call)
+        let* is_method_call = bool_of_json ctx is_method_call in
+        Ok ({ func; args; dest; is_method_call } : call)
     | _ -> Error "")
 
 and abort_kind_of_json (ctx : of_json_ctx) (js : json) :
     (abort_kind, string) result =
   combine_error_msgs js __FUNCTION__
     (match js with
-    | `Assoc [ ("Panic", panic) ] ->
-        let* panic = name_of_json ctx panic in
-        Ok (Panic panic)
+    | `Assoc [ ("Panic", `List [ x_0; x_1 ]) ] ->
+        let* x_0 = name_of_json ctx x_0 in
+        let* x_1 = option_of_json panic_location_of_json ctx x_1 in
+        Ok (Panic (x_0, x_1))
     | `String "UndefinedBehavior" -> Ok UndefinedBehavior
     | _ -> Error "")
 
+and panic_location_of_json (ctx : of_json_ctx) (js : json) :
+    (panic_location, string) result =
+  combine_error_msgs js __FUNCTION__
+    (match js with
+    | `Assoc [ ("file_id", file_id); ("line", line); ("col", col) ] ->
+        let* file_id = file_id_of_json ctx file_id in
+        let* line = int_of_json ctx line in
+        let* col = int_of_json ctx col in
+        Ok ({ file_id; line; col } : panic_location)
+    | _ -> Error "")
+
 and assertion_of_json (ctx : of_json_ctx) (js : json) :
     (assertion, string) result =
   combine_error_msgs js __FUNCTION__
     (match js with
-    | `Assoc [ ("cond", cond); ("expected", expected) ] ->
+    | `Assoc
+        [
+          ("cond", cond);
+          ("expected", expected);
+          ("comparison", comparison);
+          ("message", message);
+        ] ->
         let* cond = operand_of_json ctx cond in
         let* expected = bool_of_json ctx expected in
-        Ok ({ cond; expected } : assertion)
+        let* comparison =
+          option_of_json assert_comparison_of_json ctx comparison
+        in
+        let* message = option_of_json assert_message_of_json ctx message in
+        Ok ({ cond; expected; comparison; message } : assertion)
+    | _ -> Error "")
+
+and assert_comparison_of_json (ctx : of_json_ctx) (js : json) :
+    (assert_comparison, string) result =
+  combine_error_msgs js __FUNCTION__
+    (match js with
+    | `Assoc [ ("op", op); ("left", left); ("right", right) ] ->
+        let* op = binop_of_json ctx op in
+        let* left = operand_of_json ctx left in
+        let* right = operand_of_json ctx right in
+        Ok ({ op; left; right } : assert_comparison)
+    | _ -> Error "")
+
+and assert_message_of_json (ctx : of_json_ctx) (js : json) :
+    (assert_message, string) result =
+  combine_error_msgs js __FUNCTION__
+    (match js with
+    | `Assoc [ ("format", format); ("args", args) ] ->
+        let* format = string_of_json ctx format in
+        let* args = list_of_json operand_of_json ctx args in
+        Ok ({ format; args } : assert_message)
+    | _ -> Error "")
+
+and annotation_of_json (ctx : of_json_ctx) (js : json) :
+    (annotation, string) result =
+  combine_error_msgs js __FUNCTION__
+    (match js with
+    | `Assoc [ ("Assume", assume) ] ->
+        let* assume = string_of_json ctx assume in
+        Ok (AnnotAssume assume)
+    | `Assoc [ ("Assert", assert_) ] ->
+        let* assert_ = string_of_json ctx assert_ in
+        Ok (AnnotAssert assert_)
     | _ -> Error "")
 
 and fun_decl_id_of_json (ctx : of_json_ctx) (js : json) :
@@ -752,12 +927,14 @@ and attr_info_of_json (ctx : of_json_ctx) (js : json) :
           ("inline", inline);
           ("rename", rename);
           ("public", public);
+          ("must_use", must_use);
         ] ->
         let* attributes = list_of_json attribute_of_json ctx attributes in
         let* inline = option_of_json inline_attr_of_json ctx inline in
         let* rename = option_of_json string_of_json ctx rename in
         let* public = bool_of_json ctx public in
-        Ok ({ attributes; inline; rename; public } : attr_info)
+        let* must_use = bool_of_json ctx must_use in
+        Ok ({ attributes; inline; rename; public; must_use } : attr_info)
     | _ -> Error "")
 
 and item_meta_of_json (ctx : of_json_ctx) (js : json) :
@@ -771,20 +948,44 @@ and item_meta_of_json (ctx : of_json_ctx) (js : json) :
           ("source_text", source_text);
           ("attr_info", attr_info);
           ("is_local", is_local);
+          ("is_foreign", is_foreign);
           ("opacity", _);
           ("lang_item", lang_item);
+          ("lang_item_role", lang_item_role);
         ] ->
         let* name = name_of_json ctx name in
         let* span = span_of_json ctx span in
         let* source_text = option_of_json string_of_json ctx source_text in
         let* attr_info = attr_info_of_json ctx attr_info in
         let* is_local = bool_of_json ctx is_local in
+        let* is_foreign = bool_of_json ctx is_foreign in
         let* lang_item = option_of_json string_of_json ctx lang_item in
+        let* lang_item_role =
+          option_of_json lang_item_role_of_json ctx lang_item_role
+        in
         Ok
-          ({ name; span; source_text; attr_info; is_local; lang_item }
+          ({
+             name;
+             span;
+             source_text;
+             attr_info;
+             is_local;
+             is_foreign;
+             lang_item;
+             lang_item_role;
+           }
             : item_meta)
     | _ -> Error "")
 
+and lang_item_role_of_json (ctx : of_json_ctx) (js : json) :
+    (lang_item_role, string) result =
+  combine_error_msgs js __FUNCTION__
+    (match js with
+    | `String "PanicHandler" -> Ok PanicHandler
+    | `String "GlobalAllocator" -> Ok GlobalAllocator
+    | `String "AllocErrorHandler" -> Ok AllocErrorHandler
+    | _ -> Error "")
+
 and file_name_of_json (ctx : of_json_ctx) (js : json) :
     (file_name, string) result =
   combine_error_msgs js __FUNCTION__
@@ -909,10 +1110,11 @@ and type_var_of_json (ctx : of_json_ctx) (js : json) : (type_var, string) result
     =
   combine_error_msgs js __FUNCTION__
     (match js with
-    | `Assoc [ ("index", index); ("name", name) ] ->
+    | `Assoc [ ("index", index); ("name", name); ("default", default) ] ->
         let* index = type_var_id_of_json ctx index in
         let* name = string_of_json ctx name in
-        Ok ({ index; name } : type_var)
+        let* default = option_of_json ty_of_json ctx default in
+        Ok ({ index; name; default } : type_var)
     | _ -> Error "")
 
 and region_var_of_json (ctx : of_json_ctx) (js : json) :
@@ -929,11 +1131,14 @@ and const_generic_var_of_json (ctx : of_json_ctx) (js : json) :
     (const_generic_var, string) result =
   combine_error_msgs js __FUNCTION__
     (match js with
-    | `Assoc [ ("index", index); ("name", name); ("ty", ty) ] ->
+    | `Assoc
+        [ ("index", index); ("name", name); ("ty", ty); ("default", default) ]
+      ->
         let* index = const_generic_var_id_of_json ctx index in
         let* name = string_of_json ctx name in
         let* ty = literal_type_of_json ctx ty in
-        Ok ({ index; name; ty } : const_generic_var)
+        let* default = option_of_json const_generic_of_json ctx default in
+        Ok ({ index; name; ty; default } : const_generic_var)
     | _ -> Error "")
 
 and trait_clause_of_json (ctx : of_json_ctx) (js : json) :
@@ -1084,6 +1289,10 @@ and generics_source_of_json (ctx : of_json_ctx) (js : json) :
         let* x_0 = trait_decl_id_of_json ctx x_0 in
         let* x_1 = trait_item_name_of_json ctx x_1 in
         Ok (GSMethod (x_0, x_1))
+    | `Assoc [ ("TraitType", `List [ x_0; x_1 ]) ] ->
+        let* x_0 = trait_decl_id_of_json ctx x_0 in
+        let* x_1 = trait_item_name_of_json ctx x_1 in
+        Ok (GSTraitType (x_0, x_1))
     | `String "Builtin" -> Ok GSBuiltin
     | _ -> Error "")
 
@@ -1139,6 +1348,10 @@ and binder_kind_of_json (ctx : of_json_ctx) (js : json) :
         let* x_0 = trait_decl_id_of_json ctx x_0 in
         let* x_1 = trait_item_name_of_json ctx x_1 in
         Ok (BKTraitMethod (x_0, x_1))
+    | `Assoc [ ("TraitType", `List [ x_0; x_1 ]) ] ->
+        let* x_0 = trait_decl_id_of_json ctx x_0 in
+        let* x_1 = trait_item_name_of_json ctx x_1 in
+        Ok (BKTraitType (x_0, x_1))
     | `String "InherentImplBlock" -> Ok BKInherentImplBlock
     | `String "Other" -> Ok BKOther
     | _ -> Error "")
@@ -1234,12 +1447,75 @@ and type_decl_of_json (ctx : of_json_ctx) (js : json) :
           ("item_meta", item_meta);
           ("generics", generics);
           ("kind", kind);
+          ("drop_impl", drop_impl);
+          ("interior_mut", interior_mut);
+          ("valid_range", valid_range);
+          ("ctor_kind", ctor_kind);
+          ("repr", repr);
         ] ->
         let* def_id = type_decl_id_of_json ctx def_id in
         let* item_meta = item_meta_of_json ctx item_meta in
         let* generics = generic_params_of_json ctx generics in
         let* kind = type_decl_kind_of_json ctx kind in
-        Ok ({ def_id; item_meta; generics; kind } : type_decl)
+        let* drop_impl = option_of_json fun_decl_id_of_json ctx drop_impl in
+        let* interior_mut = bool_of_json ctx interior_mut in
+        let* valid_range =
+          option_of_json scalar_valid_range_of_json ctx valid_range
+        in
+        let* ctor_kind = option_of_json ctor_kind_of_json ctx ctor_kind in
+        let* repr = option_of_json repr_info_of_json ctx repr in
+        Ok
+          ({
+             def_id;
+             item_meta;
+             generics;
+             kind;
+             drop_impl;
+             interior_mut;
+             valid_range;
+             ctor_kind;
+             repr;
+           }
+            : type_decl)
+    | _ -> Error "")
+
+and ctor_kind_of_json (ctx : of_json_ctx) (js : json) :
+    (ctor_kind, string) result =
+  combine_error_msgs js __FUNCTION__
+    (match js with
+    | `String "Braced" -> Ok Braced
+    | `String "Tuple" -> Ok Tuple
+    | `String "Unit" -> Ok Unit
+    | _ -> Error "")
+
+and scalar_valid_range_of_json (ctx : of_json_ctx) (js : json) :
+    (scalar_valid_range, string) result =
+  combine_error_msgs js __FUNCTION__
+    (match js with
+    | `Assoc [ ("start", start); ("end", end_) ] ->
+        let* range_start = option_of_json int_of_json ctx start in
+        let* range_end = option_of_json int_of_json ctx end_ in
+        Ok ({ range_start; range_end } : scalar_valid_range)
+    | _ -> Error "")
+
+and repr_info_of_json (ctx : of_json_ctx) (js : json) :
+    (repr_info, string) result =
+  combine_error_msgs js __FUNCTION__
+    (match js with
+    | `Assoc
+        [
+          ("c", c);
+          ("transparent", transparent);
+          ("int", int);
+          ("align", align);
+          ("pack", pack);
+        ] ->
+        let* c = bool_of_json ctx c in
+        let* transparent = bool_of_json ctx transparent in
+        let* int = option_of_json integer_type_of_json ctx int in
+        let* align = option_of_json int_of_json ctx align in
+        let* pack = option_of_json int_of_json ctx pack in
+        Ok ({ c; transparent; int; align; pack } : repr_info)
     | _ -> Error "")
 
 and variant_id_of_json (ctx : of_json_ctx) (js : json) :
@@ -1273,6 +1549,11 @@ and type_decl_kind_of_json (ctx : of_json_ctx) (js : json) :
     | `Assoc [ ("Union", union) ] ->
         let* union = vector_of_json field_id_of_json field_of_json ctx union in
         Ok (Union union)
+    | `Assoc [ ("Simd", `Assoc [ ("elem_ty", elem_ty); ("lanes", lanes) ]) ]
+      ->
+        let* elem_ty = ty_of_json ctx elem_ty in
+        let* lanes = const_generic_of_json ctx lanes in
+        Ok (Simd (elem_ty, lanes))
     | `String "Opaque" -> Ok Opaque
     | `Assoc [ ("Alias", alias) ] ->
         let* alias = ty_of_json ctx alias in
@@ -1291,6 +1572,7 @@ and variant_of_json (ctx : of_json_ctx) (js : json) : (variant, string) result =
           ("attr_info", attr_info);
           ("name", name);
           ("fields", fields);
+          ("ctor_kind", ctor_kind);
           ("discriminant", discriminant);
         ] ->
         let* span = span_of_json ctx span in
@@ -1299,8 +1581,11 @@ and variant_of_json (ctx : of_json_ctx) (js : json) : (variant, string) result =
         let* fields =
           vector_of_json field_id_of_json field_of_json ctx fields
         in
+        let* ctor_kind = ctor_kind_of_json ctx ctor_kind in
         let* discriminant = scalar_value_of_json ctx discriminant in
-        Ok ({ span; attr_info; variant_name; fields; discriminant } : variant)
+        Ok
+          ({ span; attr_info; variant_name; fields; ctor_kind; discriminant }
+            : variant)
     | _ -> Error "")
 
 and field_of_json (ctx : of_json_ctx) (js : json) : (field, string) result =
@@ -1418,10 +1703,11 @@ and ty_of_json (ctx : of_json_ctx) (js : json) : (ty, string) result =
         let* x_0 = ty_of_json ctx x_0 in
         let* x_1 = ref_kind_of_json ctx x_1 in
         Ok (TRawPtr (x_0, x_1))
-    | `Assoc [ ("TraitType", `List [ x_0; x_1 ]) ] ->
+    | `Assoc [ ("TraitType", `List [ x_0; x_1; x_2 ]) ] ->
         let* x_0 = trait_ref_of_json ctx x_0 in
         let* x_1 = trait_item_name_of_json ctx x_1 in
-        Ok (TTraitType (x_0, x_1))
+        let* x_2 = generic_args_of_json ctx x_2 in
+        Ok (TTraitType (x_0, x_1, x_2))
     | `Assoc [ ("DynTrait", dyn_trait) ] ->
         let* dyn_trait = existential_predicate_of_json ctx dyn_trait in
         Ok (TDynTrait dyn_trait)
@@ -1445,6 +1731,11 @@ and builtin_ty_of_json (ctx : of_json_ctx) (js : json) :
     | `String "Array" -> Ok TArray
     | `String "Slice" -> Ok TSlice
     | `String "Str" -> Ok TStr
+    | `String "UnsafeCell" -> Ok TUnsafeCell
+    | `String "VecDeque" -> Ok TVecDeque
+    | `String "BTreeMap" -> Ok TBTreeMap
+    | `String "HashMap" -> Ok THashMap
+    | `String "HashSet" -> Ok THashSet
     | _ -> Error "")
 
 and closure_kind_of_json (ctx : of_json_ctx) (js : json) :
@@ -1473,6 +1764,8 @@ and fun_sig_of_json (ctx : of_json_ctx) (js : json) : (fun_sig, string) result =
         [
           ("is_unsafe", is_unsafe);
           ("is_closure", is_closure);
+          ("has_self_param", has_self_param);
+          ("abi", abi);
           ("closure_info", closure_info);
           ("generics", generics);
           ("inputs", inputs);
@@ -1480,6 +1773,8 @@ and fun_sig_of_json (ctx : of_json_ctx) (js : json) : (fun_sig, string) result =
         ] ->
         let* is_unsafe = bool_of_json ctx is_unsafe in
         let* is_closure = bool_of_json ctx is_closure in
+        let* has_self_param = bool_of_json ctx has_self_param in
+        let* abi = option_of_json string_of_json ctx abi in
         let* closure_info =
           option_of_json closure_info_of_json ctx closure_info
         in
@@ -1487,7 +1782,16 @@ and fun_sig_of_json (ctx : of_json_ctx) (js : json) : (fun_sig, string) result =
         let* inputs = list_of_json ty_of_json ctx inputs in
         let* output = ty_of_json ctx output in
         Ok
-          ({ is_unsafe; is_closure; closure_info; generics; inputs; output }
+          ({
+             is_unsafe;
+             is_closure;
+             has_self_param;
+             abi;
+             closure_info;
+             generics;
+             inputs;
+             output;
+           }
             : fun_sig)
     | _ -> Error "")
 
@@ -1520,6 +1824,20 @@ and literal_of_json (ctx : of_json_ctx) (js : json) : (literal, string) result =
         Ok (VStr str)
     | _ -> Error "")
 
+and literal_range_of_json (ctx : of_json_ctx) (js : json) :
+    (literal_range, string) result =
+  combine_error_msgs js __FUNCTION__
+    (match js with
+    | `Assoc
+        [ ("start", start); ("end", end_); ("end_inclusive", end_inclusive) ]
+      ->
+        let* range_start = option_of_json literal_of_json ctx start in
+        let* range_end = option_of_json literal_of_json ctx end_ in
+        let* range_end_inclusive = bool_of_json ctx end_inclusive in
+        Ok
+          ({ range_start; range_end; range_end_inclusive } : literal_range)
+    | _ -> Error "")
+
 and scalar_value_of_json (ctx : of_json_ctx) (js : json) :
     (scalar_value, string) result =
   combine_error_msgs js __FUNCTION__
@@ -1599,6 +1917,23 @@ and declaration_group_of_json (ctx : of_json_ctx) (js : json) :
         Ok (MixedGroup mixed)
     | _ -> Error "")
 
+and error_format_of_json (ctx : of_json_ctx) (js : json) :
+    (error_format, string) result =
+  combine_error_msgs js __FUNCTION__
+    (match js with
+    | `String "Human" -> Ok Human
+    | `String "Json" -> Ok Json
+    | _ -> Error "")
+
+and overflow_mode_of_json (ctx : of_json_ctx) (js : json) :
+    (overflow_mode, string) result =
+  combine_error_msgs js __FUNCTION__
+    (match js with
+    | `String "Panic" -> Ok OverflowPanic
+    | `String "Wrapping" -> Ok OverflowWrapping
+    | `String "Checked" -> Ok OverflowChecked
+    | _ -> Error "")
+
 and cli_options_of_json (ctx : of_json_ctx) (js : json) :
     (cli_options, string) result =
   combine_error_msgs js __FUNCTION__
@@ -1612,8 +1947,12 @@ and cli_options_of_json (ctx : of_json_ctx) (js : json) :
           ("mir_optimized", mir_optimized);
           ("input_file", input_file);
           ("read_llbc", read_llbc);
+          ("print_depth", print_depth);
           ("dest_dir", dest_dir);
           ("dest_file", dest_file);
+          ("split_by_kind", split_by_kind);
+          ("group_by_module", group_by_module);
+          ("output_stdout", output_stdout);
           ("use_polonius", use_polonius);
           ("skip_borrowck", skip_borrowck);
           ("no_code_duplication", no_code_duplication);
@@ -1622,19 +1961,63 @@ and cli_options_of_json (ctx : of_json_ctx) (js : json) :
           ("include", include_);
           ("opaque", opaque);
           ("exclude", exclude);
+          ("opaque_file", opaque_file);
           ("remove_associated_types", remove_associated_types);
           ("hide_marker_traits", hide_marker_traits);
           ("no_cargo", no_cargo);
           ("rustc_args", rustc_args);
+          ("extern_args", extern_args);
+          ("library_paths", library_paths);
           ("cargo_args", cargo_args);
+          ("edition", edition);
+          ("crate_name", crate_name);
+          ("version_check", version_check);
+          ("ignore_toolchain_mismatch", ignore_toolchain_mismatch);
           ("abort_on_error", abort_on_error);
           ("error_on_warnings", error_on_warnings);
+          ("error_format", error_format);
+          ("error_output", error_output);
+          ("max_errors", max_errors);
+          ("max_parallel", max_parallel);
+          ("on_unsupported", on_unsupported);
+          ("emit_opaque_manifest", emit_opaque_manifest);
+          ("emit_deps_graphviz", emit_deps_graphviz);
+          ("emit_symbol_table", emit_symbol_table);
+          ("emit_type_sizes", emit_type_sizes);
+          ("emit_dependencies", emit_dependencies);
+          ("models", models);
+          ("emit_ullbc", emit_ullbc);
           ("no_serialize", no_serialize);
+          ("check", check);
+          ("validate", validate);
           ("print_original_ullbc", print_original_ullbc);
           ("print_ullbc", print_ullbc);
           ("print_built_llbc", print_built_llbc);
           ("print_llbc", print_llbc);
           ("no_merge_goto_chains", no_merge_goto_chains);
+          ("single_return", single_return);
+          ("cse", cse);
+          ("normalize_if_polarity", normalize_if_polarity);
+          ("reconstruct_let_chains", reconstruct_let_chains);
+          ("reconstruct_let_else", reconstruct_let_else);
+          ("reconstruct_while_loops", reconstruct_while_loops);
+          ("reconstruct_range_matches", reconstruct_range_matches);
+          ("reconstruct_deref", reconstruct_deref);
+          ("reconstruct_struct_updates", reconstruct_struct_updates);
+          ("reconstruct_method_calls", reconstruct_method_calls);
+          ("overflow", overflow);
+          ("fold_constants", fold_constants);
+          ("normalize_assoc_types", normalize_assoc_types);
+          ("no_mem_builtins", no_mem_builtins);
+          ("mark_unsafe", mark_unsafe);
+          ("derived_cmp_builtins", derived_cmp_builtins);
+          ("inline_derived_eq", inline_derived_eq);
+          ("hash_builtins", hash_builtins);
+          ("abstract_formatting", abstract_formatting);
+          ("defunctionalize", defunctionalize);
+          ("stable_ids", stable_ids);
+          ("annotation_prefix", annotation_prefix);
+          ("remap_path_prefix", remap_path_prefix);
         ] ->
         let* ullbc = bool_of_json ctx ullbc in
         let* lib = bool_of_json ctx lib in
@@ -1643,8 +2026,12 @@ and cli_options_of_json (ctx : of_json_ctx) (js : json) :
         let* mir_optimized = bool_of_json ctx mir_optimized in
         let* input_file = option_of_json path_buf_of_json ctx input_file in
         let* read_llbc = option_of_json path_buf_of_json ctx read_llbc in
+        let* print_depth = option_of_json int_of_json ctx print_depth in
         let* dest_dir = option_of_json path_buf_of_json ctx dest_dir in
         let* dest_file = option_of_json path_buf_of_json ctx dest_file in
+        let* split_by_kind = option_of_json path_buf_of_json ctx split_by_kind in
+        let* group_by_module = bool_of_json ctx group_by_module in
+        let* output_stdout = bool_of_json ctx output_stdout in
         let* use_polonius = bool_of_json ctx use_polonius in
         let* skip_borrowck = bool_of_json ctx skip_borrowck in
         let* no_code_duplication = bool_of_json ctx no_code_duplication in
@@ -1653,21 +2040,91 @@ and cli_options_of_json (ctx : of_json_ctx) (js : json) :
         let* included = list_of_json string_of_json ctx include_ in
         let* opaque = list_of_json string_of_json ctx opaque in
         let* exclude = list_of_json string_of_json ctx exclude in
+        let* opaque_file = option_of_json path_buf_of_json ctx opaque_file in
         let* remove_associated_types =
           list_of_json string_of_json ctx remove_associated_types
         in
         let* hide_marker_traits = bool_of_json ctx hide_marker_traits in
         let* no_cargo = bool_of_json ctx no_cargo in
         let* rustc_args = list_of_json string_of_json ctx rustc_args in
+        let* extern_args = list_of_json string_of_json ctx extern_args in
+        let* library_paths = list_of_json string_of_json ctx library_paths in
         let* cargo_args = list_of_json string_of_json ctx cargo_args in
+        let* edition = option_of_json string_of_json ctx edition in
+        let* crate_name = option_of_json string_of_json ctx crate_name in
+        let* version_check = bool_of_json ctx version_check in
+        let* ignore_toolchain_mismatch =
+          bool_of_json ctx ignore_toolchain_mismatch
+        in
         let* abort_on_error = bool_of_json ctx abort_on_error in
         let* error_on_warnings = bool_of_json ctx error_on_warnings in
+        let* error_format = error_format_of_json ctx error_format in
+        let* error_output = option_of_json path_buf_of_json ctx error_output in
+        let* max_errors = option_of_json int_of_json ctx max_errors in
+        let* max_parallel = option_of_json int_of_json ctx max_parallel in
+        let* on_unsupported =
+          list_of_json string_of_json ctx on_unsupported
+        in
+        let* emit_opaque_manifest =
+          option_of_json path_buf_of_json ctx emit_opaque_manifest
+        in
+        let* emit_deps_graphviz =
+          option_of_json path_buf_of_json ctx emit_deps_graphviz
+        in
+        let* emit_symbol_table =
+          option_of_json path_buf_of_json ctx emit_symbol_table
+        in
+        let* emit_type_sizes =
+          option_of_json path_buf_of_json ctx emit_type_sizes
+        in
+        let* emit_dependencies =
+          option_of_json path_buf_of_json ctx emit_dependencies
+        in
+        let* models = option_of_json path_buf_of_json ctx models in
+        let* emit_ullbc = bool_of_json ctx emit_ullbc in
         let* no_serialize = bool_of_json ctx no_serialize in
+        let* check = bool_of_json ctx check in
+        let* validate = bool_of_json ctx validate in
         let* print_original_ullbc = bool_of_json ctx print_original_ullbc in
         let* print_ullbc = bool_of_json ctx print_ullbc in
         let* print_built_llbc = bool_of_json ctx print_built_llbc in
         let* print_llbc = bool_of_json ctx print_llbc in
         let* no_merge_goto_chains = bool_of_json ctx no_merge_goto_chains in
+        let* single_return = bool_of_json ctx single_return in
+        let* cse = bool_of_json ctx cse in
+        let* normalize_if_polarity = bool_of_json ctx normalize_if_polarity in
+        let* reconstruct_let_chains = bool_of_json ctx reconstruct_let_chains in
+        let* reconstruct_let_else = bool_of_json ctx reconstruct_let_else in
+        let* reconstruct_while_loops =
+          bool_of_json ctx reconstruct_while_loops
+        in
+        let* reconstruct_range_matches =
+          bool_of_json ctx reconstruct_range_matches
+        in
+        let* reconstruct_deref = bool_of_json ctx reconstruct_deref in
+        let* reconstruct_struct_updates =
+          bool_of_json ctx reconstruct_struct_updates
+        in
+        let* reconstruct_method_calls =
+          bool_of_json ctx reconstruct_method_calls
+        in
+        let* overflow = overflow_mode_of_json ctx overflow in
+        let* fold_constants = bool_of_json ctx fold_constants in
+        let* normalize_assoc_types = bool_of_json ctx normalize_assoc_types in
+        let* no_mem_builtins = bool_of_json ctx no_mem_builtins in
+        let* mark_unsafe = bool_of_json ctx mark_unsafe in
+        let* derived_cmp_builtins = bool_of_json ctx derived_cmp_builtins in
+        let* inline_derived_eq = bool_of_json ctx inline_derived_eq in
+        let* hash_builtins = bool_of_json ctx hash_builtins in
+        let* abstract_formatting = bool_of_json ctx abstract_formatting in
+        let* defunctionalize = bool_of_json ctx defunctionalize in
+        let* stable_ids = bool_of_json ctx stable_ids in
+        let* annotation_prefix =
+          option_of_json string_of_json ctx annotation_prefix
+        in
+        let* remap_path_prefix =
+          list_of_json string_of_json ctx remap_path_prefix
+        in
         Ok
           ({
              ullbc;
@@ -1677,8 +2134,12 @@ and cli_options_of_json (ctx : of_json_ctx) (js : json) :
              mir_optimized;
              input_file;
              read_llbc;
+             print_depth;
              dest_dir;
              dest_file;
+             split_by_kind;
+             group_by_module;
+             output_stdout;
              use_polonius;
              skip_borrowck;
              no_code_duplication;
@@ -1687,19 +2148,63 @@ and cli_options_of_json (ctx : of_json_ctx) (js : json) :
              included;
              opaque;
              exclude;
+             opaque_file;
              remove_associated_types;
              hide_marker_traits;
              no_cargo;
              rustc_args;
+             extern_args;
+             library_paths;
              cargo_args;
+             edition;
+             crate_name;
+             version_check;
+             ignore_toolchain_mismatch;
              abort_on_error;
              error_on_warnings;
+             error_format;
+             error_output;
+             max_errors;
+             max_parallel;
+             on_unsupported;
+             emit_opaque_manifest;
+             emit_deps_graphviz;
+             emit_symbol_table;
+             emit_type_sizes;
+             emit_dependencies;
+             models;
+             emit_ullbc;
              no_serialize;
+             check;
+             validate;
              print_original_ullbc;
              print_ullbc;
              print_built_llbc;
              print_llbc;
              no_merge_goto_chains;
+             single_return;
+             cse;
+             normalize_if_polarity;
+             reconstruct_let_chains;
+             reconstruct_let_else;
+             reconstruct_while_loops;
+             reconstruct_range_matches;
+             reconstruct_deref;
+             reconstruct_struct_updates;
+             reconstruct_method_calls;
+             overflow;
+             fold_constants;
+             normalize_assoc_types;
+             no_mem_builtins;
+             mark_unsafe;
+             derived_cmp_builtins;
+             inline_derived_eq;
+             hash_builtins;
+             abstract_formatting;
+             defunctionalize;
+             stable_ids;
+             annotation_prefix;
+             remap_path_prefix;
            }
             : cli_options)
     | _ -> Error "")
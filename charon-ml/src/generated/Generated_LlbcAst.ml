@@ -24,15 +24,69 @@ type raw_statement =
       (** Continue to (outer) loop. The loop identifier works
         the same way as for {!Break} *)
   | Nop
+  | EnterUnsafe
+      (** Marks that the statements that follow are inside an `unsafe` block, until the next
+          `ExitUnsafe`. Only emitted with `--mark-unsafe`.
+       *)
+  | ExitUnsafe
+      (** Marks the end of the statements that are inside an `unsafe` block. *)
   | Sequence of statement * statement
   | Switch of switch
+  | LetElse of place * variant_id list * block
+      (** A `let` with a refutable pattern whose `else` branch diverges:
+          ```text
+          let Some(x) = opt else { return };
+          ```
+          The `else` block runs when the scrutinee isn't one of the variants, and never falls
+          through. After this statement, the scrutinee is known to be one of the variants; the
+          bindings of the pattern are the assignments that follow.
+       *)
+  | IfLetChain of let_chain_binding list * operand option * block * block
+      (** An `if` whose condition is a chain of refutable bindings, optionally followed by a boolean
+          condition:
+          ```text
+          if let Some(a) = x && let Ok(b) = a.f() && b > 0 { .. } else { .. }
+          ```
+          The `let`s are checked in order: when the scrutinee of a `let` is one of its variants, its
+          block runs (it binds the variables of the pattern and computes what the next link
+          needs), otherwise the `else` block runs. When all the `let`s match and the condition (if
+          any) is `true`, the `then` block runs. The drops of the locals bound by the earlier links,
+          which happen before jumping to the `else` block from a later link, are left implicit.
+       *)
   | Loop of statement
+      (** A loop, which runs until a `Break` exits it. A loop that produces a value, like
+          `let x = loop { .. break v .. }`, doesn't have a result of its own: each `break v` is
+          translated to an assignment of `v` to the place that receives the result (`x`), followed by
+          a `Break`.
+       *)
+  | While of statement * operand * statement
+      (** A `while` loop:
+          ```text
+          while { cond_block; cond } { body }
+          ```
+          Before each iteration, the condition block is executed and the condition evaluated; the
+          loop exits when the condition is `false`. The condition block doesn't have side
+          effects. Like `Loop`, this counts as a loop for the `Break` and `Continue` statements in
+          the body.
+       *)
   | Error of string
 
 and statement = {
   span : span;
   content : raw_statement;
   comments_before : string list;  (** Comments that precede this statement. *)
+  annotations : annotation list;
+      (** The `// charon:` directives that precede this statement. Filled in the same pass as
+          `comments_before`.
+       *)
+}
+
+(** One `let` of a [RawStatement::IfLetChain]. *)
+and let_chain_binding = {
+  scrutinee : place;
+  variants : variant_id list;
+  block : block;
+      (** The statements that run when the scrutinee is one of the `variants`. *)
 }
 
 and block = statement
@@ -46,10 +100,9 @@ and switch =
           if (move v@2) { // Represented as `If(Move(v@2), <then branch>, <else branch>)`
           ```
        *)
-  | SwitchInt of
-      operand * integer_type * (scalar_value list * block) list * block
-      (** Gives the integer type, a map linking values to switch branches, and the
-          otherwise block. Note that matches over enumerations are performed by
+  | SwitchInt of operand * literal_type * (literal list * block) list * block
+      (** Gives the type of the scrutinee (an integer or a `char`), a map linking values to switch
+          branches, and the otherwise block. Note that matches over enumerations are performed by
           switching over the discriminant, which is an integer.
           Also, we use a `Vec` to make sure the order of the switch
           branches is preserved.
@@ -63,6 +116,20 @@ and switch =
           }
           ```
        *)
+  | SwitchRange of operand * literal_type * (literal_range * block) list * block
+      (** Gives the type of the scrutinee (an integer or a `char`), a list of ranges with the
+          branch to take when the scrutinee is in the range, and the otherwise block. The ranges are
+          tested in order, like the arms of a `match`.
+
+          This is introduced by [crate::transform::reconstruct_range_matches], from the comparisons
+          that range patterns are lowered to:
+          ```text
+          match x {
+            0..=9 => ...,
+            _ => ...
+          }
+          ```
+       *)
   | Match of place * (variant_id list * block) list * block option
       (** A match over an ADT.
 
@@ -40,12 +40,54 @@ and raw_statement_of_json (ctx : of_json_ctx) (js : json) :
         let* continue = int_of_json ctx continue in
         Ok (Continue continue)
     | `String "Nop" -> Ok Nop
+    | `String "EnterUnsafe" -> Ok EnterUnsafe
+    | `String "ExitUnsafe" -> Ok ExitUnsafe
     | `Assoc [ ("Switch", switch) ] ->
         let* switch = switch_of_json ctx switch in
         Ok (Switch switch)
+    | `Assoc
+        [
+          ( "LetElse",
+            `Assoc
+              [
+                ("scrutinee", scrutinee);
+                ("variants", variants);
+                ("else_block", else_block);
+              ] );
+        ] ->
+        let* scrutinee = place_of_json ctx scrutinee in
+        let* variants = list_of_json variant_id_of_json ctx variants in
+        let* else_block = block_of_json ctx else_block in
+        Ok (LetElse (scrutinee, variants, else_block))
+    | `Assoc
+        [
+          ( "IfLetChain",
+            `Assoc
+              [
+                ("lets", lets);
+                ("cond", cond);
+                ("then_block", then_block);
+                ("else_block", else_block);
+              ] );
+        ] ->
+        let* lets = list_of_json let_chain_binding_of_json ctx lets in
+        let* cond = option_of_json operand_of_json ctx cond in
+        let* then_block = block_of_json ctx then_block in
+        let* else_block = block_of_json ctx else_block in
+        Ok (IfLetChain (lets, cond, then_block, else_block))
     | `Assoc [ ("Loop", loop) ] ->
         let* loop = block_of_json ctx loop in
         Ok (Loop loop)
+    | `Assoc
+        [
+          ( "While",
+            `Assoc
+              [ ("cond_block", cond_block); ("cond", cond); ("body", body) ] );
+        ] ->
+        let* cond_block = block_of_json ctx cond_block in
+        let* cond = operand_of_json ctx cond in
+        let* body = block_of_json ctx body in
+        Ok (While (cond_block, cond, body))
     | `Assoc [ ("Error", error) ] ->
         let* error = string_of_json ctx error in
         Ok (Error error)
@@ -60,13 +102,29 @@ and statement_of_json (ctx : of_json_ctx) (js : json) :
           ("span", span);
           ("content", content);
           ("comments_before", comments_before);
+          ("annotations", annotations);
         ] ->
         let* span = span_of_json ctx span in
         let* content = raw_statement_of_json ctx content in
         let* comments_before =
           list_of_json string_of_json ctx comments_before
         in
-        Ok ({ span; content; comments_before } : statement)
+        let* annotations = list_of_json annotation_of_json ctx annotations in
+        Ok ({ span; content; comments_before; annotations } : statement)
+    | _ -> Error "")
+
+and let_chain_binding_of_json (ctx : of_json_ctx) (js : json) :
+    (let_chain_binding, string) result =
+  combine_error_msgs js __FUNCTION__
+    (match js with
+    | `Assoc
+        [
+          ("scrutinee", scrutinee); ("variants", variants); ("block", block);
+        ] ->
+        let* scrutinee = place_of_json ctx scrutinee in
+        let* variants = list_of_json variant_id_of_json ctx variants in
+        let* block = block_of_json ctx block in
+        Ok ({ scrutinee; variants; block } : let_chain_binding)
     | _ -> Error "")
 
 and block_of_json (ctx : of_json_ctx) (js : json) : (block, string) result =
@@ -76,7 +134,8 @@ and block_of_json (ctx : of_json_ctx) (js : json) : (block, string) result =
         let* span = span_of_json ctx span in
         let* statements = list_of_json statement_of_json ctx statements in
         match List.rev statements with
-        | [] -> Ok { span; content = Nop; comments_before = [] }
+        | [] ->
+            Ok { span; content = Nop; comments_before = []; annotations = [] }
         | last :: rest ->
             let seq =
               List.fold_left
@@ -85,6 +144,7 @@ and block_of_json (ctx : of_json_ctx) (js : json) : (block, string) result =
                     span = st.span;
                     content = Sequence (st, acc);
                     comments_before = [];
+                    annotations = [];
                   })
                 last rest
             in
@@ -102,14 +162,22 @@ and switch_of_json (ctx : of_json_ctx) (js : json) : (switch, string) result =
         Ok (If (x_0, x_1, x_2))
     | `Assoc [ ("SwitchInt", `List [ x_0; x_1; x_2; x_3 ]) ] ->
         let* x_0 = operand_of_json ctx x_0 in
-        let* x_1 = integer_type_of_json ctx x_1 in
+        let* x_1 = literal_type_of_json ctx x_1 in
         let* x_2 =
           list_of_json
-            (pair_of_json (list_of_json scalar_value_of_json) block_of_json)
+            (pair_of_json (list_of_json literal_of_json) block_of_json)
             ctx x_2
         in
         let* x_3 = block_of_json ctx x_3 in
         Ok (SwitchInt (x_0, x_1, x_2, x_3))
+    | `Assoc [ ("SwitchRange", `List [ x_0; x_1; x_2; x_3 ]) ] ->
+        let* x_0 = operand_of_json ctx x_0 in
+        let* x_1 = literal_type_of_json ctx x_1 in
+        let* x_2 =
+          list_of_json (pair_of_json literal_range_of_json block_of_json) ctx x_2
+        in
+        let* x_3 = block_of_json ctx x_3 in
+        Ok (SwitchRange (x_0, x_1, x_2, x_3))
     | `Assoc [ ("Match", `List [ x_0; x_1; x_2 ]) ] ->
         let* x_0 = place_of_json ctx x_0 in
         let* x_1 =
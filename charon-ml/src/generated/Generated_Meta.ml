@@ -110,6 +110,10 @@ and attr_info = {
         API (this is called "pub-in-priv" items). With or without the `pub use`, we set `public =
         true`; computing item reachability is harder.
      *)
+  must_use : bool;
+      (** Whether this item is marked `#[must_use]`. The message, if any, is kept in the
+        corresponding [Attribute::Unknown].
+     *)
 }
 
 (** A filename. *)
@@ -317,6 +317,8 @@ and trait_impl_ref = {
 and generics_source =
   | GSItem of any_decl_id  (** A top-level item. *)
   | GSMethod of trait_decl_id * trait_item_name  (** A trait method. *)
+  | GSTraitType of trait_decl_id * trait_item_name
+      (** A generic associated type of a trait. *)
   | GSBuiltin  (** A builtin item like `Box`. *)
 
 (** A set of generic arguments. *)
@@ -397,13 +399,13 @@ and ty =
           the second branch will have type `Never`. Also note that `Never`
           can be coerced to any type.
 
-          Note that we eliminate the variables which have this type in a micro-pass.
-          As statements don't have types, this type disappears eventually disappears
-          from the AST.
+          Note that we eliminate the variables which have this type in a micro-pass
+          ([crate::transform::remove_never]). The only ones that remain are the
+          destinations of calls to diverging functions.
        *)
   | TRef of region * ty * ref_kind  (** A borrow *)
   | TRawPtr of ty * ref_kind  (** A raw pointer. *)
-  | TTraitType of trait_ref * trait_item_name
+  | TTraitType of trait_ref * trait_item_name * generic_args
       (** A trait associated type
 
           Ex.:
@@ -412,6 +414,9 @@ and ty =
             type Bar; // type associated to the trait Foo
           }
           ```
+
+          The `GenericArgs` are the arguments of the associated type itself, in case it is a generic
+          associated type (e.g. `type Item<'a>;`). They are empty otherwise.
        *)
   | TDynTrait of existential_predicate
       (** `dyn Trait`
@@ -445,6 +450,21 @@ and builtin_ty =
   | TArray  (** Primitive type *)
   | TSlice  (** Primitive type *)
   | TStr  (** Primitive type *)
+  | TUnsafeCell
+      (** `core::cell::UnsafeCell<T>`: the primitive through which interior mutability is
+          implemented. We keep the inner type but hide the layout, since `&UnsafeCell<T>` allows
+          mutating the `T` through a shared reference.
+       *)
+  | TVecDeque
+      (** `alloc::collections::VecDeque<T>`. Like the other std collections below, we keep the type
+          arguments (minus the allocator) but not the internals, so that backends can model them
+          abstractly.
+       *)
+  | TBTreeMap  (** `alloc::collections::BTreeMap<K, V>` *)
+  | THashMap
+      (** `std::collections::HashMap<K, V>`. We drop the hasher parameter. *)
+  | THashSet
+      (** `std::collections::HashSet<T>`. We drop the hasher parameter. *)
 [@@deriving
   show,
     eq,
@@ -482,12 +502,25 @@ class ['self] map_type_decl_base =
   end
 
 type abort_kind =
-  | Panic of name  (** A built-in panicking function. *)
+  | Panic of name * panic_location option
+      (** A built-in panicking function. We also record the location that rustc passes to it, unless
+          we are inside a `#[track_caller]` function, in which case the location comes from the
+          caller.
+       *)
   | UndefinedBehavior
       (** A MIR `Unreachable` terminator corresponds to undefined behavior in the rust abstract
           machine.
        *)
 
+(** The source location of a panic, as returned by `core::panic::Location::caller()` in the
+    panicking function.
+ *)
+and panic_location = {
+  file_id : file_id;
+  line : int;  (** The (1-based) line number. *)
+  col : int;  (** The (1-based) column number, as in `Location::column`. *)
+}
+
 (** Meta information about an item (function, trait decl, trait impl, type decl, global). *)
 and item_meta = {
   name : name;
@@ -497,10 +530,24 @@ and item_meta = {
   attr_info : attr_info;  (** Attributes and visibility. *)
   is_local : bool;
       (** `true` if the type decl is a local type decl, `false` if it comes from an external crate. *)
+  is_foreign : bool;
+      (** `true` if the item is declared in an `extern { .. }` block, i.e. is implemented outside of
+        Rust (e.g. a C function). Such items never have a body.
+     *)
   lang_item : string option;
       (** If the item is built-in, record its internal builtin identifier. *)
+  lang_item_role : lang_item_role option;
+      (** If the item is registered globally as a runtime hook of the crate. *)
 }
 
+(** The runtime hooks that a crate can register with an attribute. These matter for `no_std`
+    crates, which have to provide them.
+ *)
+and lang_item_role =
+  | PanicHandler  (** The function marked `#[panic_handler]`. *)
+  | GlobalAllocator  (** The static marked `#[global_allocator]`. *)
+  | AllocErrorHandler  (** The function marked `#[alloc_error_handler]`. *)
+
 and disambiguator = (Disambiguator.id[@visitors.opaque])
 
 (** See the comments for [Name] *)
@@ -560,7 +607,13 @@ and impl_elem = ImplElemTy of ty binder | ImplElemTrait of trait_impl_id
 and name = (path_elem list[@visitors.opaque])
 
 (** A type variable in a signature or binder. *)
-and type_var = (type_var_id, string) indexed_var
+and type_var = {
+  index : type_var_id;
+      (** Index identifying the variable among other variables bound at the same level. *)
+  name : string;  (** Variable name *)
+  default : ty option;
+      (** The default value of the parameter, if any (e.g. `T = u32`). *)
+}
 
 (** A const generic variable in a signature or binder. *)
 and const_generic_var = {
@@ -568,6 +621,8 @@ and const_generic_var = {
       (** Index identifying the variable among other variables bound at the same level. *)
   name : string;  (** Const generic name *)
   ty : literal_type;  (** Type of the const generic *)
+  default : const_generic option;
+      (** The default value of the parameter, if any (e.g. `const N: usize = 4`). *)
 }
 
 (** A trait predicate in a signature, of the form `Type: Trait<Args>`. This functions like a
@@ -602,13 +657,17 @@ and binder_kind =
       (** The parameters of a trait method. Used in the `methods` lists in trait decls and trait
           impls.
        *)
+  | BKTraitType of trait_decl_id * trait_item_name
+      (** The parameters of a generic associated type. Used in the `generic_types` lists in trait
+          decls and trait impls.
+       *)
   | BKInherentImplBlock
       (** The parameters bound in a non-trait `impl` block. Used in the `Name`s of inherent methods. *)
   | BKOther  (** Some other use of a binder outside the main Charon ast. *)
 
 (** A value of type `T` bound by generic parameters. Used in any context where we're adding generic
     parameters that aren't on the top-level item, e.g. `for<'a>` clauses (uses `RegionBinder` for
-    now), trait methods, GATs (TODO).
+    now), trait methods, GATs.
  *)
 and 'a0 binder = {
   binder_params : generic_params;
@@ -658,6 +717,51 @@ and type_decl = {
   item_meta : item_meta;  (** Meta information associated with the item. *)
   generics : generic_params;
   kind : type_decl_kind;  (** The type kind: enum, struct, or opaque. *)
+  drop_impl : fun_decl_id option;
+      (** The `drop` method of the `Drop` impl of this type, if any. Only set for local types. *)
+  interior_mut : bool;
+      (** Whether values of this type contain an `UnsafeCell` (not behind a pointer), e.g. `Cell` and
+        `RefCell`. Such values may be mutated through a shared reference.
+     *)
+  valid_range : scalar_valid_range option;
+      (** The values allowed for the (scalar) contents of this type, if restricted with the
+        `rustc_layout_scalar_valid_range_start`/`_end` attributes. For instance the contents of a
+        `NonZeroU8` are never `0`. This is what enables niche optimizations such as making
+        `Option<NonZeroU8>` as big as a `u8`.
+     *)
+  ctor_kind : ctor_kind option;
+      (** For structs, the syntax used to build values of this type. The variants of enums record
+        their own.
+     *)
+  repr : repr_info option;
+      (** The `#[repr(..)]` attributes of this type, if it has any. *)
+}
+
+(** The syntax used to build the values of a struct or of an enum variant. *)
+and ctor_kind =
+  | Braced  (** Named fields: `P { x: 1, y: 2 }`. *)
+  | Tuple  (** Positional fields: `P(1, 2)`. The fields have no name. *)
+  | Unit  (** No fields: `P`. *)
+
+(** An inclusive range of raw scalar values, as given by the `rustc_layout_scalar_valid_range_*`
+    attributes. A missing bound means there is no restriction on that side.
+ *)
+and scalar_valid_range = { range_start : int option; range_end : int option }
+
+(** The `#[repr(..)]` attributes of an ADT. These can be combined, e.g. `#[repr(u8, align(4))]` on
+    an enum sets both `int` and `align`.
+ *)
+and repr_info = {
+  c : bool;  (** `repr(C)` *)
+  transparent : bool;  (** `repr(transparent)` *)
+  int : integer_type option;
+      (** For enums, the type of the discriminant given with e.g. `repr(u8)`. *)
+  align : int option;
+      (** `repr(align(N))`: the minimum alignment of the type, in bytes. *)
+  pack : int option;
+      (** `repr(packed(N))`: the maximum alignment of the fields, in bytes. `repr(packed)` is
+        `repr(packed(1))`.
+     *)
 }
 
 and variant_id = (VariantId.id[@visitors.opaque])
@@ -667,6 +771,10 @@ and type_decl_kind =
   | Struct of field list
   | Enum of variant list
   | Union of field list
+  | Simd of ty * const_generic
+      (** A `#[repr(simd)]` vector type, such as `core::simd::Simd<T, N>`. We don't translate its
+          contents; we only record the type of its lanes and how many there are.
+       *)
   | Opaque
       (** An opaque type.
 
@@ -686,6 +794,7 @@ and variant = {
   attr_info : attr_info;
   variant_name : string;
   fields : field list;
+  ctor_kind : ctor_kind;  (** The syntax used to build values of this variant. *)
   discriminant : scalar_value;
       (** The discriminant used at runtime. This is used in `remove_read_discriminant` to match up
         `SwitchInt` targets with the corresponding `Variant`.
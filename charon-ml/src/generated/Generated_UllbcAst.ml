@@ -25,20 +25,35 @@ and raw_statement =
           checks, over/underflow checks, div/rem by zero checks, pointer alignement check.
        *)
   | Nop  (** Does nothing. Useful for passes. *)
+  | EnterUnsafe
+      (** Marks that the statements that follow are inside an `unsafe` block, until the next
+          `ExitUnsafe`. Only emitted with `--mark-unsafe`.
+       *)
+  | ExitUnsafe
+      (** Marks the end of the statements that are inside an `unsafe` block. *)
 
 and statement = {
   span : span;
   content : raw_statement;
   comments_before : string list;  (** Comments that precede this statement. *)
+  annotations : annotation list;
+      (** The `// charon:` directives that precede this statement. Filled in the same pass as
+          `comments_before`.
+       *)
 }
 
 and switch =
   | If of block_id * block_id  (** Gives the `if` block and the `else` block *)
-  | SwitchInt of integer_type * (scalar_value * block_id) list * block_id
-      (** Gives the integer type, a map linking values to switch branches, and the
-          otherwise block. Note that matches over enumerations are performed by
+  | SwitchInt of literal_type * (literal * block_id) list * block_id
+      (** Gives the type of the scrutinee (an integer or a `char`), a map linking values to switch
+          branches, and the otherwise block. Note that matches over enumerations are performed by
           switching over the discriminant, which is an integer.
        *)
+  | SwitchRange of literal_type * (literal_range * block_id) list * block_id
+      (** Gives the type of the scrutinee (an integer or a `char`), a list of ranges with the
+          branch to go to when the scrutinee is in the range, and the otherwise block. The ranges
+          are tested in order. This is introduced by [crate::transform::reconstruct_range_matches].
+       *)
 [@@deriving
   show,
     eq,
@@ -81,6 +96,10 @@ and terminator = {
   span : span;
   content : raw_terminator;
   comments_before : string list;  (** Comments that precede this terminator. *)
+  annotations : annotation list;
+      (** The `// charon:` directives that precede this terminator. Filled in the same pass as
+          `comments_before`.
+       *)
 }
 
 and block = { statements : statement list; terminator : terminator }
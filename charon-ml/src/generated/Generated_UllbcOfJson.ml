@@ -50,6 +50,8 @@ and raw_statement_of_json (ctx : of_json_ctx) (js : json) :
         let* assert_ = assertion_of_json ctx assert_ in
         Ok (Assert assert_)
     | `String "Nop" -> Ok Nop
+    | `String "EnterUnsafe" -> Ok EnterUnsafe
+    | `String "ExitUnsafe" -> Ok ExitUnsafe
     | _ -> Error "")
 
 and statement_of_json (ctx : of_json_ctx) (js : json) :
@@ -61,13 +63,15 @@ and statement_of_json (ctx : of_json_ctx) (js : json) :
           ("span", span);
           ("content", content);
           ("comments_before", comments_before);
+          ("annotations", annotations);
         ] ->
         let* span = span_of_json ctx span in
         let* content = raw_statement_of_json ctx content in
         let* comments_before =
           list_of_json string_of_json ctx comments_before
         in
-        Ok ({ span; content; comments_before } : statement)
+        let* annotations = list_of_json annotation_of_json ctx annotations in
+        Ok ({ span; content; comments_before; annotations } : statement)
     | _ -> Error "")
 
 and switch_of_json (ctx : of_json_ctx) (js : json) : (switch, string) result =
@@ -78,14 +82,21 @@ and switch_of_json (ctx : of_json_ctx) (js : json) : (switch, string) result =
         let* x_1 = block_id_of_json ctx x_1 in
         Ok (If (x_0, x_1))
     | `Assoc [ ("SwitchInt", `List [ x_0; x_1; x_2 ]) ] ->
-        let* x_0 = integer_type_of_json ctx x_0 in
+        let* x_0 = literal_type_of_json ctx x_0 in
+        let* x_1 =
+          list_of_json (pair_of_json literal_of_json block_id_of_json) ctx x_1
+        in
+        let* x_2 = block_id_of_json ctx x_2 in
+        Ok (SwitchInt (x_0, x_1, x_2))
+    | `Assoc [ ("SwitchRange", `List [ x_0; x_1; x_2 ]) ] ->
+        let* x_0 = literal_type_of_json ctx x_0 in
         let* x_1 =
           list_of_json
-            (pair_of_json scalar_value_of_json block_id_of_json)
+            (pair_of_json literal_range_of_json block_id_of_json)
             ctx x_1
         in
         let* x_2 = block_id_of_json ctx x_2 in
-        Ok (SwitchInt (x_0, x_1, x_2))
+        Ok (SwitchRange (x_0, x_1, x_2))
     | _ -> Error "")
 
 and raw_terminator_of_json (ctx : of_json_ctx) (js : json) :
@@ -115,13 +126,15 @@ and terminator_of_json (ctx : of_json_ctx) (js : json) :
           ("span", span);
           ("content", content);
           ("comments_before", comments_before);
+          ("annotations", annotations);
         ] ->
         let* span = span_of_json ctx span in
         let* content = raw_terminator_of_json ctx content in
         let* comments_before =
           list_of_json string_of_json ctx comments_before
         in
-        Ok ({ span; content; comments_before } : terminator)
+        let* annotations = list_of_json annotation_of_json ctx annotations in
+        Ok ({ span; content; comments_before; annotations } : terminator)
     | _ -> Error "")
 
 and block_of_json (ctx : of_json_ctx) (js : json) : (block, string) result =
@@ -34,7 +34,21 @@ and literal =
   | VBool of bool
   | VChar of char
   | VByteStr of int list
-  | VStr of string
+      (** A byte string, e.g. `b"hello"`. Its type is a reference to an array or slice of `u8`.
+       *)
+  | VStr of string  (** A string, e.g. `"hello"`. Its type is `&str`. *)
+
+(** A range of integers or characters, as matched by a range pattern like `0..=9`, `'a'..'z'` or
+    `10..`.
+ *)
+and literal_range = {
+  range_start : literal option;
+      (** The lower bound, which is always included. `None` if the range is open on the left. *)
+  range_end : literal option;
+      (** The upper bound. `None` if the range is open on the right. *)
+  range_end_inclusive : bool;
+      (** Whether the upper bound is included (`..=`) or excluded (`..`). *)
+}
 
 (** A scalar value. *)
 and scalar_value = {
@@ -130,7 +130,7 @@ module PatternTest = struct
             match statement.content with
             | Call call -> [ call ]
             | Sequence (st1, st2) -> list_calls st1 @ list_calls st2
-            | Switch _ | Loop _ ->
+            | Switch _ | Loop _ | While _ ->
                 failwith
                   "Switches and loops are unsupported in name matcher tests"
             | _ -> []
@@ -0,0 +1,10 @@
+//! Record the rustc version Charon is built with, so that `charon` can check at startup that the
+//! toolchain it runs matches it.
+fn main() {
+    let version = rustc_version::version_meta().expect("failed to determine the rustc version");
+    println!("cargo:rustc-env=CHARON_RUSTC_RELEASE={}", version.semver);
+    println!(
+        "cargo:rustc-env=CHARON_RUSTC_COMMIT_HASH={}",
+        version.commit_hash.unwrap_or_default()
+    );
+}
@@ -13,6 +13,34 @@ use macros::EnumIsA;
 // Built-in functions
 // We treat this one specially in the `inline_local_panic_functions` pass. See there for details.
 pub static EXPLICIT_PANIC_NAME: &[&str] = &["core", "panicking", "panic_explicit"];
+// We treat these specially in the `reconstruct_assert_eq` pass. See there for details.
+pub static ASSERT_FAILED_NAME: &[&str] = &["core", "panicking", "assert_failed"];
+pub static ASSERT_KIND_NAME: &[&str] = &["core", "panicking", "AssertKind"];
+// We recover the message of the `assert!`s from these in the `reconstruct_asserts` pass.
+pub static PANIC_FMT_NAME: &[&str] = &["core", "panicking", "panic_fmt"];
+pub static FMT_ARGUMENTS_NAME: &[&str] = &["core", "fmt", "Arguments"];
+pub static FMT_ARGUMENT_NAME: &[&str] = &["core", "fmt", "rt", "Argument"];
+// We fold the calls to these on references and boxes in the `reconstruct_deref` pass.
+pub static DEREF_NAME: &[&str] = &["core", "ops", "deref", "Deref"];
+pub static DEREF_MUT_NAME: &[&str] = &["core", "ops", "deref", "DerefMut"];
+// We translate the calls to these to builtin functions, unless `--no-mem-builtins` is set.
+pub static MEM_SWAP_NAME: &[&str] = &["core", "mem", "swap"];
+pub static MEM_REPLACE_NAME: &[&str] = &["core", "mem", "replace"];
+pub static MEM_TAKE_NAME: &[&str] = &["core", "mem", "take"];
+// We translate the calls to these to builtin functions when the impl is derived and
+// `--derived-cmp-builtins` is set.
+pub static ORD_CMP_NAME: &[&str] = &["core", "cmp", "Ord", "cmp"];
+pub static PARTIAL_ORD_PARTIAL_CMP_NAME: &[&str] = &["core", "cmp", "PartialOrd", "partial_cmp"];
+// We inline the calls to the `eq` method of the derived impls of this trait in the
+// `inline_derived_eq` pass, when `--inline-derived-eq` is set.
+pub static PARTIAL_EQ_NAME: &[&str] = &["core", "cmp", "PartialEq"];
+// We translate the calls to these to builtin functions when `--hash-builtins` is set (for `hash`,
+// only when the impl is derived).
+pub static HASH_HASH_NAME: &[&str] = &["core", "hash", "Hash", "hash"];
+pub static HASHER_NAME: &[&str] = &["core", "hash", "Hasher"];
+// We translate the calls to these to builtin functions when `--abstract-formatting` is set.
+pub static DISPLAY_FMT_NAME: &[&str] = &["core", "fmt", "Display", "fmt"];
+pub static DEBUG_FMT_NAME: &[&str] = &["core", "fmt", "Debug", "fmt"];
 
 /// We redefine identifiers for built-in functions here, instead of reusing the
 /// identifiers from [ullbc_ast], because:
@@ -24,6 +52,20 @@ pub static EXPLICIT_PANIC_NAME: &[&str] = &["core", "panicking", "panic_explicit
 pub enum BuiltinFun {
     Panic,
     BoxNew,
+    PtrOffset(ast::BuiltinPtrOffsetOp),
+    MemSwap,
+    MemReplace,
+    MemTake,
+    DerivedCmp,
+    DerivedPartialCmp,
+    DerivedHash,
+    HasherWrite(Option<IntegerTy>),
+    HasherFinish,
+    FormatArgs,
+    FmtArgDisplay,
+    FmtArgDebug,
+    FmtDisplay,
+    FmtDebug,
 }
 
 impl BuiltinFun {
@@ -32,9 +74,61 @@ impl BuiltinFun {
     pub fn to_ullbc_builtin_fun(self) -> ast::BuiltinFunId {
         match self {
             BuiltinFun::BoxNew => ast::BuiltinFunId::BoxNew,
+            BuiltinFun::PtrOffset(op) => ast::BuiltinFunId::PtrOffset(op),
+            BuiltinFun::MemSwap => ast::BuiltinFunId::MemSwap,
+            BuiltinFun::MemReplace => ast::BuiltinFunId::MemReplace,
+            BuiltinFun::MemTake => ast::BuiltinFunId::MemTake,
+            BuiltinFun::DerivedCmp => ast::BuiltinFunId::DerivedCmp,
+            BuiltinFun::DerivedPartialCmp => ast::BuiltinFunId::DerivedPartialCmp,
+            BuiltinFun::DerivedHash => ast::BuiltinFunId::DerivedHash,
+            BuiltinFun::HasherWrite(ty) => ast::BuiltinFunId::HasherWrite(ty),
+            BuiltinFun::HasherFinish => ast::BuiltinFunId::HasherFinish,
+            BuiltinFun::FormatArgs => ast::BuiltinFunId::FormatArgs,
+            BuiltinFun::FmtArgDisplay => ast::BuiltinFunId::FmtArgDisplay,
+            BuiltinFun::FmtArgDebug => ast::BuiltinFunId::FmtArgDebug,
+            BuiltinFun::FmtDisplay => ast::BuiltinFunId::FmtDisplay,
+            BuiltinFun::FmtDebug => ast::BuiltinFunId::FmtDebug,
             BuiltinFun::Panic => panic!(),
         }
     }
+
+    /// Whether this replaces a trait method, in which case we drop the trait reference of the
+    /// call.
+    pub fn is_trait_method(self) -> bool {
+        matches!(
+            self,
+            BuiltinFun::DerivedCmp
+                | BuiltinFun::DerivedPartialCmp
+                | BuiltinFun::DerivedHash
+                | BuiltinFun::HasherWrite(_)
+                | BuiltinFun::HasherFinish
+                | BuiltinFun::FmtDisplay
+                | BuiltinFun::FmtDebug
+        )
+    }
+
+    /// The builtin for the method of `core::hash::Hasher` with the given name, if any.
+    pub fn hasher_method(method: &str) -> Option<Self> {
+        use IntegerTy::*;
+        let int_ty = match method {
+            "write" => return Some(BuiltinFun::HasherWrite(None)),
+            "finish" => return Some(BuiltinFun::HasherFinish),
+            "write_u8" => U8,
+            "write_u16" => U16,
+            "write_u32" => U32,
+            "write_u64" => U64,
+            "write_u128" => U128,
+            "write_usize" => Usize,
+            "write_i8" => I8,
+            "write_i16" => I16,
+            "write_i32" => I32,
+            "write_i64" => I64,
+            "write_i128" => I128,
+            "write_isize" => Isize,
+            _ => return None,
+        };
+        Some(BuiltinFun::HasherWrite(Some(int_ty)))
+    }
 }
 
 impl BuiltinTy {
@@ -44,6 +138,11 @@ impl BuiltinTy {
             BuiltinTy::Str => &["Str"],
             BuiltinTy::Array => &["Array"],
             BuiltinTy::Slice => &["Slice"],
+            BuiltinTy::UnsafeCell => &["core", "cell", "UnsafeCell"],
+            BuiltinTy::VecDeque => &["alloc", "collections", "vec_deque", "VecDeque"],
+            BuiltinTy::BTreeMap => &["alloc", "collections", "btree", "map", "BTreeMap"],
+            BuiltinTy::HashMap => &["std", "collections", "hash", "map", "HashMap"],
+            BuiltinTy::HashSet => &["std", "collections", "hash", "set", "HashSet"],
         };
         Name::from_path(name)
     }
@@ -60,6 +159,9 @@ pub fn type_to_used_params(id: BuiltinTy) -> Vec<bool> {
         BuiltinTy::Str => {
             vec![]
         }
-        BuiltinTy::Array | BuiltinTy::Slice => vec![true],
+        BuiltinTy::Array | BuiltinTy::Slice | BuiltinTy::UnsafeCell => vec![true],
+        // Ignore the allocator or the hasher.
+        BuiltinTy::VecDeque | BuiltinTy::HashSet => vec![true, false],
+        BuiltinTy::BTreeMap | BuiltinTy::HashMap => vec![true, true, false],
     }
 }
@@ -202,6 +202,11 @@ pub enum CastKind {
     ///
     /// The special case of `&[T; N]` -> `&[T]` coercion is caught by `UnOp::ArrayToSlice`.
     Unsize(Ty, Ty),
+    /// [Trait upcasting coercion](https://doc.rust-lang.org/reference/type-coercions.html#unsized-coercions),
+    /// e.g. `&dyn Sub` -> `&dyn Super` where `Super` is a supertrait of `Sub`, behind a pointer
+    /// like for [CastKind::Unsize]. The trait refs are the principal traits of the source and
+    /// target trait objects, with the trait object type itself as `Self`.
+    DynUpcast(Ty, Ty, TraitDeclRef, TraitDeclRef),
     /// Reinterprets the bits of a value of one type as another type, i.e. exactly what
     /// [`std::mem::transmute`] does.
     Transmute(Ty, Ty),
@@ -240,6 +245,13 @@ pub enum BinOp {
     CheckedSub,
     /// Like `CheckedAdd`.
     CheckedMul,
+    /// Wraps around on overflow. This operation does not fail.
+    /// Not present in MIR; introduced with `--overflow wrapping`.
+    WrappingAdd,
+    /// Like `WrappingAdd`.
+    WrappingSub,
+    /// Like `WrappingAdd`.
+    WrappingMul,
     /// Fails if the shift is bigger than the bit-size of the type.
     Shl,
     /// Fails if the shift is bigger than the bit-size of the type.
@@ -334,6 +346,55 @@ pub enum BuiltinFunId {
     /// - `fn SliceSubSliceMut<T>(&mut [T], usize, usize) -> &mut [T]`
     /// - etc
     Index(BuiltinIndexOp),
+    /// Pointer arithmetic: the `offset`, `add` and `sub` methods on `*const T` and `*mut T`, and
+    /// their `wrapping_` counterparts. The generics contain the pointee type `T`. The signature
+    /// looks like:
+    /// - `fn PtrAddShared<T>(*const T, usize) -> *const T`
+    /// - `fn PtrWrappingOffsetMut<T>(*mut T, isize) -> *mut T`
+    /// - etc
+    PtrOffset(BuiltinPtrOffsetOp),
+    /// `core::mem::swap<T>(&mut T, &mut T)`. The generics contain the type `T`.
+    MemSwap,
+    /// `core::mem::replace<T>(&mut T, T) -> T`. The generics contain the type `T`.
+    MemReplace,
+    /// `core::mem::take<T>(&mut T) -> T`. The generics contain the type `T` and its `Default`
+    /// impl.
+    MemTake,
+    /// `core::cmp::Ord::cmp<T>(&T, &T) -> Ordering`, when the impl for `T` is derived. The
+    /// generics contain the type `T`. Only emitted with `--derived-cmp-builtins`.
+    DerivedCmp,
+    /// `core::cmp::PartialOrd::partial_cmp<T, T>(&T, &T) -> Option<Ordering>`, when the impl for
+    /// `T` is derived. The generics contain the type `T` twice, as `Self` and `Rhs`. Only emitted
+    /// with `--derived-cmp-builtins`.
+    DerivedPartialCmp,
+    /// `core::hash::Hash::hash<T, H>(&T, &mut H)`, when the impl for `T` is derived. The generics
+    /// contain `T`, the hasher type `H` and its `Hasher` impl. Only emitted with `--hash-builtins`.
+    DerivedHash,
+    /// The `write` methods of `core::hash::Hasher`: `write<H>(&mut H, &[u8])` when there is no
+    /// integer type, `write_u32<H>(&mut H, u32)` etc. otherwise. The generics contain the hasher
+    /// type `H`. Only emitted with `--hash-builtins`.
+    HasherWrite(Option<IntegerTy>),
+    /// `core::hash::Hasher::finish<H>(&H) -> u64`. The generics contain the hasher type `H`. Only
+    /// emitted with `--hash-builtins`.
+    HasherFinish,
+    /// The constructors of `core::fmt::Arguments` that `format_args!` expands to: `new_const`,
+    /// `new_v1` and `new_v1_formatted`. The arguments are those of the original constructor. Only
+    /// emitted with `--abstract-formatting`.
+    FormatArgs,
+    /// `core::fmt::rt::Argument::new_display<T>(&T) -> Argument`, which `format_args!` uses for the
+    /// `{}` placeholders. The generics contain `T` and its `Display` impl. Only emitted with
+    /// `--abstract-formatting`.
+    FmtArgDisplay,
+    /// `core::fmt::rt::Argument::new_debug<T>(&T) -> Argument`, which `format_args!` uses for the
+    /// `{:?}` placeholders. The generics contain `T` and its `Debug` impl. Only emitted with
+    /// `--abstract-formatting`.
+    FmtArgDebug,
+    /// `core::fmt::Display::fmt<T>(&T, &mut Formatter) -> Result`. The generics contain `T`. Only
+    /// emitted with `--abstract-formatting`.
+    FmtDisplay,
+    /// `core::fmt::Debug::fmt<T>(&T, &mut Formatter) -> Result`. The generics contain `T`. Only
+    /// emitted with `--abstract-formatting`.
+    FmtDebug,
 }
 
 /// One of 8 built-in indexing operations.
@@ -352,6 +413,33 @@ pub struct BuiltinIndexOp {
     pub is_range: bool,
 }
 
+/// One of the built-in pointer offset operations.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Drive, DriveMut)]
+pub struct BuiltinPtrOffsetOp {
+    /// Whether this operates on a `*mut T` or a `*const T`.
+    #[charon::rename("ptr_mutability")]
+    pub mutability: RefKind,
+    /// Which method this is. Determines the type of the count argument.
+    #[drive(skip)]
+    #[charon::rename("offset_kind")]
+    pub kind: PtrOffsetKind,
+    /// Whether this is a `wrapping_` method. These are safe and wrap around the address space
+    /// instead of requiring the result to stay within the same allocation.
+    #[drive(skip)]
+    pub wrapping: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[charon::variants_prefix("PtrOp")]
+pub enum PtrOffsetKind {
+    /// `offset(count: isize)`
+    Offset,
+    /// `add(count: usize)`
+    Add,
+    /// `sub(count: usize)`
+    Sub,
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, EnumAsGetters, Serialize, Deserialize, Drive, DriveMut)]
 pub enum FunIdOrTraitMethodRef {
     #[charon::rename("FunId")]
@@ -487,7 +575,16 @@ pub struct ConstantExpr {
 /// TODO: move the aggregate kind to operands
 /// TODO: we should prefix the type variants with "R" or "Rv", this would avoid collisions
 #[derive(
-    Debug, Clone, EnumToGetters, EnumAsGetters, EnumIsA, Serialize, Deserialize, Drive, DriveMut,
+    Debug,
+    Clone,
+    PartialEq,
+    EnumToGetters,
+    EnumAsGetters,
+    EnumIsA,
+    Serialize,
+    Deserialize,
+    Drive,
+    DriveMut,
 )]
 pub enum Rvalue {
     /// Lifts an operand as an rvalue.
@@ -529,6 +626,14 @@ pub enum Rvalue {
     /// Remark: in case of closures, the aggregated value groups the closure id
     /// together with its state.
     Aggregate(AggregateKind, Vec<Operand>),
+    /// Builds a struct using struct-update syntax:
+    /// ```text
+    /// l = Foo { x: op, ..base };
+    /// ```
+    /// The explicitly-given fields are listed with their id; all the other fields are copied (or
+    /// moved) out of the base place.
+    /// Not present in MIR; introduced in [reconstruct_struct_updates.rs].
+    StructUpdate(AggregateKind, Vec<(FieldId, Operand)>, Place),
     /// Copy the value of the referenced global.
     /// Not present in MIR; introduced in [simplify_constants.rs].
     Global(GlobalDeclRef),
@@ -591,7 +696,7 @@ pub enum Rvalue {
 /// initialization, `ls` is initialized to `⊥`, then this `⊥` is expanded to
 /// `Cons (⊥, ⊥)` upon the first assignment, at which point we can initialize
 /// the field 0, etc.).
-#[derive(Debug, Clone, VariantIndexArity, Serialize, Deserialize, Drive, DriveMut)]
+#[derive(Debug, Clone, PartialEq, VariantIndexArity, Serialize, Deserialize, Drive, DriveMut)]
 #[charon::variants_prefix("Aggregated")]
 pub enum AggregateKind {
     /// A struct, enum or union aggregate. The `VariantId`, if present, indicates this is an enum
@@ -41,6 +41,35 @@ impl Place {
             ty,
         }
     }
+
+    /// If this place goes through a field of a `#[repr(packed)]` struct (without a dereference in
+    /// between), return the smallest packing of the structs involved. Such places may not be
+    /// aligned: they can be read and written, but not borrowed.
+    pub fn packed_field(&self, krate: &TranslatedCrate) -> Option<u64> {
+        let (sub, elem) = self.as_projection()?;
+        let pack = match elem {
+            ProjectionElem::Deref => return None,
+            ProjectionElem::Field(FieldProjKind::Adt(type_id, None), _) => krate
+                .type_decls
+                .get(*type_id)
+                .and_then(|decl| decl.repr.as_ref())
+                .and_then(|repr| repr.pack),
+            _ => None,
+        };
+        match (pack, sub.packed_field(krate)) {
+            (Some(pack), Some(sub_pack)) => Some(pack.min(sub_pack)),
+            (pack, sub_pack) => pack.or(sub_pack),
+        }
+    }
+}
+
+impl Operand {
+    pub fn ty(&self) -> &Ty {
+        match self {
+            Operand::Copy(place) | Operand::Move(place) => place.ty(),
+            Operand::Const(constant_expr) => &constant_expr.ty,
+        }
+    }
 }
 
 impl Rvalue {
@@ -138,7 +167,7 @@ impl ProjectionElem {
                                     .clone()
                                     .substitute(generics)
                             }
-                            Opaque | Alias(_) | Error(_) => return Err(()),
+                            Simd { .. } | Opaque | Alias(_) | Error(_) => return Err(()),
                         }
                     }
                     Tuple(_) => ty
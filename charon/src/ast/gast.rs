@@ -20,6 +20,22 @@ pub struct Var {
     /// The variable type
     #[charon::rename("var_ty")]
     pub ty: Ty,
+    /// How the variable was bound, if it was introduced by the user (in a `let`, a `match` arm, a
+    /// function parameter, etc.). For instance, in `match &opt { Some(x) => .. }` the default
+    /// binding mode makes `x` a reference, hence [BindingMode::ByRef].
+    #[drive(skip)]
+    pub binding_mode: Option<BindingMode>,
+}
+
+/// The binding mode of a variable introduced by a pattern.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum BindingMode {
+    /// `x`: the variable is bound to (a move or copy of) the matched value.
+    ByMove,
+    /// `ref x`: the variable is bound to a shared borrow of the matched value.
+    ByRef,
+    /// `ref mut x`: the variable is bound to a mutable borrow of the matched value.
+    ByMutRef,
 }
 
 /// Marker to indicate that a declaration is opaque (i.e. we don't inspect its body).
@@ -154,6 +170,22 @@ pub struct FunDeclRef {
     pub generics: GenericArgs,
 }
 
+/// The kind of a global declaration.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, EnumIsA, Serialize, Deserialize, Drive, DriveMut)]
+pub enum GlobalKind {
+    /// A `static` item.
+    Static,
+    /// A `static mut` item. Accessing it is unsafe: reads and writes go through the raw pointer
+    /// given by [Rvalue::GlobalRef] with [RefKind::Mut], never through a copy of the value.
+    StaticMut,
+    /// A `const` item, at the top level or in an impl/trait block.
+    NamedConst,
+    /// A `#[thread_local]` static, as generated by the `thread_local!` macro. Each thread has its
+    /// own copy of the value, initialized with `init`. Accesses to it are translated to
+    /// [Rvalue::GlobalRef].
+    ThreadLocal,
+}
+
 /// A global variable definition (constant or static).
 #[derive(Debug, Clone, Serialize, Deserialize, Drive, DriveMut)]
 pub struct GlobalDecl {
@@ -165,6 +197,8 @@ pub struct GlobalDecl {
     pub ty: Ty,
     /// The global kind: "regular" function, trait const declaration, etc.
     pub kind: ItemKind,
+    /// Whether this is a `static`, a `static mut`, a `const` or a thread-local static.
+    pub global_kind: GlobalKind,
     /// The initializer function used to compute the initial value for this constant/static. It
     /// uses the same generic parameters as the global.
     #[charon::rename("body")]
@@ -245,6 +279,9 @@ pub struct TraitDecl {
     pub const_defaults: IndexMap<TraitItemName, GlobalDeclRef>,
     /// The associated types declared in the trait.
     pub types: Vec<TraitItemName>,
+    /// The generic associated types declared in the trait, along with their own generic
+    /// parameters. Only lifetime parameters are supported for now.
+    pub generic_types: Vec<(TraitItemName, GenericParams)>,
     /// Records associated types that have a default value.
     #[charon::opaque]
     pub type_defaults: IndexMap<TraitItemName, Ty>,
@@ -289,6 +326,9 @@ pub struct TraitImpl {
     pub consts: Vec<(TraitItemName, GlobalDeclRef)>,
     /// The associated types declared in the trait.
     pub types: Vec<(TraitItemName, Ty)>,
+    /// The values of the generic associated types declared in the trait. The binder binds the
+    /// parameters of the associated type, in the same order as in the `TraitDecl`.
+    pub generic_types: Vec<(TraitItemName, Binder<Ty>)>,
     /// The `Vec` corresponds to the same `Vector` in `TraitDecl`. In the same way, this is
     /// empty after the `lift_associated_item_clauses` pass.
     #[charon::opaque]
@@ -300,39 +340,108 @@ pub struct TraitImpl {
 /// A function operand is used in function calls.
 /// It either designates a top-level function, or a place in case
 /// we are using function pointers stored in local variables.
-#[derive(Debug, Clone, Serialize, Deserialize, Drive, DriveMut)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Drive, DriveMut)]
 #[charon::variants_prefix("FnOp")]
 pub enum FnOperand {
     /// Regular case: call to a top-level function, trait method, etc.
     Regular(FnPtr),
     /// Use of a function pointer stored in a local variable
     Move(Place),
+    /// Call through a closure trait object (`dyn Fn`, `dyn FnMut` or `dyn FnOnce`, possibly behind
+    /// a reference or a `Box`): the function to call is only known at runtime. The arguments are
+    /// given here as a tuple, hence the `args` of the [Call] are empty.
+    DynClosure {
+        kind: ClosureKind,
+        /// The trait object, or a reference/box to it.
+        closure: Operand,
+        /// The tuple of arguments.
+        args: Operand,
+    },
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, Drive, DriveMut)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Drive, DriveMut)]
 pub struct Call {
     pub func: FnOperand,
     pub args: Vec<Operand>,
     pub dest: Place,
+    /// `true` if this is a call to a method whose first argument is the receiver, in which case we
+    /// print it with the method-call syntax `x.method(y)`. Computed in
+    /// [crate::transform::reconstruct_method_calls].
+    #[drive(skip)]
+    pub is_method_call: bool,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, Drive, DriveMut)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Drive, DriveMut)]
 pub enum AbortKind {
-    /// A built-in panicking function.
-    Panic(Name),
+    /// A built-in panicking function. We also record the location that rustc passes to it, unless
+    /// we are inside a `#[track_caller]` function, in which case the location comes from the
+    /// caller.
+    Panic(Name, #[drive(skip)] Option<PanicLocation>),
     /// A MIR `Unreachable` terminator corresponds to undefined behavior in the rust abstract
     /// machine.
     UndefinedBehavior,
 }
 
+/// The source location of a panic, as returned by `core::panic::Location::caller()` in the
+/// panicking function.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PanicLocation {
+    pub file_id: FileId,
+    /// The (1-based) line number.
+    pub line: usize,
+    /// The (1-based) column number, as in `Location::column`.
+    pub col: usize,
+}
+
 /// Asserts are special constructs introduced by Rust to perform dynamic
 /// checks, to detect out-of-bounds accesses or divisions by zero for
 /// instance. We eliminate the assertions in [crate::remove_dynamic_checks],
 /// then introduce other dynamic checks in [crate::reconstruct_asserts].
-#[derive(Debug, Clone, Serialize, Deserialize, Drive, DriveMut)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Drive, DriveMut)]
 #[charon::rename("Assertion")]
 pub struct Assert {
     pub cond: Operand,
     #[drive(skip)]
     pub expected: bool,
+    /// Set when the assertion comes from an `assert_eq!` or `assert_ne!`. See
+    /// [crate::reconstruct_assert_eq].
+    pub comparison: Option<AssertComparison>,
+    /// The message of an `assert!(cond, "...", args..)`. See [crate::reconstruct_asserts].
+    pub message: Option<AssertMessage>,
+}
+
+/// The comparison checked by an `assert_eq!(left, right)` or `assert_ne!(left, right)`. `cond` is
+/// then the result of comparing the two operands, and `expected` is `true` for `assert_eq!` and
+/// `false` for `assert_ne!`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Drive, DriveMut)]
+pub struct AssertComparison {
+    /// Either [BinOp::Eq] or [BinOp::Ne].
+    #[drive(skip)]
+    pub op: BinOp,
+    pub left: Operand,
+    pub right: Operand,
+}
+
+/// The message of a failed `assert!(cond, "bad: {}", x)`. The arguments are evaluated before the
+/// assertion, whether it fails or not.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Drive, DriveMut)]
+pub struct AssertMessage {
+    /// The format string, e.g. `"bad: {}"`.
+    #[drive(skip)]
+    pub format: String,
+    /// The interpolated arguments, in the order of the `{}`s of the format string.
+    pub args: Vec<Operand>,
+}
+
+/// A verification directive written as a whole-line `// charon: <directive>` comment inside a
+/// function body (the prefix is configurable with `--annotation-prefix`). The conditions are kept
+/// as source text: we don't parse them. Annotations are attached to the statement that follows
+/// them by the `recover_body_comments` pass.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[charon::variants_prefix("Annot")]
+pub enum Annotation {
+    /// `assume <cond>`: the condition can be assumed to hold at this point.
+    Assume(String),
+    /// `assert <cond>`: the condition must be proven to hold at this point.
+    Assert(String),
 }
@@ -49,6 +49,7 @@ impl Locals {
             index,
             name,
             ty: ty.clone(),
+            binding_mode: None,
         });
         Place::new(var_id, ty)
     }
@@ -77,6 +78,18 @@ impl std::ops::Index<VarId> for Locals {
     }
 }
 
+impl PanicLocation {
+    /// The location rustc reports for a panic at this span. For code that comes from a macro
+    /// expansion this is the location of the macro call.
+    pub fn from_span(span: Span) -> Self {
+        PanicLocation {
+            file_id: span.span.file_id,
+            line: span.span.beg.line,
+            col: span.span.beg.col + 1,
+        }
+    }
+}
+
 impl TraitDecl {
     pub fn methods(&self) -> impl Iterator<Item = &(TraitItemName, Binder<FunDeclRef>)> {
         self.methods.iter()
@@ -108,6 +108,22 @@ pub struct TranslatedCrate {
     /// the proper options.
     #[drive(skip)]
     pub options: crate::options::CliOpts,
+    /// The `cfg` flags that were active when compiling the crate, in the `--cfg` syntax and sorted,
+    /// e.g. `feature="std"` or `target_os="linux"`. Items gated behind inactive flags are removed
+    /// by rustc before we see them, so this tells under which configuration the crate was
+    /// extracted.
+    #[drive(skip)]
+    pub cfg: Vec<String>,
+    /// The version of the crate, as declared in its manifest. This is `None` when Charon isn't
+    /// called through cargo.
+    #[drive(skip)]
+    pub crate_version: Option<String>,
+    /// The commit hash of the rustc that compiled the crate.
+    #[drive(skip)]
+    pub rustc_commit_hash: String,
+    /// The level of MIR the bodies were extracted from.
+    #[drive(skip)]
+    pub mir_level: crate::options::MirLevel,
 
     /// All the item ids, in the order in which we encountered them
     #[drive(skip)]
@@ -132,7 +148,10 @@ pub struct TranslatedCrate {
     pub trait_decls: Vector<TraitDeclId, TraitDecl>,
     /// The translated trait declarations
     pub trait_impls: Vector<TraitImplId, TraitImpl>,
-    /// The re-ordered groups of declarations, initialized as empty.
+    /// The re-ordered groups of declarations, initialized as empty. Each group is either a single
+    /// non-recursive item or a group of mutually recursive items (a strongly-connected component
+    /// of the dependency graph); the groups are sorted so that every item comes after the items
+    /// it depends on. This is computed by [crate::transform::reorder_decls].
     #[drive(skip)]
     pub ordered_decls: Option<DeclarationsGroups>,
 }
@@ -271,9 +290,19 @@ impl<'ctx> AnyTransItemMut<'ctx> {
     }
 }
 
-impl fmt::Display for TranslatedCrate {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        let fmt: FmtCtx = self.into_fmt();
+impl TranslatedCrate {
+    /// Pretty-print the crate, eliding the statements nested more than `max_depth` blocks deep.
+    pub fn to_string_with_max_depth(&self, max_depth: Option<usize>) -> String {
+        let fmt = FmtCtx {
+            max_depth,
+            ..self.into_fmt()
+        };
+        let mut out = String::new();
+        let _ = self.fmt_decls(&fmt, &mut out);
+        out
+    }
+
+    fn fmt_decls(&self, fmt: &FmtCtx, f: &mut impl fmt::Write) -> fmt::Result {
         match &self.ordered_decls {
             None => {
                 // We do simple: types, globals, traits, functions
@@ -305,6 +334,13 @@ impl fmt::Display for TranslatedCrate {
     }
 }
 
+impl fmt::Display for TranslatedCrate {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let fmt: FmtCtx = self.into_fmt();
+        self.fmt_decls(&fmt, f)
+    }
+}
+
 impl<'tcx, 'ctx, 'a> IntoFormatter for &'a TranslatedCrate {
     type C = FmtCtx<'a>;
 
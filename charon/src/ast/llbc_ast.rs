@@ -14,7 +14,16 @@ use serde::{Deserialize, Serialize};
 
 /// A raw statement: a statement without meta data.
 #[derive(
-    Debug, Clone, EnumIsA, EnumToGetters, EnumAsGetters, Serialize, Deserialize, Drive, DriveMut,
+    Debug,
+    Clone,
+    PartialEq,
+    EnumIsA,
+    EnumToGetters,
+    EnumAsGetters,
+    Serialize,
+    Deserialize,
+    Drive,
+    DriveMut,
 )]
 pub enum RawStatement {
     /// Assigns an `Rvalue` to a `Place`. e.g. `let y = x;` could become
@@ -47,13 +56,68 @@ pub enum RawStatement {
     Continue(usize),
     /// No-op.
     Nop,
+    /// Marks that the statements that follow are inside an `unsafe` block, until the next
+    /// `ExitUnsafe`. Only emitted with `--mark-unsafe`.
+    EnterUnsafe,
+    /// Marks the end of the statements that are inside an `unsafe` block.
+    ExitUnsafe,
     Switch(Switch),
+    /// A `let` with a refutable pattern whose `else` branch diverges:
+    /// ```text
+    /// let Some(x) = opt else { return };
+    /// ```
+    /// The `else_block` runs when `scrutinee` isn't one of the `variants`, and never falls
+    /// through. After this statement, `scrutinee` is known to be one of the `variants`; the
+    /// bindings of the pattern are the assignments that follow.
+    ///
+    /// Introduced by [crate::transform::reconstruct_let_else].
+    LetElse {
+        scrutinee: Place,
+        variants: Vec<VariantId>,
+        else_block: Block,
+    },
+    /// An `if` whose condition is a chain of refutable bindings, optionally followed by a boolean
+    /// condition:
+    /// ```text
+    /// if let Some(a) = x && let Ok(b) = a.f() && b > 0 { .. } else { .. }
+    /// ```
+    /// The `lets` are checked in order: when the scrutinee of a `let` is one of its variants, its
+    /// `block` runs (it binds the variables of the pattern and computes what the next link
+    /// needs), otherwise the `else_block` runs. When all the `let`s match and `cond` (if any) is
+    /// `true`, the `then_block` runs. The drops of the locals bound by the earlier links, which
+    /// happen before jumping to the `else_block` from a later link, are left implicit.
+    ///
+    /// Introduced by [crate::transform::reconstruct_let_chains].
+    IfLetChain {
+        lets: Vec<LetChainBinding>,
+        cond: Option<Operand>,
+        then_block: Block,
+        else_block: Block,
+    },
+    /// A loop, which runs until a `Break` exits it. A loop that produces a value, like
+    /// `let x = loop { .. break v .. }`, doesn't have a result of its own: each `break v` is
+    /// translated to an assignment of `v` to the place that receives the result (`x`), followed by
+    /// a `Break`.
     Loop(Block),
+    /// A `while` loop:
+    /// ```text
+    /// while { cond_block; cond } { body }
+    /// ```
+    /// Before each iteration, `cond_block` is executed and `cond` evaluated; the loop exits when
+    /// `cond` is `false`. `cond_block` doesn't have side effects. Like `Loop`, this counts as a loop
+    /// for the `Break` and `Continue` statements in `body`.
+    ///
+    /// Introduced by [crate::transform::reconstruct_while_loops].
+    While {
+        cond_block: Block,
+        cond: Operand,
+        body: Block,
+    },
     #[drive(skip)]
     Error(String),
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, Drive, DriveMut)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Drive, DriveMut)]
 pub struct Statement {
     pub span: Span,
     pub content: RawStatement,
@@ -61,9 +125,22 @@ pub struct Statement {
     // This is filled in a late pass after all the control-flow manipulation.
     #[drive(skip)]
     pub comments_before: Vec<String>,
+    /// The `// charon:` directives that precede this statement. Filled in the same pass as
+    /// `comments_before`.
+    #[drive(skip)]
+    pub annotations: Vec<Annotation>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, Drive, DriveMut)]
+/// One `let` of a [RawStatement::IfLetChain].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Drive, DriveMut)]
+pub struct LetChainBinding {
+    pub scrutinee: Place,
+    pub variants: Vec<VariantId>,
+    /// The statements that run when the scrutinee is one of the `variants`.
+    pub block: Block,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Drive, DriveMut)]
 pub struct Block {
     pub span: Span,
     pub statements: Vec<Statement>,
@@ -72,6 +149,7 @@ pub struct Block {
 #[derive(
     Debug,
     Clone,
+    PartialEq,
     EnumIsA,
     EnumToGetters,
     EnumAsGetters,
@@ -90,8 +168,8 @@ pub enum Switch {
     /// if (move v@2) { // Represented as `If(Move(v@2), <then branch>, <else branch>)`
     /// ```
     If(Operand, Block, Block),
-    /// Gives the integer type, a map linking values to switch branches, and the
-    /// otherwise block. Note that matches over enumerations are performed by
+    /// Gives the type of the scrutinee (an integer or a `char`), a map linking values to switch
+    /// branches, and the otherwise block. Note that matches over enumerations are performed by
     /// switching over the discriminant, which is an integer.
     /// Also, we use a `Vec` to make sure the order of the switch
     /// branches is preserved.
@@ -104,7 +182,20 @@ pub enum Switch {
     ///   E::V3 => ...
     /// }
     /// ```
-    SwitchInt(Operand, IntegerTy, Vec<(Vec<ScalarValue>, Block)>, Block),
+    SwitchInt(Operand, LiteralTy, Vec<(Vec<Literal>, Block)>, Block),
+    /// Gives the type of the scrutinee (an integer or a `char`), a list of ranges with the
+    /// branch to take when the scrutinee is in the range, and the otherwise block. The ranges are
+    /// tested in order, like the arms of a `match`.
+    ///
+    /// This is introduced by [crate::transform::reconstruct_range_matches], from the comparisons
+    /// that range patterns are lowered to:
+    /// ```text
+    /// match x {
+    ///   0..=9 => ...,
+    ///   _ => ...
+    /// }
+    /// ```
+    SwitchRange(Operand, LiteralTy, Vec<(LiteralRange, Block)>, Block),
     /// A match over an ADT.
     ///
     /// The match statement is introduced in [crate::remove_read_discriminant]
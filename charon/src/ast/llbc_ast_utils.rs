@@ -15,6 +15,11 @@ pub fn combine_switch_targets_span(targets: &Switch) -> Span {
             let mbranches = meta::combine_span_iter(branches);
             meta::combine_span(&mbranches, &otherwise.span)
         }
+        Switch::SwitchRange(_, _, branches, otherwise) => {
+            let branches = branches.iter().map(|b| &b.1.span);
+            let mbranches = meta::combine_span_iter(branches);
+            meta::combine_span(&mbranches, &otherwise.span)
+        }
         Switch::Match(_, branches, otherwise) => {
             let branches = branches.iter().map(|b| &b.1.span);
             let mbranches = meta::combine_span_iter(branches);
@@ -35,9 +40,12 @@ impl Switch {
             Switch::SwitchInt(_, _, targets, otherwise) => Either::Right(Either::Left(
                 targets.iter().map(|(_, tgt)| tgt).chain([otherwise]),
             )),
-            Switch::Match(_, targets, otherwise) => Either::Right(Either::Right(
-                targets.iter().map(|(_, tgt)| tgt).chain(otherwise.as_ref()),
+            Switch::SwitchRange(_, _, targets, otherwise) => Either::Right(Either::Right(
+                Either::Left(targets.iter().map(|(_, tgt)| tgt).chain([otherwise])),
             )),
+            Switch::Match(_, targets, otherwise) => Either::Right(Either::Right(Either::Right(
+                targets.iter().map(|(_, tgt)| tgt).chain(otherwise.as_ref()),
+            ))),
         }
     }
 
@@ -48,12 +56,15 @@ impl Switch {
             Switch::SwitchInt(_, _, targets, otherwise) => Either::Right(Either::Left(
                 targets.iter_mut().map(|(_, tgt)| tgt).chain([otherwise]),
             )),
-            Switch::Match(_, targets, otherwise) => Either::Right(Either::Right(
+            Switch::SwitchRange(_, _, targets, otherwise) => Either::Right(Either::Right(
+                Either::Left(targets.iter_mut().map(|(_, tgt)| tgt).chain([otherwise])),
+            )),
+            Switch::Match(_, targets, otherwise) => Either::Right(Either::Right(Either::Right(
                 targets
                     .iter_mut()
                     .map(|(_, tgt)| tgt)
                     .chain(otherwise.as_mut()),
-            )),
+            ))),
         }
     }
 }
@@ -64,6 +75,7 @@ impl Statement {
             span,
             content,
             comments_before: vec![],
+            annotations: vec![],
         }
     }
 
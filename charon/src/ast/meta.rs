@@ -165,6 +165,9 @@ pub struct AttrInfo {
     /// API (this is called "pub-in-priv" items). With or without the `pub use`, we set `public =
     /// true`; computing item reachability is harder.
     pub public: bool,
+    /// Whether this item is marked `#[must_use]`. The message, if any, is kept in the
+    /// corresponding [Attribute::Unknown].
+    pub must_use: bool,
 }
 
 #[derive(
@@ -216,6 +219,10 @@ pub struct ItemMeta {
     /// `true` if the type decl is a local type decl, `false` if it comes from an external crate.
     #[drive(skip)]
     pub is_local: bool,
+    /// `true` if the item is declared in an `extern { .. }` block, i.e. is implemented outside of
+    /// Rust (e.g. a C function). Such items never have a body.
+    #[drive(skip)]
+    pub is_foreign: bool,
     /// Whether this item is considered opaque. For function and globals, this means we don't
     /// translate the body (the code); for ADTs, this means we don't translate the fields/variants.
     /// For traits and trait impls, this doesn't change anything. For modules, this means we don't
@@ -229,6 +236,21 @@ pub struct ItemMeta {
     /// If the item is built-in, record its internal builtin identifier.
     #[drive(skip)]
     pub lang_item: Option<String>,
+    /// If the item is registered globally as a runtime hook of the crate.
+    #[drive(skip)]
+    pub lang_item_role: Option<LangItemRole>,
+}
+
+/// The runtime hooks that a crate can register with an attribute. These matter for `no_std`
+/// crates, which have to provide them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum LangItemRole {
+    /// The function marked `#[panic_handler]`.
+    PanicHandler,
+    /// The static marked `#[global_allocator]`.
+    GlobalAllocator,
+    /// The function marked `#[alloc_error_handler]`.
+    AllocErrorHandler,
 }
 
 /// A filename.
@@ -208,6 +208,8 @@ pub enum GenericsSource {
     Item(AnyTransId),
     /// A trait method.
     Method(TraitDeclId, TraitItemName),
+    /// A generic associated type of a trait.
+    TraitType(TraitDeclId, TraitItemName),
     /// A builtin item like `Box`.
     Builtin,
     /// Some other use of generics outside the main Charon ast.
@@ -248,6 +250,9 @@ pub enum BinderKind {
     /// The parameters of a trait method. Used in the `methods` lists in trait decls and trait
     /// impls.
     TraitMethod(TraitDeclId, TraitItemName),
+    /// The parameters of a generic associated type. Used in the `generic_types` lists in trait
+    /// decls and trait impls.
+    TraitType(TraitDeclId, TraitItemName),
     /// The parameters bound in a non-trait `impl` block. Used in the `Name`s of inherent methods.
     InherentImplBlock,
     /// Some other use of a binder outside the main Charon ast.
@@ -256,7 +261,7 @@ pub enum BinderKind {
 
 /// A value of type `T` bound by generic parameters. Used in any context where we're adding generic
 /// parameters that aren't on the top-level item, e.g. `for<'a>` clauses (uses `RegionBinder` for
-/// now), trait methods, GATs (TODO).
+/// now), trait methods, GATs.
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize, Drive, DriveMut)]
 pub struct Binder<T> {
     #[charon::rename("binder_params")]
@@ -365,6 +370,63 @@ pub struct TypeDecl {
     pub generics: GenericParams,
     /// The type kind: enum, struct, or opaque.
     pub kind: TypeDeclKind,
+    /// The `drop` method of the `Drop` impl of this type, if any. Only set for local types.
+    pub drop_impl: Option<FunDeclId>,
+    /// Whether values of this type contain an `UnsafeCell` (not behind a pointer), e.g. `Cell` and
+    /// `RefCell`. Such values may be mutated through a shared reference.
+    #[drive(skip)]
+    pub interior_mut: bool,
+    /// The values allowed for the (scalar) contents of this type, if restricted with the
+    /// `rustc_layout_scalar_valid_range_start`/`_end` attributes. For instance the contents of a
+    /// `NonZeroU8` are never `0`. This is what enables niche optimizations such as making
+    /// `Option<NonZeroU8>` as big as a `u8`.
+    #[drive(skip)]
+    pub valid_range: Option<ScalarValidRange>,
+    /// For structs, the syntax used to build values of this type. The variants of enums record
+    /// their own.
+    #[drive(skip)]
+    pub ctor_kind: Option<CtorKind>,
+    /// The `#[repr(..)]` attributes of this type, if it has any.
+    #[drive(skip)]
+    pub repr: Option<ReprInfo>,
+}
+
+/// The syntax used to build the values of a struct or of an enum variant.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, EnumIsA, Serialize, Deserialize)]
+pub enum CtorKind {
+    /// Named fields: `P { x: 1, y: 2 }`.
+    Braced,
+    /// Positional fields: `P(1, 2)`. The fields have no name.
+    Tuple,
+    /// No fields: `P`.
+    Unit,
+}
+
+/// An inclusive range of raw scalar values, as given by the `rustc_layout_scalar_valid_range_*`
+/// attributes. A missing bound means there is no restriction on that side.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ScalarValidRange {
+    #[charon::rename("range_start")]
+    pub start: Option<u128>,
+    #[charon::rename("range_end")]
+    pub end: Option<u128>,
+}
+
+/// The `#[repr(..)]` attributes of an ADT. These can be combined, e.g. `#[repr(u8, align(4))]` on
+/// an enum sets both `int` and `align`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ReprInfo {
+    /// `repr(C)`
+    pub c: bool,
+    /// `repr(transparent)`
+    pub transparent: bool,
+    /// For enums, the type of the discriminant given with e.g. `repr(u8)`.
+    pub int: Option<IntegerTy>,
+    /// `repr(align(N))`: the minimum alignment of the type, in bytes.
+    pub align: Option<u64>,
+    /// `repr(packed(N))`: the maximum alignment of the fields, in bytes. `repr(packed)` is
+    /// `repr(packed(1))`.
+    pub pack: Option<u64>,
 }
 
 generate_index_type!(VariantId, "Variant");
@@ -375,6 +437,12 @@ pub enum TypeDeclKind {
     Struct(Vector<FieldId, Field>),
     Enum(Vector<VariantId, Variant>),
     Union(Vector<FieldId, Field>),
+    /// A `#[repr(simd)]` vector type, such as `core::simd::Simd<T, N>`. We don't translate its
+    /// contents; we only record the type of its lanes and how many there are.
+    Simd {
+        elem_ty: Ty,
+        lanes: ConstGeneric,
+    },
     /// An opaque type.
     ///
     /// Either a local type marked as opaque, or an external type.
@@ -398,6 +466,9 @@ pub struct Variant {
     #[drive(skip)]
     pub name: String,
     pub fields: Vector<FieldId, Field>,
+    /// The syntax used to build values of this variant.
+    #[drive(skip)]
+    pub ctor_kind: CtorKind,
     /// The discriminant used at runtime. This is used in `remove_read_discriminant` to match up
     /// `SwitchInt` targets with the corresponding `Variant`.
     pub discriminant: ScalarValue,
@@ -668,9 +739,9 @@ pub enum TyKind {
     /// the second branch will have type `Never`. Also note that `Never`
     /// can be coerced to any type.
     ///
-    /// Note that we eliminate the variables which have this type in a micro-pass.
-    /// As statements don't have types, this type disappears eventually disappears
-    /// from the AST.
+    /// Note that we eliminate the variables which have this type in a micro-pass
+    /// ([crate::transform::remove_never]). The only ones that remain are the
+    /// destinations of calls to diverging functions.
     Never,
     // We don't support floating point numbers on purpose (for now)
     /// A borrow
@@ -685,7 +756,10 @@ pub enum TyKind {
     ///   type Bar; // type associated to the trait Foo
     /// }
     /// ```
-    TraitType(TraitRef, TraitItemName),
+    ///
+    /// The `GenericArgs` are the arguments of the associated type itself, in case it is a generic
+    /// associated type (e.g. `type Item<'a>;`). They are empty otherwise.
+    TraitType(TraitRef, TraitItemName, GenericArgs),
     /// `dyn Trait`
     ///
     /// This carries an existentially quantified list of predicates, e.g. `exists<T> where T:
@@ -740,6 +814,20 @@ pub enum BuiltinTy {
     Slice,
     /// Primitive type
     Str,
+    /// `core::cell::UnsafeCell<T>`: the primitive through which interior mutability is
+    /// implemented. We keep the inner type but hide the layout, since `&UnsafeCell<T>` allows
+    /// mutating the `T` through a shared reference.
+    UnsafeCell,
+    /// `alloc::collections::VecDeque<T>`. Like the other std collections below, we keep the type
+    /// arguments (minus the allocator) but not the internals, so that backends can model them
+    /// abstractly.
+    VecDeque,
+    /// `alloc::collections::BTreeMap<K, V>`
+    BTreeMap,
+    /// `std::collections::HashMap<K, V>`. We drop the hasher parameter.
+    HashMap,
+    /// `std::collections::HashSet<T>`. We drop the hasher parameter.
+    HashSet,
 }
 
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize, Drive, DriveMut)]
@@ -782,6 +870,14 @@ pub struct FunSig {
     /// - the region variables are local to the closure
     #[drive(skip)]
     pub is_closure: bool,
+    /// `true` if the function is an associated function which takes a `self` parameter, i.e. a
+    /// method which can be called with the `x.method(..)` syntax.
+    #[drive(skip)]
+    pub has_self_param: bool,
+    /// The ABI of the function if it isn't the default Rust ABI, e.g. `"C"` for `extern "C" fn`
+    /// and for the functions of an `extern "C" { .. }` block.
+    #[drive(skip)]
+    pub abi: Option<String>,
     /// Additional information if this is the signature of a closure.
     pub closure_info: Option<ClosureInfo>,
     pub generics: GenericParams,
@@ -105,6 +105,8 @@ pub struct TypeVar {
     /// Variable name
     #[drive(skip)]
     pub name: String,
+    /// The default value of the parameter, if any (e.g. `T = u32`).
+    pub default: Option<Ty>,
 }
 
 /// A region variable in a signature or binder.
@@ -129,6 +131,8 @@ pub struct ConstGenericVar {
     pub name: String,
     /// Type of the const generic
     pub ty: LiteralTy,
+    /// The default value of the parameter, if any (e.g. `const N: usize = 4`).
+    pub default: Option<ConstGeneric>,
 }
 
 /// A trait predicate in a signature, of the form `Type: Trait<Args>`. This functions like a
@@ -260,7 +264,11 @@ where
 
 impl TypeVar {
     pub fn new(index: TypeVarId, name: String) -> TypeVar {
-        TypeVar { index, name }
+        TypeVar {
+            index,
+            name,
+            default: None,
+        }
     }
 }
 
@@ -385,8 +385,9 @@ impl GenericsSource {
     pub fn item_name(&self, translated: &TranslatedCrate, fmt_ctx: &FmtCtx) -> String {
         match self {
             GenericsSource::Item(id) => translated.item_name(*id).unwrap().fmt_with_ctx(fmt_ctx),
-            GenericsSource::Method(trait_id, method_name) => format!(
-                "{}::{method_name}",
+            GenericsSource::Method(trait_id, item_name)
+            | GenericsSource::TraitType(trait_id, item_name) => format!(
+                "{}::{item_name}",
                 translated
                     .item_name(*trait_id)
                     .unwrap()
@@ -610,6 +611,46 @@ impl Ty {
             _ => None,
         }
     }
+
+    /// Whether this type is known to have no values, e.g. `!` or an enum without variants. We
+    /// don't look through the generic arguments of ADTs, so this may return `false` for some
+    /// uninhabited types.
+    pub fn is_uninhabited(&self, krate: &TranslatedCrate) -> bool {
+        self.is_uninhabited_inner(krate, &mut HashSet::new())
+    }
+
+    fn is_uninhabited_inner(
+        &self,
+        krate: &TranslatedCrate,
+        visited: &mut HashSet<TypeDeclId>,
+    ) -> bool {
+        match self.kind() {
+            TyKind::Never => true,
+            TyKind::Adt(TypeId::Tuple, generics) => generics
+                .types
+                .iter()
+                .any(|ty| ty.is_uninhabited_inner(krate, visited)),
+            TyKind::Adt(TypeId::Adt(type_id), _) => {
+                // A recursive type that contains itself is uninhabited only if it is so
+                // regardless of the recursion.
+                if !visited.insert(*type_id) {
+                    return false;
+                }
+                let uninhabited = match krate.type_decls.get(*type_id).map(|decl| &decl.kind) {
+                    Some(TypeDeclKind::Enum(variants)) => variants
+                        .iter()
+                        .all(|variant| variant.is_uninhabited_inner(krate, visited)),
+                    Some(TypeDeclKind::Struct(fields)) => fields
+                        .iter()
+                        .any(|field| field.ty.is_uninhabited_inner(krate, visited)),
+                    _ => false,
+                };
+                visited.remove(type_id);
+                uninhabited
+            }
+            _ => false,
+        }
+    }
 }
 
 impl TyKind {
@@ -679,6 +720,19 @@ impl Field {
     }
 }
 
+impl TypeDecl {
+    /// The syntax used to build values of this struct, or of the given variant of this enum.
+    pub fn get_ctor_kind(&self, variant_id: Option<VariantId>) -> Option<CtorKind> {
+        match (&self.kind, variant_id) {
+            (TypeDeclKind::Struct(..), None) => self.ctor_kind,
+            (TypeDeclKind::Enum(variants), Some(variant_id)) => {
+                variants.get(variant_id).map(|variant| variant.ctor_kind)
+            }
+            _ => None,
+        }
+    }
+}
+
 impl Variant {
     /// The new name for this variant, as suggested by the `#[charon::rename]` and
     /// `#[charon::variants_prefix]` attributes.
@@ -696,6 +750,22 @@ impl Variant {
             .iter()
             .any(|attr| attr.is_opaque())
     }
+
+    /// Whether this variant is known to have no values, i.e. one of its fields is uninhabited (see
+    /// [Ty::is_uninhabited]). Rustc doesn't require match arms for such variants.
+    pub fn is_uninhabited(&self, krate: &TranslatedCrate) -> bool {
+        self.is_uninhabited_inner(krate, &mut HashSet::new())
+    }
+
+    fn is_uninhabited_inner(
+        &self,
+        krate: &TranslatedCrate,
+        visited: &mut HashSet<TypeDeclId>,
+    ) -> bool {
+        self.fields
+            .iter()
+            .any(|field| field.ty.is_uninhabited_inner(krate, visited))
+    }
 }
 
 impl RefKind {
@@ -35,6 +35,11 @@ pub enum RawStatement {
     Assert(Assert),
     /// Does nothing. Useful for passes.
     Nop,
+    /// Marks that the statements that follow are inside an `unsafe` block, until the next
+    /// `ExitUnsafe`. Only emitted with `--mark-unsafe`.
+    EnterUnsafe,
+    /// Marks the end of the statements that are inside an `unsafe` block.
+    ExitUnsafe,
     #[charon::opaque]
     #[drive(skip)]
     Error(String),
@@ -48,6 +53,10 @@ pub struct Statement {
     // This is filled in a late pass after all the control-flow manipulation.
     #[drive(skip)]
     pub comments_before: Vec<String>,
+    /// The `// charon:` directives that precede this statement. Filled in the same pass as
+    /// `comments_before`.
+    #[drive(skip)]
+    pub annotations: Vec<Annotation>,
 }
 
 #[derive(
@@ -66,10 +75,14 @@ pub struct Statement {
 pub enum SwitchTargets {
     /// Gives the `if` block and the `else` block
     If(BlockId, BlockId),
-    /// Gives the integer type, a map linking values to switch branches, and the
-    /// otherwise block. Note that matches over enumerations are performed by
+    /// Gives the type of the scrutinee (an integer or a `char`), a map linking values to switch
+    /// branches, and the otherwise block. Note that matches over enumerations are performed by
     /// switching over the discriminant, which is an integer.
-    SwitchInt(IntegerTy, Vec<(ScalarValue, BlockId)>, BlockId),
+    SwitchInt(LiteralTy, Vec<(Literal, BlockId)>, BlockId),
+    /// Gives the type of the scrutinee (an integer or a `char`), a list of ranges with the
+    /// branch to go to when the scrutinee is in the range, and the otherwise block. The ranges
+    /// are tested in order. This is introduced by [crate::transform::reconstruct_range_matches].
+    SwitchRange(LiteralTy, Vec<(LiteralRange, BlockId)>, BlockId),
 }
 
 /// A raw terminator: a terminator without meta data.
@@ -95,6 +108,10 @@ pub struct Terminator {
     // This is filled in a late pass after all the control-flow manipulation.
     #[drive(skip)]
     pub comments_before: Vec<String>,
+    /// The `// charon:` directives that precede this terminator. Filled in the same pass as
+    /// `comments_before`.
+    #[drive(skip)]
+    pub annotations: Vec<Annotation>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Drive, DriveMut)]
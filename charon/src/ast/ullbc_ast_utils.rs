@@ -17,6 +17,14 @@ impl SwitchTargets {
                 all_targets.push(*otherwise);
                 all_targets
             }
+            SwitchTargets::SwitchRange(_, targets, otherwise) => {
+                let mut all_targets = vec![];
+                for (_, target) in targets {
+                    all_targets.push(*target);
+                }
+                all_targets.push(*otherwise);
+                all_targets
+            }
         }
     }
 }
@@ -27,6 +35,7 @@ impl Statement {
             span,
             content,
             comments_before: vec![],
+            annotations: vec![],
         }
     }
 }
@@ -37,6 +46,7 @@ impl Terminator {
             span,
             content,
             comments_before: vec![],
+            annotations: vec![],
         }
     }
 }
@@ -41,12 +41,30 @@ pub enum Literal {
     Bool(bool),
     #[drive(skip)]
     Char(char),
+    /// A byte string, e.g. `b"hello"`. Its type is a reference to an array or slice of `u8`.
     #[drive(skip)]
     ByteStr(Vec<u8>),
+    /// A string, e.g. `"hello"`. Its type is `&str`.
     #[drive(skip)]
     Str(String),
 }
 
+/// A range of integers or characters, as matched by a range pattern like `0..=9`, `'a'..'z'` or
+/// `10..`.
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize, Drive, DriveMut, Hash)]
+pub struct LiteralRange {
+    /// The lower bound, which is always included. `None` if the range is open on the left.
+    #[charon::rename("range_start")]
+    pub start: Option<Literal>,
+    /// The upper bound. `None` if the range is open on the right.
+    #[charon::rename("range_end")]
+    pub end: Option<Literal>,
+    /// Whether the upper bound is included (`..=`) or excluded (`..`).
+    #[drive(skip)]
+    #[charon::rename("range_end_inclusive")]
+    pub end_inclusive: bool,
+}
+
 /// A scalar value.
 // We encode it as `{ value: ??; int_ty: IntegerTy; }` in json and on the ocaml side. We therefore
 // use a custom (de)serializer.
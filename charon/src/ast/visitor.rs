@@ -41,11 +41,12 @@ use indexmap::IndexMap;
     visitor(drive_mut(&mut VisitAstMut)),
     // Types that we unconditionally explore.
     drive(
-        AbortKind, Assert, BinOp, Body, BorrowKind, BuiltinFunId, BuiltinIndexOp, BuiltinTy, Call,
+        AbortKind, Assert, AssertComparison, AssertMessage, BinOp, Body, BorrowKind, BuiltinFunId, BuiltinIndexOp, BuiltinPtrOffsetOp, BuiltinTy, Call,
         CastKind, ClosureInfo, ClosureKind, ConstantExpr, ConstGenericVar, ConstGenericVarId,
         Disambiguator, ExistentialPredicate, Field, FieldId, FieldProjKind, FloatTy, FloatValue,
-        FnOperand, FunId, FunIdOrTraitMethodRef, FunSig, ImplElem, IntegerTy, Literal, LiteralTy,
-        llbc_ast::Block, llbc_ast::ExprBody, llbc_ast::RawStatement, llbc_ast::Switch,
+        FnOperand, FunId, FunIdOrTraitMethodRef, FunSig, ImplElem, IntegerTy, Literal, LiteralRange, LiteralTy,
+        llbc_ast::Block, llbc_ast::ExprBody, llbc_ast::LetChainBinding, llbc_ast::RawStatement,
+        llbc_ast::Switch,
         Locals, Name, NullOp, Opaque, Operand, PathElem, Place, PlaceKind, ProjectionElem, RawConstantExpr,
         RefKind, RegionId, RegionVar, Rvalue, ScalarValue, TraitClauseId, TraitItemName,
         TranslatedCrate, TypeDeclKind, TypeId, TypeVar, TypeVarId,
@@ -146,8 +147,8 @@ impl<K: Any, T: AstVisitable> AstVisitable for IndexMap<K, T> {
     ),
     // Types that we unconditionally explore.
     drive(
-        Assert, PlaceKind,
-        llbc_ast::ExprBody, llbc_ast::RawStatement, llbc_ast::Switch,
+        Assert, AssertComparison, AssertMessage, PlaceKind,
+        llbc_ast::ExprBody, llbc_ast::LetChainBinding, llbc_ast::RawStatement, llbc_ast::Switch,
         ullbc_ast::BlockData, ullbc_ast::ExprBody, ullbc_ast::RawStatement,
         ullbc_ast::RawTerminator, ullbc_ast::SwitchTargets,
         Body, Opaque,
@@ -246,3 +247,93 @@ where
         Continue(())
     }
 }
+
+/// The syntactic position in which a type appears. See [`TranslatedCrate::visit_types`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TyContext {
+    /// The type of a struct, enum variant or union field.
+    Field,
+    /// The right-hand side of a type alias.
+    Alias,
+    /// One of the inputs of a function signature.
+    Param,
+    /// The output of a function signature.
+    Return,
+    /// The type of a local variable in a function body. This includes the return place and the
+    /// locals that hold the arguments.
+    Local,
+    /// The type of a global.
+    Global,
+    /// Any other position: generic parameters and arguments, trait clauses, item names, types
+    /// mentioned in statements, etc.
+    Other,
+}
+
+impl TranslatedCrate {
+    /// Call `f` on every type that appears in the crate, along with the syntactic position in
+    /// which it appears. Types nested inside another type (e.g. `u32` inside `&u32`) are visited
+    /// too, in pre-order, with the context of the outermost type.
+    pub fn visit_types(&self, mut f: impl FnMut(&Ty, TyContext)) {
+        fn visit_in<T: AstVisitable>(
+            x: &T,
+            context: TyContext,
+            f: &mut impl FnMut(&Ty, TyContext),
+        ) {
+            x.dyn_visit(|ty: &Ty| f(ty, context));
+        }
+        let f = &mut f;
+
+        for item in self.all_items() {
+            match item {
+                // Everything in trait declarations and implementations is in `Other` position.
+                AnyTransItem::TraitDecl(decl) => visit_in(decl, TyContext::Other, f),
+                AnyTransItem::TraitImpl(decl) => visit_in(decl, TyContext::Other, f),
+                AnyTransItem::Type(decl) => {
+                    visit_in(&decl.item_meta, TyContext::Other, f);
+                    visit_in(&decl.generics, TyContext::Other, f);
+                    match &decl.kind {
+                        TypeDeclKind::Struct(fields) | TypeDeclKind::Union(fields) => {
+                            visit_in(fields, TyContext::Field, f)
+                        }
+                        TypeDeclKind::Enum(variants) => {
+                            for variant in variants {
+                                visit_in(&variant.fields, TyContext::Field, f)
+                            }
+                        }
+                        TypeDeclKind::Simd { elem_ty, .. } => {
+                            visit_in(elem_ty, TyContext::Field, f)
+                        }
+                        TypeDeclKind::Alias(ty) => visit_in(ty, TyContext::Alias, f),
+                        TypeDeclKind::Opaque | TypeDeclKind::Error(_) => {}
+                    }
+                }
+                AnyTransItem::Fun(decl) => {
+                    let sig = &decl.signature;
+                    visit_in(&decl.item_meta, TyContext::Other, f);
+                    visit_in(&sig.generics, TyContext::Other, f);
+                    visit_in(&sig.closure_info, TyContext::Other, f);
+                    visit_in(&sig.inputs, TyContext::Param, f);
+                    visit_in(&sig.output, TyContext::Return, f);
+                    visit_in(&decl.kind, TyContext::Other, f);
+                    match &decl.body {
+                        Ok(Body::Unstructured(body)) => {
+                            visit_in(&body.locals, TyContext::Local, f);
+                            visit_in(&body.body, TyContext::Other, f);
+                        }
+                        Ok(Body::Structured(body)) => {
+                            visit_in(&body.locals, TyContext::Local, f);
+                            visit_in(&body.body, TyContext::Other, f);
+                        }
+                        Err(Opaque) => {}
+                    }
+                }
+                AnyTransItem::Global(decl) => {
+                    visit_in(&decl.item_meta, TyContext::Other, f);
+                    visit_in(&decl.generics, TyContext::Other, f);
+                    visit_in(&decl.ty, TyContext::Global, f);
+                    visit_in(&decl.kind, TyContext::Other, f);
+                }
+            }
+        }
+    }
+}
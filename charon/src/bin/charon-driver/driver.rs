@@ -8,9 +8,10 @@ use rustc_interface::Config;
 use rustc_interface::{interface::Compiler, Queries};
 use rustc_middle::util::Providers;
 use rustc_session::config::{OutputType, OutputTypes, Polonius};
+use std::collections::HashSet;
 use std::ops::Deref;
 use std::sync::atomic::{AtomicBool, Ordering};
-use std::{env, fmt};
+use std::{env, fmt, mem};
 
 /// Helper that runs the compiler and catches its fatal errors.
 fn run_compiler_with_callbacks(
@@ -180,10 +181,35 @@ pub fn run_rustc_driver(options: &CliOpts) -> Result<Option<TransformCtx>, Charo
         for extra_flag in options.rustc_args.iter().cloned() {
             compiler_args.push(extra_flag);
         }
+        if let Some(edition) = &options.edition {
+            // Override the edition chosen by cargo, if any.
+            remove_arg(&mut compiler_args, "--edition");
+            compiler_args.push(format!("--edition={edition}"));
+        }
+        if let Some(crate_name) = &options.crate_name {
+            // Override the name chosen by cargo or derived from the input file name.
+            remove_arg(&mut compiler_args, "--crate-name");
+            compiler_args.push(format!("--crate-name={crate_name}"));
+        }
+        add_extern_args(&mut compiler_args, options);
+
+        // Open the diagnostics file before doing any work, so that a bad path is reported as a
+        // plain error.
+        let error_output = match &options.error_output {
+            Some(path) => match std::fs::File::create(path) {
+                Ok(file) => Some(file),
+                Err(e) => {
+                    error!("Could not create `{}`: {e}", path.display());
+                    return Err(CharonFailure::ErrorOutput);
+                }
+            },
+            None => None,
+        };
 
         // Call the Rust compiler with our custom callback.
         let mut callback = CharonCallbacks {
             options,
+            error_output,
             transform_ctx: None,
         };
         run_compiler_with_callbacks(compiler_args, &mut callback)?;
@@ -197,6 +223,8 @@ pub fn run_rustc_driver(options: &CliOpts) -> Result<Option<TransformCtx>, Charo
 /// The callbacks for Charon
 pub struct CharonCallbacks<'a> {
     options: &'a CliOpts,
+    /// The file to write the diagnostics to, if `--error-output` was passed.
+    error_output: Option<std::fs::File>,
     /// This is to be filled during the extraction; it contains the translated crate.
     transform_ctx: Option<TransformCtx>,
 }
@@ -219,7 +247,12 @@ impl<'a> Callbacks for CharonCallbacks<'a> {
             .swap(&(def_id_debug as fn(_, &mut fmt::Formatter<'_>) -> _));
 
         let transform_ctx = queries.global_ctxt().unwrap().get_mut().enter(|tcx| {
-            translate_crate_to_ullbc::translate(&self.options, tcx, compiler.sess.sysroot.clone())
+            translate_crate_to_ullbc::translate(
+                &self.options,
+                tcx,
+                compiler.sess.sysroot.clone(),
+                self.error_output.take(),
+            )
         });
         self.transform_ctx = Some(transform_ctx);
         Compilation::Continue
@@ -244,6 +277,71 @@ impl<'a> Callbacks for RunCompilerNormallyCallbacks<'a> {
     }
 }
 
+/// Add the user-provided `--extern` and `-L` flags to the compiler arguments. A user-provided
+/// `--extern` replaces the existing `--extern` flags for the same crate (e.g. the ones computed by
+/// cargo).
+fn add_extern_args(compiler_args: &mut Vec<String>, options: &CliOpts) {
+    // The value of `--extern` is `[modifiers:]name[=path]`.
+    fn extern_crate_name(value: &str) -> &str {
+        let name = value.split('=').next().unwrap();
+        name.rsplit(':').next().unwrap()
+    }
+    let overridden: HashSet<&str> = options
+        .extern_args
+        .iter()
+        .map(|value| extern_crate_name(value))
+        .collect();
+
+    let mut args = mem::take(compiler_args).into_iter();
+    while let Some(arg) = args.next() {
+        if arg == "--extern" {
+            let value = args.next();
+            if let Some(value) = &value
+                && overridden.contains(extern_crate_name(value))
+            {
+                continue;
+            }
+            compiler_args.push(arg);
+            compiler_args.extend(value);
+        } else if let Some(value) = arg.strip_prefix("--extern=")
+            && overridden.contains(extern_crate_name(value))
+        {
+            continue;
+        } else {
+            compiler_args.push(arg);
+        }
+    }
+
+    for value in &options.extern_args {
+        compiler_args.push("--extern".to_owned());
+        compiler_args.push(value.clone());
+    }
+    for path in &options.library_paths {
+        compiler_args.push("-L".to_owned());
+        compiler_args.push(path.clone());
+    }
+}
+
+/// Removes all the occurrences of the given command-line option, along with their value. The
+/// options are assumed to be of the form `--arg=value` or `--arg value`.
+fn remove_arg(args: &mut Vec<String>, needle: &str) {
+    let mut i = 0;
+    while i < args.len() {
+        if args[i] == needle {
+            // `--arg value` form
+            args.drain(i..(i + 2).min(args.len()));
+        } else if args[i]
+            .split_once('=')
+            .is_some_and(|(arg, _)| arg == needle)
+        {
+            // `--arg=value` form
+            args.remove(i);
+        } else {
+            i += 1;
+        }
+    }
+}
+
 /// Returns the values of the command-line options that match `find_arg`. The options are built-in
 /// to be of the form `--arg=value` or `--arg value`.
 fn arg_values<'a, T: Deref<Target = str>>(
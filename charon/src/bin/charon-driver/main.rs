@@ -30,14 +30,19 @@ mod driver;
 mod translate;
 
 use charon_lib::{
-    export, logger,
+    export,
+    formatter::IntoFormatter,
+    logger,
     options::{self, CliOpts},
+    pretty::FmtWithCtx,
     transform::{
-        Pass, PrintCtxPass, FINAL_CLEANUP_PASSES, INITIAL_CLEANUP_PASSES, LLBC_PASSES,
-        SHARED_FINALIZING_PASSES, ULLBC_PASSES,
+        apply_models, emit_dependencies, emit_deps_graphviz, emit_opaque_manifest,
+        emit_symbol_table, emit_type_sizes, save_ullbc, validate, Pass, PrintCtxPass, TransformCtx,
+        FINAL_CLEANUP_PASSES, INITIAL_CLEANUP_PASSES, LLBC_PASSES, SHARED_FINALIZING_PASSES,
+        ULLBC_PASSES,
     },
 };
-use std::{env, fmt, panic};
+use std::{env, fmt, panic, path::PathBuf};
 
 pub enum CharonFailure {
     /// The usize is the number of errors.
@@ -45,6 +50,8 @@ pub enum CharonFailure {
     RustcError,
     Panic,
     Serialize,
+    /// We couldn't create the file passed to `--error-output`.
+    ErrorOutput,
 }
 
 impl fmt::Display for CharonFailure {
@@ -57,6 +64,7 @@ impl fmt::Display for CharonFailure {
             )?,
             CharonFailure::Panic => write!(f, "Compilation panicked")?,
             CharonFailure::Serialize => write!(f, "Could not serialize output file")?,
+            CharonFailure::ErrorOutput => write!(f, "Could not create the diagnostics file")?,
         }
         Ok(())
     }
@@ -72,6 +80,10 @@ pub fn transformation_passes(options: &CliOpts) -> Vec<Pass> {
     )));
 
     passes.extend(INITIAL_CLEANUP_PASSES);
+    if let Some(path) = &options.models {
+        // The models are in ULLBC, so they go through the same cleanups as the other bodies.
+        passes.push(Pass::NonBody(apply_models::Transform::new(path.clone())));
+    }
     passes.extend(ULLBC_PASSES);
 
     if !options.ullbc {
@@ -83,6 +95,9 @@ pub fn transformation_passes(options: &CliOpts) -> Vec<Pass> {
     }
 
     if !options.ullbc {
+        if options.emit_ullbc {
+            passes.push(Pass::NonBody(&save_ullbc::Transform));
+        }
         passes.extend(LLBC_PASSES);
     }
     passes.extend(SHARED_FINALIZING_PASSES);
@@ -103,6 +118,34 @@ pub fn transformation_passes(options: &CliOpts) -> Vec<Pass> {
     // Run the final passes after pretty-printing so that we get some output even if check_generics
     // fails.
     passes.extend(FINAL_CLEANUP_PASSES);
+
+    if options.validate {
+        passes.push(Pass::NonBody(&validate::Check));
+    }
+
+    if let Some(path) = &options.emit_opaque_manifest {
+        passes.push(Pass::NonBody(emit_opaque_manifest::Transform::new(
+            path.clone(),
+        )));
+    }
+    if let Some(path) = &options.emit_deps_graphviz {
+        passes.push(Pass::NonBody(emit_deps_graphviz::Transform::new(
+            path.clone(),
+        )));
+    }
+    if let Some(path) = &options.emit_symbol_table {
+        passes.push(Pass::NonBody(emit_symbol_table::Transform::new(
+            path.clone(),
+        )));
+    }
+    if let Some(path) = &options.emit_type_sizes {
+        passes.push(Pass::NonBody(emit_type_sizes::Transform::new(path.clone())));
+    }
+    if let Some(path) = &options.emit_dependencies {
+        passes.push(Pass::NonBody(emit_dependencies::Transform::new(
+            path.clone(),
+        )));
+    }
     passes
 }
 
@@ -113,25 +156,92 @@ fn run_charon(options: CliOpts) -> Result<usize, CharonFailure> {
         // We didn't run charon.
         return Ok(0);
     };
+    check_max_failed_items(&options, &ctx)?;
 
     // The bulk of the translation is done, we no longer need to interact with rustc internals. We
     // run several passes that simplify the items and cleanup the bodies.
     for pass in transformation_passes(&options) {
         trace!("# Starting pass {}", pass.name());
         pass.run(&mut ctx);
+        check_max_failed_items(&options, &ctx)?;
     }
 
     let error_count = ctx.errors.borrow().error_count;
+    let warning_count = ctx.errors.borrow().warning_count;
+    let hard_error_count = ctx.errors.borrow().hard_error_count;
+
+    if options.check {
+        // Only the items that failed to translate make the check fail; plain warnings don't.
+        if error_count != 0 {
+            report_failed_items(&ctx);
+            return Err(CharonFailure::CharonError(error_count));
+        }
+    } else if !options.no_serialize {
+        // # Final step: generate the files.
+        let ullbc_data = export::CrateData::take_ullbc_snapshot(&mut ctx);
+        let extension = if options.ullbc { "ullbc" } else { "llbc" };
+        let dest_file = options.dest_file.clone();
+        let mut crate_data = export::CrateData::new(ctx);
+        if options.group_by_module {
+            crate_data.group_by_module();
+        }
+        serialize(&options, crate_data, dest_file, extension)?;
+        if let Some(mut ullbc_data) = ullbc_data {
+            if options.group_by_module {
+                ullbc_data.group_by_module();
+            }
+            // The ullbc file goes next to the llbc one.
+            let dest_file = options
+                .dest_file
+                .as_ref()
+                .map(|f| f.with_extension("ullbc"));
+            serialize(&options, ullbc_data, dest_file, "ullbc")?;
+        }
+    }
+
+    if hard_error_count != 0 || (options.error_on_warnings && error_count + warning_count != 0) {
+        return Err(CharonFailure::CharonError(error_count + warning_count));
+    }
+
+    Ok(error_count + warning_count)
+}
 
-    // # Final step: generate the files.
-    if !options.no_serialize {
-        let crate_data = export::CrateData::new(ctx);
-        let dest_file = match options.dest_file.clone() {
+/// Stop if `--max-errors` items failed to translate.
+fn check_max_failed_items(options: &CliOpts, ctx: &TransformCtx) -> Result<(), CharonFailure> {
+    if !ctx.errors.borrow().reached_max_failed_items {
+        return Ok(());
+    }
+    eprintln!(
+        "error: aborting after {} item(s) failed to translate, as requested by `--max-errors`",
+        options.max_errors.unwrap_or_default()
+    );
+    report_failed_items(ctx);
+    Err(CharonFailure::CharonError(ctx.errors.borrow().error_count))
+}
+
+/// Write the crate to `dest_file`, or to the output location selected in the options with the
+/// given extension.
+fn serialize(
+    options: &CliOpts,
+    crate_data: export::CrateData,
+    dest_file: Option<PathBuf>,
+    extension: &str,
+) -> Result<(), CharonFailure> {
+    if options.output_stdout {
+        crate_data
+            .serialize_to_stdout()
+            .map_err(|()| CharonFailure::Serialize)
+    } else if let Some(dest_dir) = &options.split_by_kind {
+        trace!("Target directory: {:?}", dest_dir);
+        crate_data
+            .serialize_split_to_dir(dest_dir, extension)
+            .map_err(|()| CharonFailure::Serialize)
+    } else {
+        let dest_file = match dest_file {
             Some(f) => f,
             None => {
                 let mut target_filename = options.dest_dir.clone().unwrap_or_default();
                 let crate_name = &crate_data.translated.crate_name;
-                let extension = if options.ullbc { "ullbc" } else { "llbc" };
                 target_filename.push(format!("{crate_name}.{extension}"));
                 target_filename
             }
@@ -139,14 +249,31 @@ fn run_charon(options: CliOpts) -> Result<usize, CharonFailure> {
         trace!("Target file: {:?}", dest_file);
         crate_data
             .serialize_to_file(&dest_file)
-            .map_err(|()| CharonFailure::Serialize)?;
+            .map_err(|()| CharonFailure::Serialize)
     }
+}
 
-    if options.error_on_warnings && error_count != 0 {
-        return Err(CharonFailure::CharonError(error_count));
+/// List the items for which translation failed. Used in `--check` mode and when reaching
+/// `--max-errors`.
+fn report_failed_items(ctx: &TransformCtx) {
+    let errors = ctx.errors.borrow();
+    let mut failed: Vec<String> = errors
+        .decls_with_errors
+        .iter()
+        .map(|id| match ctx.translated.item_names.get(id) {
+            Some(name) => name.fmt_with_ctx(&ctx.into_fmt()),
+            None => format!("{id:?}"),
+        })
+        .collect();
+    failed.sort();
+    eprintln!(
+        "error: failed to translate {} item(s) ({} error(s)):",
+        failed.len(),
+        errors.error_count
+    );
+    for name in failed {
+        eprintln!("  - {name}");
     }
-
-    Ok(error_count)
 }
 
 fn main() {
@@ -176,7 +303,9 @@ fn main() {
         Err(err) => {
             log::error!("{err}");
             let exit_code = match err {
-                CharonFailure::CharonError(_) | CharonFailure::Serialize => 1,
+                CharonFailure::CharonError(_)
+                | CharonFailure::Serialize
+                | CharonFailure::ErrorOutput => 1,
                 CharonFailure::RustcError => 2,
                 // This is a real panic, exit with the standard rust panic error code.
                 CharonFailure::Panic => 101,
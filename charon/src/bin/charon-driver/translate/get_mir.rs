@@ -5,7 +5,7 @@ use std::rc::Rc;
 use hax_frontend_exporter as hax;
 use hax_frontend_exporter::{HasMirSetter, HasOwnerIdSetter};
 use rustc_hir::def_id::DefId;
-use rustc_middle::mir::Body;
+use rustc_middle::mir::{BindingForm, Body, ClearCrossCrate, LocalInfo, VarBindingForm};
 use rustc_middle::ty::TyCtxt;
 
 use charon_lib::ast::*;
@@ -24,11 +24,13 @@ pub fn boxes_are_desugared(level: MirLevel) -> bool {
 }
 
 impl TranslateCtx<'_> {
+    /// Get the MIR body of an item, along with the binding modes of its locals (see
+    /// [binding_modes]).
     pub fn get_mir(
         &mut self,
         def_id: DefId,
         span: Span,
-    ) -> Result<Option<hax::MirBody<()>>, Error> {
+    ) -> Result<Option<(hax::MirBody<()>, Vec<Option<BindingMode>>)>, Error> {
         let tcx = self.tcx;
         let mir_level = self.options.mir_level;
         Ok(match get_mir_for_def_id_and_level(tcx, def_id, mir_level) {
@@ -41,8 +43,9 @@ impl TranslateCtx<'_> {
                     .with_owner_id(def_id)
                     .with_mir(Rc::new(body.clone()));
                 // Translate
+                let binding_modes = binding_modes(&body);
                 let body: hax::MirBody<()> = self.catch_sinto(&state, span, &body)?;
-                Some(body)
+                Some((body, binding_modes))
             }
             None => None,
         })
@@ -98,3 +101,50 @@ fn get_mir_for_def_id_and_level(
     };
     Some(body)
 }
+
+/// The binding modes of the locals of a body, in order. This is `None` for the locals that don't
+/// correspond to a user variable, and for all the locals of a body whose local information was
+/// cleared (e.g. because it comes from another crate).
+fn binding_modes(body: &Body<'_>) -> Vec<Option<BindingMode>> {
+    body.local_decls
+        .iter()
+        .map(|decl| match &decl.local_info {
+            ClearCrossCrate::Set(box LocalInfo::User(BindingForm::Var(VarBindingForm {
+                binding_mode: rustc_ast::BindingMode(by_ref, _),
+                ..
+            }))) => Some(match by_ref {
+                rustc_ast::ByRef::No => BindingMode::ByMove,
+                rustc_ast::ByRef::Yes(rustc_ast::Mutability::Not) => BindingMode::ByRef,
+                rustc_ast::ByRef::Yes(rustc_ast::Mutability::Mut) => BindingMode::ByMutRef,
+            }),
+            _ => None,
+        })
+        .collect()
+}
+
+/// The spans of the user-written `unsafe` blocks in the body of a local item. MIR doesn't record
+/// which source scopes are unsafe anymore, so we recover the blocks from the HIR.
+pub fn unsafe_block_spans(tcx: TyCtxt<'_>, def_id: DefId) -> Vec<rustc_span::Span> {
+    use rustc_hir::intravisit::{self, Visitor};
+    struct UnsafeBlocks(Vec<rustc_span::Span>);
+    impl<'tcx> Visitor<'tcx> for UnsafeBlocks {
+        fn visit_block(&mut self, block: &'tcx rustc_hir::Block<'tcx>) {
+            if let rustc_hir::BlockCheckMode::UnsafeBlock(rustc_hir::UnsafeSource::UserProvided) =
+                block.rules
+            {
+                self.0.push(block.span);
+            }
+            intravisit::walk_block(self, block)
+        }
+    }
+
+    let Some(local_def_id) = def_id.as_local() else {
+        return Vec::new();
+    };
+    let Some(body) = tcx.hir().maybe_body_owned_by(local_def_id) else {
+        return Vec::new();
+    };
+    let mut visitor = UnsafeBlocks(Vec::new());
+    visitor.visit_body(body);
+    visitor.0
+}
@@ -2,6 +2,8 @@
 use super::translate_ctx::*;
 use charon_lib::ast::*;
 use hax_frontend_exporter as hax;
+use rustc_hir::def_id::DefId;
+use rustc_middle::ty;
 
 impl<'tcx, 'ctx> BodyTransCtx<'tcx, 'ctx> {
     fn translate_constant_literal_to_raw_constant_expr(
@@ -72,6 +74,17 @@ impl<'tcx, 'ctx> BodyTransCtx<'tcx, 'ctx> {
     ) -> Result<ConstantExpr, Error> {
         use hax::ConstantExprKind;
         let ty = self.translate_ty(span, &v.ty)?;
+        // A closure that captures nothing is a zero-sized constant, and its type translates to a
+        // plain arrow type. We refer to the function that implements it instead, so that callers
+        // of higher-order functions know which closure they're passed.
+        if let hax::TyKind::Closure(def_id, closure_args) = v.ty.kind() {
+            let (fun_id, generics) = self.translate_closure_ref(span, def_id, closure_args)?;
+            let value = RawConstantExpr::FnPtr(FnPtr {
+                func: FunIdOrTraitMethodRef::Fun(FunId::Regular(fun_id)),
+                generics,
+            });
+            return Ok(ConstantExpr { value, ty });
+        }
         let value = match v.contents.as_ref() {
             ConstantExprKind::Literal(lit) => {
                 self.translate_constant_literal_to_raw_constant_expr(span, lit)?
@@ -192,9 +205,40 @@ impl<'tcx, 'ctx> BodyTransCtx<'tcx, 'ctx> {
             }
         };
 
+        // Integer literals carry their type: it must be the type rustc inferred for the constant,
+        // including for unsuffixed literals.
+        if let RawConstantExpr::Literal(Literal::Scalar(scalar)) = &value
+            && let TyKind::Literal(LiteralTy::Integer(int_ty)) = ty.kind()
+        {
+            error_assert!(
+                self,
+                span,
+                scalar.get_integer_ty() == *int_ty,
+                "integer literal {scalar} doesn't have the type of its constant, `{int_ty:?}`"
+            );
+        }
+
         Ok(ConstantExpr { value, ty })
     }
 
+    /// Evaluate the `usize` associated constant with the given name that the impl defines. Returns
+    /// `None` if the impl doesn't define it (e.g. because it uses the default value of the trait)
+    /// or if it depends on generic parameters.
+    fn eval_impl_usize_const(&self, impl_id: &hax::DefId, name: &str) -> Option<u64> {
+        let tcx = self.t_ctx.tcx;
+        let impl_id: DefId = impl_id.into();
+        let item = tcx
+            .associated_items(impl_id)
+            .in_definition_order()
+            .find(|item| item.kind == ty::AssocKind::Const && item.name.as_str() == name)?;
+        if tcx.generics_of(item.def_id).count() != 0 {
+            return None;
+        }
+        tcx.const_eval_poly(item.def_id)
+            .ok()?
+            .try_to_target_usize(tcx)
+    }
+
     /// Remark: [hax::ConstantExpr] contains span information, but it is often
     /// the default span (i.e., it is useless), hence the additional span argument.
     pub(crate) fn translate_constant_expr_to_const_generic(
@@ -202,6 +246,16 @@ impl<'tcx, 'ctx> BodyTransCtx<'tcx, 'ctx> {
         span: Span,
         v: &hax::ConstantExpr,
     ) -> Result<ConstGeneric, Error> {
+        // An associated constant of a concrete impl, e.g. the `Self::LEN` of `[u8; Self::LEN]` in
+        // an impl that sets `LEN`: we evaluate it to get a concrete array length.
+        if let hax::TyKind::Uint(hax::UintTy::Usize) = v.ty.kind()
+            && let hax::ConstantExprKind::TraitConst { impl_expr, name } = v.contents.as_ref()
+            && let hax::ImplExprAtom::Concrete { id: impl_id, .. } = &impl_expr.r#impl
+            && let Some(len) = self.eval_impl_usize_const(impl_id, name)
+        {
+            let len = Literal::Scalar(ScalarValue::Usize(len));
+            return Ok(ConstGeneric::Value(len));
+        }
         // Remark: we can't user globals as constant generics (meaning
         // the user provided type annotation should always be none).
         let value = self
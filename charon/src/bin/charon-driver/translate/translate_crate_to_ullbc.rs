@@ -1,11 +1,15 @@
 use super::translate_ctx::*;
 use charon_lib::ast::*;
 use charon_lib::options::{CliOpts, TranslateOptions};
+use charon_lib::transform::emit_type_sizes::TypeLayout;
 use charon_lib::transform::TransformCtx;
 use hax_frontend_exporter::{self as hax, SInto};
+use rustc_hir::def::DefKind;
 use rustc_hir::def_id::DefId;
-use rustc_middle::ty::TyCtxt;
+use rustc_middle::ty::{ParamEnv, TyCtxt, TypeVisitableExt};
+use rustc_span::BytePos;
 use std::cell::RefCell;
+use std::collections::{HashMap, HashSet};
 use std::path::PathBuf;
 
 impl<'tcx, 'ctx> TranslateCtx<'tcx> {
@@ -222,11 +226,59 @@ impl<'tcx, 'ctx> TranslateCtx<'tcx> {
     }
 }
 
+/// `#[global_allocator]` and `#[alloc_error_handler]` are builtin macros: they leave the item
+/// unchanged and add next to it a `const _: () = { ... };` containing the shims that the runtime
+/// calls. That constant has the same source range as the item, in the context of the macro
+/// expansion, which is how we find the registered items.
+fn find_registered_items(tcx: TyCtxt<'_>) -> HashMap<(BytePos, BytePos), LangItemRole> {
+    use rustc_span::hygiene::{ExpnKind, MacroKind};
+    use rustc_span::sym;
+    tcx.hir_crate_items(())
+        .definitions()
+        .filter(|&def_id| matches!(tcx.def_kind(def_id), DefKind::Const))
+        .filter_map(|def_id| {
+            let span = tcx.source_span(def_id);
+            let role = match span.ctxt().outer_expn_data().kind {
+                ExpnKind::Macro(MacroKind::Attr, name) if name == sym::global_allocator => {
+                    LangItemRole::GlobalAllocator
+                }
+                ExpnKind::Macro(MacroKind::Attr, name) if name == sym::alloc_error_handler => {
+                    LangItemRole::AllocErrorHandler
+                }
+                _ => return None,
+            };
+            Some(((span.lo(), span.hi()), role))
+        })
+        .collect()
+}
+
+/// The size and alignment of the given struct, enum or union, if rustc can compute them. This is
+/// not the case for the types that have type or const parameters.
+fn type_layout(tcx: TyCtxt<'_>, def_id: DefId) -> Option<TypeLayout> {
+    if !matches!(
+        tcx.def_kind(def_id),
+        DefKind::Struct | DefKind::Enum | DefKind::Union
+    ) {
+        return None;
+    }
+    // The lifetime parameters don't matter for the layout.
+    let ty = tcx.erase_regions(tcx.type_of(def_id).instantiate_identity());
+    if ty.has_param() {
+        return None;
+    }
+    let layout = tcx.layout_of(ParamEnv::reveal_all().and(ty)).ok()?;
+    Some(TypeLayout {
+        size: layout.size.bytes(),
+        align: layout.align.abi.bytes(),
+    })
+}
+
 #[tracing::instrument(skip(tcx))]
 pub fn translate<'tcx, 'ctx>(
     options: &CliOpts,
     tcx: TyCtxt<'tcx>,
     sysroot: PathBuf,
+    error_output: Option<std::fs::File>,
 ) -> TransformCtx {
     let hax_state = hax::state::State::new(
         tcx,
@@ -243,17 +295,57 @@ pub fn translate<'tcx, 'ctx>(
     let crate_name = crate_def_id.krate.clone();
     trace!("# Crate: {}", crate_name);
 
-    let mut error_ctx = ErrorCtx::new(!options.abort_on_error, options.error_on_warnings);
+    let error_output = error_output
+        .map(|file| -> Box<dyn std::io::Write> { Box::new(std::io::BufWriter::new(file)) });
+    let mut error_ctx = ErrorCtx::new(
+        !options.abort_on_error,
+        options.error_on_warnings,
+        options.error_format,
+        error_output,
+        options.max_errors,
+    );
     let translate_options = TranslateOptions::new(&mut error_ctx, options);
+    let mir_level = translate_options.mir_level;
+
+    // Record the configuration under which the crate is compiled.
+    let mut cfg: Vec<String> = tcx
+        .sess
+        .psess
+        .config
+        .iter()
+        .map(|(name, value)| match value {
+            Some(value) => format!("{name}={:?}", value.as_str()),
+            None => name.to_string(),
+        })
+        .collect();
+    cfg.sort();
+
+    // The source paths in this directory are exported relative to it (see
+    // `TranslateCtx::remap_path`).
+    let crate_root = tcx
+        .sess
+        .io
+        .input
+        .opt_path()
+        .and_then(|path| std::path::absolute(path).ok())
+        .and_then(|path| Some(path.parent()?.to_path_buf()));
+
     let mut ctx = TranslateCtx {
         tcx,
         sysroot,
+        crate_root,
         hax_state,
         options: translate_options,
         errors: RefCell::new(error_ctx),
         translated: TranslatedCrate {
             crate_name,
             options: options.clone(),
+            cfg,
+            // Cargo sets this when it invokes the compiler on a package.
+            crate_version: std::env::var("CARGO_PKG_VERSION").ok(),
+            // The driver is linked against the rustc it was built with.
+            rustc_commit_hash: env!("CHARON_RUSTC_COMMIT_HASH").to_owned(),
+            mir_level,
             ..TranslatedCrate::default()
         },
         id_map: Default::default(),
@@ -263,6 +355,7 @@ pub fn translate<'tcx, 'ctx>(
         translate_stack: Default::default(),
         cached_item_metas: Default::default(),
         cached_names: Default::default(),
+        registered_items: find_registered_items(tcx),
     };
 
     // Recursively register all the items in the crate, starting from the crate root. We could
@@ -287,12 +380,53 @@ pub fn translate<'tcx, 'ctx>(
     while let Some((item_src, trans_id)) = ctx.items_to_translate.pop_first() {
         trace!("About to translate item: {:?}", item_src);
         ctx.translate_item(item_src, trans_id);
+        if ctx.errors.borrow().reached_max_failed_items {
+            // The driver reports the failure.
+            break;
+        }
     }
 
+    // Record the rustc ids before we drop the id map.
+    let rustc_def_ids = if options.emit_symbol_table.is_some() {
+        ctx.reverse_id_map
+            .iter()
+            .map(|(id, src)| (*id, tcx.def_path_debug_str(src.to_def_id())))
+            .collect()
+    } else {
+        HashMap::new()
+    };
+    let type_layouts = if options.emit_type_sizes.is_some() {
+        ctx.reverse_id_map
+            .iter()
+            .filter_map(|(id, src)| {
+                let id = *id.as_type()?;
+                Some((id, type_layout(tcx, src.to_def_id())?))
+            })
+            .collect()
+    } else {
+        HashMap::new()
+    };
+    let derived_impls = if options.inline_derived_eq {
+        ctx.reverse_id_map
+            .iter()
+            .filter_map(|(id, src)| {
+                let id = *id.as_trait_impl()?;
+                tcx.has_attr(src.to_def_id(), rustc_span::sym::automatically_derived)
+                    .then_some(id)
+            })
+            .collect()
+    } else {
+        HashSet::new()
+    };
+
     // Return the context, dropping the hax state and rustc `tcx`.
     TransformCtx {
         options: ctx.options,
         translated: ctx.translated,
         errors: ctx.errors,
+        ullbc_snapshot: None,
+        rustc_def_ids,
+        type_layouts,
+        derived_impls,
     }
 }
@@ -4,7 +4,7 @@ use charon_lib::ast::*;
 use charon_lib::common::hash_by_addr::HashByAddr;
 use charon_lib::formatter::{FmtCtx, IntoFormatter};
 use charon_lib::ids::{MapGenerator, Vector};
-use charon_lib::options::TranslateOptions;
+use charon_lib::options::{TranslateOptions, UnsupportedFeature, UnsupportedPolicy};
 use charon_lib::ullbc_ast as ast;
 use hax_frontend_exporter::SInto;
 use hax_frontend_exporter::{self as hax, DefPathItem};
@@ -12,6 +12,7 @@ use itertools::Itertools;
 use macros::VariantIndexArity;
 use rustc_hir::def_id::DefId;
 use rustc_middle::ty::TyCtxt;
+use rustc_span::BytePos;
 use std::borrow::Cow;
 use std::cell::RefCell;
 use std::cmp::Ord;
@@ -78,6 +79,8 @@ pub struct TranslateCtx<'tcx> {
     pub tcx: TyCtxt<'tcx>,
     /// Path to the toolchain root.
     pub sysroot: PathBuf,
+    /// The absolute path to the directory that contains the crate root file, if we know it.
+    pub crate_root: Option<PathBuf>,
     /// The Hax context
     pub hax_state: hax::StateWithBase<'tcx>,
 
@@ -106,6 +109,9 @@ pub struct TranslateCtx<'tcx> {
     pub cached_names: HashMap<DefId, Name>,
     /// Cache the `ItemMeta`s to compute them only once each.
     pub cached_item_metas: HashMap<DefId, ItemMeta>,
+    /// The source ranges of the local items registered with `#[global_allocator]` or
+    /// `#[alloc_error_handler]`. See `translate_crate_to_ullbc::find_registered_items`.
+    pub registered_items: HashMap<(BytePos, BytePos), LangItemRole>,
 }
 
 /// A level of binding for type-level variables. Each item has a top-level binding level
@@ -184,17 +190,37 @@ impl BindingLevel {
         rid
     }
 
-    pub(crate) fn push_type_var(&mut self, rid: u32, name: String) -> TypeVarId {
-        let var_id = self.params.types.push_with(|index| TypeVar { index, name });
+    pub(crate) fn push_type_var(
+        &mut self,
+        rid: u32,
+        name: String,
+        default: Option<Ty>,
+    ) -> TypeVarId {
+        let var_id = self.params.types.push_with(|index| TypeVar {
+            index,
+            name,
+            default,
+        });
         self.type_vars_map.insert(rid, var_id);
         var_id
     }
 
-    pub(crate) fn push_const_generic_var(&mut self, rid: u32, ty: LiteralTy, name: String) {
+    pub(crate) fn push_const_generic_var(
+        &mut self,
+        rid: u32,
+        ty: LiteralTy,
+        name: String,
+        default: Option<ConstGeneric>,
+    ) {
         let var_id = self
             .params
             .const_generics
-            .push_with(|index| ConstGenericVar { index, name, ty });
+            .push_with(|index| ConstGenericVar {
+                index,
+                name,
+                ty,
+                default,
+            });
         self.const_generic_vars_map.insert(rid, var_id);
     }
 
@@ -263,6 +289,8 @@ pub(crate) struct BodyTransCtx<'tcx, 'ctx> {
     /// writing the translation functions as recursive functions. We do
     /// so because we had stack overflows in the past.
     pub blocks_stack: VecDeque<hax::BasicBlock>,
+    /// The spans of the `unsafe` blocks of the current body. Only computed with `--mark-unsafe`.
+    pub unsafe_spans: Vec<rustc_span::Span>,
 }
 
 /// Translates `T` into `U` using `hax`'s `SInto` trait, catching any hax panics.
@@ -296,6 +324,24 @@ impl<'tcx, 'ctx> TranslateCtx<'tcx> {
             .span_err(&self.translated, span, msg, level)
     }
 
+    /// Report the use of a feature we can't translate, following the `--on-unsupported` policy of
+    /// its category. The returned error makes the caller give up on the current body or type,
+    /// which becomes opaque; with the `opaque` policy, that is all that happens.
+    pub fn unsupported(&self, span: Span, feature: UnsupportedFeature, msg: &str) -> Error {
+        match self.options.unsupported_policy(feature) {
+            UnsupportedPolicy::Error => {
+                let err = self.span_err(span, msg, Level::Error);
+                self.errors.borrow_mut().hard_error_count += 1;
+                err
+            }
+            UnsupportedPolicy::Warn => self.span_err(span, msg, Level::Warning),
+            UnsupportedPolicy::Opaque => Error {
+                span,
+                msg: msg.to_owned(),
+            },
+        }
+    }
+
     /// Register a file if it is a "real" file and was not already registered
     /// `span` must be a span from which we obtained that filename.
     fn register_file(&mut self, filename: FileName, span: rustc_span::Span) -> FileId {
@@ -505,12 +551,16 @@ impl<'tcx, 'ctx> TranslateCtx<'tcx> {
             }
             rename
         };
+        let must_use = attributes
+            .iter()
+            .any(|a| a.as_unknown().is_some_and(|raw| raw.path == "must_use"));
 
         AttrInfo {
             attributes,
             inline,
             public,
             rename,
+            must_use,
         }
     }
 
@@ -533,9 +583,10 @@ impl<'tcx, 'ctx> TranslateCtx<'tcx> {
             .clone()
             .or_else(|| def.diagnostic_item.clone());
 
-        let opacity = if self.is_extern_item(def)
-            || attr_info.attributes.iter().any(|attr| attr.is_opaque())
-        {
+        let lang_item_role = self.translate_lang_item_role(def, lang_item.as_deref());
+        let is_foreign = self.is_extern_item(def);
+
+        let opacity = if is_foreign || attr_info.attributes.iter().any(|attr| attr.is_opaque()) {
             // Force opaque in these cases.
             ItemOpacity::Opaque.max(name_opacity)
         } else {
@@ -548,14 +599,59 @@ impl<'tcx, 'ctx> TranslateCtx<'tcx> {
             source_text: def.source_text.clone(),
             attr_info,
             is_local,
+            is_foreign,
             opacity,
             lang_item,
+            lang_item_role,
         };
         self.cached_item_metas
             .insert(def.rust_def_id(), item_meta.clone());
         item_meta
     }
 
+    /// Whether this item was registered with `#[panic_handler]`, `#[global_allocator]` or
+    /// `#[alloc_error_handler]`.
+    fn translate_lang_item_role(
+        &self,
+        def: &hax::FullDef,
+        lang_item: Option<&str>,
+    ) -> Option<LangItemRole> {
+        // `#[panic_handler]` makes the function the `panic_impl` lang item.
+        if lang_item == Some("panic_impl") {
+            return Some(LangItemRole::PanicHandler);
+        }
+        let span = self.tcx.source_span(def.rust_def_id().as_local()?);
+        self.registered_items.get(&(span.lo(), span.hi())).copied()
+    }
+
+    /// Apply the `--remap-path-prefix` mappings to a source path. If none applies, the paths in
+    /// the directory of the crate root are made relative to it, so that the output doesn't depend
+    /// on where the crate is on disk.
+    fn remap_path(&self, path: PathBuf) -> PathBuf {
+        let remapped = self
+            .options
+            .remap_path_prefix
+            .iter()
+            .rev()
+            .find_map(|(from, to)| {
+                let rest = path.strip_prefix(from).ok()?;
+                Some(if rest.as_os_str().is_empty() {
+                    to.clone()
+                } else {
+                    to.join(rest)
+                })
+            });
+        if let Some(remapped) = remapped {
+            remapped
+        } else if let Some(crate_root) = &self.crate_root
+            && let Ok(rest) = path.strip_prefix(crate_root)
+        {
+            rest.to_path_buf()
+        } else {
+            path
+        }
+    }
+
     pub fn translate_filename(&mut self, name: &hax::FileName) -> meta::FileName {
         match name {
             hax::FileName::Real(name) => {
@@ -580,7 +676,7 @@ impl<'tcx, 'ctx> TranslateCtx<'tcx> {
                         } else {
                             path.clone()
                         };
-                        FileName::Local(path)
+                        FileName::Local(self.remap_path(path))
                     }
                     RealFileName::Remapped { virtual_name, .. } => {
                         // We use the virtual name because it is always available.
@@ -597,9 +693,9 @@ impl<'tcx, 'ctx> TranslateCtx<'tcx> {
                                 .into_iter()
                                 .chain(components_iter)
                                 .collect();
-                            FileName::Virtual(path_without_hash)
+                            FileName::Virtual(self.remap_path(path_without_hash))
                         } else {
-                            FileName::Virtual(virtual_name.clone())
+                            FileName::Virtual(self.remap_path(virtual_name.clone()))
                         }
                     }
                 }
@@ -913,6 +1009,7 @@ impl<'tcx, 'ctx> BodyTransCtx<'tcx, 'ctx> {
             blocks: Default::default(),
             blocks_map: Default::default(),
             blocks_stack: Default::default(),
+            unsafe_spans: Default::default(),
         }
     }
 
@@ -920,6 +1017,10 @@ impl<'tcx, 'ctx> BodyTransCtx<'tcx, 'ctx> {
         self.t_ctx.span_err(span, msg, level)
     }
 
+    pub fn unsupported(&self, span: Span, feature: UnsupportedFeature, msg: &str) -> Error {
+        self.t_ctx.unsupported(span, feature, msg)
+    }
+
     pub(crate) fn translate_span_from_hax(&mut self, rspan: &hax::Span) -> Span {
         self.t_ctx.translate_span_from_hax(rspan)
     }
@@ -1203,8 +1304,82 @@ impl<'tcx, 'ctx> BodyTransCtx<'tcx, 'ctx> {
         })
     }
 
-    pub(crate) fn push_var(&mut self, rid: usize, ty: Ty, name: Option<String>) {
-        let var_id = self.locals.vars.push_with(|index| Var { index, name, ty });
+    /// Push the generic parameters of a generic associated type, call the continuation, and pop
+    /// the parameters. Only lifetime parameters are supported for now.
+    pub(crate) fn translate_gat_binder<F, U>(
+        &mut self,
+        span: Span,
+        kind: BinderKind,
+        generics: &hax::TyGenerics,
+        f: F,
+    ) -> Result<Binder<U>, Error>
+    where
+        F: FnOnce(&mut Self) -> Result<U, Error>,
+    {
+        assert!(!self.binding_levels.is_empty());
+        if generics
+            .params
+            .iter()
+            .any(|param| !matches!(param.kind, hax::GenericParamDefKind::Lifetime))
+        {
+            raise_error!(
+                self,
+                span,
+                "Generic associated types with type or const parameters are not supported"
+            );
+        }
+
+        self.binding_levels.push(BindingLevel::new(true));
+        // Important: do not short-circuit on error here.
+        let res = match self.push_generic_params(generics) {
+            Ok(()) => f(self),
+            Err(err) => Err(err),
+        };
+        let params = self.binding_levels.pop().unwrap().params;
+
+        res.map(|skip_binder| Binder {
+            kind,
+            params,
+            skip_binder,
+        })
+    }
+
+    /// Register the clauses of a lifetime GAT with `f`, in the context of the GAT parameters. The
+    /// parent clauses of a trait can't refer to these parameters, so we erase them.
+    pub(crate) fn translate_gat_clauses<F>(
+        &mut self,
+        span: Span,
+        name: &TraitItemName,
+        generics: &hax::TyGenerics,
+        f: F,
+    ) -> Result<(), Error>
+    where
+        F: FnOnce(&mut Self) -> Result<(), Error>,
+    {
+        let binder = self.translate_gat_binder(span, BinderKind::Other, generics, f)?;
+        if let Some(clauses) = self.item_trait_clauses.get_mut(name) {
+            let bound_clauses = RegionBinder {
+                regions: binder.params.regions,
+                skip_binder: mem::take(clauses),
+            };
+            *clauses = bound_clauses.erase();
+        }
+        Ok(())
+    }
+
+    pub(crate) fn push_var(
+        &mut self,
+        rid: usize,
+        ty: Ty,
+        name: Option<String>,
+        binding_mode: Option<BindingMode>,
+    ) {
+        let var_id = self.locals.vars.push_with(|index| Var {
+            index,
+            name,
+            ty,
+            binding_mode,
+        });
         self.vars_map.insert(rid, var_id);
     }
 
@@ -1243,6 +1418,7 @@ impl<'tcx, 'ctx, 'a> IntoFormatter for &'a BodyTransCtx<'tcx, 'ctx> {
             translated: Some(&self.t_ctx.translated),
             generics: self.binding_levels.map_ref(|bl| Cow::Borrowed(&bl.params)),
             locals: Some(&self.locals),
+            max_depth: None,
         }
     }
 }
@@ -6,12 +6,13 @@
 use std::mem;
 use std::panic;
 
-use super::get_mir::boxes_are_desugared;
+use super::get_mir::{boxes_are_desugared, unsafe_block_spans};
 use super::translate_ctx::*;
 use charon_lib::ast::*;
 use charon_lib::common::*;
 use charon_lib::formatter::{Formatter, IntoFormatter};
 use charon_lib::ids::Vector;
+use charon_lib::options::UnsupportedFeature;
 use charon_lib::pretty::FmtWithCtx;
 use charon_lib::ullbc_ast::*;
 use hax_frontend_exporter as hax;
@@ -53,6 +54,36 @@ fn translate_borrow_kind(borrow_kind: hax::BorrowKind) -> BorrowKind {
     }
 }
 
+/// Recognize the pointer offset methods of `core`, e.g. `core::ptr::const_ptr::{impl *const T}::add`.
+fn recognize_ptr_offset(name: &Name) -> Option<BuiltinPtrOffsetOp> {
+    let [PathElem::Ident(krate, _), .., PathElem::Impl(ImplElem::Ty(impl_ty), _), PathElem::Ident(method, _)] =
+        name.name.as_slice()
+    else {
+        return None;
+    };
+    if krate != "core" {
+        return None;
+    }
+    let TyKind::RawPtr(_, mutability) = impl_ty.skip_binder.kind() else {
+        return None;
+    };
+    let (wrapping, method) = match method.strip_prefix("wrapping_") {
+        Some(method) => (true, method),
+        None => (false, method.as_str()),
+    };
+    let kind = match method {
+        "offset" => PtrOffsetKind::Offset,
+        "add" => PtrOffsetKind::Add,
+        "sub" => PtrOffsetKind::Sub,
+        _ => return None,
+    };
+    Some(BuiltinPtrOffsetOp {
+        mutability: *mutability,
+        kind,
+        wrapping,
+    })
+}
+
 impl<'tcx, 'ctx> TranslateCtx<'tcx> {
     fn translate_binaryop_kind(&mut self, span: Span, binop: hax::BinOp) -> Result<BinOp, Error> {
         Ok(match binop {
@@ -173,7 +204,11 @@ impl<'tcx, 'ctx> BodyTransCtx<'tcx, 'ctx> {
     }
 
     /// Translate a function's local variables by adding them in the environment.
-    fn translate_body_locals(&mut self, body: &hax::MirBody<()>) -> Result<(), Error> {
+    fn translate_body_locals(
+        &mut self,
+        body: &hax::MirBody<()>,
+        binding_modes: &[Option<BindingMode>],
+    ) -> Result<(), Error> {
         // Translate the parameters
         for (index, var) in body.local_decls.raw.iter().enumerate() {
             trace!("Translating local of index {} and type {:?}", index, var.ty);
@@ -186,7 +221,8 @@ impl<'tcx, 'ctx> BodyTransCtx<'tcx, 'ctx> {
             let ty = self.translate_ty(span, &var.ty)?;
 
             // Add the variable to the environment
-            self.push_var(index, ty, name);
+            let binding_mode = binding_modes.get(index).copied().flatten();
+            self.push_var(index, ty, name, binding_mode);
         }
 
         Ok(())
@@ -238,19 +274,44 @@ impl<'tcx, 'ctx> BodyTransCtx<'tcx, 'ctx> {
 
         // Translate the statements
         let mut statements = Vec::new();
+        // Whether the last statement we pushed comes from an `unsafe` block.
+        let mut in_unsafe = false;
         for statement in &block.statements {
             trace!("statement: {:?}", statement);
 
             // Some statements might be ignored, hence the optional returned value
             let opt_statement = self.translate_statement(body, statement)?;
-            if let Some(statement) = opt_statement {
-                statements.push(statement)
+            if let Some(t_statement) = opt_statement {
+                self.push_unsafe_marker(
+                    &mut statements,
+                    &mut in_unsafe,
+                    t_statement.span,
+                    &statement.source_info,
+                );
+                statements.push(t_statement)
             }
         }
 
-        // Translate the terminator
+        // Translate the terminator. It may push statements (e.g. calls), so we place the marker
+        // before translating it.
         let terminator = block.terminator.as_ref().unwrap();
+        if self.t_ctx.options.mark_unsafe {
+            let span = self
+                .t_ctx
+                .translate_span_from_source_info(&body.source_scopes, &terminator.source_info);
+            self.push_unsafe_marker(
+                &mut statements,
+                &mut in_unsafe,
+                span,
+                &terminator.source_info,
+            );
+        }
         let terminator = self.translate_terminator(body, terminator, &mut statements)?;
+        // The markers don't cross block boundaries: we close the `unsafe` region at the end of
+        // each block.
+        if in_unsafe {
+            statements.push(Statement::new(terminator.span, RawStatement::ExitUnsafe));
+        }
 
         // Insert the block in the translated blocks
         let block = BlockData {
@@ -263,6 +324,35 @@ impl<'tcx, 'ctx> BodyTransCtx<'tcx, 'ctx> {
         Ok(())
     }
 
+    /// With `--mark-unsafe`, push an `EnterUnsafe` (resp. `ExitUnsafe`) marker if the statement
+    /// with the given source info enters (resp. exits) an `unsafe` block. `in_unsafe` tracks
+    /// whether the statements pushed so far in the current block are in an `unsafe` block.
+    fn push_unsafe_marker(
+        &mut self,
+        statements: &mut Vec<Statement>,
+        in_unsafe: &mut bool,
+        span: Span,
+        source_info: &hax::SourceInfo,
+    ) {
+        if !self.t_ctx.options.mark_unsafe {
+            return;
+        }
+        let rust_span = source_info.span.rust_span_data.unwrap().span();
+        let is_unsafe = self
+            .unsafe_spans
+            .iter()
+            .any(|unsafe_span| unsafe_span.contains(rust_span));
+        if is_unsafe != *in_unsafe {
+            let marker = if is_unsafe {
+                RawStatement::EnterUnsafe
+            } else {
+                RawStatement::ExitUnsafe
+            };
+            statements.push(Statement::new(span, marker));
+            *in_unsafe = is_unsafe;
+        }
+    }
+
     /// Translate a place
     /// TODO: Hax represents places in a different manner than MIR. We should
     /// update our representation of places to match the Hax representation.
@@ -277,6 +367,15 @@ impl<'tcx, 'ctx> BodyTransCtx<'tcx, 'ctx> {
                 kind,
             } => {
                 let ty = self.translate_ty(span, &place.ty)?;
+                // The variant of the enum this place goes through, if the projection below is
+                // a downcast.
+                let downcast_variant = match &subplace.kind {
+                    hax::PlaceKind::Projection {
+                        kind: hax::ProjectionElem::Downcast(_, variant),
+                        ..
+                    } => Some(translate_variant_id(*variant)),
+                    _ => None,
+                };
                 // Compute the type of the value *before* projection - we use this
                 // to disambiguate
                 let subplace = self.translate_place(span, subplace)?;
@@ -317,7 +416,12 @@ impl<'tcx, 'ctx> BodyTransCtx<'tcx, 'ctx> {
                                 index,
                             } => {
                                 let field_id = translate_field_id(*index);
-                                let variant_id = variant.map(translate_variant_id);
+                                // When the enum is behind a reference (e.g. a `ref` binding in
+                                // `match &opt { Some(ref x) => .. }`), the place is `(*opt as
+                                // Some).0`: the variant is given by the downcast that directly
+                                // precedes the field access.
+                                let variant_id =
+                                    downcast_variant.or(variant.map(translate_variant_id));
                                 match subplace.ty().kind() {
                                     TyKind::Adt(TypeId::Adt(type_id), ..) => {
                                         let proj_kind = FieldProjKind::Adt(*type_id, variant_id);
@@ -466,12 +570,22 @@ impl<'tcx, 'ctx> BodyTransCtx<'tcx, 'ctx> {
                 let borrow_kind = translate_borrow_kind(*borrow_kind);
                 Ok(Rvalue::Ref(place, borrow_kind))
             }
-            hax::Rvalue::ThreadLocalRef(_) => {
-                raise_error!(
-                    self,
-                    span,
-                    "charon does not support thread local references"
-                );
+            hax::Rvalue::ThreadLocalRef(def_id) => {
+                // Thread-local statics are translated as globals marked with
+                // `GlobalKind::ThreadLocal`; taking a reference to one reads the current thread's
+                // copy. Like for `static mut`, mutable thread-locals are accessed through a raw
+                // pointer.
+                let mutability = if self.t_ctx.tcx.is_mutable_static(def_id.to_rust_def_id()) {
+                    RefKind::Mut
+                } else {
+                    RefKind::Shared
+                };
+                let id = self.register_global_decl_id(span, def_id);
+                let global_ref = GlobalDeclRef {
+                    id,
+                    generics: GenericArgs::empty(GenericsSource::item(id)),
+                };
+                Ok(Rvalue::GlobalRef(global_ref, mutability))
             }
             hax::Rvalue::RawPtr(mtbl, place) => {
                 let mtbl = if *mtbl { RefKind::Mut } else { RefKind::Shared };
@@ -496,13 +610,13 @@ impl<'tcx, 'ctx> BodyTransCtx<'tcx, 'ctx> {
                 };
                 Ok(Rvalue::Len(place, ty, cg))
             }
-            hax::Rvalue::Cast(cast_kind, operand, tgt_ty) => {
+            hax::Rvalue::Cast(cast_kind, hax_operand, hax_tgt_ty) => {
                 trace!("Rvalue::Cast: {:?}", rvalue);
                 // Translate the target type
-                let tgt_ty = self.translate_ty(span, tgt_ty)?;
+                let tgt_ty = self.translate_ty(span, hax_tgt_ty)?;
 
                 // Translate the operand
-                let (operand, src_ty) = self.translate_operand_with_type(span, operand)?;
+                let (operand, src_ty) = self.translate_operand_with_type(span, hax_operand)?;
 
                 match cast_kind {
                     hax::CastKind::IntToInt
@@ -568,6 +682,22 @@ impl<'tcx, 'ctx> BodyTransCtx<'tcx, 'ctx> {
                                 generics.types[0].clone(),
                                 generics.const_generics[0].clone(),
                             )
+                        } else if let hax::Operand::Copy(place) | hax::Operand::Move(place) =
+                            hax_operand
+                            && let Some(src_trait) =
+                                self.translate_dyn_principal(span, &place.ty)?
+                            && let Some(tgt_trait) =
+                                self.translate_dyn_principal(span, hax_tgt_ty)?
+                            && src_trait.trait_id != tgt_trait.trait_id
+                        {
+                            // `dyn Sub` -> `dyn Super`. Coercions that only add or remove auto
+                            // traits keep the same principal trait and are plain unsizings.
+                            UnOp::Cast(CastKind::DynUpcast(
+                                src_ty.clone(),
+                                tgt_ty.clone(),
+                                src_trait,
+                                tgt_trait,
+                            ))
                         } else {
                             UnOp::Cast(CastKind::Unsize(src_ty.clone(), tgt_ty.clone()))
                         };
@@ -680,8 +810,17 @@ impl<'tcx, 'ctx> BodyTransCtx<'tcx, 'ctx> {
                         let _ = user_annotation;
 
                         let type_id = self.translate_type_id(span, adt_id)?;
-                        // Sanity check
-                        assert!(matches!(&type_id, TypeId::Adt(_)));
+                        // Apart from `UnsafeCell`, whose constructor builds it directly, builtin
+                        // types can only be built through their API.
+                        error_assert!(
+                            self,
+                            span,
+                            matches!(
+                                &type_id,
+                                TypeId::Adt(_) | TypeId::Builtin(BuiltinTy::UnsafeCell)
+                            ),
+                            "Unsupported aggregate of builtin type {type_id:?}"
+                        );
 
                         // Translate the substitution
                         let generics = self.translate_generic_args(
@@ -712,19 +851,8 @@ impl<'tcx, 'ctx> BodyTransCtx<'tcx, 'ctx> {
                             closure_args.tupled_sig
                         );
 
-                        let fun_id = self.register_fun_decl_id(span, def_id);
-
-                        // Retrieve the late-bound variables.
-                        let binder = closure_args.tupled_sig.as_ref().rebind(());
-                        // Translate the substitution
-                        let generics = self.translate_generic_args(
-                            span,
-                            &closure_args.parent_args,
-                            &closure_args.parent_trait_refs,
-                            Some(binder),
-                            GenericsSource::item(fun_id),
-                        )?;
-
+                        let (fun_id, generics) =
+                            self.translate_closure_ref(span, def_id, closure_args)?;
                         let akind = AggregateKind::Closure(fun_id, generics);
 
                         Ok(Rvalue::Aggregate(akind, operands_t))
@@ -735,7 +863,11 @@ impl<'tcx, 'ctx> BodyTransCtx<'tcx, 'ctx> {
                     }
                     hax::AggregateKind::Coroutine(..)
                     | hax::AggregateKind::CoroutineClosure(..) => {
-                        raise_error!(self, span, "Coroutines are not supported");
+                        return Err(self.unsupported(
+                            span,
+                            UnsupportedFeature::Coroutine,
+                            "Coroutines are not supported",
+                        ));
                     }
                 }
             }
@@ -751,7 +883,7 @@ impl<'tcx, 'ctx> BodyTransCtx<'tcx, 'ctx> {
     fn recognize_builtin_fun(&mut self, def: &hax::FullDef) -> Result<Option<BuiltinFun>, Error> {
         let name = self.t_ctx.hax_def_id_to_name(&def.def_id)?;
         let panic_lang_items = &["panic", "panic_fmt", "begin_panic"];
-        let panic_names = &[&["core", "panicking", "assert_failed"], EXPLICIT_PANIC_NAME];
+        let panic_names = &[ASSERT_FAILED_NAME, EXPLICIT_PANIC_NAME];
 
         if def.diagnostic_item.as_deref() == Some("box_new") {
             Ok(Some(BuiltinFun::BoxNew))
@@ -762,6 +894,117 @@ impl<'tcx, 'ctx> BodyTransCtx<'tcx, 'ctx> {
             || panic_names.iter().any(|panic| name.equals_ref_name(panic))
         {
             Ok(Some(BuiltinFun::Panic))
+        } else if let Some(op) = recognize_ptr_offset(&name) {
+            Ok(Some(BuiltinFun::PtrOffset(op)))
+        } else if !self.t_ctx.options.no_mem_builtins
+            && let Some(fun) = [
+                (MEM_SWAP_NAME, BuiltinFun::MemSwap),
+                (MEM_REPLACE_NAME, BuiltinFun::MemReplace),
+                (MEM_TAKE_NAME, BuiltinFun::MemTake),
+            ]
+            .into_iter()
+            .find_map(|(ref_name, fun)| name.equals_ref_name(ref_name).then_some(fun))
+        {
+            Ok(Some(fun))
+        } else if self.t_ctx.options.abstract_formatting
+            && let Some(fun) = self.recognize_format_args(&def.def_id, &name)
+        {
+            Ok(Some(fun))
+        } else {
+            Ok(None)
+        }
+    }
+
+    /// Checks whether the given id is one of the constructors of `core::fmt::Arguments` and
+    /// `core::fmt::rt::Argument` that `format_args!` expands to. We recognize the types by their
+    /// lang items since they are unstable implementation details of the macro.
+    fn recognize_format_args(&self, def_id: &hax::DefId, name: &Name) -> Option<BuiltinFun> {
+        let [.., PathElem::Impl(ImplElem::Ty(_), _), PathElem::Ident(method, _)] =
+            name.name.as_slice()
+        else {
+            return None;
+        };
+        let tcx = self.t_ctx.tcx;
+        let def_id: DefId = def_id.into();
+        let impl_id = tcx.opt_parent(def_id)?;
+        let adt_id = tcx
+            .type_of(impl_id)
+            .instantiate_identity()
+            .ty_adt_def()?
+            .did();
+        let lang_items = tcx.lang_items();
+        if lang_items.format_arguments() == Some(adt_id) {
+            matches!(method.as_str(), "new_const" | "new_v1" | "new_v1_formatted")
+                .then_some(BuiltinFun::FormatArgs)
+        } else if lang_items.format_argument() == Some(adt_id) {
+            match method.as_str() {
+                "new_display" => Some(BuiltinFun::FmtArgDisplay),
+                "new_debug" => Some(BuiltinFun::FmtArgDebug),
+                _ => None,
+            }
+        } else {
+            None
+        }
+    }
+
+    /// Checks whether the given id is a trait method that we translate to a builtin: the
+    /// comparison and hashing methods called on an impl generated by `#[derive(..)]`, with
+    /// `--derived-cmp-builtins` and `--hash-builtins`, the methods of `Hasher`, with
+    /// `--hash-builtins`, and the `fmt` methods of `Display` and `Debug`, with
+    /// `--abstract-formatting`.
+    fn recognize_trait_method_builtin(
+        &mut self,
+        def_id: &hax::DefId,
+        trait_info: &Option<hax::ImplExpr>,
+    ) -> Result<Option<BuiltinFun>, Error> {
+        let options = &self.t_ctx.options;
+        if trait_info.is_none()
+            || !(options.derived_cmp_builtins
+                || options.hash_builtins
+                || options.abstract_formatting)
+        {
+            return Ok(None);
+        }
+        let name = self.t_ctx.hax_def_id_to_name(def_id)?;
+
+        if self.t_ctx.options.abstract_formatting {
+            if name.equals_ref_name(DISPLAY_FMT_NAME) {
+                return Ok(Some(BuiltinFun::FmtDisplay));
+            } else if name.equals_ref_name(DEBUG_FMT_NAME) {
+                return Ok(Some(BuiltinFun::FmtDebug));
+            }
+        }
+
+        if self.t_ctx.options.hash_builtins
+            && let Some(PathElem::Ident(method, _)) = name.name.last()
+            && name.equals_ref_name(&[HASHER_NAME, &[method.as_str()]].concat())
+        {
+            return Ok(BuiltinFun::hasher_method(method));
+        }
+
+        let derived = match trait_info {
+            Some(hax::ImplExpr {
+                r#impl: hax::ImplExprAtom::Concrete { id: impl_id, .. },
+                ..
+            }) => {
+                let impl_id: DefId = impl_id.into();
+                self.t_ctx
+                    .tcx
+                    .has_attr(impl_id, rustc_span::sym::automatically_derived)
+            }
+            _ => false,
+        };
+        if !derived {
+            return Ok(None);
+        }
+        let options = &self.t_ctx.options;
+        if options.derived_cmp_builtins && name.equals_ref_name(ORD_CMP_NAME) {
+            Ok(Some(BuiltinFun::DerivedCmp))
+        } else if options.derived_cmp_builtins && name.equals_ref_name(PARTIAL_ORD_PARTIAL_CMP_NAME)
+        {
+            Ok(Some(BuiltinFun::DerivedPartialCmp))
+        } else if options.hash_builtins && name.equals_ref_name(HASH_HASH_NAME) {
+            Ok(Some(BuiltinFun::DerivedHash))
         } else {
             Ok(None)
         }
@@ -788,7 +1031,10 @@ impl<'tcx, 'ctx> BodyTransCtx<'tcx, 'ctx> {
         trait_info: &Option<hax::ImplExpr>,
     ) -> Result<SubstFunIdOrPanic, Error> {
         let fun_def = self.t_ctx.hax_def(def_id)?;
-        let builtin_fun = self.recognize_builtin_fun(&fun_def)?;
+        let builtin_fun = match self.recognize_builtin_fun(&fun_def)? {
+            Some(fun) => Some(fun),
+            None => self.recognize_trait_method_builtin(def_id, trait_info)?,
+        };
         if matches!(builtin_fun, Some(BuiltinFun::Panic)) {
             let name = self.t_ctx.hax_def_id_to_name(def_id)?;
             return Ok(SubstFunIdOrPanic::Panic(name));
@@ -826,7 +1072,9 @@ impl<'tcx, 'ctx> BodyTransCtx<'tcx, 'ctx> {
             // is translated to:
             // `box_deref<T>`
             // (the type parameter is not `Box<T>` but `T`).
-            assert!(trait_info.is_none());
+            // Some builtins are trait methods, but we don't need the impl: they are fully
+            // determined by the types.
+            assert!(trait_info.is_none() || builtin_fun.is_trait_method());
 
             let aid = builtin_fun.to_ullbc_builtin_fun();
 
@@ -842,7 +1090,21 @@ impl<'tcx, 'ctx> BodyTransCtx<'tcx, 'ctx> {
             // We have to retrieve the type `Box<u32>` and check that it is of the
             // form `Box<T>` (and we generate `box_deref<u32>`).
             match aid {
-                BuiltinFunId::BoxNew => {
+                BuiltinFunId::BoxNew
+                | BuiltinFunId::PtrOffset(..)
+                | BuiltinFunId::MemSwap
+                | BuiltinFunId::MemReplace
+                | BuiltinFunId::MemTake
+                | BuiltinFunId::DerivedCmp
+                | BuiltinFunId::DerivedPartialCmp
+                | BuiltinFunId::DerivedHash
+                | BuiltinFunId::HasherWrite(_)
+                | BuiltinFunId::HasherFinish
+                | BuiltinFunId::FormatArgs
+                | BuiltinFunId::FmtArgDisplay
+                | BuiltinFunId::FmtArgDebug
+                | BuiltinFunId::FmtDisplay
+                | BuiltinFunId::FmtDebug => {
                     // Nothing to do
                 }
                 BuiltinFunId::Index { .. }
@@ -952,6 +1214,8 @@ impl<'tcx, 'ctx> BodyTransCtx<'tcx, 'ctx> {
                 Some(RawStatement::Assert(Assert {
                     cond: op,
                     expected: true,
+                    comparison: None,
+                    message: None,
                 }))
             }
             StatementKind::Intrinsic(hax::NonDivergingIntrinsic::CopyNonOverlapping(..)) => {
@@ -1052,6 +1316,8 @@ impl<'tcx, 'ctx> BodyTransCtx<'tcx, 'ctx> {
                 let assert = Assert {
                     cond: self.translate_operand(span, cond)?,
                     expected: *expected,
+                    comparison: None,
+                    message: None,
                 };
                 statements.push(Statement::new(span, RawStatement::Assert(assert)));
                 let target = self.translate_basic_block_id(*target);
@@ -1085,7 +1351,11 @@ impl<'tcx, 'ctx> BodyTransCtx<'tcx, 'ctx> {
                 RawTerminator::Goto { target }
             }
             TerminatorKind::InlineAsm { .. } => {
-                raise_error!(self, span, "Inline assembly is not supported");
+                return Err(self.unsupported(
+                    span,
+                    UnsupportedFeature::Asm,
+                    "Inline assembly is not supported",
+                ));
             }
             TerminatorKind::CoroutineDrop
             | TerminatorKind::TailCall { .. }
@@ -1119,21 +1389,59 @@ impl<'tcx, 'ctx> BodyTransCtx<'tcx, 'ctx> {
                 Ok(SwitchTargets::If(if_block, then_block))
             }
             LiteralTy::Integer(int_ty) => {
-                let targets: Vec<(ScalarValue, BlockId)> = targets
+                let targets: Vec<(Literal, BlockId)> = targets
                     .iter()
                     .map(|(v, tgt)| {
                         let v = ScalarValue::from_le_bytes(int_ty, v.data_le_bytes);
                         let tgt = self.translate_basic_block_id(*tgt);
-                        Ok((v, tgt))
+                        Ok((Literal::Scalar(v), tgt))
+                    })
+                    .try_collect()?;
+                let otherwise = self.translate_basic_block_id(*otherwise);
+                Ok(SwitchTargets::SwitchInt(switch_ty, targets, otherwise))
+            }
+            LiteralTy::Char => {
+                // Chars are matched on their code point.
+                let targets: Vec<(Literal, BlockId)> = targets
+                    .iter()
+                    .map(|(v, tgt)| {
+                        let code_point = u128::from_le_bytes(v.data_le_bytes);
+                        let Some(c) = u32::try_from(code_point).ok().and_then(char::from_u32)
+                        else {
+                            raise_error!(self, span, "Invalid char value in match: {code_point}")
+                        };
+                        let tgt = self.translate_basic_block_id(*tgt);
+                        Ok((Literal::Char(c), tgt))
                     })
                     .try_collect()?;
                 let otherwise = self.translate_basic_block_id(*otherwise);
-                Ok(SwitchTargets::SwitchInt(int_ty, targets, otherwise))
+                Ok(SwitchTargets::SwitchInt(switch_ty, targets, otherwise))
             }
             _ => raise_error!(self, span, "Can't match on type {switch_ty}"),
         }
     }
 
+    /// Translate a reference to the function that implements a closure.
+    pub(crate) fn translate_closure_ref(
+        &mut self,
+        span: Span,
+        def_id: &hax::DefId,
+        closure_args: &hax::ClosureArgs,
+    ) -> Result<(FunDeclId, GenericArgs), Error> {
+        let fun_id = self.register_fun_decl_id(span, def_id);
+        // Retrieve the late-bound variables.
+        let binder = closure_args.tupled_sig.as_ref().rebind(());
+        // Translate the substitution
+        let generics = self.translate_generic_args(
+            span,
+            &closure_args.parent_args,
+            &closure_args.parent_trait_refs,
+            Some(binder),
+            GenericsSource::item(fun_id),
+        )?;
+        Ok((fun_id, generics))
+    }
+
     /// Translate a function call statement.
     /// Note that `body` is the body of the function being translated, not of the
     /// function referenced in the function call: we need it in order to translate
@@ -1154,7 +1462,34 @@ impl<'tcx, 'ctx> BodyTransCtx<'tcx, 'ctx> {
         // are using a local function pointer (i.e., the operand is a "move").
         let lval = self.translate_place(span, destination)?;
         let next_block = target.map(|target| self.translate_basic_block_id(target));
+        let next_terminator = match next_block {
+            Some(target) => RawTerminator::Goto { target },
+            None => RawTerminator::Abort(AbortKind::UndefinedBehavior),
+        };
+
+        // `transmute` reinterprets its argument: we translate it to a cast which records the
+        // source and target types.
+        if let hax::FunOperand::Static { def_id, .. } = fun
+            && self
+                .t_ctx
+                .tcx
+                .is_intrinsic(def_id.into(), rustc_span::sym::transmute)
+        {
+            let args = self.translate_arguments(span, args)?;
+            let Ok([arg]) = <[Operand; 1]>::try_from(args) else {
+                raise_error!(self, span, "Unexpected arguments to `transmute`")
+            };
+            let cast = CastKind::Transmute(arg.ty().clone(), lval.ty.clone());
+            let rvalue = Rvalue::UnaryOp(UnOp::Cast(cast), arg);
+            statements.push(Statement::new(span, RawStatement::Assign(lval, rvalue)));
+            return Ok(next_terminator);
+        }
+
         let (fn_operand, args) = match fun {
+            // Calls through closure trait objects are dynamically dispatched.
+            _ if let Some(fn_operand) = self.translate_dyn_closure_call(span, fun, args)? => {
+                (fn_operand, Vec::new())
+            }
             hax::FunOperand::Static {
                 def_id,
                 generics,
@@ -1180,8 +1515,18 @@ impl<'tcx, 'ctx> BodyTransCtx<'tcx, 'ctx> {
                         // If the call is `panic!`, then the target is `None`.
                         // I don't know in which other cases it can be `None`.
                         assert!(target.is_none());
-                        // We ignore the arguments
-                        return Ok(RawTerminator::Abort(AbortKind::Panic(name)));
+                        // We ignore the arguments. Inside a `#[track_caller]` function the
+                        // location is that of our caller, which we don't know.
+                        let location = if self
+                            .t_ctx
+                            .tcx
+                            .has_attr(self.def_id, rustc_span::sym::track_caller)
+                        {
+                            None
+                        } else {
+                            Some(PanicLocation::from_span(span))
+                        };
+                        return Ok(RawTerminator::Abort(AbortKind::Panic(name, location)));
                     }
                     SubstFunIdOrPanic::Fun(fid) => {
                         let fn_operand = FnOperand::Regular(fid.func);
@@ -1210,14 +1555,118 @@ impl<'tcx, 'ctx> BodyTransCtx<'tcx, 'ctx> {
             func: fn_operand,
             args,
             dest: lval,
+            // Computed in [crate::transform::reconstruct_method_calls].
+            is_method_call: false,
         };
         statements.push(Statement::new(span, RawStatement::Call(call)));
-        Ok(match next_block {
-            Some(target) => RawTerminator::Goto { target },
-            None => RawTerminator::Abort(AbortKind::UndefinedBehavior),
+        Ok(next_terminator)
+    }
+
+    /// If `ty` is a pointer to a trait object (`&dyn Trait`, `*const dyn Trait`, `Box<dyn Trait>`,
+    /// ...), return the principal (i.e. non-auto) trait of that trait object, with the trait
+    /// object type itself as `Self`.
+    fn translate_dyn_principal(
+        &mut self,
+        span: Span,
+        ty: &hax::Ty,
+    ) -> Result<Option<TraitDeclRef>, Error> {
+        let pointee = match ty.kind() {
+            hax::TyKind::Ref(_, pointee, _) | hax::TyKind::RawPtr(pointee, _) => pointee,
+            hax::TyKind::Adt {
+                generic_args,
+                def_id,
+                ..
+            } if self.is_smart_pointer(def_id.into()) => match generic_args.first() {
+                Some(hax::GenericArg::Type(pointee)) => pointee,
+                _ => return Ok(None),
+            },
+            _ => return Ok(None),
+        };
+        let hax::TyKind::Dynamic(preds, _, _) = pointee.kind() else {
+            return Ok(None);
+        };
+        let Some(trait_ref) = preds.iter().find_map(|pred| match &pred.value {
+            hax::ExistentialPredicate::Trait(trait_ref) => Some(trait_ref),
+            _ => None,
+        }) else {
+            return Ok(None);
+        };
+        let trait_id = self.register_trait_decl_id(span, &trait_ref.def_id);
+        let args = std::iter::once(hax::GenericArg::Type(pointee.clone()))
+            .chain(trait_ref.args.iter().cloned())
+            .collect_vec();
+        let generics =
+            self.translate_generic_args(span, &args, &[], None, GenericsSource::item(trait_id))?;
+        Ok(Some(TraitDeclRef { trait_id, generics }))
+    }
+
+    /// Whether this ADT is a smart pointer like `Box` or `Rc`, i.e. it implements `CoerceUnsized`
+    /// so that `Ptr<T>` can be coerced to `Ptr<dyn Trait>`. Other ADTs, e.g.
+    /// `PhantomData<dyn Trait>`, merely mention the trait object.
+    fn is_smart_pointer(&self, adt_id: DefId) -> bool {
+        let tcx = self.t_ctx.tcx;
+        let Some(coerce_unsized) = tcx.lang_items().coerce_unsized_trait() else {
+            return false;
+        };
+        tcx.all_impls(coerce_unsized).any(|impl_id| {
+            tcx.type_of(impl_id)
+                .instantiate_identity()
+                .ty_adt_def()
+                .is_some_and(|adt| adt.did() == adt_id)
         })
     }
 
+    /// Calls to a method of `Fn`, `FnMut` or `FnOnce` on a closure trait object (possibly behind
+    /// a reference or a `Box`) are dynamically dispatched: we translate them to a
+    /// [FnOperand::DynClosure].
+    fn translate_dyn_closure_call(
+        &mut self,
+        span: Span,
+        fun: &hax::FunOperand,
+        args: &Vec<hax::Spanned<hax::Operand>>,
+    ) -> Result<Option<FnOperand>, Error> {
+        let hax::FunOperand::Static { def_id, .. } = fun else {
+            return Ok(None);
+        };
+        let tcx = self.t_ctx.tcx;
+        let Some(trait_id) = tcx.trait_of_item(def_id.into()) else {
+            return Ok(None);
+        };
+        let kind = match tcx.fn_trait_kind_from_def_id(trait_id) {
+            Some(rustc_middle::ty::ClosureKind::Fn) => ClosureKind::Fn,
+            Some(rustc_middle::ty::ClosureKind::FnMut) => ClosureKind::FnMut,
+            Some(rustc_middle::ty::ClosureKind::FnOnce) => ClosureKind::FnOnce,
+            None => return Ok(None),
+        };
+        // Check the type of the receiver first: the arguments of the other calls are translated
+        // by the caller.
+        let receiver_ty = match args.first().map(|arg| &arg.node) {
+            Some(hax::Operand::Copy(place) | hax::Operand::Move(place)) => &place.ty,
+            Some(hax::Operand::Constant(const_op)) => &const_op.evaluated.ty,
+            None => return Ok(None),
+        };
+        let receiver_ty = self.translate_ty(span, receiver_ty)?;
+        let mut ty = &receiver_ty;
+        if let TyKind::Ref(_, inner, _) = ty.kind() {
+            ty = inner;
+        }
+        if let Some(inner) = ty.as_box() {
+            ty = inner;
+        }
+        if !ty.kind().is_dyn_trait() {
+            return Ok(None);
+        }
+        let args = self.translate_arguments(span, args)?;
+        let Ok([closure, args]) = <[Operand; 2]>::try_from(args) else {
+            raise_error!(self, span, "Unexpected arguments to a closure call")
+        };
+        Ok(Some(FnOperand::DynClosure {
+            kind,
+            closure,
+            args,
+        }))
+    }
+
     /// Evaluate function arguments in a context, and return the list of computed
     /// values.
     fn translate_arguments(
@@ -1370,14 +1819,18 @@ impl<'tcx, 'ctx> BodyTransCtx<'tcx, 'ctx> {
 
         // Retrieve the body
         let rust_id = def.rust_def_id();
-        let Some(body) = self.t_ctx.get_mir(rust_id, item_meta.span)? else {
+        let Some((body, binding_modes)) = self.t_ctx.get_mir(rust_id, item_meta.span)? else {
             return Ok(Err(Opaque));
         };
 
+        if self.t_ctx.options.mark_unsafe {
+            self.unsafe_spans = unsafe_block_spans(self.t_ctx.tcx, rust_id);
+        }
+
         // Initialize the local variables
         trace!("Translating the body locals");
         self.locals.arg_count = sig.inputs.len();
-        self.translate_body_locals(&body)?;
+        self.translate_body_locals(&body, &binding_modes)?;
 
         // Translate the expression body
         trace!("Translating the expression body");
@@ -1479,6 +1932,14 @@ impl<'tcx, 'ctx> BodyTransCtx<'tcx, 'ctx> {
             hax::Safety::Safe => false,
         };
 
+        let abi = match &def.kind {
+            hax::FullDefKind::Fn { .. } | hax::FullDefKind::AssocFn { .. } => {
+                let abi = self.t_ctx.tcx.fn_sig(def.rust_def_id()).skip_binder().abi();
+                (abi != rustc_target::spec::abi::Abi::Rust).then(|| abi.name().to_owned())
+            }
+            _ => None,
+        };
+
         let closure_info = match &def.kind {
             hax::FullDefKind::Closure { args, .. } => {
                 let kind = match args.kind {
@@ -1536,6 +1997,12 @@ impl<'tcx, 'ctx> BodyTransCtx<'tcx, 'ctx> {
             generics: self.the_only_binder().params.clone(),
             is_unsafe,
             is_closure: matches!(&def.kind, hax::FullDefKind::Closure { .. }),
+            has_self_param: self
+                .t_ctx
+                .tcx
+                .opt_associated_item(def.rust_def_id())
+                .is_some_and(|item| item.fn_has_self_parameter),
+            abi,
             closure_info,
             inputs,
             output,
@@ -1623,13 +2090,28 @@ impl BodyTransCtx<'_, '_> {
         self.translate_def_generics(span, def)?;
 
         // Retrieve the kind
-        let global_kind = self.get_item_kind(span, def)?;
+        let item_kind = self.get_item_kind(span, def)?;
 
         trace!("Translating global type");
-        let ty = match &def.kind {
-            hax::FullDefKind::Const { ty, .. }
-            | hax::FullDefKind::AssocConst { ty, .. }
-            | hax::FullDefKind::Static { ty, .. } => ty,
+        let (ty, global_kind) = match &def.kind {
+            hax::FullDefKind::Const { ty, .. } | hax::FullDefKind::AssocConst { ty, .. } => {
+                (ty, GlobalKind::NamedConst)
+            }
+            hax::FullDefKind::Static { ty, .. } => {
+                // `thread_local!` expands to a static marked with the (unstable) `#[thread_local]`
+                // attribute.
+                let is_thread_local = item_meta.attr_info.attributes.iter().any(
+                    |attr| matches!(attr, Attribute::Unknown(raw) if raw.path == "thread_local"),
+                );
+                let global_kind = if is_thread_local {
+                    GlobalKind::ThreadLocal
+                } else if self.t_ctx.tcx.is_mutable_static(rust_id) {
+                    GlobalKind::StaticMut
+                } else {
+                    GlobalKind::Static
+                };
+                (ty, global_kind)
+            }
             _ => panic!("Unexpected def for constant: {def:?}"),
         };
         let ty = self.translate_ty(span, ty)?;
@@ -1641,7 +2123,8 @@ impl BodyTransCtx<'_, '_> {
             item_meta,
             generics: self.into_generics(),
             ty,
-            kind: global_kind,
+            kind: item_kind,
+            global_kind,
             init: initializer,
         })
     }
@@ -249,7 +249,12 @@ impl<'tcx, 'ctx> BodyTransCtx<'tcx, 'ctx> {
                             index,
                             ..
                         } => {
-                            if !generic_args.is_empty() {
+                            // The clauses of lifetime GATs don't depend on the GAT arguments
+                            // since we erase them (see `translate_gat_clauses`).
+                            if !generic_args
+                                .iter()
+                                .all(|arg| matches!(arg, hax::GenericArg::Lifetime(_)))
+                            {
                                 raise_error!(
                                     self,
                                     span,
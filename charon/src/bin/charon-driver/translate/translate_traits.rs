@@ -76,6 +76,7 @@ impl BodyTransCtx<'_, '_> {
         let mut consts = Vec::new();
         let mut const_defaults = IndexMap::new();
         let mut types = Vec::new();
+        let mut generic_types = Vec::new();
         let mut type_clauses = Vec::new();
         let mut type_defaults = IndexMap::new();
         let mut methods = Vec::new();
@@ -150,14 +151,20 @@ impl BodyTransCtx<'_, '_> {
                 hax::FullDefKind::AssocTy { param_env, .. }
                     if !param_env.generics.params.is_empty() =>
                 {
-                    raise_error!(
-                        self,
+                    // TODO: translate the default values of GATs.
+                    if let Some(clauses) = self.item_trait_clauses.get(item_name) {
+                        type_clauses.push((item_name.clone(), clauses.clone()));
+                    }
+                    let binder_kind = BinderKind::TraitType(def_id, item_name.clone());
+                    let binder = self.translate_gat_binder(
                         item_span,
-                        "Generic associated types are not supported"
-                    );
+                        binder_kind,
+                        &param_env.generics,
+                        |_| Ok(()),
+                    )?;
+                    generic_types.push((item_name.clone(), binder.params));
                 }
                 hax::FullDefKind::AssocTy { value, .. } => {
-                    // TODO: handle generics (i.e. GATs).
                     if let Some(clauses) = self.item_trait_clauses.get(item_name) {
                         type_clauses.push((item_name.clone(), clauses.clone()));
                     }
@@ -183,6 +190,7 @@ impl BodyTransCtx<'_, '_> {
             consts,
             const_defaults,
             types,
+            generic_types,
             type_defaults,
             methods,
         })
@@ -245,6 +253,7 @@ impl BodyTransCtx<'_, '_> {
         // Explore the associated items
         let mut consts = Vec::new();
         let mut types: Vec<(TraitItemName, Ty)> = Vec::new();
+        let mut generic_types = Vec::new();
         let mut methods = Vec::new();
         let mut type_clauses = Vec::new();
 
@@ -334,10 +343,40 @@ impl BodyTransCtx<'_, '_> {
                     let gref = GlobalDeclRef { id, generics };
                     consts.push((name, gref));
                 }
-                hax::FullDefKind::AssocTy { param_env, .. }
-                    if !param_env.generics.params.is_empty() =>
-                {
-                    // We don't support GATs; the error was already reported in the trait declaration.
+                hax::FullDefKind::AssocTy {
+                    param_env, value, ..
+                } if !param_env.generics.params.is_empty() => {
+                    let ty = match &impl_item.value {
+                        Provided { .. } => value.as_ref().unwrap(),
+                        DefaultedTy { ty, .. } => ty,
+                        _ => unreachable!(),
+                    };
+                    let binder_kind = BinderKind::TraitType(trait_id, name.clone());
+                    let bound_ty = self.translate_gat_binder(
+                        item_span,
+                        binder_kind,
+                        &param_env.generics,
+                        |ctx| ctx.translate_ty(item_span, ty),
+                    )?;
+                    generic_types.push((name.clone(), bound_ty));
+
+                    // Like in the trait declaration, we erase the GAT parameters in its clauses.
+                    let bound_refs = self.translate_gat_binder(
+                        item_span,
+                        BinderKind::Other,
+                        &param_env.generics,
+                        |ctx| {
+                            ctx.translate_trait_impl_exprs(
+                                item_span,
+                                &impl_item.required_impl_exprs,
+                            )
+                        },
+                    )?;
+                    let bound_refs = RegionBinder {
+                        regions: bound_refs.params.regions,
+                        skip_binder: bound_refs.skip_binder,
+                    };
+                    type_clauses.push((name, bound_refs.erase()));
                 }
                 hax::FullDefKind::AssocTy { value, .. } => {
                     let ty = match &impl_item.value {
@@ -365,6 +404,7 @@ impl BodyTransCtx<'_, '_> {
             type_clauses,
             consts,
             types,
+            generic_types,
             methods,
         })
     }
@@ -5,9 +5,51 @@ use charon_lib::ast::*;
 use charon_lib::builtins;
 use charon_lib::common::hash_by_addr::HashByAddr;
 use charon_lib::ids::Vector;
+use charon_lib::options::{UnsupportedFeature, UnsupportedPolicy};
 use core::convert::*;
-use hax::Visibility;
+use hax::{HasOwnerIdSetter, Visibility};
 use hax_frontend_exporter as hax;
+use rustc_hir::def_id::DefId;
+use rustc_middle::ty::{self, TyCtxt};
+use std::collections::HashSet;
+
+/// Small helper: how the values of a struct or variant are built.
+fn translate_ctor_kind(ctor_kind: Option<rustc_hir::def::CtorKind>) -> CtorKind {
+    match ctor_kind {
+        None => CtorKind::Braced,
+        Some(rustc_hir::def::CtorKind::Fn) => CtorKind::Tuple,
+        Some(rustc_hir::def::CtorKind::Const) => CtorKind::Unit,
+    }
+}
+
+/// Small helper: translate the `#[repr(..)]` attributes of an ADT, if it has any.
+fn translate_repr(repr: ty::ReprOptions) -> Option<ReprInfo> {
+    use rustc_target::abi::{Integer, IntegerType};
+    let int = repr.int.map(|int| match int {
+        IntegerType::Pointer(true) => IntegerTy::Isize,
+        IntegerType::Pointer(false) => IntegerTy::Usize,
+        IntegerType::Fixed(int, signed) => match (int, signed) {
+            (Integer::I8, true) => IntegerTy::I8,
+            (Integer::I16, true) => IntegerTy::I16,
+            (Integer::I32, true) => IntegerTy::I32,
+            (Integer::I64, true) => IntegerTy::I64,
+            (Integer::I128, true) => IntegerTy::I128,
+            (Integer::I8, false) => IntegerTy::U8,
+            (Integer::I16, false) => IntegerTy::U16,
+            (Integer::I32, false) => IntegerTy::U32,
+            (Integer::I64, false) => IntegerTy::U64,
+            (Integer::I128, false) => IntegerTy::U128,
+        },
+    });
+    let repr = ReprInfo {
+        c: repr.c(),
+        transparent: repr.transparent(),
+        int,
+        align: repr.align.map(|align| align.bytes()),
+        pack: repr.pack.map(|pack| pack.bytes()),
+    };
+    (repr != ReprInfo::default()).then_some(repr)
+}
 
 /// Small helper: we ignore some region names (when they are equal to "'_")
 fn check_region_name(s: String) -> Option<String> {
@@ -18,6 +60,29 @@ fn check_region_name(s: String) -> Option<String> {
     }
 }
 
+/// Whether values of this type contain an `UnsafeCell` which isn't behind a pointer. We look
+/// through the fields of ADTs, including opaque ones, so that e.g. `Cell` is recognized.
+fn ty_has_interior_mut<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    ty: ty::Ty<'tcx>,
+    visited: &mut HashSet<ty::Ty<'tcx>>,
+) -> bool {
+    match ty.kind() {
+        ty::Adt(adt, _) if adt.is_unsafe_cell() => true,
+        ty::Adt(adt, args) => {
+            // The set of visited types guards against infinite recursion. We key it on the
+            // instantiated type, since e.g. `W<u32>` and `W<Cell<u32>>` differ.
+            visited.insert(ty)
+                && adt
+                    .all_fields()
+                    .any(|field| ty_has_interior_mut(tcx, field.ty(tcx, args), visited))
+        }
+        ty::Tuple(tys) => tys.iter().any(|ty| ty_has_interior_mut(tcx, ty, visited)),
+        ty::Array(ty, _) | ty::Slice(ty) => ty_has_interior_mut(tcx, *ty, visited),
+        _ => false,
+    }
+}
+
 pub fn translate_bound_region_kind_name(kind: &hax::BoundRegionKind) -> Option<String> {
     use hax::BoundRegionKind::*;
     let s = match kind {
@@ -125,7 +190,21 @@ impl<'tcx, 'ctx> BodyTransCtx<'tcx, 'ctx> {
                 } => {
                     let trait_ref = self.translate_trait_impl_expr(span, impl_expr)?;
                     let name = TraitItemName(assoc_item.name.clone());
-                    TyKind::TraitType(trait_ref, name)
+                    // The arguments of the projection start with the arguments of the trait; the
+                    // remaining ones are the arguments of the associated type itself (for GATs).
+                    let trait_args_len = impl_expr.r#trait.hax_skip_binder_ref().generic_args.len();
+                    let target = GenericsSource::TraitType(
+                        trait_ref.trait_decl_ref.skip_binder.trait_id,
+                        name.clone(),
+                    );
+                    let args = self.translate_generic_args(
+                        span,
+                        &alias.args[trait_args_len..],
+                        &[],
+                        None,
+                        target,
+                    )?;
+                    TyKind::TraitType(trait_ref, name, args)
                 }
                 hax::AliasKind::Opaque { hidden_ty, .. } => {
                     return self.translate_ty(span, hidden_ty)
@@ -270,7 +349,11 @@ impl<'tcx, 'ctx> BodyTransCtx<'tcx, 'ctx> {
 
             hax::TyKind::Coroutine(..) => {
                 trace!("Coroutine");
-                raise_error!(self, span, "Coroutine types are not supported yet")
+                return Err(self.unsupported(
+                    span,
+                    UnsupportedFeature::Coroutine,
+                    "Coroutine types are not supported yet",
+                ));
             }
 
             hax::TyKind::Bound(_, _) => {
@@ -374,10 +457,16 @@ impl<'tcx, 'ctx> BodyTransCtx<'tcx, 'ctx> {
     /// Checks whether the given id corresponds to a built-in type.
     fn recognize_builtin_type(&mut self, def_id: &hax::DefId) -> Result<Option<BuiltinTy>, Error> {
         let def = self.t_ctx.hax_def(def_id)?;
-        let ty = if def.lang_item.as_deref() == Some("owned_box") {
-            Some(BuiltinTy::Box)
-        } else {
-            None
+        let ty = match def.lang_item.as_deref() {
+            Some("owned_box") => Some(BuiltinTy::Box),
+            Some("unsafe_cell") => Some(BuiltinTy::UnsafeCell),
+            _ => match def.diagnostic_item.as_deref() {
+                Some("VecDeque") => Some(BuiltinTy::VecDeque),
+                Some("BTreeMap") => Some(BuiltinTy::BTreeMap),
+                Some("HashMap") => Some(BuiltinTy::HashMap),
+                Some("HashSet") => Some(BuiltinTy::HashSet),
+                _ => None,
+            },
         };
         Ok(ty)
     }
@@ -404,6 +493,7 @@ impl<'tcx, 'ctx> BodyTransCtx<'tcx, 'ctx> {
     fn translate_adt_def(
         &mut self,
         trans_id: TypeDeclId,
+        rust_id: DefId,
         def_span: Span,
         item_meta: &ItemMeta,
         adt: &hax::AdtDef,
@@ -416,6 +506,20 @@ impl<'tcx, 'ctx> BodyTransCtx<'tcx, 'ctx> {
 
         trace!("{}", trans_id);
 
+        // SIMD vectors get special treatment: we only record their element type and lane count.
+        let is_simd = item_meta.attr_info.attributes.iter().any(|attr| {
+            attr.as_unknown().is_some_and(|raw| {
+                raw.path == "repr"
+                    && raw
+                        .args
+                        .as_deref()
+                        .is_some_and(|args| args.split(',').any(|arg| arg.trim() == "simd"))
+            })
+        });
+        if is_simd {
+            return self.translate_simd_adt_def(def_span, adt);
+        }
+
         // In case the type is external, check if we should consider the type as
         // transparent (i.e., extract its body). If it is an enumeration, then yes
         // (because the variants of public enumerations are public, together with their
@@ -441,6 +545,7 @@ impl<'tcx, 'ctx> BodyTransCtx<'tcx, 'ctx> {
         }
 
         // The type is transparent: explore the variants
+        let rust_variants: Vec<_> = self.t_ctx.tcx.adt_def(rust_id).variants().iter().collect();
         let mut variants: Vector<VariantId, Variant> = Default::default();
         for (i, var_def) in adt.variants.iter().enumerate() {
             trace!("variant {i}: {var_def:?}");
@@ -487,12 +592,14 @@ impl<'tcx, 'ctx> BodyTransCtx<'tcx, 'ctx> {
             let variant_name = var_def.name.clone();
             let variant_full_def = self.t_ctx.hax_def(&var_def.def_id)?;
             let variant_attrs = self.t_ctx.translate_attr_info(&variant_full_def);
+            let ctor_kind = translate_ctor_kind(rust_variants[i].ctor_kind());
 
             let mut variant = Variant {
                 span: variant_span,
                 attr_info: variant_attrs,
                 name: variant_name,
                 fields,
+                ctor_kind,
                 discriminant,
             };
             // Propagate a `#[charon::variants_prefix(..)]` or `#[charon::variants_suffix(..)]` attribute to the variants.
@@ -531,6 +638,47 @@ impl<'tcx, 'ctx> BodyTransCtx<'tcx, 'ctx> {
         Ok(type_def_kind)
     }
 
+    /// Translate a `#[repr(simd)]` struct. These are either of the form `struct S([T; N])` or,
+    /// in the older style, `struct S(T, T, T, T)`.
+    fn translate_simd_adt_def(
+        &mut self,
+        def_span: Span,
+        adt: &hax::AdtDef,
+    ) -> Result<TypeDeclKind, Error> {
+        error_assert!(
+            self,
+            def_span,
+            matches!(adt.adt_kind, hax::AdtKind::Struct) && adt.variants.len() == 1
+        );
+        let field_tys: Vec<Ty> = adt.variants[0]
+            .fields
+            .iter()
+            .map(|field_def| self.translate_ty(def_span, &field_def.ty))
+            .try_collect()?;
+        let (elem_ty, lanes) = match field_tys.as_slice() {
+            [ty] if let TyKind::Adt(TypeId::Builtin(BuiltinTy::Array), generics) = ty.kind() => (
+                generics.types[0].clone(),
+                generics.const_generics[0].clone(),
+            ),
+            [ty, rest @ ..] if rest.iter().all(|other| other == ty) => {
+                let lanes = ScalarValue::Usize(field_tys.len() as u64);
+                (ty.clone(), ConstGeneric::Value(Literal::Scalar(lanes)))
+            }
+            _ => {
+                let feature = UnsupportedFeature::Simd;
+                if self.t_ctx.options.unsupported_policy(feature) == UnsupportedPolicy::Opaque {
+                    return Ok(TypeDeclKind::Opaque);
+                }
+                return Err(self.unsupported(
+                    def_span,
+                    feature,
+                    "Unsupported `#[repr(simd)]` layout",
+                ));
+            }
+        };
+        Ok(TypeDeclKind::Simd { elem_ty, lanes })
+    }
+
     fn translate_discriminant(
         &mut self,
         def_span: Span,
@@ -664,7 +812,7 @@ impl<'tcx, 'ctx> BodyTransCtx<'tcx, 'ctx> {
                 && include_assoc_ty_clauses
             {
                 // Also add the predicates on associated types.
-                // FIXME(gat): don't skip GATs.
+                // FIXME(gat): don't skip GATs with type or const parameters.
                 // FIXME: don't mix up implied and required predicates.
                 for (item, item_def) in items {
                     if let hax::FullDefKind::AssocTy {
@@ -672,14 +820,22 @@ impl<'tcx, 'ctx> BodyTransCtx<'tcx, 'ctx> {
                         implied_predicates,
                         ..
                     } = &item_def.kind
-                        && param_env.generics.params.is_empty()
                     {
                         let name = TraitItemName(item.name.clone());
-                        self.register_predicates(
-                            &implied_predicates,
-                            PredicateOrigin::TraitItem(name.clone()),
-                            &PredicateLocation::Item(name),
-                        )?;
+                        let origin = PredicateOrigin::TraitItem(name.clone());
+                        let location = PredicateLocation::Item(name.clone());
+                        if param_env.generics.params.is_empty() {
+                            self.register_predicates(&implied_predicates, origin, &location)?;
+                        } else if param_env
+                            .generics
+                            .params
+                            .iter()
+                            .all(|param| matches!(param.kind, hax::GenericParamDefKind::Lifetime))
+                        {
+                            self.translate_gat_clauses(span, &name, &param_env.generics, |ctx| {
+                                ctx.register_predicates(&implied_predicates, origin, &location)
+                            })?;
+                        }
                     }
                 }
             }
@@ -717,6 +873,33 @@ impl<'tcx, 'ctx> BodyTransCtx<'tcx, 'ctx> {
         Ok(())
     }
 
+    /// `impl Trait` in argument position introduces an anonymous type parameter, which rustc names
+    /// after its bounds, e.g. `impl Iterator<Item = u32>`. We name it after the trait instead, e.g.
+    /// `ImplIterator`, adding a number if that name is already taken.
+    fn synthetic_type_var_name(&self, rustc_name: &str) -> String {
+        let bounds = rustc_name.strip_prefix("impl ").unwrap_or(rustc_name);
+        // Skip a `for<'a>` binder.
+        let bounds = match bounds.strip_prefix("for<").and_then(|s| s.split_once("> ")) {
+            Some((_, bounds)) => bounds,
+            None => bounds,
+        };
+        let path: String = bounds
+            .chars()
+            .take_while(|c| c.is_alphanumeric() || *c == '_' || *c == ':')
+            .collect();
+        let trait_name = path.rsplit("::").next().unwrap_or_default();
+        let base = format!("Impl{trait_name}");
+        let params = &self.innermost_binder().params;
+        let is_taken = |name: &str| params.types.iter().any(|var| var.name == name);
+        let mut name = base.clone();
+        let mut i = 1;
+        while is_taken(&name) {
+            name = format!("{base}{i}");
+            i += 1;
+        }
+        name
+    }
+
     pub(crate) fn push_generic_param(&mut self, param: &hax::GenericParamDef) -> Result<(), Error> {
         match &param.kind {
             hax::GenericParamDefKind::Lifetime => {
@@ -726,21 +909,62 @@ impl<'tcx, 'ctx> BodyTransCtx<'tcx, 'ctx> {
                 };
                 let _ = self.innermost_binder_mut().push_early_region(region);
             }
-            hax::GenericParamDefKind::Type { .. } => {
+            hax::GenericParamDefKind::Type {
+                has_default,
+                synthetic,
+            } => {
+                let default = if *has_default {
+                    let span = self.def_span(&param.def_id);
+                    let def_id: DefId = (&param.def_id).into();
+                    let tcx = self.t_ctx.tcx;
+                    let ty = tcx.type_of(def_id).instantiate_identity();
+                    // Translate the default in the context of the item which declares it.
+                    let state = self
+                        .t_ctx
+                        .hax_state
+                        .clone()
+                        .with_owner_id(tcx.parent(def_id));
+                    let ty: hax::Ty = self.t_ctx.catch_sinto(&state, span, &ty)?;
+                    Some(self.translate_ty(span, &ty)?)
+                } else {
+                    None
+                };
+                let name = if *synthetic {
+                    self.synthetic_type_var_name(&param.name)
+                } else {
+                    param.name.clone()
+                };
                 let _ = self
                     .innermost_binder_mut()
-                    .push_type_var(param.index, param.name.clone());
+                    .push_type_var(param.index, name, default);
             }
-            hax::GenericParamDefKind::Const { ty, .. } => {
+            hax::GenericParamDefKind::Const {
+                ty, has_default, ..
+            } => {
                 let span = self.def_span(&param.def_id);
                 // The type should be primitive, meaning it shouldn't contain variables,
                 // non-primitive adts, etc. As a result, we can use an empty context.
                 let ty = self.translate_ty(span, ty)?;
+                let default = if *has_default {
+                    let def_id: DefId = (&param.def_id).into();
+                    let tcx = self.t_ctx.tcx;
+                    let value = tcx.const_param_default(def_id).instantiate_identity();
+                    let state = self
+                        .t_ctx
+                        .hax_state
+                        .clone()
+                        .with_owner_id(tcx.parent(def_id));
+                    let value: hax::ConstantExpr = self.t_ctx.catch_sinto(&state, span, &value)?;
+                    Some(self.translate_constant_expr_to_const_generic(span, &value)?)
+                } else {
+                    None
+                };
                 match ty.kind().as_literal() {
                     Some(ty) => self.innermost_binder_mut().push_const_generic_var(
                         param.index,
                         *ty,
                         param.name.clone(),
+                        default,
                     ),
                     None => raise_error!(
                         self,
@@ -787,7 +1011,7 @@ impl BodyTransCtx<'_, '_> {
             hax::FullDefKind::Struct { def, .. }
             | hax::FullDefKind::Enum { def, .. }
             | hax::FullDefKind::Union { def, .. } => {
-                self.translate_adt_def(trans_id, span, &item_meta, def)
+                self.translate_adt_def(trans_id, def.rust_def_id(), span, &item_meta, def)
             }
             _ => panic!("Unexpected item when translating types: {def:?}"),
         };
@@ -796,11 +1020,76 @@ impl BodyTransCtx<'_, '_> {
             Ok(kind) => kind,
             Err(err) => TypeDeclKind::Error(err.msg),
         };
+
+        // Link local ADTs to their destructor, if they implement `Drop`. We don't do it for
+        // foreign types to avoid pulling in the destructors of all the std types we encounter.
+        let drop_impl = match &def.kind {
+            hax::FullDefKind::Struct { .. }
+            | hax::FullDefKind::Enum { .. }
+            | hax::FullDefKind::Union { .. }
+                if item_meta.is_local =>
+            {
+                let tcx = self.t_ctx.tcx;
+                tcx.adt_destructor(def.rust_def_id())
+                    .map(|destructor| self.register_fun_decl_id(span, destructor.did))
+            }
+            _ => None,
+        };
+
+        let interior_mut = match &def.kind {
+            hax::FullDefKind::Struct { .. }
+            | hax::FullDefKind::Enum { .. }
+            | hax::FullDefKind::Union { .. } => {
+                let tcx = self.t_ctx.tcx;
+                let ty = tcx.type_of(def.rust_def_id()).instantiate_identity();
+                ty_has_interior_mut(tcx, ty, &mut HashSet::new())
+            }
+            _ => false,
+        };
+
+        let valid_range = match &def.kind {
+            hax::FullDefKind::Struct { .. } => {
+                let bound = |bound| match bound {
+                    std::ops::Bound::Included(x) => Some(x),
+                    _ => None,
+                };
+                let (start, end) = self.t_ctx.tcx.layout_scalar_valid_range(def.rust_def_id());
+                let range = ScalarValidRange {
+                    start: bound(start),
+                    end: bound(end),
+                };
+                (range.start.is_some() || range.end.is_some()).then_some(range)
+            }
+            _ => None,
+        };
+
+        let ctor_kind = match &def.kind {
+            hax::FullDefKind::Struct { .. } => {
+                let adt_def = self.t_ctx.tcx.adt_def(def.rust_def_id());
+                Some(translate_ctor_kind(adt_def.non_enum_variant().ctor_kind()))
+            }
+            _ => None,
+        };
+
+        let repr = match &def.kind {
+            hax::FullDefKind::Struct { .. }
+            | hax::FullDefKind::Enum { .. }
+            | hax::FullDefKind::Union { .. } => {
+                translate_repr(self.t_ctx.tcx.adt_def(def.rust_def_id()).repr())
+            }
+            _ => None,
+        };
+
         let type_def = TypeDecl {
             def_id: trans_id,
             item_meta,
             generics: self.into_generics(),
             kind,
+            drop_impl,
+            interior_mut,
+            valid_range,
+            ctor_kind,
+            repr,
         };
 
         Ok(type_def)
@@ -77,6 +77,12 @@ pub fn main() -> Result<()> {
         )
     }
 
+    toolchain::check_version(options.ignore_toolchain_mismatch)?;
+    if options.version_check {
+        println!("The toolchain matches the one Charon was built with.");
+        return Ok(());
+    }
+
     let cmd = toolchain::driver_cmd()?;
     let rustc_version = rustc_version::VersionMeta::for_command(cmd).unwrap_or_else(|err| {
         panic!("failed to determine underlying rustc version of Charon:\n{err:?}",)
@@ -85,7 +91,7 @@ pub fn main() -> Result<()> {
 
     let exit_status = if let Some(llbc_file) = options.read_llbc {
         let krate = charon_lib::deserialize_llbc(&llbc_file)?;
-        println!("{krate}");
+        println!("{}", krate.to_string_with_max_depth(options.print_depth));
         ExitStatus::default()
     } else if options.no_cargo {
         if !options.cargo_args.is_empty() {
@@ -109,6 +115,14 @@ pub fn main() -> Result<()> {
             cmd.arg(host);
         }
 
+        // Use the edition of the manifest of the input file if there is one, unless set
+        // explicitly.
+        if options.edition.is_none() && !is_specified("--edition") {
+            if let Some(input_file) = &options.input_file {
+                options.edition = toml_config::read_manifest_edition(input_file);
+            }
+        }
+
         // Extract rustc args and pass as cli args to charon-driver.
         // `Take` is needed, because charon-driver add options.rustc_args to compiled_args.
         cmd.args(std::mem::take(&mut options.rustc_args));
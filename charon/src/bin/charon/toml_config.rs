@@ -1,7 +1,7 @@
 //! Processing of the contents of a `Charon.toml` file.
 use charon_lib::options::CliOpts;
 use serde::Deserialize;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 /// The struct used to define the options available in `Charon.toml` files.
 #[derive(Debug, Deserialize)]
@@ -66,6 +66,29 @@ impl TomlConfig {
     }
 }
 
+/// Read the edition from the manifest of the package that contains `input_file`, i.e. the closest
+/// `Cargo.toml` in the ancestors of its directory, if there is one and it specifies an edition.
+pub(crate) fn read_manifest_edition(input_file: &Path) -> Option<String> {
+    #[derive(Deserialize)]
+    struct Manifest {
+        package: Option<Package>,
+    }
+    #[derive(Deserialize)]
+    struct Package {
+        edition: Option<String>,
+    }
+
+    let input_file = input_file.canonicalize().ok()?;
+    let manifest_path = input_file
+        .ancestors()
+        .skip(1)
+        .map(|dir| dir.join("Cargo.toml"))
+        .find(|path| path.is_file())?;
+    let contents = std::fs::read_to_string(manifest_path).ok()?;
+    let manifest: Manifest = toml::from_str(&contents).ok()?;
+    manifest.package?.edition
+}
+
 /// Read `./Charon.toml` if there is such a file.
 pub(crate) fn read_toml() -> Option<TomlConfig> {
     trace!("Reading options from the `Charon.toml` file");
@@ -4,6 +4,9 @@ use std::{env, ffi::OsStr, path::PathBuf, process::Command};
 
 // Store the toolchain details directly in the binary.
 static PINNED_TOOLCHAIN: &str = include_str!("../../../rust-toolchain");
+// The version of the rustc Charon was built with; set by our build script.
+static BUILT_WITH_RELEASE: &str = env!("CHARON_RUSTC_RELEASE");
+static BUILT_WITH_COMMIT_HASH: &str = env!("CHARON_RUSTC_COMMIT_HASH");
 
 /// This struct is used to deserialize the "rust-toolchain" file.
 #[derive(Deserialize)]
@@ -106,3 +109,28 @@ pub fn driver_cmd() -> Result<Command> {
     cmd.arg("rustc");
     Ok(cmd)
 }
+
+/// Check that the rustc of the toolchain we are about to use is the one Charon was built with.
+/// Otherwise the driver typically fails much later with a cryptic error when decoding the
+/// metadata of the compiled dependencies. If `ignore_mismatch` is set, a mismatch is only a
+/// warning.
+pub fn check_version(ignore_mismatch: bool) -> Result<()> {
+    let cmd = in_toolchain("rustc")?;
+    let version = rustc_version::VersionMeta::for_command(cmd)?;
+    let release = version.semver.to_string();
+    let commit_hash = version.commit_hash.unwrap_or_default();
+    if release == BUILT_WITH_RELEASE && commit_hash == BUILT_WITH_COMMIT_HASH {
+        trace!("Toolchain matches: rustc {release} ({commit_hash})");
+        return Ok(());
+    }
+    let msg = format!(
+        "Toolchain mismatch: Charon was built with rustc {BUILT_WITH_RELEASE} \
+        ({BUILT_WITH_COMMIT_HASH}) but the toolchain in use has rustc {release} ({commit_hash})."
+    );
+    if ignore_mismatch {
+        warn!("{msg}");
+        Ok(())
+    } else {
+        bail!("{msg}\nUse `--ignore-toolchain-mismatch` to proceed anyway.")
+    }
+}
@@ -500,6 +500,7 @@ fn type_decl_to_json_deserializer(ctx: &GenerateCtx, decl: &TypeDecl) -> String
                 .join("\n")
         }
         TypeDeclKind::Union(..) => todo!(),
+        TypeDeclKind::Simd { .. } => todo!(),
         TypeDeclKind::Opaque => todo!(),
         TypeDeclKind::Error(_) => todo!(),
     };
@@ -682,6 +683,7 @@ fn type_decl_to_ocaml_decl(ctx: &GenerateCtx, decl: &TypeDecl, co_rec: bool) ->
                 .join("")
         }
         TypeDeclKind::Union(..) => todo!(),
+        TypeDeclKind::Simd { .. } => todo!(),
         TypeDeclKind::Opaque => todo!(),
         TypeDeclKind::Error(_) => todo!(),
     };
@@ -991,7 +993,6 @@ fn generate_ml(
         // Handwritten because we use `indexed_var` as a hack to be able to reuse field names.
         // TODO: remove the need for this hack.
         ("RegionVar", "(region_id, string option) indexed_var"),
-        ("TypeVar", "(type_var_id, string) indexed_var"),
     ];
     let manual_json_impls = &[
         // Hand-written because we filter out `None` values.
@@ -1051,11 +1052,11 @@ fn generate_ml(
                       list_of_json statement_of_json ctx statements
                     in
                     match List.rev statements with
-                    | [] -> Ok { span; content = Nop; comments_before = [] }
+                    | [] -> Ok { span; content = Nop; comments_before = []; annotations = [] }
                     | last :: rest ->
                         let seq =
                           List.fold_left
-                            (fun acc st -> { span = st.span; content = Sequence (st, acc); comments_before = [] })
+                            (fun acc st -> { span = st.span; content = Sequence (st, acc); comments_before = []; annotations = [] })
                             last rest
                         in
                         Ok seq
@@ -1170,6 +1171,7 @@ fn generate_ml(
                     extra_types: &[],
                 })), &[
                     "Literal",
+                    "LiteralRange",
                     "IntegerTy",
                     "LiteralTy",
                 ]),
@@ -1246,6 +1248,7 @@ fn generate_ml(
                 })), &[
                     "Call",
                     "Assert",
+                    "Annotation",
                     "ItemKind",
                     "Locals",
                     "FunSig",
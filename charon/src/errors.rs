@@ -1,13 +1,16 @@
 //! Utilities to generate error reports about the external dependencies.
 use crate::ast::*;
 use crate::formatter::{Formatter, IntoFormatter};
+use crate::options::ErrorFormat;
 pub use annotate_snippets::Level;
 use itertools::Itertools;
 use macros::VariantIndexArity;
 use petgraph::algo::dijkstra::dijkstra;
 use petgraph::prelude::DiGraphMap;
+use serde::Serialize;
 use std::cmp::{Ord, PartialOrd};
 use std::collections::{HashMap, HashSet};
+use std::io::Write;
 
 #[macro_export]
 macro_rules! register_error {
@@ -113,6 +116,50 @@ impl Error {
     }
 }
 
+/// A diagnostic, as emitted with `--error-format json`. Each diagnostic is printed as a single
+/// line of JSON.
+#[derive(Debug, Serialize)]
+pub struct Diagnostic {
+    /// `error`, `warning` or `note`.
+    pub level: String,
+    pub message: String,
+    pub span: Option<DiagnosticSpan>,
+    /// Identifies the kind of diagnostic, for those that have one.
+    pub code: Option<&'static str>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct DiagnosticSpan {
+    pub file: String,
+    pub beg: Loc,
+    pub end: Loc,
+}
+
+impl Diagnostic {
+    pub fn new(
+        krate: &TranslatedCrate,
+        span: Option<Span>,
+        level: Level,
+        message: String,
+        code: Option<&'static str>,
+    ) -> Self {
+        let span = span.map(|span| span.span).and_then(|span| {
+            let file = krate.files.get(span.file_id)?;
+            Some(DiagnosticSpan {
+                file: file.name.to_string(),
+                beg: span.beg,
+                end: span.end,
+            })
+        });
+        Diagnostic {
+            level: format!("{level:?}").to_lowercase(),
+            message,
+            span,
+            code,
+        }
+    }
+}
+
 /// We use this to save the origin of an id. This is useful for the external
 /// dependencies, especially if some external dependencies don't extract:
 /// we use this information to tell the user what is the code which
@@ -176,11 +223,19 @@ pub struct ErrorCtx {
     pub continue_on_failure: bool,
     /// If true, print the warnings as errors, and abort if any errors were raised.
     pub error_on_warnings: bool,
+    /// How to emit the diagnostics.
+    pub error_format: ErrorFormat,
+    /// Where to write the json diagnostics. Defaults to stderr.
+    pub error_output: Option<Box<dyn Write>>,
+    /// Stop once this many items failed to translate.
+    pub max_failed_items: Option<usize>,
 
     /// The ids of the external_declarations for which extraction we encountered errors.
     pub external_decls_with_errors: HashSet<AnyTransId>,
     /// The ids of the declarations we completely failed to extract and had to ignore.
     pub ignored_failed_decls: HashSet<AnyTransId>,
+    /// The ids of all the declarations for which we encountered errors, local or not.
+    pub decls_with_errors: HashSet<AnyTransId>,
     /// Graph of dependencies between items: there is an edge from item `a` to item `b` if `b`
     /// registered the id for `a` during its translation. Because we only use this to report errors
     /// on external items, we only record edges where `a` is an external item.
@@ -191,19 +246,39 @@ pub struct ErrorCtx {
     pub def_id_is_local: bool,
     /// The number of errors encountered so far.
     pub error_count: usize,
+    /// The number of warnings that don't make any item fail, see [ErrorCtx::span_warn].
+    pub warning_count: usize,
+    /// Whether `max_failed_items` items failed to translate. We then stop as soon as possible.
+    pub reached_max_failed_items: bool,
+    /// The number of errors that make the translation fail even without `--error-on-warnings`,
+    /// namely the unsupported features whose policy is `error` (see `--on-unsupported`).
+    pub hard_error_count: usize,
 }
 
 impl ErrorCtx {
-    pub fn new(continue_on_failure: bool, error_on_warnings: bool) -> Self {
+    pub fn new(
+        continue_on_failure: bool,
+        error_on_warnings: bool,
+        error_format: ErrorFormat,
+        error_output: Option<Box<dyn Write>>,
+        max_failed_items: Option<usize>,
+    ) -> Self {
         Self {
             continue_on_failure,
             error_on_warnings,
+            error_format,
+            error_output,
+            max_failed_items,
             external_decls_with_errors: HashSet::new(),
             ignored_failed_decls: HashSet::new(),
+            decls_with_errors: HashSet::new(),
             external_dep_graph: DepGraph::new(),
             def_id: None,
             def_id_is_local: false,
             error_count: 0,
+            warning_count: 0,
+            reached_max_failed_items: false,
+            hard_error_count: 0,
         }
     }
 
@@ -214,16 +289,34 @@ impl ErrorCtx {
         self.error_count > 0
     }
 
+    /// Emit a diagnostic in the json format.
+    fn emit_json(&mut self, diagnostic: &Diagnostic) {
+        let line = serde_json::to_string(diagnostic).unwrap();
+        match &mut self.error_output {
+            Some(out) => {
+                // There isn't much we can do if writing the diagnostic fails.
+                let _ = writeln!(out, "{line}").and_then(|()| out.flush());
+            }
+            None => eprintln!("{line}"),
+        }
+    }
+
     /// Report an error without registering anything.
     pub fn display_error(
-        &self,
+        &mut self,
         krate: &TranslatedCrate,
         span: Span,
         level: Level,
         msg: String,
     ) -> Error {
         let error = Error { span, msg };
-        anstream::eprintln!("{}\n", error.render(krate, level));
+        match self.error_format {
+            ErrorFormat::Human => anstream::eprintln!("{}\n", error.render(krate, level)),
+            ErrorFormat::Json => {
+                let diagnostic = Diagnostic::new(krate, Some(span), level, error.msg.clone(), None);
+                self.emit_json(&diagnostic);
+            }
+        }
         error
     }
 
@@ -242,6 +335,14 @@ impl ErrorCtx {
         };
         let err = self.display_error(krate, span, level, msg.to_string());
         self.error_count += 1;
+        if let Some(id) = self.def_id {
+            self.decls_with_errors.insert(id);
+        }
+        if let Some(max) = self.max_failed_items
+            && self.decls_with_errors.len() >= max
+        {
+            self.reached_max_failed_items = true;
+        }
         // If this item comes from an external crate, after the first error for that item we
         // display where in the local crate that item was reached from.
         if !self.def_id_is_local
@@ -256,10 +357,54 @@ impl ErrorCtx {
         err
     }
 
+    /// Report a warning about something we could translate anyway. Unlike [Self::span_err], this
+    /// doesn't count as an error and doesn't make the current item fail.
+    pub fn span_warn(&mut self, krate: &TranslatedCrate, span: Span, msg: &str) {
+        let level = if self.error_on_warnings {
+            Level::Error
+        } else {
+            Level::Warning
+        };
+        self.display_error(krate, span, level, msg.to_string());
+        self.warning_count += 1;
+    }
+
     pub fn ignore_failed_decl(&mut self, id: AnyTransId) {
         self.ignored_failed_decls.insert(id);
     }
 
+    /// Update the item ids we keep track of, after the items got renumbered.
+    pub fn rename_ids(&mut self, rename: impl Fn(AnyTransId) -> AnyTransId) {
+        let rename_node = |node: DepNode| match node {
+            DepNode::External(id) => DepNode::External(rename(id)),
+            DepNode::Local(id, span) => DepNode::Local(rename(id), span),
+        };
+        let mut graph = DepGraph::new();
+        for (from, to, _) in self.external_dep_graph.dgraph.all_edges() {
+            graph.insert_edge(rename_node(from), rename_node(to));
+        }
+        for node in self.external_dep_graph.dgraph.nodes() {
+            graph.insert_node(rename_node(node));
+        }
+        self.external_dep_graph = graph;
+        self.external_decls_with_errors = self
+            .external_decls_with_errors
+            .iter()
+            .map(|id| rename(*id))
+            .collect();
+        self.ignored_failed_decls = self
+            .ignored_failed_decls
+            .iter()
+            .map(|id| rename(*id))
+            .collect();
+        self.decls_with_errors = self
+            .decls_with_errors
+            .iter()
+            .map(|id| rename(*id))
+            .collect();
+        self.def_id = self.def_id.map(&rename);
+    }
+
     /// Register the fact that `id` is a dependency of `src` (if `src` is not `None`).
     pub fn register_dep_source(
         &mut self,
@@ -285,7 +430,7 @@ impl ErrorCtx {
     /// In case errors happened when extracting the definitions coming from the external
     /// dependencies, print a detailed report to explain to the user which dependencies were
     /// problematic, and where they are used in the code.
-    pub fn report_external_dep_error(&self, krate: &TranslatedCrate, id: AnyTransId) {
+    pub fn report_external_dep_error(&mut self, krate: &TranslatedCrate, id: AnyTransId) {
         use annotate_snippets::*;
 
         // Use `Dijkstra's` algorithm to find the local items reachable from the current non-local
@@ -335,6 +480,26 @@ impl ErrorCtx {
              which is (transitively) used at the following location(s):",
             krate.into_fmt().format_object(id)
         );
+        if self.error_format == ErrorFormat::Json {
+            // Emit one note per location.
+            let diagnostics = by_file
+                .iter()
+                .flat_map(|(_, _, _, spans)| spans)
+                .map(|span| {
+                    Diagnostic::new(
+                        krate,
+                        Some(*span),
+                        level,
+                        msg.clone(),
+                        Some("external-dependency"),
+                    )
+                })
+                .collect_vec();
+            for diagnostic in diagnostics {
+                self.emit_json(&diagnostic);
+            }
+            return;
+        }
         let message = level.title(&msg).snippets(snippets);
         let out = Renderer::styled().render(message).to_string();
         anstream::eprintln!("{}", out);
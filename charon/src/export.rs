@@ -2,8 +2,14 @@ use crate::ast::*;
 use crate::transform::TransformCtx;
 use serde::{Deserialize, Deserializer, Serialize};
 use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::mem;
 use std::path::Path;
 
+/// The parts written by `--split-by-kind`, in the order in which we write them. The first one
+/// contains everything except the item declarations.
+pub const SPLIT_PARTS: &[&str] = &["index", "types", "funs", "globals", "traits"];
+
 /// The data of a generic crate. We serialize this to pass it to `charon-ml`, so this must be as
 /// stable as possible. This is used for both ULLBC and LLBC.
 #[derive(Serialize, Deserialize)]
@@ -14,17 +20,202 @@ pub struct CrateData {
     #[serde(deserialize_with = "ensure_version")]
     pub charon_version: String,
     pub translated: TranslatedCrate,
+    /// With `--group-by-module`, the ids of the items nested under the modules that define them.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub modules: Option<ModuleGroup>,
     #[serde(skip)]
     /// If there were errors, this contains only a partial description of the input crate.
     pub has_errors: bool,
 }
 
+/// The items defined in a module, and its submodules. The root group has an empty path and has
+/// one submodule per crate.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct ModuleGroup {
+    /// The path of the module, e.g. `["test_crate", "foo", "bar"]`.
+    pub path: Vec<String>,
+    /// The items defined directly in this module.
+    pub items: Vec<AnyTransId>,
+    /// The submodules that contain items.
+    pub modules: Vec<ModuleGroup>,
+}
+
+impl ModuleGroup {
+    /// Group the items of the crate by the module that defines them. We compute the module from
+    /// the name of the item: items defined inside a function are grouped under that function.
+    pub fn of_crate(krate: &TranslatedCrate) -> Self {
+        let mut root = ModuleGroup::default();
+        for (id, item) in krate.all_items_with_ids() {
+            let name = &item.item_meta().name.name;
+            let mut path = name
+                .iter()
+                .map_while(|elem| elem.as_ident().map(|(ident, _)| ident.clone()))
+                .collect::<Vec<_>>();
+            if path.len() == name.len() {
+                // The last element is the item itself.
+                path.pop();
+                // The methods and associated constants of a trait are named after the trait.
+                let in_trait = match item {
+                    AnyTransItem::Fun(decl) => matches!(decl.kind, ItemKind::TraitDecl { .. }),
+                    AnyTransItem::Global(decl) => matches!(decl.kind, ItemKind::TraitDecl { .. }),
+                    _ => false,
+                };
+                if in_trait {
+                    path.pop();
+                }
+            }
+            root.insert(&path, id);
+        }
+        root
+    }
+
+    fn insert(&mut self, module: &[String], id: AnyTransId) {
+        if self.path.len() == module.len() {
+            self.items.push(id);
+            return;
+        }
+        let sub_path = &module[..self.path.len() + 1];
+        let i = match self.modules.iter().position(|sub| sub.path == sub_path) {
+            Some(i) => i,
+            None => {
+                self.modules.push(ModuleGroup {
+                    path: sub_path.to_vec(),
+                    ..ModuleGroup::default()
+                });
+                self.modules.len() - 1
+            }
+        };
+        self.modules[i].insert(module, id)
+    }
+}
+
 impl CrateData {
     pub fn new(ctx: TransformCtx) -> Self {
         CrateData {
             charon_version: crate::VERSION.to_owned(),
             has_errors: ctx.has_errors(),
             translated: ctx.translated,
+            modules: None,
+        }
+    }
+
+    /// Record the items of the crate grouped by module, for `--group-by-module`.
+    pub fn group_by_module(&mut self) {
+        self.modules = Some(ModuleGroup::of_crate(&self.translated));
+    }
+
+    /// Take the copy of the crate saved before control-flow reconstruction by
+    /// [crate::transform::save_ullbc], if any.
+    pub fn take_ullbc_snapshot(ctx: &mut TransformCtx) -> Option<Self> {
+        let mut translated = ctx.ullbc_snapshot.take()?;
+        // The declaration groups are computed after control-flow reconstruction. The two crates
+        // have the same items with the same ids, so we can share them.
+        translated.ordered_decls = ctx.translated.ordered_decls.clone();
+        Some(CrateData {
+            charon_version: crate::VERSION.to_owned(),
+            has_errors: ctx.has_errors(),
+            translated,
+            modules: None,
+        })
+    }
+
+    /// Split the crate by item kind, following [SPLIT_PARTS]. The index keeps the names of all
+    /// the items and the declaration order; each other part only contains the declarations of
+    /// one kind. Since a `Vector` serializes its empty slots, item ids don't change and the parts
+    /// can be merged back with [CrateData::merge_split].
+    pub fn split_by_kind(mut self) -> Vec<(&'static str, CrateData)> {
+        let mut index = self.translated;
+        let crate_name = index.crate_name.clone();
+        let empty = || TranslatedCrate {
+            crate_name: crate_name.clone(),
+            ..TranslatedCrate::default()
+        };
+        let types = TranslatedCrate {
+            type_decls: mem::take(&mut index.type_decls),
+            ..empty()
+        };
+        let funs = TranslatedCrate {
+            fun_decls: mem::take(&mut index.fun_decls),
+            ..empty()
+        };
+        let globals = TranslatedCrate {
+            global_decls: mem::take(&mut index.global_decls),
+            ..empty()
+        };
+        let traits = TranslatedCrate {
+            trait_decls: mem::take(&mut index.trait_decls),
+            trait_impls: mem::take(&mut index.trait_impls),
+            ..empty()
+        };
+        [index, types, funs, globals, traits]
+            .into_iter()
+            .zip(SPLIT_PARTS)
+            .map(|(translated, kind)| {
+                let part = CrateData {
+                    charon_version: self.charon_version.clone(),
+                    translated,
+                    // The index keeps the module groups, if any.
+                    modules: self.modules.take(),
+                    has_errors: self.has_errors,
+                };
+                (*kind, part)
+            })
+            .collect()
+    }
+
+    /// Merge back the parts produced by [CrateData::split_by_kind].
+    pub fn merge_split(parts: impl IntoIterator<Item = CrateData>) -> Self {
+        let mut parts = parts.into_iter();
+        let mut merged = parts.next().expect("missing the index of a split crate");
+        for part in parts {
+            let krate = &mut merged.translated;
+            let part = part.translated;
+            if !part.type_decls.is_empty() {
+                krate.type_decls = part.type_decls;
+            }
+            if !part.fun_decls.is_empty() {
+                krate.fun_decls = part.fun_decls;
+            }
+            if !part.global_decls.is_empty() {
+                krate.global_decls = part.global_decls;
+            }
+            if !part.trait_decls.is_empty() {
+                krate.trait_decls = part.trait_decls;
+            }
+            if !part.trait_impls.is_empty() {
+                krate.trait_impls = part.trait_impls;
+            }
+        }
+        merged
+    }
+
+    /// Export the translated definitions to one JSON file per item kind in `target_dir`, named
+    /// `<kind>.<extension>`.
+    #[allow(clippy::result_unit_err)]
+    pub fn serialize_split_to_dir(self, target_dir: &Path, extension: &str) -> Result<(), ()> {
+        for (kind, part) in self.split_by_kind() {
+            part.serialize_to_file(&target_dir.join(format!("{kind}.{extension}")))?;
+        }
+        Ok(())
+    }
+
+    /// Export the translated definitions as JSON to stdout.
+    #[allow(clippy::result_unit_err)]
+    pub fn serialize_to_stdout(&self) -> Result<(), ()> {
+        let mut stdout = BufWriter::new(std::io::stdout().lock());
+        match serde_json::to_writer(&mut stdout, self) {
+            Ok(()) => {}
+            Err(err) => {
+                error!("Could not write to stdout: {err:?}");
+                return Err(());
+            }
+        }
+        match stdout.flush() {
+            Ok(()) => Ok(()),
+            Err(err) => {
+                error!("Could not write to stdout: {err:?}");
+                Err(())
+            }
         }
     }
 
@@ -33,6 +33,7 @@ pub mod ast;
 pub mod common;
 pub mod errors;
 pub mod export;
+pub mod llbc_text;
 pub mod name_matcher;
 pub mod options;
 pub mod pretty;
@@ -48,6 +49,25 @@ const VERSION: &str = env!("CARGO_PKG_VERSION");
 
 /// Read a `.llbc` file.
 pub fn deserialize_llbc(path: &std::path::Path) -> anyhow::Result<ast::TranslatedCrate> {
+    Ok(read_crate_data(path)?.translated)
+}
+
+/// Read the directory of `.llbc` files written by `--split-by-kind`.
+pub fn deserialize_split_llbc(dir: &std::path::Path) -> anyhow::Result<ast::TranslatedCrate> {
+    use crate::export::{CrateData, SPLIT_PARTS};
+    let extension = if dir.join("index.ullbc").exists() {
+        "ullbc"
+    } else {
+        "llbc"
+    };
+    let parts = SPLIT_PARTS
+        .iter()
+        .map(|kind| read_crate_data(&dir.join(format!("{kind}.{extension}"))))
+        .collect::<anyhow::Result<Vec<_>>>()?;
+    Ok(CrateData::merge_split(parts).translated)
+}
+
+fn read_crate_data(path: &std::path::Path) -> anyhow::Result<export::CrateData> {
     use crate::export::CrateData;
     use anyhow::Context;
     use serde::Deserialize;
@@ -61,5 +81,5 @@ pub fn deserialize_llbc(path: &std::path::Path) -> anyhow::Result<ast::Translate
     deserializer.disable_recursion_limit();
     // Grow stack space as needed.
     let deserializer = serde_stacker::Deserializer::new(&mut deserializer);
-    Ok(CrateData::deserialize(deserializer)?.translated)
+    Ok(CrateData::deserialize(deserializer)?)
 }
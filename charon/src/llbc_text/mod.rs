@@ -0,0 +1,133 @@
+//! A textual syntax for LLBC, meant to be read and written by humans (e.g. for golden tests or
+//! to hand-edit extracted code). [print_crate] turns a [TranslatedCrate] into text, and
+//! [parse_crate] reads it back; `.llbc.txt` files written by one can be read by the other.
+//!
+//! Only a subset of LLBC is supported for now: type declarations and functions with structured
+//! bodies. The printer errors on anything else, e.g. trait clauses, globals, trait method calls or
+//! array projections. Source locations, comments, attributes and other metadata are not recorded.
+//!
+//! A crate looks like this:
+//! ```text
+//! crate test_crate;
+//!
+//! pub struct test_crate::Pair<T> {
+//!     pub a: T,
+//!     b: u32,
+//! }
+//!
+//! enum test_crate::E {
+//!     A = 0isize,
+//!     B(u32, bool) = 1isize,
+//! }
+//!
+//! fn test_crate::first<'_0, T>(&'_0 test_crate::Pair<T>) -> &'_0 T {
+//!     let @0: &'_ T;
+//!     let p@1: &'_ test_crate::Pair<T>;
+//!
+//!     @0 := &(*p@1).0;
+//!     return;
+//! }
+//!
+//! fn test_crate::get(test_crate::E) -> u32 {
+//!     let @0: u32;
+//!     let e@1: test_crate::E;
+//!
+//!     match e@1 {
+//!         B => {
+//!             @0 := copy (e@1 as B).0;
+//!         }
+//!         _ => {
+//!             @0 := const 0u32;
+//!         }
+//!     }
+//!     return;
+//! }
+//! ```
+//! Items are referred to by their name and locals by their index, like in the pretty-printed
+//! output. Types come before functions, and a function body may only use the types declared
+//! before it. Each item starts on a new line. `//` comments are allowed anywhere.
+//!
+//! Generic variables are parsed as [DeBruijnVar::Free], like in the serialized crate. The regions
+//! of the local variables and places are erased, which we write `'_`.
+use crate::ast::*;
+
+mod parser;
+mod printer;
+
+pub use parser::parse_crate;
+pub use printer::print_crate;
+
+/// The binary operators with their syntax. An operator comes before the operators whose syntax is
+/// a prefix of its own.
+const BINOPS: [(BinOp, &str); 22] = [
+    (BinOp::CheckedAdd, "checked.+"),
+    (BinOp::CheckedSub, "checked.-"),
+    (BinOp::CheckedMul, "checked.*"),
+    (BinOp::WrappingAdd, "wrapping.+"),
+    (BinOp::WrappingSub, "wrapping.-"),
+    (BinOp::WrappingMul, "wrapping.*"),
+    (BinOp::Shl, "<<"),
+    (BinOp::Shr, ">>"),
+    (BinOp::Le, "<="),
+    (BinOp::Ge, ">="),
+    (BinOp::Eq, "=="),
+    (BinOp::Ne, "!="),
+    (BinOp::Lt, "<"),
+    (BinOp::Gt, ">"),
+    (BinOp::Add, "+"),
+    (BinOp::Sub, "-"),
+    (BinOp::Mul, "*"),
+    (BinOp::Div, "/"),
+    (BinOp::Rem, "%"),
+    (BinOp::BitXor, "^"),
+    (BinOp::BitAnd, "&"),
+    (BinOp::BitOr, "|"),
+];
+
+/// The text that identifies an item. Impl blocks have no syntax; the printer rejects them.
+fn path_key(name: &Name) -> String {
+    name.name
+        .iter()
+        .map(|elem| match elem {
+            PathElem::Ident(s, d) if d.index() == 0 => s.clone(),
+            PathElem::Ident(s, d) => format!("{s}#{}", d.index()),
+            PathElem::Impl(..) => "{impl}".to_owned(),
+        })
+        .collect::<Vec<_>>()
+        .join("::")
+}
+
+fn literal_ty_name(ty: LiteralTy) -> String {
+    match ty {
+        LiteralTy::Integer(ty) => ty.to_string(),
+        LiteralTy::Float(ty) => ty.to_string(),
+        LiteralTy::Bool => "bool".to_owned(),
+        LiteralTy::Char => "char".to_owned(),
+    }
+}
+
+fn literal_ty_from_name(name: &str) -> Option<LiteralTy> {
+    use IntegerTy::*;
+    let int = |ty| Some(LiteralTy::Integer(ty));
+    match name {
+        "isize" => int(Isize),
+        "i8" => int(I8),
+        "i16" => int(I16),
+        "i32" => int(I32),
+        "i64" => int(I64),
+        "i128" => int(I128),
+        "usize" => int(Usize),
+        "u8" => int(U8),
+        "u16" => int(U16),
+        "u32" => int(U32),
+        "u64" => int(U64),
+        "u128" => int(U128),
+        "f16" => Some(LiteralTy::Float(FloatTy::F16)),
+        "f32" => Some(LiteralTy::Float(FloatTy::F32)),
+        "f64" => Some(LiteralTy::Float(FloatTy::F64)),
+        "f128" => Some(LiteralTy::Float(FloatTy::F128)),
+        "bool" => Some(LiteralTy::Bool),
+        "char" => Some(LiteralTy::Char),
+        _ => None,
+    }
+}
@@ -0,0 +1,1218 @@
+use std::collections::HashMap;
+
+use nom::{
+    branch::alt,
+    bytes::complete::{tag, take_while, take_while1},
+    character::complete::{anychar, char, digit1, hex_digit1, multispace1, satisfy},
+    combinator::{map_res, not, opt, recognize, value},
+    error::{ErrorKind, ParseError},
+    multi::{many0, separated_list0, separated_list1},
+    sequence::{delimited, pair, preceded, terminated},
+};
+use nom_supreme::{
+    error::{BaseErrorKind, ErrorTree},
+    final_parser::{final_parser, Location},
+};
+
+use super::{literal_ty_from_name, path_key, BINOPS};
+use crate::ast::*;
+use crate::llbc_ast::*;
+
+type ParseResult<'a, T> = nom::IResult<&'a str, T, ErrorTree<&'a str>>;
+
+/// Parse a crate written in the textual LLBC syntax.
+pub fn parse_crate(text: &str) -> anyhow::Result<TranslatedCrate> {
+    let mut parser = CrateParser::default();
+    // Assign ids to all the items first, so that they can refer to each other in any order.
+    for line in text.lines() {
+        let Ok((_, (is_fun, name))) = item_header(line) else {
+            continue;
+        };
+        let key = path_key(&name);
+        let fresh = if is_fun {
+            let id = parser.krate.fun_decls.reserve_slot();
+            parser.fun_ids.insert(key.clone(), id).is_none()
+        } else {
+            let id = parser.krate.type_decls.reserve_slot();
+            parser.type_ids.insert(key.clone(), id).is_none()
+        };
+        if !fresh {
+            anyhow::bail!("`{key}` is defined twice")
+        }
+    }
+    final_parser(|i| parser.krate(i))(text).map_err(|e: ErrorTree<&str>| {
+        let e = e.map_locations(|tail| Location::locate_tail(text, tail));
+        anyhow::anyhow!("Failed to parse textual LLBC: {e}")
+    })?;
+
+    let mut krate = parser.krate;
+    for decl in &krate.type_decls {
+        krate.all_ids.insert(decl.def_id.into());
+        krate
+            .item_names
+            .insert(decl.def_id.into(), decl.item_meta.name.clone());
+    }
+    for decl in &krate.fun_decls {
+        krate.all_ids.insert(decl.def_id.into());
+        krate
+            .item_names
+            .insert(decl.def_id.into(), decl.item_meta.name.clone());
+    }
+    Ok(krate)
+}
+
+/// An error that stops the parsing, e.g. a reference to an unknown item.
+fn failure(i: &str, msg: String) -> nom::Err<ErrorTree<&str>> {
+    nom::Err::Failure(ErrorTree::Base {
+        location: i,
+        kind: BaseErrorKind::External(msg.into()),
+    })
+}
+
+fn fail<T>(i: &str, msg: String) -> ParseResult<'_, T> {
+    Err(failure(i, msg))
+}
+
+/// Turn a recoverable error into `None`, so that we can try something else.
+fn try_parse<T>(res: ParseResult<'_, T>) -> Result<Option<(&str, T)>, nom::Err<ErrorTree<&str>>> {
+    match res {
+        Ok(res) => Ok(Some(res)),
+        Err(nom::Err::Error(_)) => Ok(None),
+        Err(e) => Err(e),
+    }
+}
+
+/// Whitespace and comments.
+fn ws(i: &str) -> ParseResult<'_, ()> {
+    let comment = recognize(pair(tag("//"), take_while(|c| c != '\n')));
+    value((), many0(alt((multispace1, comment))))(i)
+}
+
+fn tok<'a>(s: &'static str) -> impl FnMut(&'a str) -> ParseResult<'a, &'a str> {
+    move |i| preceded(ws, tag(s))(i)
+}
+
+fn is_ident_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
+
+/// A keyword. Locals are written `name@N`, so a keyword can't be followed by `@` either.
+fn kw<'a>(s: &'static str) -> impl FnMut(&'a str) -> ParseResult<'a, &'a str> {
+    move |i| {
+        preceded(
+            ws,
+            terminated(tag(s), not(satisfy(|c| is_ident_char(c) || c == '@'))),
+        )(i)
+    }
+}
+
+fn raw_ident(i: &str) -> ParseResult<'_, &str> {
+    recognize(pair(
+        satisfy(|c| c.is_alphabetic() || c == '_'),
+        take_while(is_ident_char),
+    ))(i)
+}
+
+fn ident(i: &str) -> ParseResult<'_, &str> {
+    preceded(ws, raw_ident)(i)
+}
+
+fn number(i: &str) -> ParseResult<'_, usize> {
+    map_res(digit1, |s: &str| s.parse::<usize>())(i)
+}
+
+/// A path like `crate::module::Type`, where the identifiers can have a disambiguator (`f#1`).
+fn path(i: &str) -> ParseResult<'_, Name> {
+    let elem = pair(raw_ident, opt(preceded(char('#'), number)));
+    let (i, elems) = preceded(ws, separated_list1(tag("::"), elem))(i)?;
+    let name = elems
+        .into_iter()
+        .map(|(s, d)| PathElem::Ident(s.to_owned(), Disambiguator::new(d.unwrap_or(0))))
+        .collect();
+    Ok((i, Name { name }))
+}
+
+/// `struct`, `fn`, etc. followed by the item name. Also returns whether this is a function.
+fn item_header(i: &str) -> ParseResult<'_, (bool, Name)> {
+    let (i, _) = opt(kw("pub"))(i)?;
+    let (i, is_fun) = alt((
+        value(true, pair(opt(kw("unsafe")), kw("fn"))),
+        value(false, pair(kw("opaque"), kw("type"))),
+        value(
+            false,
+            alt((kw("struct"), kw("enum"), kw("union"), kw("type"))),
+        ),
+    ))(i)?;
+    let (i, name) = path(i)?;
+    Ok((i, (is_fun, name)))
+}
+
+fn literal_ty(i: &str) -> ParseResult<'_, LiteralTy> {
+    let (rest, name) = ident(i)?;
+    match literal_ty_from_name(name) {
+        Some(ty) => Ok((rest, ty)),
+        None => fail(i, format!("`{name}` is not a literal type")),
+    }
+}
+
+fn char_literal(i: &str) -> ParseResult<'_, char> {
+    let (i, _) = char('\'')(i)?;
+    let (i, c) = anychar(i)?;
+    let (i, c) = if c == '\\' {
+        let (i, escape) = anychar(i)?;
+        match escape {
+            'n' => (i, '\n'),
+            'r' => (i, '\r'),
+            't' => (i, '\t'),
+            '0' => (i, '\0'),
+            '\\' | '\'' | '"' => (i, escape),
+            'u' => {
+                let (rest, code) = delimited(char('{'), hex_digit1, char('}'))(i)?;
+                match u32::from_str_radix(code, 16).ok().and_then(char::from_u32) {
+                    Some(c) => (rest, c),
+                    None => return fail(i, format!("invalid unicode escape `{code}`")),
+                }
+            }
+            _ => return fail(i, format!("unknown escape `\\{escape}`")),
+        }
+    } else {
+        (i, c)
+    };
+    let (i, _) = char('\'')(i)?;
+    Ok((i, c))
+}
+
+/// A literal: `true`, `'c'`, or a number with its type as suffix, e.g. `-1i32` or `0.5f64`.
+fn literal(i: &str) -> ParseResult<'_, Literal> {
+    let (i, _) = ws(i)?;
+    if let Some((i, b)) = try_parse(alt((value(true, kw("true")), value(false, kw("false"))))(i))? {
+        return Ok((i, Literal::Bool(b)));
+    }
+    if let Some((i, c)) = try_parse(char_literal(i))? {
+        return Ok((i, Literal::Char(c)));
+    }
+    let start = i;
+    let (i, num) = recognize(pair(
+        pair(opt(char('-')), digit1),
+        take_while(|c: char| c.is_ascii_digit() || matches!(c, '.' | 'e' | 'E' | '+' | '-')),
+    ))(i)?;
+    let (i, suffix) = take_while1(|c: char| c.is_ascii_alphanumeric())(i)?;
+    let lit = match literal_ty_from_name(suffix) {
+        Some(LiteralTy::Float(ty)) => Literal::Float(FloatValue {
+            value: num.to_owned(),
+            ty,
+        }),
+        Some(LiteralTy::Integer(ty)) => {
+            let v = if ty.is_signed() {
+                num.parse::<i128>()
+                    .ok()
+                    .and_then(|v| ScalarValue::from_int(ty, v).ok())
+            } else {
+                num.parse::<u128>()
+                    .ok()
+                    .and_then(|v| ScalarValue::from_uint(ty, v).ok())
+            };
+            match v {
+                Some(v) => Literal::Scalar(v),
+                None => return fail(start, format!("invalid `{ty}` literal `{num}`")),
+            }
+        }
+        _ => return fail(start, format!("unknown literal suffix `{suffix}`")),
+    };
+    Ok((i, lit))
+}
+
+fn literal_type(lit: &Literal) -> LiteralTy {
+    match lit {
+        Literal::Scalar(v) => LiteralTy::Integer(v.get_integer_ty()),
+        Literal::Float(v) => LiteralTy::Float(v.ty),
+        Literal::Bool(_) => LiteralTy::Bool,
+        Literal::Char(_) => LiteralTy::Char,
+        Literal::Str(_) | Literal::ByteStr(_) => unreachable!(),
+    }
+}
+
+/// A region parameter: `'a`, or `'_N` for anonymous regions.
+fn region_param(i: &str) -> ParseResult<'_, Option<String>> {
+    let (i, name) = preceded(tok("'"), raw_ident)(i)?;
+    let is_anonymous = name
+        .strip_prefix('_')
+        .is_some_and(|n| !n.is_empty() && n.chars().all(|c| c.is_ascii_digit()));
+    Ok((i, (!is_anonymous).then(|| name.to_owned())))
+}
+
+enum GenericParam {
+    Region(Option<String>),
+    Type(String),
+    Const(String, LiteralTy),
+}
+
+fn generic_param(i: &str) -> ParseResult<'_, GenericParam> {
+    if let Some((i, name)) = try_parse(region_param(i))? {
+        return Ok((i, GenericParam::Region(name)));
+    }
+    if let Some((i, _)) = try_parse(kw("const")(i))? {
+        let (i, name) = ident(i)?;
+        let (i, _) = tok(":")(i)?;
+        let (i, ty) = literal_ty(i)?;
+        return Ok((i, GenericParam::Const(name.to_owned(), ty)));
+    }
+    let (i, name) = ident(i)?;
+    Ok((i, GenericParam::Type(name.to_owned())))
+}
+
+fn generic_params(i: &str) -> ParseResult<'_, GenericParams> {
+    let mut generics = GenericParams::empty();
+    let Some((i, params)) = try_parse(delimited(
+        tok("<"),
+        separated_list0(tok(","), generic_param),
+        tok(">"),
+    )(i))?
+    else {
+        return Ok((i, generics));
+    };
+    for param in params {
+        match param {
+            GenericParam::Region(name) => {
+                generics
+                    .regions
+                    .push_with(|index| RegionVar { index, name });
+            }
+            GenericParam::Type(name) => {
+                generics.types.push_with(|index| TypeVar::new(index, name));
+            }
+            GenericParam::Const(name, ty) => {
+                generics.const_generics.push_with(|index| ConstGenericVar {
+                    index,
+                    name,
+                    ty,
+                    default: None,
+                });
+            }
+        }
+    }
+    Ok((i, generics))
+}
+
+fn item_meta(name: Name, public: bool) -> ItemMeta {
+    ItemMeta {
+        name,
+        span: Span::dummy(),
+        source_text: None,
+        attr_info: attr_info(public),
+        is_local: true,
+        is_foreign: false,
+        opacity: ItemOpacity::Transparent,
+        lang_item: None,
+        lang_item_role: None,
+    }
+}
+
+fn attr_info(public: bool) -> AttrInfo {
+    AttrInfo {
+        attributes: vec![],
+        inline: None,
+        rename: None,
+        public,
+        must_use: false,
+    }
+}
+
+fn field(name: Option<String>, public: bool, ty: Ty) -> Field {
+    Field {
+        span: Span::dummy(),
+        attr_info: attr_info(public),
+        name,
+        ty,
+    }
+}
+
+/// Substitute the generic parameters of a type declaration in the type of one of its fields.
+fn instantiate(ty: &Ty, args: &GenericArgs) -> Ty {
+    let region = |r: &Region| match r {
+        Region::Var(DeBruijnVar::Free(id)) => {
+            args.regions.get(*id).cloned().unwrap_or_else(|| r.clone())
+        }
+        _ => r.clone(),
+    };
+    let generics = |generics: &GenericArgs| GenericArgs {
+        regions: generics.regions.map_ref(region),
+        types: generics.types.map_ref(|ty| instantiate(ty, args)),
+        const_generics: generics.const_generics.map_ref(|cg| match cg {
+            ConstGeneric::Var(DeBruijnVar::Free(id)) => args
+                .const_generics
+                .get(*id)
+                .cloned()
+                .unwrap_or_else(|| cg.clone()),
+            _ => cg.clone(),
+        }),
+        ..generics.clone()
+    };
+    match ty.kind() {
+        TyKind::TypeVar(DeBruijnVar::Free(id)) => {
+            args.types.get(*id).cloned().unwrap_or_else(|| ty.clone())
+        }
+        TyKind::Adt(id, type_args) => TyKind::Adt(*id, generics(type_args)).into_ty(),
+        TyKind::Ref(r, inner, kind) => {
+            TyKind::Ref(region(r), instantiate(inner, args), *kind).into_ty()
+        }
+        TyKind::RawPtr(inner, kind) => TyKind::RawPtr(instantiate(inner, args), *kind).into_ty(),
+        _ => ty.clone(),
+    }
+}
+
+enum Item {
+    Type(TypeDecl),
+    Fun(FunDecl),
+}
+
+#[derive(Default)]
+struct CrateParser {
+    krate: TranslatedCrate,
+    type_ids: HashMap<String, TypeDeclId>,
+    fun_ids: HashMap<String, FunDeclId>,
+}
+
+impl CrateParser {
+    fn krate<'a>(&mut self, i: &'a str) -> ParseResult<'a, ()> {
+        let (mut i, name) = delimited(kw("crate"), ident, tok(";"))(i)?;
+        self.krate.crate_name = name.to_owned();
+        loop {
+            let (rest, _) = ws(i)?;
+            if rest.is_empty() {
+                return Ok((rest, ()));
+            }
+            let (rest, item) = alt((
+                |i| self.type_decl(i).map(|(i, decl)| (i, Item::Type(decl))),
+                |i| self.fun_decl(i).map(|(i, decl)| (i, Item::Fun(decl))),
+            ))(i)?;
+            match item {
+                Item::Type(decl) => self.krate.type_decls.set_slot(decl.def_id, decl),
+                Item::Fun(decl) => self.krate.fun_decls.set_slot(decl.def_id, decl),
+            }
+            i = rest;
+        }
+    }
+
+    fn item_parser<'p>(&'p self, generics: &'p GenericParams) -> ItemParser<'p> {
+        ItemParser {
+            cx: self,
+            generics,
+            locals: None,
+        }
+    }
+
+    fn type_decl<'a>(&self, i: &'a str) -> ParseResult<'a, TypeDecl> {
+        let (i, public) = opt(kw("pub"))(i)?;
+        let (i, keyword) = alt((
+            kw("struct"),
+            kw("enum"),
+            kw("union"),
+            terminated(kw("opaque"), kw("type")),
+            kw("type"),
+        ))(i)?;
+        let start = i;
+        let (i, name) = path(i)?;
+        let Some(&def_id) = self.type_ids.get(&path_key(&name)) else {
+            return fail(start, "this item should start on a new line".to_owned());
+        };
+        let (i, generics) = generic_params(i)?;
+        let item = self.item_parser(&generics);
+        let (i, (kind, ctor_kind)) = match keyword {
+            "struct" => {
+                if let Some((i, _)) = try_parse(tok(";")(i))? {
+                    (
+                        i,
+                        (TypeDeclKind::Struct(Vector::new()), Some(CtorKind::Unit)),
+                    )
+                } else if let Some((i, fields)) = try_parse(item.tuple_fields(i))? {
+                    let (i, _) = tok(";")(i)?;
+                    (i, (TypeDeclKind::Struct(fields), Some(CtorKind::Tuple)))
+                } else {
+                    let (i, fields) = item.named_fields(i)?;
+                    (i, (TypeDeclKind::Struct(fields), Some(CtorKind::Braced)))
+                }
+            }
+            "union" => {
+                let (i, fields) = item.named_fields(i)?;
+                (i, (TypeDeclKind::Union(fields), None))
+            }
+            "enum" => {
+                let (i, variants) = delimited(
+                    tok("{"),
+                    separated_list0(tok(","), |i| item.variant(i)),
+                    pair(opt(tok(",")), tok("}")),
+                )(i)?;
+                (i, (TypeDeclKind::Enum(variants.into()), None))
+            }
+            "opaque" => {
+                let (i, _) = tok(";")(i)?;
+                (i, (TypeDeclKind::Opaque, None))
+            }
+            _ => {
+                let (i, ty) = delimited(tok("="), |i| item.ty(i), tok(";"))(i)?;
+                (i, (TypeDeclKind::Alias(ty), None))
+            }
+        };
+        let decl = TypeDecl {
+            def_id,
+            item_meta: item_meta(name, public.is_some()),
+            generics,
+            kind,
+            drop_impl: None,
+            interior_mut: false,
+            valid_range: None,
+            ctor_kind,
+            repr: None,
+        };
+        Ok((i, decl))
+    }
+
+    fn fun_decl<'a>(&self, i: &'a str) -> ParseResult<'a, FunDecl> {
+        let (i, public) = opt(kw("pub"))(i)?;
+        let (i, is_unsafe) = opt(kw("unsafe"))(i)?;
+        let (i, _) = kw("fn")(i)?;
+        let start = i;
+        let (i, name) = path(i)?;
+        let Some(&def_id) = self.fun_ids.get(&path_key(&name)) else {
+            return fail(start, "this item should start on a new line".to_owned());
+        };
+        let (i, generics) = generic_params(i)?;
+        let item = self.item_parser(&generics);
+        let (i, inputs) = delimited(
+            tok("("),
+            separated_list0(tok(","), |i| item.ty(i)),
+            tok(")"),
+        )(i)?;
+        let (i, output) = preceded(tok("->"), |i| item.ty(i))(i)?;
+        let (i, body) = if let Some((i, _)) = try_parse(tok(";")(i))? {
+            (i, Err(Opaque))
+        } else {
+            let (i, body) = item.body(i, inputs.len())?;
+            (i, Ok(Body::Structured(body)))
+        };
+        let signature = FunSig {
+            is_unsafe: is_unsafe.is_some(),
+            is_closure: false,
+            has_self_param: false,
+            abi: None,
+            closure_info: None,
+            generics,
+            inputs,
+            output,
+        };
+        let decl = FunDecl {
+            def_id,
+            item_meta: item_meta(name, public.is_some()),
+            signature,
+            kind: ItemKind::Regular,
+            is_global_initializer: None,
+            body,
+        };
+        Ok((i, decl))
+    }
+}
+
+/// Parses the contents of an item, whose generic parameters are known.
+#[derive(Clone, Copy)]
+struct ItemParser<'p> {
+    cx: &'p CrateParser,
+    generics: &'p GenericParams,
+    /// The locals of the body being parsed.
+    locals: Option<&'p Locals>,
+}
+
+impl<'p> ItemParser<'p> {
+    fn type_decl<'a>(
+        &self,
+        i: &'a str,
+        id: TypeDeclId,
+    ) -> Result<&'p TypeDecl, nom::Err<ErrorTree<&'a str>>> {
+        self.cx.krate.type_decls.get(id).ok_or_else(|| {
+            failure(
+                i,
+                format!("type {id} must be declared before this function"),
+            )
+        })
+    }
+
+    fn region<'a>(&self, i: &'a str) -> ParseResult<'a, Region> {
+        let (rest, name) = preceded(tok("'"), raw_ident)(i)?;
+        let region = match name {
+            "_" => Region::Erased,
+            "static" => Region::Static,
+            _ => {
+                let var = self.generics.regions.iter().find(|var| match &var.name {
+                    Some(var_name) => var_name == name,
+                    None => name == format!("_{}", var.index.index()),
+                });
+                match var {
+                    Some(var) => Region::Var(DeBruijnVar::Free(var.index)),
+                    None => return fail(i, format!("unknown region `'{name}`")),
+                }
+            }
+        };
+        Ok((rest, region))
+    }
+
+    fn const_generic<'a>(&self, i: &'a str) -> ParseResult<'a, ConstGeneric> {
+        if let Some((i, lit)) = try_parse(literal(i))? {
+            return Ok((i, ConstGeneric::Value(lit)));
+        }
+        let (rest, name) = ident(i)?;
+        match self
+            .generics
+            .const_generics
+            .iter()
+            .find(|var| var.name == name)
+        {
+            Some(var) => Ok((rest, ConstGeneric::Var(DeBruijnVar::Free(var.index)))),
+            None => fail(i, format!("unknown const generic `{name}`")),
+        }
+    }
+
+    /// Generic arguments, if there are any.
+    fn generic_args<'a>(&self, i: &'a str, target: GenericsSource) -> ParseResult<'a, GenericArgs> {
+        let mut args = GenericArgs::empty(target);
+        let Some((mut i, _)) = try_parse(tok("<")(i))? else {
+            return Ok((i, args));
+        };
+        loop {
+            if let Some((rest, lit)) = try_parse(literal(i))? {
+                args.const_generics.push(ConstGeneric::Value(lit));
+                i = rest;
+            } else if let Some((rest, r)) = try_parse(self.region(i))? {
+                args.regions.push(r);
+                i = rest;
+            } else if let Some((rest, name)) = try_parse(ident(i))?
+                && let Some(var) = self
+                    .generics
+                    .const_generics
+                    .iter()
+                    .find(|var| var.name == name)
+            {
+                args.const_generics
+                    .push(ConstGeneric::Var(DeBruijnVar::Free(var.index)));
+                i = rest;
+            } else {
+                let (rest, ty) = self.ty(i)?;
+                args.types.push(ty);
+                i = rest;
+            }
+            if let Some((rest, _)) = try_parse(tok(",")(i))? {
+                i = rest;
+            } else {
+                let (i, _) = tok(">")(i)?;
+                return Ok((i, args));
+            }
+        }
+    }
+
+    fn ty<'a>(&self, i: &'a str) -> ParseResult<'a, Ty> {
+        if let Some((i, _)) = try_parse(tok("!")(i))? {
+            return Ok((i, TyKind::Never.into_ty()));
+        }
+        if let Some((i, _)) = try_parse(tok("(")(i))? {
+            let (i, tys) = separated_list0(tok(","), |i| self.ty(i))(i)?;
+            let (i, trailing_comma) = opt(tok(","))(i)?;
+            let (i, _) = tok(")")(i)?;
+            if tys.len() == 1 && trailing_comma.is_none() {
+                return fail(i, "one-element tuples are written `(T,)`".to_owned());
+            }
+            let args = GenericArgs::new_for_builtin(tys.into());
+            return Ok((i, TyKind::Adt(TypeId::Tuple, args).into_ty()));
+        }
+        if let Some((i, _)) = try_parse(tok("&")(i))? {
+            let (i, region) = self.region(i)?;
+            let (i, mutability) = opt(kw("mut"))(i)?;
+            let (i, ty) = self.ty(i)?;
+            let kind = if mutability.is_some() {
+                RefKind::Mut
+            } else {
+                RefKind::Shared
+            };
+            return Ok((i, TyKind::Ref(region, ty, kind).into_ty()));
+        }
+        if let Some((i, _)) = try_parse(tok("*")(i))? {
+            let (i, kind) = alt((
+                value(RefKind::Shared, kw("const")),
+                value(RefKind::Mut, kw("mut")),
+            ))(i)?;
+            let (i, ty) = self.ty(i)?;
+            return Ok((i, TyKind::RawPtr(ty, kind).into_ty()));
+        }
+        if let Some((i, _)) = try_parse(tok("[")(i))? {
+            let (i, ty) = self.ty(i)?;
+            if let Some((i, _)) = try_parse(tok("]")(i))? {
+                let args = GenericArgs::new_for_builtin(vec![ty].into());
+                return Ok((
+                    i,
+                    TyKind::Adt(TypeId::Builtin(BuiltinTy::Slice), args).into_ty(),
+                ));
+            }
+            let (i, len) = delimited(tok(";"), |i| self.const_generic(i), tok("]"))(i)?;
+            let mut args = GenericArgs::new_for_builtin(vec![ty].into());
+            args.const_generics.push(len);
+            return Ok((
+                i,
+                TyKind::Adt(TypeId::Builtin(BuiltinTy::Array), args).into_ty(),
+            ));
+        }
+
+        let start = i;
+        let (i, name) = path(i)?;
+        let key = path_key(&name);
+        if let Some(ty) = literal_ty_from_name(&key) {
+            return Ok((i, TyKind::Literal(ty).into_ty()));
+        }
+        if key == "str" {
+            let args = GenericArgs::empty(GenericsSource::Builtin);
+            return Ok((
+                i,
+                TyKind::Adt(TypeId::Builtin(BuiltinTy::Str), args).into_ty(),
+            ));
+        }
+        if let Some(var) = self.generics.types.iter().find(|var| var.name == key) {
+            return Ok((i, TyKind::TypeVar(DeBruijnVar::Free(var.index)).into_ty()));
+        }
+        let type_id = if key == "alloc::boxed::Box" {
+            TypeId::Builtin(BuiltinTy::Box)
+        } else if let Some(&id) = self.cx.type_ids.get(&key) {
+            TypeId::Adt(id)
+        } else {
+            return fail(start, format!("unknown type `{key}`"));
+        };
+        let target = match type_id {
+            TypeId::Adt(id) => GenericsSource::Item(id.into()),
+            _ => GenericsSource::Builtin,
+        };
+        let (i, args) = self.generic_args(i, target)?;
+        Ok((i, TyKind::Adt(type_id, args).into_ty()))
+    }
+
+    /// `(T, pub U)`
+    fn tuple_fields<'a>(&self, i: &'a str) -> ParseResult<'a, Vector<FieldId, Field>> {
+        let tuple_field = |i: &'a str| {
+            let (i, public) = opt(kw("pub"))(i)?;
+            let (i, ty) = self.ty(i)?;
+            Ok((i, field(None, public.is_some(), ty)))
+        };
+        let (i, fields) = delimited(
+            tok("("),
+            separated_list0(tok(","), tuple_field),
+            pair(opt(tok(",")), tok(")")),
+        )(i)?;
+        Ok((i, fields.into()))
+    }
+
+    /// `{ a: T, pub b: U }`
+    fn named_fields<'a>(&self, i: &'a str) -> ParseResult<'a, Vector<FieldId, Field>> {
+        let named_field = |i: &'a str| {
+            let (i, public) = opt(kw("pub"))(i)?;
+            let (i, name) = ident(i)?;
+            let (i, ty) = preceded(tok(":"), |i| self.ty(i))(i)?;
+            Ok((i, field(Some(name.to_owned()), public.is_some(), ty)))
+        };
+        let (i, fields) = delimited(
+            tok("{"),
+            separated_list0(tok(","), named_field),
+            pair(opt(tok(",")), tok("}")),
+        )(i)?;
+        Ok((i, fields.into()))
+    }
+
+    /// `Name(T) = 1isize`
+    fn variant<'a>(&self, i: &'a str) -> ParseResult<'a, Variant> {
+        let (i, name) = ident(i)?;
+        let (i, (fields, ctor_kind)) = if let Some((i, fields)) = try_parse(self.tuple_fields(i))? {
+            (i, (fields, CtorKind::Tuple))
+        } else if let Some((i, fields)) = try_parse(self.named_fields(i))? {
+            (i, (fields, CtorKind::Braced))
+        } else {
+            (i, (Vector::new(), CtorKind::Unit))
+        };
+        let (rest, discriminant) = preceded(tok("="), literal)(i)?;
+        let Literal::Scalar(discriminant) = discriminant else {
+            return fail(i, "discriminants must be integers".to_owned());
+        };
+        let variant = Variant {
+            span: Span::dummy(),
+            attr_info: attr_info(false),
+            name: name.to_owned(),
+            fields,
+            ctor_kind,
+            discriminant,
+        };
+        Ok((rest, variant))
+    }
+
+    fn body<'a>(&self, i: &'a str, arg_count: usize) -> ParseResult<'a, ExprBody> {
+        let local_decl = |i: &'a str| {
+            let (i, _) = kw("let")(i)?;
+            let start = i;
+            let (i, (name, index)) = self.local(i)?;
+            let (i, ty) = delimited(tok(":"), |i| self.ty(i), tok(";"))(i)?;
+            Ok((i, (start, name, index, ty)))
+        };
+        let (i, decls) = preceded(tok("{"), many0(local_decl))(i)?;
+        let mut locals = Locals {
+            arg_count,
+            vars: Vector::new(),
+        };
+        for (start, name, index, ty) in decls {
+            if index != locals.vars.slot_count() {
+                return fail(start, "locals must be declared in order".to_owned());
+            }
+            let name = name.map(str::to_owned);
+            locals.vars.push_with(|index| Var {
+                index,
+                name,
+                ty,
+                binding_mode: None,
+            });
+        }
+        if locals.vars.elem_count() <= arg_count {
+            return fail(
+                i,
+                "the return value and the arguments must be declared".to_owned(),
+            );
+        }
+        let body_parser = ItemParser {
+            locals: Some(&locals),
+            ..*self
+        };
+        let (i, block) = body_parser.block_contents(i)?;
+        let (i, _) = tok("}")(i)?;
+        let body = ExprBody {
+            span: Span::dummy(),
+            locals,
+            comments: vec![],
+            body: block,
+        };
+        Ok((i, body))
+    }
+
+    /// `name@N` or `@N`
+    fn local<'a>(&self, i: &'a str) -> ParseResult<'a, (Option<&'a str>, usize)> {
+        preceded(ws, pair(opt(raw_ident), preceded(char('@'), number)))(i)
+    }
+
+    fn local_place<'a>(&self, i: &'a str) -> ParseResult<'a, Place> {
+        let (rest, (name, index)) = self.local(i)?;
+        let var = self
+            .locals
+            .and_then(|locals| locals.vars.get(VarId::new(index)));
+        match var {
+            Some(var) if var.name.as_deref() == name => {
+                Ok((rest, Place::new(var.index, var.ty.clone())))
+            }
+            Some(_) => fail(i, format!("local {index} is declared with another name")),
+            None => fail(i, format!("unknown local {index}")),
+        }
+    }
+
+    fn place<'a>(&self, i: &'a str) -> ParseResult<'a, Place> {
+        if let Some((i, _)) = try_parse(tok("*")(i))? {
+            let (rest, inner) = self.place(i)?;
+            let target_ty = match inner.ty.kind() {
+                TyKind::Ref(_, ty, _) | TyKind::RawPtr(ty, _) => ty.clone(),
+                _ => match inner.ty.as_box() {
+                    Some(ty) => ty.clone(),
+                    None => {
+                        return fail(
+                            i,
+                            "only references, pointers and boxes can be dereferenced".to_owned(),
+                        )
+                    }
+                },
+            };
+            return Ok((rest, inner.project(ProjectionElem::Deref, target_ty)));
+        }
+
+        // A local, or a place between parentheses, possibly cast to an enum variant.
+        let (mut i, (mut place, mut variant)) = if let Some((i, _)) = try_parse(tok("(")(i))? {
+            let (i, inner) = self.place(i)?;
+            let (i, variant) = opt(preceded(kw("as"), ident))(i)?;
+            let (i, _) = tok(")")(i)?;
+            (i, (inner, variant))
+        } else {
+            let (i, place) = self.local_place(i)?;
+            (i, (place, None))
+        };
+        // Field projections.
+        while let Some((rest, field_id)) = try_parse(preceded(tok("."), number)(i))? {
+            place = self.project_field(i, place, variant.take(), FieldId::new(field_id))?;
+            i = rest;
+        }
+        if variant.is_some() {
+            return fail(i, "expected a field of the variant".to_owned());
+        }
+        Ok((i, place))
+    }
+
+    fn project_field<'a>(
+        &self,
+        i: &'a str,
+        place: Place,
+        variant: Option<&str>,
+        field_id: FieldId,
+    ) -> Result<Place, nom::Err<ErrorTree<&'a str>>> {
+        let err = |msg: String| failure(i, msg);
+        let (kind, field_ty) = match (place.ty.kind(), variant) {
+            (TyKind::Adt(TypeId::Tuple, args), None) => {
+                let Some(ty) = args.types.get(TypeVarId::new(field_id.index())) else {
+                    return Err(err(format!("the tuple has no field {field_id}")));
+                };
+                (FieldProjKind::Tuple(args.types.elem_count()), ty.clone())
+            }
+            (TyKind::Adt(TypeId::Adt(type_id), args), variant) => {
+                let decl = self.type_decl(i, *type_id)?;
+                let (variant_id, fields) = match (&decl.kind, variant) {
+                    (TypeDeclKind::Struct(fields) | TypeDeclKind::Union(fields), None) => {
+                        (None, fields)
+                    }
+                    (TypeDeclKind::Enum(variants), Some(variant)) => {
+                        let Some((variant_id, variant)) =
+                            variants.iter_indexed().find(|(_, v)| v.name == variant)
+                        else {
+                            return Err(err(format!("unknown variant `{variant}`")));
+                        };
+                        (Some(variant_id), &variant.fields)
+                    }
+                    _ => {
+                        return Err(err(
+                            "fields of enums are accessed through `(place as Variant).N`"
+                                .to_owned(),
+                        ))
+                    }
+                };
+                let Some(field) = fields.get(field_id) else {
+                    return Err(err(format!("unknown field {field_id}")));
+                };
+                (
+                    FieldProjKind::Adt(*type_id, variant_id),
+                    instantiate(&field.ty, args),
+                )
+            }
+            _ => return Err(err("this place has no fields".to_owned())),
+        };
+        Ok(place.project(ProjectionElem::Field(kind, field_id), field_ty))
+    }
+
+    fn operand<'a>(&self, i: &'a str) -> ParseResult<'a, Operand> {
+        if let Some((i, _)) = try_parse(kw("copy")(i))? {
+            let (i, place) = self.place(i)?;
+            return Ok((i, Operand::Copy(place)));
+        }
+        if let Some((i, _)) = try_parse(kw("move")(i))? {
+            let (i, place) = self.place(i)?;
+            return Ok((i, Operand::Move(place)));
+        }
+        let (i, lit) = preceded(kw("const"), literal)(i)?;
+        let ty = TyKind::Literal(literal_type(&lit)).into_ty();
+        let constant = ConstantExpr {
+            value: RawConstantExpr::Literal(lit),
+            ty,
+        };
+        Ok((i, Operand::Const(constant)))
+    }
+
+    fn operands<'a>(
+        &self,
+        i: &'a str,
+        open: &'static str,
+        close: &'static str,
+    ) -> ParseResult<'a, (Vec<Operand>, bool)> {
+        let (i, ops) = preceded(tok(open), separated_list0(tok(","), |i| self.operand(i)))(i)?;
+        let (i, trailing_comma) = terminated(opt(tok(",")), tok(close))(i)?;
+        Ok((i, (ops, trailing_comma.is_some())))
+    }
+
+    fn rvalue<'a>(&self, i: &'a str) -> ParseResult<'a, Rvalue> {
+        if let Some((i, _)) = try_parse(tok("&")(i))? {
+            let raw_kind = alt((
+                value(RefKind::Shared, kw("const")),
+                value(RefKind::Mut, kw("mut")),
+            ));
+            if let Some((i, kind)) = try_parse(preceded(kw("raw"), raw_kind)(i))? {
+                let (i, place) = self.place(i)?;
+                return Ok((i, Rvalue::RawPtr(place, kind)));
+            }
+            let (i, kind) = opt(alt((
+                value(BorrowKind::Mut, kw("mut")),
+                value(BorrowKind::TwoPhaseMut, kw("two_phase_mut")),
+                value(BorrowKind::Shallow, kw("shallow")),
+                value(BorrowKind::UniqueImmutable, kw("uniq")),
+            )))(i)?;
+            let (i, place) = self.place(i)?;
+            return Ok((i, Rvalue::Ref(place, kind.unwrap_or(BorrowKind::Shared))));
+        }
+        if let Some((i, _)) = try_parse(kw("discriminant")(i))? {
+            let (rest, place) = delimited(tok("("), |i| self.place(i), tok(")"))(i)?;
+            let TyKind::Adt(TypeId::Adt(type_id), _) = place.ty.kind() else {
+                return fail(i, "only the discriminant of an enum can be read".to_owned());
+            };
+            let type_id = *type_id;
+            return Ok((rest, Rvalue::Discriminant(place, type_id)));
+        }
+        if let Some((i, _)) = try_parse(kw("cast")(i))? {
+            let (i, (from, to)) = delimited(
+                tok("<"),
+                pair(literal_ty, preceded(tok(","), literal_ty)),
+                tok(">"),
+            )(i)?;
+            let (i, op) = delimited(tok("("), |i| self.operand(i), tok(")"))(i)?;
+            return Ok((
+                i,
+                Rvalue::UnaryOp(UnOp::Cast(CastKind::Scalar(from, to)), op),
+            ));
+        }
+        if let Some((i, _)) = try_parse(tok("!")(i))? {
+            let (i, op) = self.operand(i)?;
+            return Ok((i, Rvalue::UnaryOp(UnOp::Not, op)));
+        }
+        if let Some((i, _)) = try_parse(tok("-")(i))? {
+            let (i, op) = self.operand(i)?;
+            return Ok((i, Rvalue::UnaryOp(UnOp::Neg, op)));
+        }
+        if let Some((i, (ops, trailing_comma))) = try_parse(self.operands(i, "(", ")"))? {
+            if ops.len() == 1 && !trailing_comma {
+                return fail(i, "one-element tuples are written `(op,)`".to_owned());
+            }
+            let kind = AggregateKind::Adt(
+                TypeId::Tuple,
+                None,
+                None,
+                GenericArgs::empty(GenericsSource::Builtin),
+            );
+            return Ok((i, Rvalue::Aggregate(kind, ops)));
+        }
+        if let Some((i, lhs)) = try_parse(self.operand(i))? {
+            let Some((i, op)) = try_parse(binop(i))? else {
+                return Ok((i, Rvalue::Use(lhs)));
+            };
+            let (i, rhs) = self.operand(i)?;
+            return Ok((i, Rvalue::BinaryOp(op, lhs, rhs)));
+        }
+        self.aggregate(i)
+    }
+
+    /// `path::Type<Args> { ops }` or `path::Type<Args>::Variant { ops }`
+    fn aggregate<'a>(&self, i: &'a str) -> ParseResult<'a, Rvalue> {
+        let start = i;
+        let (i, mut name) = path(i)?;
+        let (i, type_id, mut variant) = if let Some(&id) = self.cx.type_ids.get(&path_key(&name)) {
+            (i, id, None)
+        } else if let Some(PathElem::Ident(variant, _)) = name.name.pop()
+            && let Some(&id) = self.cx.type_ids.get(&path_key(&name))
+        {
+            (i, id, Some(variant))
+        } else {
+            return fail(start, "expected an rvalue".to_owned());
+        };
+        let (i, args) = self.generic_args(i, GenericsSource::Item(type_id.into()))?;
+        let (i, variant_after_args) = opt(preceded(tok("::"), ident))(i)?;
+        if let Some(v) = variant_after_args {
+            if variant.is_some() {
+                return fail(
+                    i,
+                    "expected generic arguments before the variant".to_owned(),
+                );
+            }
+            variant = Some(v.to_owned());
+        }
+        let decl = self.type_decl(start, type_id)?;
+        let variant_id = match (&decl.kind, variant) {
+            (TypeDeclKind::Struct(_), None) => None,
+            (TypeDeclKind::Enum(variants), Some(variant)) => {
+                match variants.iter_indexed().find(|(_, v)| v.name == variant) {
+                    Some((id, _)) => Some(id),
+                    None => return fail(start, format!("unknown variant `{variant}`")),
+                }
+            }
+            _ => {
+                return fail(
+                    start,
+                    "only structs and enum variants can be built".to_owned(),
+                )
+            }
+        };
+        let (i, (ops, _)) = self.operands(i, "{", "}")?;
+        let kind = AggregateKind::Adt(TypeId::Adt(type_id), variant_id, None, args);
+        Ok((i, Rvalue::Aggregate(kind, ops)))
+    }
+
+    /// `f<Args>(ops)`
+    fn call<'a>(&self, i: &'a str, dest: Place) -> ParseResult<'a, Call> {
+        let start = i;
+        let (i, name) = path(i)?;
+        let Some(&fun_id) = self.cx.fun_ids.get(&path_key(&name)) else {
+            return fail(start, format!("unknown function `{}`", path_key(&name)));
+        };
+        let (i, generics) = self.generic_args(i, GenericsSource::Item(fun_id.into()))?;
+        let (i, (args, _)) = self.operands(i, "(", ")")?;
+        let call = Call {
+            func: FnOperand::Regular(FnPtr {
+                func: FunIdOrTraitMethodRef::Fun(FunId::Regular(fun_id)),
+                generics,
+            }),
+            args,
+            dest,
+            is_method_call: false,
+        };
+        Ok((i, call))
+    }
+
+    fn block<'a>(&self, i: &'a str) -> ParseResult<'a, Block> {
+        delimited(tok("{"), |i| self.block_contents(i), tok("}"))(i)
+    }
+
+    fn block_contents<'a>(&self, i: &'a str) -> ParseResult<'a, Block> {
+        let (i, statements) = many0(|i| self.statement(i))(i)?;
+        let block = Block {
+            span: Span::dummy(),
+            statements,
+        };
+        Ok((i, block))
+    }
+
+    /// `pat | pat => { .. }`
+    fn arms<'a, T>(
+        &self,
+        i: &'a str,
+        pat: impl FnMut(&'a str) -> ParseResult<'a, T>,
+    ) -> ParseResult<'a, Vec<(Vec<T>, Block)>> {
+        let arm = pair(terminated(separated_list1(tok("|"), pat), tok("=>")), |i| {
+            self.block(i)
+        });
+        many0(arm)(i)
+    }
+
+    fn statement<'a>(&self, i: &'a str) -> ParseResult<'a, Statement> {
+        let (i, content) = self.raw_statement(i)?;
+        Ok((i, Statement::new(Span::dummy(), content)))
+    }
+
+    fn raw_statement<'a>(&self, i: &'a str) -> ParseResult<'a, RawStatement> {
+        let end = |i: &'a str| tok(";")(i);
+        if let Some((i, _)) = try_parse(kw("fake_read")(i))? {
+            let (i, place) = terminated(|i| self.place(i), end)(i)?;
+            return Ok((i, RawStatement::FakeRead(place)));
+        }
+        if let Some((i, _)) = try_parse(kw("drop")(i))? {
+            let (i, place) = terminated(|i| self.place(i), end)(i)?;
+            return Ok((i, RawStatement::Drop(place)));
+        }
+        if let Some((i, _)) = try_parse(kw("assert")(i))? {
+            let (i, not) = opt(tok("!"))(i)?;
+            let (i, cond) = terminated(|i| self.operand(i), end)(i)?;
+            let assert = Assert {
+                cond,
+                expected: not.is_none(),
+                comparison: None,
+                message: None,
+            };
+            return Ok((i, RawStatement::Assert(assert)));
+        }
+        if let Some((i, _)) = try_parse(kw("panic")(i))? {
+            let (i, name) = terminated(path, end)(i)?;
+            return Ok((i, RawStatement::Abort(AbortKind::Panic(name, None))));
+        }
+        if let Some((i, _)) = try_parse(terminated(kw("undefined_behavior"), end)(i))? {
+            return Ok((i, RawStatement::Abort(AbortKind::UndefinedBehavior)));
+        }
+        if let Some((i, _)) = try_parse(terminated(kw("return"), end)(i))? {
+            return Ok((i, RawStatement::Return));
+        }
+        if let Some((i, _)) = try_parse(terminated(kw("nop"), end)(i))? {
+            return Ok((i, RawStatement::Nop));
+        }
+        if let Some((i, _)) = try_parse(kw("break")(i))? {
+            let (i, depth) = terminated(preceded(ws, number), end)(i)?;
+            return Ok((i, RawStatement::Break(depth)));
+        }
+        if let Some((i, _)) = try_parse(kw("continue")(i))? {
+            let (i, depth) = terminated(preceded(ws, number), end)(i)?;
+            return Ok((i, RawStatement::Continue(depth)));
+        }
+        if let Some((i, _)) = try_parse(kw("if")(i))? {
+            let (i, cond) = self.operand(i)?;
+            let (i, then_block) = self.block(i)?;
+            let (i, else_block) = preceded(kw("else"), |i| self.block(i))(i)?;
+            return Ok((
+                i,
+                RawStatement::Switch(Switch::If(cond, then_block, else_block)),
+            ));
+        }
+        if let Some((i, _)) = try_parse(kw("switch")(i))? {
+            let start = i;
+            let (i, discr) = self.operand(i)?;
+            let (i, arms) = preceded(tok("{"), |i| self.arms(i, literal))(i)?;
+            let (i, otherwise) =
+                delimited(pair(tok("_"), tok("=>")), |i| self.block(i), tok("}"))(i)?;
+            let TyKind::Literal(ty) = discr.ty().kind() else {
+                return fail(
+                    start,
+                    "only integers, booleans and chars can be switched on".to_owned(),
+                );
+            };
+            let ty = *ty;
+            let switch = Switch::SwitchInt(discr, ty, arms, otherwise);
+            return Ok((i, RawStatement::Switch(switch)));
+        }
+        if let Some((i, _)) = try_parse(kw("match")(i))? {
+            let start = i;
+            let (i, scrutinee) = self.place(i)?;
+            let (rest, arms) = delimited(tok("{"), |i| self.arms(i, ident), tok("}"))(i)?;
+            let TyKind::Adt(TypeId::Adt(type_id), _) = scrutinee.ty.kind() else {
+                return fail(start, "only enums can be matched on".to_owned());
+            };
+            let TypeDeclKind::Enum(variants) = &self.type_decl(start, *type_id)?.kind else {
+                return fail(start, "only enums can be matched on".to_owned());
+            };
+            let mut variant_arms = Vec::new();
+            let mut otherwise = None;
+            for (names, block) in arms {
+                if names == ["_"] {
+                    otherwise = Some(block);
+                    continue;
+                }
+                let mut ids = Vec::new();
+                for name in names {
+                    match variants.iter_indexed().find(|(_, v)| v.name == name) {
+                        Some((id, _)) => ids.push(id),
+                        None => return fail(i, format!("unknown variant `{name}`")),
+                    }
+                }
+                variant_arms.push((ids, block));
+            }
+            let switch = Switch::Match(scrutinee, variant_arms, otherwise);
+            return Ok((rest, RawStatement::Switch(switch)));
+        }
+        if let Some((i, _)) = try_parse(kw("loop")(i))? {
+            let (i, block) = self.block(i)?;
+            return Ok((i, RawStatement::Loop(block)));
+        }
+
+        let (i, dest) = terminated(|i| self.place(i), tok(":="))(i)?;
+        if let Some((i, _)) = try_parse(kw("call")(i))? {
+            let (i, call) = self.call(i, dest)?;
+            let (i, _) = end(i)?;
+            return Ok((i, RawStatement::Call(call)));
+        }
+        let (i, rvalue) = terminated(|i| self.rvalue(i), end)(i)?;
+        Ok((i, RawStatement::Assign(dest, rvalue)))
+    }
+}
+
+fn binop(i: &str) -> ParseResult<'_, BinOp> {
+    for (op, symbol) in BINOPS {
+        if let Ok((i, _)) = tok(symbol)(i) {
+            return Ok((i, op));
+        }
+    }
+    Err(nom::Err::Error(ErrorTree::from_error_kind(
+        i,
+        ErrorKind::Tag,
+    )))
+}
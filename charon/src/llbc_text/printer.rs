@@ -0,0 +1,645 @@
+use std::fmt::Write;
+
+use anyhow::{bail, Context, Result};
+
+use super::{literal_ty_name, path_key, BINOPS};
+use crate::ast::*;
+use crate::llbc_ast::*;
+
+/// Print the crate in the textual LLBC syntax. Errors if the crate contains constructs that this
+/// syntax doesn't support.
+pub fn print_crate(krate: &TranslatedCrate) -> Result<String> {
+    if !krate.global_decls.is_empty() {
+        bail!("globals are not supported by the textual LLBC syntax")
+    }
+    if !krate.trait_decls.is_empty() || !krate.trait_impls.is_empty() {
+        bail!("traits are not supported by the textual LLBC syntax")
+    }
+    let mut out = String::new();
+    writeln!(out, "crate {};", krate.crate_name)?;
+    for decl in &krate.type_decls {
+        let printer = Printer {
+            krate,
+            generics: &decl.generics,
+            locals: None,
+        };
+        writeln!(out)?;
+        printer
+            .type_decl(&mut out, decl)
+            .with_context(|| format!("Could not print `{}`", path_key(&decl.item_meta.name)))?;
+    }
+    for decl in &krate.fun_decls {
+        let printer = Printer {
+            krate,
+            generics: &decl.signature.generics,
+            locals: None,
+        };
+        writeln!(out)?;
+        printer
+            .fun_decl(&mut out, decl)
+            .with_context(|| format!("Could not print `{}`", path_key(&decl.item_meta.name)))?;
+    }
+    Ok(out)
+}
+
+fn is_ident(s: &str) -> bool {
+    let mut chars = s.chars();
+    chars.next().is_some_and(|c| c.is_alphabetic() || c == '_')
+        && chars.all(|c| c.is_alphanumeric() || c == '_')
+}
+
+fn ident(s: &str) -> Result<&str> {
+    if !is_ident(s) {
+        bail!("`{s}` is not an identifier")
+    }
+    Ok(s)
+}
+
+fn name(name: &Name) -> Result<String> {
+    for elem in &name.name {
+        match elem {
+            PathElem::Ident(s, _) => {
+                ident(s)?;
+            }
+            PathElem::Impl(..) => bail!("impl blocks are not supported in names"),
+        }
+    }
+    Ok(path_key(name))
+}
+
+fn visibility(attr_info: &AttrInfo) -> &'static str {
+    if attr_info.public {
+        "pub "
+    } else {
+        ""
+    }
+}
+
+fn literal(lit: &Literal) -> Result<String> {
+    Ok(match lit {
+        Literal::Scalar(v) => match v.as_int() {
+            Ok(i) => format!("{i}{}", v.get_integer_ty()),
+            Err(_) => format!("{}{}", v.to_bits(), v.get_integer_ty()),
+        },
+        Literal::Float(v) => {
+            if !v
+                .value
+                .chars()
+                .all(|c| c.is_ascii_digit() || matches!(c, '.' | 'e' | 'E' | '+' | '-'))
+            {
+                bail!("the float `{}` is not supported", v.value)
+            }
+            format!("{}{}", v.value, v.ty)
+        }
+        Literal::Bool(b) => b.to_string(),
+        Literal::Char(c) => format!("'{}'", c.escape_default()),
+        Literal::Str(_) | Literal::ByteStr(_) => bail!("string literals are not supported"),
+    })
+}
+
+/// Returns the variable if it refers to the generics of the item.
+fn item_var<Id: Copy>(var: &DeBruijnVar<Id>) -> Result<Id> {
+    match *var {
+        DeBruijnVar::Free(id) => Ok(id),
+        DeBruijnVar::Bound(dbid, id) if dbid.is_zero() => Ok(id),
+        DeBruijnVar::Bound(..) => bail!("higher-ranked variables are not supported"),
+    }
+}
+
+fn region_var(var: &RegionVar) -> Result<String> {
+    Ok(match &var.name {
+        Some(name) => format!("'{}", ident(name)?),
+        None => format!("'_{}", var.index.index()),
+    })
+}
+
+#[derive(Clone, Copy)]
+struct Printer<'a> {
+    krate: &'a TranslatedCrate,
+    /// The generic parameters of the item being printed.
+    generics: &'a GenericParams,
+    /// The locals of the body being printed.
+    locals: Option<&'a Locals>,
+}
+
+impl Printer<'_> {
+    fn type_name(&self, id: TypeDeclId) -> Result<String> {
+        match self.krate.type_decls.get(id) {
+            Some(decl) => name(&decl.item_meta.name),
+            None => bail!("missing type declaration {id}"),
+        }
+    }
+
+    fn fun_name(&self, id: FunDeclId) -> Result<String> {
+        match self.krate.fun_decls.get(id) {
+            Some(decl) => name(&decl.item_meta.name),
+            None => bail!("missing function declaration {id}"),
+        }
+    }
+
+    fn variants(&self, id: TypeDeclId) -> Result<&Vector<VariantId, Variant>> {
+        match self.krate.type_decls.get(id).map(|decl| &decl.kind) {
+            Some(TypeDeclKind::Enum(variants)) => Ok(variants),
+            _ => bail!("type declaration {id} is not an enum"),
+        }
+    }
+
+    fn variant_name(&self, id: TypeDeclId, variant_id: VariantId) -> Result<&str> {
+        match self.variants(id)?.get(variant_id) {
+            Some(variant) => ident(&variant.name),
+            None => bail!("missing variant {variant_id} of type declaration {id}"),
+        }
+    }
+
+    fn generic_params(&self, params: &GenericParams) -> Result<String> {
+        if !params.trait_clauses.is_empty()
+            || !params.regions_outlive.is_empty()
+            || !params.types_outlive.is_empty()
+            || !params.trait_type_constraints.is_empty()
+        {
+            bail!("trait clauses and outlives constraints are not supported")
+        }
+        let mut params_s = Vec::new();
+        for var in &params.regions {
+            params_s.push(region_var(var)?);
+        }
+        for var in &params.types {
+            if var.default.is_some() {
+                bail!("default type parameters are not supported")
+            }
+            params_s.push(ident(&var.name)?.to_owned());
+        }
+        for var in &params.const_generics {
+            if var.default.is_some() {
+                bail!("default const parameters are not supported")
+            }
+            params_s.push(format!(
+                "const {}: {}",
+                ident(&var.name)?,
+                literal_ty_name(var.ty)
+            ));
+        }
+        Ok(if params_s.is_empty() {
+            String::new()
+        } else {
+            format!("<{}>", params_s.join(", "))
+        })
+    }
+
+    fn generic_args(&self, args: &GenericArgs) -> Result<String> {
+        if !args.trait_refs.is_empty() {
+            bail!("trait references are not supported")
+        }
+        let mut args_s = Vec::new();
+        for r in &args.regions {
+            args_s.push(self.region(r)?);
+        }
+        for ty in &args.types {
+            args_s.push(self.ty(ty)?);
+        }
+        for cg in &args.const_generics {
+            args_s.push(self.const_generic(cg)?);
+        }
+        Ok(if args_s.is_empty() {
+            String::new()
+        } else {
+            format!("<{}>", args_s.join(", "))
+        })
+    }
+
+    fn region(&self, r: &Region) -> Result<String> {
+        match r {
+            Region::Erased => Ok("'_".to_owned()),
+            Region::Static => Ok("'static".to_owned()),
+            Region::Var(var) => match self.generics.regions.get(item_var(var)?) {
+                Some(var) => region_var(var),
+                None => bail!("unbound region variable"),
+            },
+        }
+    }
+
+    fn const_generic(&self, cg: &ConstGeneric) -> Result<String> {
+        match cg {
+            ConstGeneric::Value(lit) => literal(lit),
+            ConstGeneric::Var(var) => match self.generics.const_generics.get(item_var(var)?) {
+                Some(var) => Ok(var.name.clone()),
+                None => bail!("unbound const generic variable"),
+            },
+            ConstGeneric::Global(_) => bail!("globals are not supported"),
+        }
+    }
+
+    fn ty(&self, ty: &Ty) -> Result<String> {
+        Ok(match ty.kind() {
+            TyKind::Literal(ty) => literal_ty_name(*ty),
+            TyKind::Never => "!".to_owned(),
+            TyKind::TypeVar(var) => match self.generics.types.get(item_var(var)?) {
+                Some(var) => var.name.clone(),
+                None => bail!("unbound type variable"),
+            },
+            TyKind::Ref(r, ty, RefKind::Shared) => format!("&{} {}", self.region(r)?, self.ty(ty)?),
+            TyKind::Ref(r, ty, RefKind::Mut) => {
+                format!("&{} mut {}", self.region(r)?, self.ty(ty)?)
+            }
+            TyKind::RawPtr(ty, RefKind::Shared) => format!("*const {}", self.ty(ty)?),
+            TyKind::RawPtr(ty, RefKind::Mut) => format!("*mut {}", self.ty(ty)?),
+            TyKind::Adt(TypeId::Tuple, args) => {
+                let tys = args
+                    .types
+                    .iter()
+                    .map(|ty| self.ty(ty))
+                    .collect::<Result<Vec<_>>>()?;
+                match tys.as_slice() {
+                    [ty] => format!("({ty},)"),
+                    _ => format!("({})", tys.join(", ")),
+                }
+            }
+            TyKind::Adt(TypeId::Adt(id), args) => {
+                format!("{}{}", self.type_name(*id)?, self.generic_args(args)?)
+            }
+            TyKind::Adt(TypeId::Builtin(BuiltinTy::Box), args) => {
+                format!("alloc::boxed::Box{}", self.generic_args(args)?)
+            }
+            TyKind::Adt(TypeId::Builtin(BuiltinTy::Str), _) => "str".to_owned(),
+            TyKind::Adt(TypeId::Builtin(BuiltinTy::Slice), args)
+                if args.types.elem_count() == 1 =>
+            {
+                format!("[{}]", self.ty(&args.types[TypeVarId::ZERO])?)
+            }
+            TyKind::Adt(TypeId::Builtin(BuiltinTy::Array), args)
+                if args.types.elem_count() == 1 && args.const_generics.elem_count() == 1 =>
+            {
+                format!(
+                    "[{}; {}]",
+                    self.ty(&args.types[TypeVarId::ZERO])?,
+                    self.const_generic(&args.const_generics[ConstGenericVarId::ZERO])?
+                )
+            }
+            _ => bail!("this kind of type is not supported"),
+        })
+    }
+
+    fn named_fields(&self, fields: &Vector<FieldId, Field>) -> Result<Vec<String>> {
+        fields
+            .iter()
+            .map(|field| match &field.name {
+                Some(name) => Ok(format!(
+                    "{}{}: {}",
+                    visibility(&field.attr_info),
+                    ident(name)?,
+                    self.ty(&field.ty)?
+                )),
+                None => bail!("the fields of braced structs and variants must be named"),
+            })
+            .collect()
+    }
+
+    fn tuple_fields(&self, fields: &Vector<FieldId, Field>) -> Result<String> {
+        let fields = fields
+            .iter()
+            .map(|field| -> Result<String> {
+                Ok(format!(
+                    "{}{}",
+                    visibility(&field.attr_info),
+                    self.ty(&field.ty)?
+                ))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        Ok(fields.join(", "))
+    }
+
+    fn type_decl(&self, out: &mut String, decl: &TypeDecl) -> Result<()> {
+        let vis = visibility(&decl.item_meta.attr_info);
+        let name = name(&decl.item_meta.name)?;
+        let generics = self.generic_params(&decl.generics)?;
+        match &decl.kind {
+            TypeDeclKind::Struct(fields) => match decl.ctor_kind {
+                Some(CtorKind::Tuple) => writeln!(
+                    out,
+                    "{vis}struct {name}{generics}({});",
+                    self.tuple_fields(fields)?
+                )?,
+                Some(CtorKind::Unit) if fields.is_empty() => {
+                    writeln!(out, "{vis}struct {name}{generics};")?
+                }
+                _ => {
+                    writeln!(out, "{vis}struct {name}{generics} {{")?;
+                    for field in self.named_fields(fields)? {
+                        writeln!(out, "    {field},")?;
+                    }
+                    writeln!(out, "}}")?;
+                }
+            },
+            TypeDeclKind::Union(fields) => {
+                writeln!(out, "{vis}union {name}{generics} {{")?;
+                for field in self.named_fields(fields)? {
+                    writeln!(out, "    {field},")?;
+                }
+                writeln!(out, "}}")?;
+            }
+            TypeDeclKind::Enum(variants) => {
+                writeln!(out, "{vis}enum {name}{generics} {{")?;
+                for variant in variants {
+                    let fields = match variant.ctor_kind {
+                        CtorKind::Unit if variant.fields.is_empty() => String::new(),
+                        CtorKind::Tuple => format!("({})", self.tuple_fields(&variant.fields)?),
+                        _ => match self.named_fields(&variant.fields)?.as_slice() {
+                            [] => " {}".to_owned(),
+                            fields => format!(" {{ {} }}", fields.join(", ")),
+                        },
+                    };
+                    let discriminant = literal(&Literal::Scalar(variant.discriminant))?;
+                    writeln!(
+                        out,
+                        "    {}{fields} = {discriminant},",
+                        ident(&variant.name)?
+                    )?;
+                }
+                writeln!(out, "}}")?;
+            }
+            TypeDeclKind::Opaque => writeln!(out, "{vis}opaque type {name}{generics};")?,
+            TypeDeclKind::Alias(ty) => {
+                writeln!(out, "{vis}type {name}{generics} = {};", self.ty(ty)?)?
+            }
+            TypeDeclKind::Simd { .. } | TypeDeclKind::Error(_) => {
+                bail!("this kind of type declaration is not supported")
+            }
+        }
+        Ok(())
+    }
+
+    fn fun_decl(&self, out: &mut String, decl: &FunDecl) -> Result<()> {
+        let sig = &decl.signature;
+        if sig.is_closure
+            || sig.closure_info.is_some()
+            || sig.abi.is_some()
+            || decl.kind != ItemKind::Regular
+            || decl.is_global_initializer.is_some()
+        {
+            bail!("only regular functions are supported")
+        }
+        let vis = visibility(&decl.item_meta.attr_info);
+        let unsafe_kw = if sig.is_unsafe { "unsafe " } else { "" };
+        let name = name(&decl.item_meta.name)?;
+        let generics = self.generic_params(&sig.generics)?;
+        let inputs = sig
+            .inputs
+            .iter()
+            .map(|ty| self.ty(ty))
+            .collect::<Result<Vec<_>>>()?;
+        write!(
+            out,
+            "{vis}{unsafe_kw}fn {name}{generics}({}) -> {}",
+            inputs.join(", "),
+            self.ty(&sig.output)?
+        )?;
+        match &decl.body {
+            Err(Opaque) => writeln!(out, ";")?,
+            Ok(Body::Structured(body)) => {
+                writeln!(out, " {{")?;
+                Printer {
+                    locals: Some(&body.locals),
+                    ..*self
+                }
+                .body(out, body)?;
+                writeln!(out, "}}")?;
+            }
+            Ok(Body::Unstructured(_)) => bail!("only structured bodies are supported"),
+        }
+        Ok(())
+    }
+
+    fn body(&self, out: &mut String, body: &ExprBody) -> Result<()> {
+        for var in &body.locals.vars {
+            writeln!(
+                out,
+                "    let {}: {};",
+                self.local(var.index)?,
+                self.ty(&var.ty)?
+            )?;
+        }
+        writeln!(out)?;
+        self.block(out, &body.body, 1)
+    }
+
+    fn local(&self, var_id: VarId) -> Result<String> {
+        let Some(var) = self.locals.and_then(|locals| locals.vars.get(var_id)) else {
+            bail!("missing local {var_id}")
+        };
+        Ok(match &var.name {
+            Some(name) => format!("{}@{}", ident(name)?, var_id.index()),
+            None => format!("@{}", var_id.index()),
+        })
+    }
+
+    fn place(&self, place: &Place) -> Result<String> {
+        Ok(match &place.kind {
+            PlaceKind::Base(var_id) => self.local(*var_id)?,
+            PlaceKind::Projection(inner, ProjectionElem::Deref) => {
+                format!("*{}", self.place(inner)?)
+            }
+            PlaceKind::Projection(inner, ProjectionElem::Field(kind, field_id)) => {
+                let inner = match (kind, &inner.kind) {
+                    (FieldProjKind::Adt(type_id, Some(variant_id)), _) => format!(
+                        "({} as {})",
+                        self.place(inner)?,
+                        self.variant_name(*type_id, *variant_id)?
+                    ),
+                    (FieldProjKind::ClosureState, _) => bail!("closure states are not supported"),
+                    (_, PlaceKind::Projection(_, ProjectionElem::Deref)) => {
+                        format!("({})", self.place(inner)?)
+                    }
+                    _ => self.place(inner)?,
+                };
+                format!("{inner}.{}", field_id.index())
+            }
+            PlaceKind::Projection(..) => bail!("index and subslice projections are not supported"),
+        })
+    }
+
+    fn operand(&self, op: &Operand) -> Result<String> {
+        Ok(match op {
+            Operand::Copy(place) => format!("copy {}", self.place(place)?),
+            Operand::Move(place) => format!("move {}", self.place(place)?),
+            Operand::Const(ConstantExpr {
+                value: RawConstantExpr::Literal(lit),
+                ..
+            }) => format!("const {}", literal(lit)?),
+            Operand::Const(_) => bail!("only literal constants are supported"),
+        })
+    }
+
+    fn operands(&self, ops: &[Operand]) -> Result<Vec<String>> {
+        ops.iter().map(|op| self.operand(op)).collect()
+    }
+
+    fn rvalue(&self, rv: &Rvalue) -> Result<String> {
+        Ok(match rv {
+            Rvalue::Use(op) => self.operand(op)?,
+            Rvalue::Ref(place, kind) => {
+                let kind = match kind {
+                    BorrowKind::Shared => "",
+                    BorrowKind::Mut => "mut ",
+                    BorrowKind::TwoPhaseMut => "two_phase_mut ",
+                    BorrowKind::Shallow => "shallow ",
+                    BorrowKind::UniqueImmutable => "uniq ",
+                };
+                format!("&{kind}{}", self.place(place)?)
+            }
+            Rvalue::RawPtr(place, RefKind::Shared) => format!("&raw const {}", self.place(place)?),
+            Rvalue::RawPtr(place, RefKind::Mut) => format!("&raw mut {}", self.place(place)?),
+            Rvalue::BinaryOp(op, lhs, rhs) => {
+                let Some((_, op)) = BINOPS.iter().find(|(binop, _)| binop == op) else {
+                    bail!("unknown binary operator")
+                };
+                format!("{} {op} {}", self.operand(lhs)?, self.operand(rhs)?)
+            }
+            Rvalue::UnaryOp(UnOp::Not, op) => format!("!{}", self.operand(op)?),
+            Rvalue::UnaryOp(UnOp::Neg, op) => format!("-{}", self.operand(op)?),
+            Rvalue::UnaryOp(UnOp::Cast(CastKind::Scalar(from, to)), op) => format!(
+                "cast<{}, {}>({})",
+                literal_ty_name(*from),
+                literal_ty_name(*to),
+                self.operand(op)?
+            ),
+            Rvalue::Discriminant(place, _) => format!("discriminant({})", self.place(place)?),
+            Rvalue::Aggregate(AggregateKind::Adt(TypeId::Tuple, None, None, _), ops) => {
+                match self.operands(ops)?.as_slice() {
+                    [op] => format!("({op},)"),
+                    ops => format!("({})", ops.join(", ")),
+                }
+            }
+            Rvalue::Aggregate(AggregateKind::Adt(TypeId::Adt(id), variant_id, None, args), ops) => {
+                let mut head = format!("{}{}", self.type_name(*id)?, self.generic_args(args)?);
+                if let Some(variant_id) = variant_id {
+                    write!(head, "::{}", self.variant_name(*id, *variant_id)?)?;
+                }
+                match self.operands(ops)?.as_slice() {
+                    [] => format!("{head} {{}}"),
+                    ops => format!("{head} {{ {} }}", ops.join(", ")),
+                }
+            }
+            _ => bail!("this kind of rvalue is not supported"),
+        })
+    }
+
+    fn call(&self, call: &Call) -> Result<String> {
+        let FnOperand::Regular(FnPtr {
+            func: FunIdOrTraitMethodRef::Fun(FunId::Regular(fun_id)),
+            generics,
+        }) = &call.func
+        else {
+            bail!("only calls to the functions of the crate are supported")
+        };
+        Ok(format!(
+            "{}{}({})",
+            self.fun_name(*fun_id)?,
+            self.generic_args(generics)?,
+            self.operands(&call.args)?.join(", ")
+        ))
+    }
+
+    fn block(&self, out: &mut String, block: &Block, indent: usize) -> Result<()> {
+        for st in &block.statements {
+            self.statement(out, st, indent)?;
+        }
+        Ok(())
+    }
+
+    /// Print an arm of a `switch` or `match`.
+    fn arm(&self, out: &mut String, pat: &str, block: &Block, indent: usize) -> Result<()> {
+        let tab = "    ".repeat(indent);
+        writeln!(out, "{tab}{pat} => {{")?;
+        self.block(out, block, indent + 1)?;
+        writeln!(out, "{tab}}}")?;
+        Ok(())
+    }
+
+    fn statement(&self, out: &mut String, st: &Statement, indent: usize) -> Result<()> {
+        if !st.annotations.is_empty() {
+            bail!("annotations are not supported")
+        }
+        let tab = "    ".repeat(indent);
+        match &st.content {
+            RawStatement::Assign(place, rv) => {
+                writeln!(out, "{tab}{} := {};", self.place(place)?, self.rvalue(rv)?)?
+            }
+            RawStatement::Call(call) => writeln!(
+                out,
+                "{tab}{} := call {};",
+                self.place(&call.dest)?,
+                self.call(call)?
+            )?,
+            RawStatement::FakeRead(place) => {
+                writeln!(out, "{tab}fake_read {};", self.place(place)?)?
+            }
+            RawStatement::Drop(place) => writeln!(out, "{tab}drop {};", self.place(place)?)?,
+            RawStatement::Assert(assert) => {
+                if assert.comparison.is_some() {
+                    bail!("the assertions of `assert_eq!` and `assert_ne!` are not supported")
+                }
+                if assert.message.is_some() {
+                    bail!("the messages of assertions are not supported")
+                }
+                let not = if assert.expected { "" } else { "!" };
+                writeln!(out, "{tab}assert {not}{};", self.operand(&assert.cond)?)?
+            }
+            RawStatement::Abort(AbortKind::Panic(name, _)) => {
+                writeln!(out, "{tab}panic {};", self::name(name)?)?
+            }
+            RawStatement::Abort(AbortKind::UndefinedBehavior) => {
+                writeln!(out, "{tab}undefined_behavior;")?
+            }
+            RawStatement::Return => writeln!(out, "{tab}return;")?,
+            RawStatement::Break(i) => writeln!(out, "{tab}break {i};")?,
+            RawStatement::Continue(i) => writeln!(out, "{tab}continue {i};")?,
+            RawStatement::Nop => writeln!(out, "{tab}nop;")?,
+            RawStatement::Switch(Switch::If(op, then_block, else_block)) => {
+                writeln!(out, "{tab}if {} {{", self.operand(op)?)?;
+                self.block(out, then_block, indent + 1)?;
+                writeln!(out, "{tab}}} else {{")?;
+                self.block(out, else_block, indent + 1)?;
+                writeln!(out, "{tab}}}")?;
+            }
+            RawStatement::Switch(Switch::SwitchInt(op, _, arms, otherwise)) => {
+                writeln!(out, "{tab}switch {} {{", self.operand(op)?)?;
+                for (lits, block) in arms {
+                    if lits.is_empty() {
+                        bail!("empty switch arms are not supported")
+                    }
+                    let lits = lits.iter().map(literal).collect::<Result<Vec<_>>>()?;
+                    self.arm(out, &lits.join(" | "), block, indent + 1)?;
+                }
+                self.arm(out, "_", otherwise, indent + 1)?;
+                writeln!(out, "{tab}}}")?;
+            }
+            RawStatement::Switch(Switch::Match(place, arms, otherwise)) => {
+                let TyKind::Adt(TypeId::Adt(type_id), _) = place.ty.kind() else {
+                    bail!("matched place is not an enum")
+                };
+                writeln!(out, "{tab}match {} {{", self.place(place)?)?;
+                for (variant_ids, block) in arms {
+                    if variant_ids.is_empty() {
+                        bail!("empty match arms are not supported")
+                    }
+                    let variants = variant_ids
+                        .iter()
+                        .map(|id| self.variant_name(*type_id, *id))
+                        .collect::<Result<Vec<_>>>()?;
+                    self.arm(out, &variants.join(" | "), block, indent + 1)?;
+                }
+                if let Some(otherwise) = otherwise {
+                    self.arm(out, "_", otherwise, indent + 1)?;
+                }
+                writeln!(out, "{tab}}}")?;
+            }
+            RawStatement::Loop(block) => {
+                writeln!(out, "{tab}loop {{")?;
+                self.block(out, block, indent + 1)?;
+                writeln!(out, "{tab}}}")?;
+            }
+            _ => bail!("this kind of statement is not supported"),
+        }
+        Ok(())
+    }
+}
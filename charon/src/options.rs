@@ -1,6 +1,8 @@
 //! The options that control charon behavior.
+use clap::ValueEnum;
 use indoc::indoc;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::path::PathBuf;
 
 use crate::{ast::*, errors::ErrorCtx, name_matcher::NamePattern, raise_error, register_error};
@@ -51,6 +53,11 @@ pub struct CliOpts {
     #[clap(long = "read-llbc", value_parser)]
     #[serde(default)]
     pub read_llbc: Option<PathBuf>,
+    /// When pretty-printing with `--read-llbc`, elide the statements nested more than this many
+    /// blocks deep. This avoids overflowing the stack on pathological bodies.
+    #[clap(long = "print-depth", value_parser)]
+    #[serde(default)]
+    pub print_depth: Option<usize>,
     /// The destination directory. Files will be generated as `<dest_dir>/<crate_name>.{u}llbc`,
     /// unless `dest_file` is set. `dest_dir` defaults to the current directory.
     #[clap(long = "dest", value_parser)]
@@ -61,6 +68,24 @@ pub struct CliOpts {
     #[clap(long = "dest-file", value_parser)]
     #[serde(default)]
     pub dest_file: Option<PathBuf>,
+    /// Instead of a single file, write the translated items to this directory, split by kind:
+    /// `types.llbc`, `funs.llbc`, `globals.llbc` and `traits.llbc`, plus an `index.llbc` that
+    /// contains the item names and the declaration order. Item ids are the same across files so
+    /// cross-references are preserved. If this is set we ignore `dest_dir` and `dest_file`.
+    #[clap(long = "split-by-kind", value_parser)]
+    #[serde(default)]
+    pub split_by_kind: Option<PathBuf>,
+    /// Add to the output a `modules` tree that nests the ids of the items under the module that
+    /// defines them, so that consumers don't have to parse the item names.
+    #[clap(long = "group-by-module")]
+    #[serde(default)]
+    pub group_by_module: bool,
+    /// Write the serialized crate to stdout instead of a file, e.g. to pipe it into another
+    /// tool. Logs and diagnostics still go to stderr. Can't be combined with the options that
+    /// choose an output file, nor with the `--print-*` options.
+    #[clap(long = "output-stdout")]
+    #[serde(default)]
+    pub output_stdout: bool,
     /// If activated, use Polonius' non-lexical lifetimes (NLL) analysis.
     /// Otherwise, use the standard borrow checker.
     #[clap(long = "polonius")]
@@ -164,6 +189,18 @@ pub struct CliOpts {
     )]
     #[serde(default)]
     pub exclude: Vec<String>,
+    #[clap(
+        long = "opaque-file",
+        value_parser,
+        help = indoc!("
+            Read more patterns of items to keep opaque from this file, one per line, for when
+            passing them all with `--opaque` is unwieldy. A line starting with `!` is a pattern of
+            items to keep transparent instead, e.g. to make an exception inside an opaque module.
+            Empty lines and lines starting with `#` are ignored. The patterns are combined with
+            the ones of `--include` and `--opaque`, see the doc there.
+    "))]
+    #[serde(default)]
+    pub opaque_file: Option<PathBuf>,
     /// List of traits for which we transform associated types to type parameters.
     #[clap(
         long = "remove-associated-types",
@@ -186,10 +223,41 @@ pub struct CliOpts {
     #[clap(long = "rustc-flag", alias = "rustc-arg")]
     #[serde(default)]
     pub rustc_args: Vec<String>,
+    /// Extra `--extern <name>=<path>` flags to pass to rustc, e.g. for a dependency built outside
+    /// of cargo. These take precedence over the `--extern` flags for the same crate that cargo
+    /// computed.
+    #[clap(long = "extern", value_name = "NAME=PATH")]
+    #[serde(default)]
+    pub extern_args: Vec<String>,
+    /// Extra library search paths to pass to rustc as `-L <path>`.
+    #[clap(short = 'L', value_name = "PATH")]
+    #[serde(default)]
+    pub library_paths: Vec<String>,
     /// Extra flags to pass to cargo. Incompatible with `--no-cargo`.
     #[clap(long = "cargo-arg")]
     #[serde(default)]
     pub cargo_args: Vec<String>,
+    /// The Rust edition to compile the crate with. Defaults to the edition of the `Cargo.toml`
+    /// manifest: cargo passes it when using `charon cargo`, and `charon rustc` reads it from
+    /// the closest `Cargo.toml` above the input file if there is one.
+    #[clap(long = "edition", value_parser = ["2015", "2018", "2021", "2024"])]
+    #[serde(default)]
+    pub edition: Option<String>,
+    /// Override the name of the crate, which appears in the names of its items and in the name of
+    /// the output file `<dest_dir>/<crate_name>.llbc`. This is passed to rustc as `--crate-name`.
+    #[clap(long = "crate-name")]
+    #[serde(default)]
+    pub crate_name: Option<String>,
+    /// Check that the rustc toolchain Charon will use matches the one Charon was built with, then
+    /// exit without translating anything.
+    #[clap(long = "version-check")]
+    #[serde(default)]
+    pub version_check: bool,
+    /// Only warn (instead of erroring) when the rustc toolchain Charon will use doesn't match the
+    /// one Charon was built with.
+    #[clap(long = "ignore-toolchain-mismatch")]
+    #[serde(default)]
+    pub ignore_toolchain_mismatch: bool,
     /// Panic on the first error. This is useful for debugging.
     #[clap(long = "abort-on-error")]
     #[serde(default)]
@@ -198,12 +266,97 @@ pub struct CliOpts {
     #[clap(long = "error-on-warnings", help = "Consider any warnings as errors")]
     #[serde(default)]
     pub error_on_warnings: bool,
+    /// How to emit diagnostics. `human` prints rendered diagnostics on stderr; `json` prints one
+    /// JSON object per line, of the shape `{ level, message, span, code }`.
+    #[clap(long = "error-format", value_enum, default_value = "human")]
+    #[serde(default)]
+    pub error_format: ErrorFormat,
+    /// Write the `json` diagnostics to this file instead of stderr. Use `/dev/fd/<n>` to write
+    /// them to an already-open file descriptor.
+    #[clap(long = "error-output", value_parser)]
+    #[serde(default)]
+    pub error_output: Option<PathBuf>,
+    /// Stop the translation once this many items failed to translate, listing them. By default we
+    /// translate as much as we can whatever the number of failures.
+    #[clap(long = "max-errors", value_name = "N")]
+    #[serde(default)]
+    pub max_errors: Option<usize>,
+    /// Run the passes that transform each function body on its own on up to this many threads. The
+    /// output is the same whatever the number of threads. By default we use a single thread.
+    #[clap(long = "max-parallel", value_name = "N")]
+    #[serde(default)]
+    pub max_parallel: Option<usize>,
+    #[clap(
+        long = "on-unsupported",
+        value_name = "[CATEGORY=]POLICY",
+        help = indoc!("
+            What to do when we encounter a Rust feature that we can't translate. The categories
+            are `asm` (inline assembly), `simd` (`#[repr(simd)]` types with an unsupported layout)
+            and `coroutine` (coroutines and async code). The policies are `warn` (the default:
+            report a warning, and make the body or type that uses the feature opaque), `opaque`
+            (make it opaque without reporting anything) and `error` (report an error and make
+            charon fail). Without a category, the policy applies to all of them. Can be given
+            several times; the last mention of a category wins.
+    "))]
+    #[serde(default)]
+    pub on_unsupported: Vec<String>,
+    /// Write to this file the list of the items whose contents we didn't translate (opaque types
+    /// and functions without a body), grouped by crate, along with the reason why.
+    #[clap(long = "emit-opaque-manifest", value_parser)]
+    #[serde(default)]
+    pub emit_opaque_manifest: Option<PathBuf>,
+    /// Write the dependency graph between items to this file, in the graphviz `.dot` format.
+    /// Groups of mutually recursive items are drawn as clusters.
+    #[clap(long = "emit-deps-graphviz", value_parser)]
+    #[serde(default)]
+    pub emit_deps_graphviz: Option<PathBuf>,
+    /// Write to this file a JSON table that maps each translated function and type to its name,
+    /// the rustc `DefId` it comes from and its source span. Useful to correlate the output with
+    /// other tools such as rustdoc.
+    #[clap(long = "emit-symbol-table", value_parser)]
+    #[serde(default)]
+    pub emit_symbol_table: Option<PathBuf>,
+    /// Write to this file a JSON table that maps the index of each translated type to its size and
+    /// alignment in bytes, as computed by rustc. The types rustc can't lay out, such as the
+    /// generic types, are omitted.
+    #[clap(long = "emit-type-sizes", value_parser)]
+    #[serde(default)]
+    pub emit_type_sizes: Option<PathBuf>,
+    /// Write to this file a JSON list of the external crates whose items appear in the output,
+    /// with their versions when they are listed in the `Cargo.lock` of the translated crate.
+    #[clap(long = "emit-dependencies", value_parser)]
+    #[serde(default)]
+    pub emit_dependencies: Option<PathBuf>,
+    /// Read models for the functions we don't have the body of from this JSON file, and use them
+    /// as the bodies of these functions. Each model gives a name pattern, an unstructured body and
+    /// optionally a signature; models that don't fit the signature of the function are rejected.
+    #[clap(long = "models", value_parser)]
+    #[serde(default)]
+    pub models: Option<PathBuf>,
+    /// Also write the ULLBC, as it is just before control-flow reconstruction, to a `.ullbc` file
+    /// next to the `.llbc` one. The two files share the same item ids. Has no effect with
+    /// `--ullbc`.
+    #[clap(long = "emit-ullbc")]
+    #[serde(default)]
+    pub emit_ullbc: bool,
     #[clap(
         long = "no-serialize",
         help = "Don't serialize the final (U)LLBC to a file."
     )]
     #[serde(default)]
     pub no_serialize: bool,
+    /// Run the whole translation without writing the output file, and exit with an error code if
+    /// any item failed to translate. Unless `--abort-on-error` is set, the items that failed are
+    /// listed. This is a quick way to check that Charon supports a given crate.
+    #[clap(long = "check")]
+    #[serde(default)]
+    pub check: bool,
+    /// Check some invariants of the translated crate before exporting it: that the items referred
+    /// to exist, that the locals used in bodies are declared, and that the control-flow targets
+    /// exist. Violations are reported as errors; they indicate a bug in Charon.
+    #[clap(long = "validate")]
+    #[serde(default)]
+    pub validate: bool,
     #[clap(
         long = "print-original-ullbc",
         help = "Print the ULLBC immediately after extraction from MIR."
@@ -235,6 +388,214 @@ pub struct CliOpts {
     "))]
     #[serde(default)]
     pub no_merge_goto_chains: bool,
+    #[clap(
+        long = "single-return",
+        help = indoc!("
+            Give each body a single exit point: all the `return`s are replaced with a `goto` to a
+            single exit block. The returned value is always stored in the return local first.
+    "))]
+    #[serde(default)]
+    pub single_return: bool,
+    #[clap(
+        long = "cse",
+        help = indoc!("
+            Hoist the side-effect-free computations which are done identically at the start of
+            both branches of an `if` to before the `if`.
+    "))]
+    #[serde(default)]
+    pub cse: bool,
+    #[clap(
+        long = "normalize-if-polarity",
+        help = indoc!("
+            Rewrite `if !c { A } else { B }` into `if c { B } else { A }`, so that the conditions
+            of `if`s are never a single negation.
+    "))]
+    #[serde(default)]
+    pub normalize_if_polarity: bool,
+    #[clap(
+        long = "reconstruct-let-chains",
+        help = indoc!("
+            Turn the nested matches that `if let A = x && let B = y && cond` chains are lowered to
+            back into a single chain of refutable bindings followed by a condition.
+    "))]
+    #[serde(default)]
+    pub reconstruct_let_chains: bool,
+    #[clap(
+        long = "reconstruct-let-else",
+        help = indoc!("
+            Turn the matches with a diverging branch into `let .. else` statements. MIR doesn't
+            distinguish `let .. else` from the `match`es and `if let`s with a diverging branch, e.g.
+            the ones `?` is lowered to, so those are turned into `let .. else` too.
+    "))]
+    #[serde(default)]
+    pub reconstruct_let_else: bool,
+    #[clap(
+        long = "reconstruct-while-loops",
+        help = indoc!("
+            Turn the loops that start by checking a side-effect-free condition into `while` loops.
+            MIR doesn't distinguish `while` loops from the `loop`s that start with an
+            `if .. { break }`, so those are turned into `while` loops too.
+    "))]
+    #[serde(default)]
+    pub reconstruct_while_loops: bool,
+    #[clap(
+        long = "reconstruct-range-matches",
+        help = indoc!("
+            Turn the chains of comparisons that range patterns like `0..=9` are lowered to into
+            `switch`es over ranges. MIR doesn't distinguish these from `if`s that compare a value
+            to constants, so those are turned into range `switch`es too.
+    "))]
+    #[serde(default)]
+    pub reconstruct_range_matches: bool,
+    #[clap(
+        long = "reconstruct-deref",
+        help = indoc!("
+            Replace the calls to `Deref::deref` and `DerefMut::deref_mut` on references and boxes
+            with the reborrow of a dereference, e.g. `&**b`. Calls to other `Deref` impls are kept
+            as trait method calls.
+    "))]
+    #[serde(default)]
+    pub reconstruct_deref: bool,
+    #[clap(
+        long = "reconstruct-struct-updates",
+        help = indoc!("
+            Turn the aggregates that read some of their fields from another value of the same
+            struct back into struct-update syntax, e.g. `Foo { x: 1, ..base }`. MIR doesn't
+            distinguish this from fields written out explicitly, like `Foo { x: 1, y: base.y }`,
+            so these are turned into struct updates too.
+    "))]
+    #[serde(default)]
+    pub reconstruct_struct_updates: bool,
+    #[clap(
+        long = "reconstruct-method-calls",
+        help = indoc!("
+            Print the calls to methods whose receiver is a reference with the method-call syntax,
+            e.g. `(x).len()`.
+    "))]
+    #[serde(default)]
+    pub reconstruct_method_calls: bool,
+    #[clap(
+        long = "overflow",
+        value_enum,
+        default_value = "panic",
+        help = indoc!("
+            How to model the overflow of `+`, `-` and `*` on integers. `panic` merges the overflow
+            checks inserted by rustc into the operations, which fail on overflow; `wrapping` removes
+            them and makes the operations wrap around; `checked` keeps the checks explicit.
+    "))]
+    #[serde(default)]
+    pub overflow: OverflowMode,
+    #[clap(
+        long = "fold-constants",
+        help = indoc!("
+            Evaluate the `+`, `-` and `*` operations on integer constants. How overflowing
+            operations are folded depends on `--overflow`.
+    "))]
+    #[serde(default)]
+    pub fold_constants: bool,
+    #[clap(
+        long = "normalize-assoc-types",
+        help = indoc!("
+            Replace the associated type projections `<T as Trait>::Type` with the type the impl
+            assigns to them, when the impl is local and non-generic. Projections on type parameters
+            are kept.
+    "))]
+    #[serde(default)]
+    pub normalize_assoc_types: bool,
+    #[clap(
+        long = "no-mem-builtins",
+        help = indoc!("
+            Translate the calls to `core::mem::swap`, `core::mem::replace` and `core::mem::take` as
+            calls to regular functions, instead of to the `MemSwap`, `MemReplace` and `MemTake`
+            builtins.
+    "))]
+    #[serde(default)]
+    pub no_mem_builtins: bool,
+    #[clap(
+        long = "mark-unsafe",
+        help = indoc!("
+            Insert `EnterUnsafe`/`ExitUnsafe` markers around the statements that come from an
+            `unsafe` block.
+    "))]
+    #[serde(default)]
+    pub mark_unsafe: bool,
+    #[clap(
+        long = "derived-cmp-builtins",
+        help = indoc!("
+            Translate the calls to `Ord::cmp` and `PartialOrd::partial_cmp` that resolve to an impl
+            generated by `#[derive(..)]` as calls to the `DerivedCmp` and `DerivedPartialCmp`
+            builtins, instead of calls to the generated code. Backends can then model these with
+            the lexicographic ordering of the fields.
+    "))]
+    #[serde(default)]
+    pub derived_cmp_builtins: bool,
+    #[clap(
+        long = "inline-derived-eq",
+        help = indoc!("
+            Replace the calls to `PartialEq::eq` that resolve to an impl generated by
+            `#[derive(..)]` on a local enum with the comparison it performs: check that both values
+            have the same variant, then compare their fields. Only applies to the enums without
+            type parameters whose fields are all of primitive types.
+    "))]
+    #[serde(default)]
+    pub inline_derived_eq: bool,
+    #[clap(
+        long = "hash-builtins",
+        help = indoc!("
+            Translate the calls to `Hash::hash` that resolve to an impl generated by
+            `#[derive(..)]` as calls to the `DerivedHash` builtin, and the calls to the methods of
+            `Hasher` as calls to the `HasherWrite` and `HasherFinish` builtins, so that the
+            hashing internals don't need to be verified.
+    "))]
+    #[serde(default)]
+    pub hash_builtins: bool,
+    #[clap(
+        long = "abstract-formatting",
+        help = indoc!("
+            Translate the constructors of `core::fmt::Arguments` that `format_args!` (hence
+            `format!`, `println!`, etc.) expands to as calls to the `FormatArgs`, `FmtArgDisplay`
+            and `FmtArgDebug` builtins, and the calls to `Display::fmt` and `Debug::fmt` as calls to
+            the `FmtDisplay` and `FmtDebug` builtins, so that backends can treat formatting
+            abstractly.
+    "))]
+    #[serde(default)]
+    pub abstract_formatting: bool,
+    #[clap(
+        long = "defunctionalize",
+        help = indoc!("
+            Translate each closure as a top-level function that takes a struct holding the captured
+            values as first argument. The closure values built and called in the same function
+            become values of that struct, and the calls to them become direct calls to the closure
+            function.
+    "))]
+    #[serde(default)]
+    pub defunctionalize: bool,
+    /// Allocate the item ids in the order of the item names instead of the order in which we
+    /// encounter the items, so that the output doesn't depend on how rustc explores the crate.
+    /// See `transform::stable_ids` for the exact ordering key.
+    #[clap(long = "stable-ids")]
+    #[serde(default)]
+    pub stable_ids: bool,
+    #[clap(
+        long = "annotation-prefix",
+        help = indoc!("
+            Parse the whole-line `//` comments inside function bodies that start with this prefix
+            as verification directives (`assume <cond>` or `assert <cond>`), and attach them to the
+            following statement as annotations instead of plain comments. Defaults to `charon:`.
+    "))]
+    #[serde(default)]
+    pub annotation_prefix: Option<String>,
+    #[clap(
+        long = "remap-path-prefix",
+        value_name = "FROM=TO",
+        help = indoc!("
+            Replace the prefix `FROM` of the source paths recorded in the output with `TO`, like
+            the rustc option of the same name. When several mappings match a path, the last one
+            wins. The paths that no mapping matches and that are in the directory of the crate root
+            file are made relative to that directory.
+    "))]
+    #[serde(default)]
+    pub remap_path_prefix: Vec<String>,
 }
 
 impl CliOpts {
@@ -249,15 +610,87 @@ impl CliOpts {
             !self.mir_promoted || !self.mir_optimized,
             "Can't use --mir_promoted and --mir_optimized at the same time"
         );
+
+        assert!(
+            !self.output_stdout
+                || (self.dest_dir.is_none()
+                    && self.dest_file.is_none()
+                    && self.split_by_kind.is_none()
+                    && !self.emit_ullbc),
+            "Can't use --output-stdout with --dest, --dest-file, --split-by-kind or --emit-ullbc"
+        );
+        assert!(
+            !self.output_stdout
+                || !(self.print_original_ullbc
+                    || self.print_ullbc
+                    || self.print_built_llbc
+                    || self.print_llbc),
+            "Can't use --output-stdout with the --print-* options"
+        );
     }
 }
 
+/// The format in which diagnostics are emitted.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, clap::ValueEnum, Serialize, Deserialize)]
+pub enum ErrorFormat {
+    /// Rendered with source snippets, for humans.
+    #[default]
+    Human,
+    /// Newline-delimited JSON, for tools.
+    Json,
+}
+
+/// How the overflow of the arithmetic operations `+`, `-` and `*` is modeled.
+///
+/// Which overflow checks rustc inserts depends on the MIR level: in built and promoted MIR they
+/// are present in the bodies that are compiled with overflow checks (which is the case of
+/// constants), while optimized MIR may already have removed or folded some of them. This option
+/// only decides what we do with the checks and operations that are left.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, clap::ValueEnum, Serialize, Deserialize)]
+#[charon::variants_prefix("Overflow")]
+pub enum OverflowMode {
+    /// The operations fail on overflow: the checks inserted by rustc are merged into them, and
+    /// folding an overflowing operation is an error.
+    #[default]
+    Panic,
+    /// The operations wrap around on overflow: the checks inserted by rustc are removed, and the
+    /// operations become [crate::ast::BinOp::WrappingAdd] etc.
+    Wrapping,
+    /// The checks inserted by rustc are kept as a [crate::ast::BinOp::CheckedAdd] (etc.) followed
+    /// by an `assert`.
+    Checked,
+}
+
+/// The categories of Rust features that we can't translate. See `--on-unsupported`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, clap::ValueEnum)]
+pub enum UnsupportedFeature {
+    /// Inline assembly.
+    Asm,
+    /// `#[repr(simd)]` types whose layout we don't recognize.
+    Simd,
+    /// Coroutines, including `async` blocks and functions.
+    Coroutine,
+}
+
+/// What to do when we encounter a feature that we can't translate.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum UnsupportedPolicy {
+    /// Report an error, and fail at the end of the translation.
+    Error,
+    /// Report a warning and make the body or type that uses the feature opaque.
+    #[default]
+    Warn,
+    /// Make the body or type that uses the feature opaque without reporting anything.
+    Opaque,
+}
+
 /// TODO: maybe we should always target MIR Built, this would make things
 /// simpler. In particular, the MIR optimized is very low level and
 /// reveals too many types and data-structures that we don't want to manipulate.
-#[derive(Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum MirLevel {
     /// Original MIR, directly translated from HIR.
+    #[default]
     Built,
     /// Not sure what this is. Not well tested.
     Promoted,
@@ -281,17 +714,93 @@ pub struct TranslateOptions {
     pub hide_marker_traits: bool,
     /// Do not merge the chains of gotos.
     pub no_merge_goto_chains: bool,
+    /// Redirect all the `return`s to a single exit block.
+    pub single_return: bool,
     /// Print the llbc just after control-flow reconstruction.
     pub print_built_llbc: bool,
+    /// Hoist the computations common to both branches of an `if`.
+    pub cse: bool,
+    /// Remove the negations in the conditions of `if`s by swapping the branches.
+    pub normalize_if_polarity: bool,
+    /// Reconstruct the `if let .. && ..` chains.
+    pub reconstruct_let_chains: bool,
+    /// Reconstruct the `let .. else` statements.
+    pub reconstruct_let_else: bool,
+    /// Reconstruct the `while` loops whose condition has no side effects.
+    pub reconstruct_while_loops: bool,
+    /// Turn the comparisons of a value with constants into `switch`es over ranges.
+    pub reconstruct_range_matches: bool,
+    /// Replace the calls to the `Deref` impls of references and boxes with a dereference.
+    pub reconstruct_deref: bool,
+    /// Reconstruct struct-update syntax, e.g. `Foo { x: 1, ..base }`.
+    pub reconstruct_struct_updates: bool,
+    /// Print the calls to methods with the method-call syntax, e.g. `(x).len()`.
+    pub reconstruct_method_calls: bool,
+    /// How the overflow of arithmetic operations is modeled.
+    pub overflow: OverflowMode,
+    /// Evaluate the arithmetic operations on integer constants.
+    pub fold_constants: bool,
+    /// Replace the projections on local non-generic impls with the type they assign.
+    pub normalize_assoc_types: bool,
+    /// Translate the `core::mem` functions as regular functions instead of builtins.
+    pub no_mem_builtins: bool,
+    /// Insert markers around the statements that come from an `unsafe` block.
+    pub mark_unsafe: bool,
+    /// Translate the comparisons with derived impls as builtins.
+    pub derived_cmp_builtins: bool,
+    /// Inline the derived `PartialEq::eq` on local enums.
+    pub inline_derived_eq: bool,
+    /// Translate the hashing with derived impls and the `Hasher` methods as builtins.
+    pub hash_builtins: bool,
+    /// Translate the formatting machinery as builtins.
+    pub abstract_formatting: bool,
+    /// Turn the closures into top-level functions that take an explicit state struct.
+    pub defunctionalize: bool,
+    /// Allocate the item ids in the order of the item names.
+    pub stable_ids: bool,
+    /// The number of threads the passes over single bodies may use. See `--max-parallel`.
+    pub max_parallel: usize,
+    /// The prefix that marks a `//` comment as a `charon` directive.
+    pub annotation_prefix: String,
     /// List of patterns to assign a given opacity to. Same as the corresponding `TranslateOptions`
     /// field.
     pub item_opacities: Vec<(NamePattern, ItemOpacity)>,
     /// List of traits for which we transform associated types to type parameters.
     pub remove_associated_types: Vec<NamePattern>,
+    /// The `(from, to)` prefixes to replace in the source paths.
+    pub remap_path_prefix: Vec<(PathBuf, PathBuf)>,
+    /// What to do for each category of unsupported features. The categories that aren't in the
+    /// map use the default policy.
+    pub on_unsupported: HashMap<UnsupportedFeature, UnsupportedPolicy>,
 }
 
 impl TranslateOptions {
     pub fn new(error_ctx: &mut ErrorCtx, options: &CliOpts) -> Self {
+        // The `(pattern, is_opaque)` pairs read from the `--opaque-file`.
+        let opaque_file_patterns: Vec<(String, bool)> = match &options.opaque_file {
+            None => vec![],
+            Some(path) => match std::fs::read_to_string(path) {
+                Ok(contents) => contents
+                    .lines()
+                    .map(str::trim)
+                    .filter(|line| !line.is_empty() && !line.starts_with('#'))
+                    .map(|line| match line.strip_prefix('!') {
+                        Some(pat) => (pat.trim().to_owned(), false),
+                        None => (line.to_owned(), true),
+                    })
+                    .collect(),
+                Err(e) => {
+                    register_error!(
+                        error_ctx,
+                        crate(&TranslatedCrate::default()),
+                        Span::dummy(),
+                        "failed to read the opaque file `{}` ({e})",
+                        path.display()
+                    );
+                    vec![]
+                }
+            },
+        };
         let mut parse_pattern = |s: &str| match NamePattern::parse(s) {
             Ok(p) => Ok(p),
             Err(e) => {
@@ -332,6 +841,9 @@ impl TranslateOptions {
             for pat in options.opaque.iter() {
                 opacities.push((pat.to_string(), Opaque));
             }
+            for (pat, is_opaque) in opaque_file_patterns {
+                opacities.push((pat, if is_opaque { Opaque } else { Transparent }));
+            }
             for pat in options.exclude.iter() {
                 opacities.push((pat.to_string(), Invisible));
             }
@@ -355,18 +867,100 @@ impl TranslateOptions {
             .filter_map(|s| parse_pattern(&s).ok())
             .collect();
 
+        let remap_path_prefix = options
+            .remap_path_prefix
+            .iter()
+            .filter_map(|s| match s.split_once('=') {
+                Some((from, to)) => Some((PathBuf::from(from), PathBuf::from(to))),
+                None => {
+                    register_error!(
+                        error_ctx,
+                        crate(&TranslatedCrate::default()),
+                        Span::dummy(),
+                        "invalid path prefix mapping `{s}`, expected `FROM=TO`"
+                    );
+                    None
+                }
+            })
+            .collect();
+
+        let mut on_unsupported = HashMap::new();
+        for s in &options.on_unsupported {
+            let (features, policy) = match s.split_once('=') {
+                Some((feature, policy)) => (
+                    UnsupportedFeature::from_str(feature, true).map(|f| vec![f]),
+                    policy,
+                ),
+                None => (
+                    Ok(UnsupportedFeature::value_variants().to_vec()),
+                    s.as_str(),
+                ),
+            };
+            match (features, UnsupportedPolicy::from_str(policy, true)) {
+                (Ok(features), Ok(policy)) => {
+                    for feature in features {
+                        on_unsupported.insert(feature, policy);
+                    }
+                }
+                (Err(e), _) | (_, Err(e)) => {
+                    register_error!(
+                        error_ctx,
+                        crate(&TranslatedCrate::default()),
+                        Span::dummy(),
+                        "invalid `--on-unsupported` value `{s}` ({e})"
+                    );
+                }
+            }
+        }
+
         TranslateOptions {
             mir_level,
             no_code_duplication: options.no_code_duplication,
             hide_marker_traits: options.hide_marker_traits,
             no_merge_goto_chains: options.no_merge_goto_chains,
+            single_return: options.single_return,
             print_built_llbc: options.print_built_llbc,
+            cse: options.cse,
+            normalize_if_polarity: options.normalize_if_polarity,
+            reconstruct_let_chains: options.reconstruct_let_chains,
+            reconstruct_let_else: options.reconstruct_let_else,
+            reconstruct_while_loops: options.reconstruct_while_loops,
+            reconstruct_range_matches: options.reconstruct_range_matches,
+            reconstruct_deref: options.reconstruct_deref,
+            reconstruct_struct_updates: options.reconstruct_struct_updates,
+            reconstruct_method_calls: options.reconstruct_method_calls,
+            overflow: options.overflow,
+            fold_constants: options.fold_constants,
+            normalize_assoc_types: options.normalize_assoc_types,
+            no_mem_builtins: options.no_mem_builtins,
+            mark_unsafe: options.mark_unsafe,
+            derived_cmp_builtins: options.derived_cmp_builtins,
+            inline_derived_eq: options.inline_derived_eq,
+            hash_builtins: options.hash_builtins,
+            abstract_formatting: options.abstract_formatting,
+            defunctionalize: options.defunctionalize,
+            stable_ids: options.stable_ids,
+            max_parallel: options.max_parallel.unwrap_or(1).max(1),
+            annotation_prefix: options
+                .annotation_prefix
+                .clone()
+                .unwrap_or_else(|| "charon:".to_owned()),
             item_opacities,
             remove_associated_types,
+            remap_path_prefix,
+            on_unsupported,
             translate_all_methods: options.translate_all_methods,
         }
     }
 
+    /// The policy to follow when we encounter an unsupported feature of this category.
+    pub fn unsupported_policy(&self, feature: UnsupportedFeature) -> UnsupportedPolicy {
+        self.on_unsupported
+            .get(&feature)
+            .copied()
+            .unwrap_or_default()
+    }
+
     /// Find the opacity requested for the given name. This does not take into account
     /// `#[charon::opaque]` annotations, only cli parameters.
     pub fn opacity_for_name(&self, krate: &TranslatedCrate, name: &Name) -> ItemOpacity {
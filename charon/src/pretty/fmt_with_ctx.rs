@@ -52,7 +52,7 @@ pub trait FmtWithCtx<C> {
 impl<C: AstFormatter> FmtWithCtx<C> for AbortKind {
     fn fmt_with_ctx_and_indent(&self, tab: &str, ctx: &C) -> String {
         match self {
-            AbortKind::Panic(name) => format!("{tab}panic({})", name.fmt_with_ctx(ctx)),
+            AbortKind::Panic(name, _) => format!("{tab}panic({})", name.fmt_with_ctx(ctx)),
             AbortKind::UndefinedBehavior => format!("{tab}undefined_behavior"),
         }
     }
@@ -72,11 +72,37 @@ impl<C: AstFormatter> FmtWithCtx<C> for AnyTransItem<'_> {
 
 impl<C: AstFormatter> FmtWithCtx<C> for Assert {
     fn fmt_with_ctx(&self, ctx: &C) -> String {
-        format!(
-            "assert({} == {})",
-            self.cond.fmt_with_ctx(ctx),
-            self.expected,
-        )
+        match &self.comparison {
+            Some(cmp) => {
+                let macro_name = match cmp.op {
+                    BinOp::Ne => "assert_ne",
+                    _ => "assert_eq",
+                };
+                format!(
+                    "{macro_name}({}, {})",
+                    cmp.left.fmt_with_ctx(ctx),
+                    cmp.right.fmt_with_ctx(ctx),
+                )
+            }
+            None => {
+                let message = match &self.message {
+                    Some(message) => {
+                        let args = message
+                            .args
+                            .iter()
+                            .map(|arg| format!(", {}", arg.fmt_with_ctx(ctx)))
+                            .join("");
+                        format!(", {:?}{args}", message.format)
+                    }
+                    None => String::new(),
+                };
+                format!(
+                    "assert({} == {}{message})",
+                    self.cond.fmt_with_ctx(ctx),
+                    self.expected,
+                )
+            }
+        }
     }
 }
 
@@ -103,6 +129,13 @@ impl<C: AstFormatter> FmtWithCtx<C> for llbc::Block {
     }
 
     fn fmt_with_ctx_and_indent(&self, tab: &str, ctx: &C) -> String {
+        let depth = tab.len() / TAB_INCR.len();
+        if ctx
+            .get_max_depth()
+            .is_some_and(|max_depth| depth > max_depth)
+        {
+            return format!("{tab}...\n");
+        }
         self.statements
             .iter()
             .map(|st| st.fmt_with_ctx_and_indent(tab, ctx))
@@ -161,6 +194,14 @@ impl<C: AstFormatter> FmtWithCtx<C> for CastKind {
                     tgt.fmt_with_ctx(ctx)
                 )
             }
+            CastKind::DynUpcast(src, tgt, _, tgt_trait) => {
+                format!(
+                    "upcast<{}, {}>({})",
+                    src.fmt_with_ctx(ctx),
+                    tgt.fmt_with_ctx(ctx),
+                    tgt_trait.fmt_with_ctx(ctx)
+                )
+            }
             CastKind::Transmute(src, tgt) => {
                 format!(
                     "transmute<{}, {}>",
@@ -191,7 +232,15 @@ impl<C: AstFormatter> FmtWithCtx<C> for ConstGeneric {
 impl<C: AstFormatter> FmtWithCtx<C> for ConstGenericVar {
     fn fmt_with_ctx(&self, ctx: &C) -> String {
         let ty = self.ty.fmt_with_ctx(ctx);
-        format!("const {} : {}", self.name, ty)
+        match &self.default {
+            Some(default) => format!(
+                "const {} : {} = {}",
+                self.name,
+                ty,
+                default.fmt_with_ctx(ctx)
+            ),
+            None => format!("const {} : {}", self.name, ty),
+        }
     }
 }
 
@@ -229,6 +278,7 @@ impl<C: AstFormatter> FmtWithCtx<C> for FnOperand {
         match self {
             FnOperand::Regular(func) => func.fmt_with_ctx(ctx),
             FnOperand::Move(p) => format!("(move {})", p.fmt_with_ctx(ctx)),
+            FnOperand::DynClosure { kind, .. } => format!("@Dyn{kind:?}::call"),
         }
     }
 }
@@ -577,7 +627,12 @@ where
     for<'a, 'b, 'c> <<C as SetGenerics<'a>>::C as SetLocals<'b>>::C: AstFormatter,
 {
     fn fmt_with_ctx_and_indent(&self, tab: &str, ctx: &C) -> String {
-        let intro = self.item_meta.fmt_item_intro(ctx, tab, "global");
+        let keyword = match self.global_kind {
+            GlobalKind::ThreadLocal => "thread_local global",
+            GlobalKind::StaticMut => "static mut global",
+            GlobalKind::Static | GlobalKind::NamedConst => "global",
+        };
+        let intro = self.item_meta.fmt_item_intro(ctx, tab, keyword);
 
         // Update the context with the generics
         let ctx = &ctx.set_generics(&self.generics);
@@ -916,32 +971,48 @@ impl<C: AstFormatter> FmtWithCtx<C> for Rvalue {
                     AggregateKind::Adt(def_id, variant_id, field_id, _) => {
                         match def_id {
                             TypeId::Tuple => format!("({})", ops_s.join(", ")),
-                            TypeId::Builtin(_) => unreachable!(),
+                            TypeId::Builtin(_) => {
+                                format!("{}({})", def_id.fmt_with_ctx(ctx), ops_s.join(", "))
+                            }
                             TypeId::Adt(def_id) => {
-                                // Format every field
-                                let mut fields = vec![];
-                                for (i, op) in ops.iter().enumerate() {
-                                    let field_id = match *field_id {
-                                        None => FieldId::new(i),
-                                        Some(field_id) => {
-                                            assert_eq!(i, 0); // there should be only one operand
-                                            field_id
-                                        }
-                                    };
-                                    let field_name =
-                                        ctx.format_object((*def_id, *variant_id, field_id));
-                                    fields.push(format!(
-                                        "{}: {}",
-                                        field_name,
-                                        op.fmt_with_ctx(ctx)
-                                    ));
-                                }
-
                                 let variant = match variant_id {
                                     None => ctx.format_object(*def_id),
                                     Some(variant_id) => ctx.format_object((*def_id, *variant_id)),
                                 };
-                                format!("{} {{ {} }}", variant, fields.join(", "))
+                                // Unions are built one field at a time, which we print with the
+                                // braced syntax.
+                                let ctor_kind = match field_id {
+                                    None => ctx.get_ctor_kind(*def_id, *variant_id),
+                                    Some(_) => None,
+                                };
+                                match ctor_kind {
+                                    Some(CtorKind::Unit) => variant,
+                                    Some(CtorKind::Tuple) => {
+                                        format!("{}({})", variant, ops_s.join(", "))
+                                    }
+                                    Some(CtorKind::Braced) | None => {
+                                        // Format every field
+                                        let mut fields = vec![];
+                                        for (i, op) in ops.iter().enumerate() {
+                                            let field_id = match *field_id {
+                                                None => FieldId::new(i),
+                                                Some(field_id) => {
+                                                    // There should be only one operand.
+                                                    assert_eq!(i, 0);
+                                                    field_id
+                                                }
+                                            };
+                                            let field_name =
+                                                ctx.format_object((*def_id, *variant_id, field_id));
+                                            fields.push(format!(
+                                                "{}: {}",
+                                                field_name,
+                                                op.fmt_with_ctx(ctx)
+                                            ));
+                                        }
+                                        format!("{} {{ {} }}", variant, fields.join(", "))
+                                    }
+                                }
                             }
                         }
                     }
@@ -958,6 +1029,20 @@ impl<C: AstFormatter> FmtWithCtx<C> for Rvalue {
                     }
                 }
             }
+            Rvalue::StructUpdate(kind, fields, base) => {
+                let AggregateKind::Adt(TypeId::Adt(def_id), variant_id, _, _) = kind else {
+                    unreachable!()
+                };
+                let mut fields: Vec<String> = fields
+                    .iter()
+                    .map(|(field_id, op)| {
+                        let field_name = ctx.format_object((*def_id, *variant_id, *field_id));
+                        format!("{}: {}", field_name, op.fmt_with_ctx(ctx))
+                    })
+                    .collect();
+                fields.push(format!("..{}", base.fmt_with_ctx(ctx)));
+                format!("{} {{ {} }}", ctx.format_object(*def_id), fields.join(", "))
+            }
             Rvalue::Global(global_ref) => global_ref.fmt_with_ctx(ctx),
             Rvalue::GlobalRef(global_ref, RefKind::Shared) => {
                 format!("&{}", global_ref.fmt_with_ctx(ctx))
@@ -992,6 +1077,9 @@ impl<C: AstFormatter> FmtWithCtx<C> for ullbc::Statement {
         for line in &self.comments_before {
             let _ = writeln!(&mut out, "{tab}// {line}");
         }
+        for annot in &self.annotations {
+            let _ = writeln!(&mut out, "{tab}{annot}");
+        }
         let _ = match &self.content {
             RawStatement::Assign(place, rvalue) => write!(
                 &mut out,
@@ -1023,6 +1111,8 @@ impl<C: AstFormatter> FmtWithCtx<C> for ullbc::Statement {
             }
             RawStatement::Assert(assert) => write!(&mut out, "{tab}{}", assert.fmt_with_ctx(ctx)),
             RawStatement::Nop => write!(&mut out, "{tab}nop"),
+            RawStatement::EnterUnsafe => write!(&mut out, "{tab}@enter_unsafe"),
+            RawStatement::ExitUnsafe => write!(&mut out, "{tab}@exit_unsafe"),
             RawStatement::Error(s) => write!(&mut out, "{tab}@Error({})", s),
         };
         out
@@ -1041,6 +1131,9 @@ impl<C: AstFormatter> FmtWithCtx<C> for llbc::Statement {
         for line in &self.comments_before {
             let _ = writeln!(&mut out, "{tab}// {line}");
         }
+        for annot in &self.annotations {
+            let _ = writeln!(&mut out, "{tab}{annot}");
+        }
         let _ = match &self.content {
             RawStatement::Assign(place, rvalue) => write!(
                 &mut out,
@@ -1076,6 +1169,8 @@ impl<C: AstFormatter> FmtWithCtx<C> for llbc::Statement {
             RawStatement::Break(index) => write!(&mut out, "{tab}break {index}"),
             RawStatement::Continue(index) => write!(&mut out, "{tab}continue {index}"),
             RawStatement::Nop => write!(&mut out, "{tab}nop"),
+            RawStatement::EnterUnsafe => write!(&mut out, "{tab}@enter_unsafe"),
+            RawStatement::ExitUnsafe => write!(&mut out, "{tab}@exit_unsafe"),
             RawStatement::Switch(switch) => match switch {
                 Switch::If(discr, true_st, false_st) => {
                     let inner_tab = format!("{tab}{TAB_INCR}");
@@ -1114,6 +1209,30 @@ impl<C: AstFormatter> FmtWithCtx<C> for llbc::Statement {
                         maps.iter().format(""),
                     )
                 }
+                Switch::SwitchRange(discr, _ty, maps, otherwise) => {
+                    let inner_tab1 = format!("{tab}{TAB_INCR}");
+                    let inner_tab2 = format!("{inner_tab1}{TAB_INCR}");
+                    let mut maps: Vec<String> = maps
+                        .iter()
+                        .map(|(range, st)| {
+                            format!(
+                                "{inner_tab1}{range} => {{\n{}{inner_tab1}}},\n",
+                                st.fmt_with_ctx_and_indent(&inner_tab2, ctx),
+                            )
+                        })
+                        .collect();
+                    maps.push(format!(
+                        "{inner_tab1}_ => {{\n{}{inner_tab1}}},\n",
+                        otherwise.fmt_with_ctx_and_indent(&inner_tab2, ctx),
+                    ));
+
+                    write!(
+                        &mut out,
+                        "{tab}switch {} {{\n{}{tab}}}",
+                        discr.fmt_with_ctx(ctx),
+                        maps.iter().format(""),
+                    )
+                }
                 Switch::Match(discr, maps, otherwise) => {
                     let inner_tab1 = format!("{tab}{TAB_INCR}");
                     let inner_tab2 = format!("{inner_tab1}{TAB_INCR}");
@@ -1144,6 +1263,52 @@ impl<C: AstFormatter> FmtWithCtx<C> for llbc::Statement {
                     )
                 }
             },
+            RawStatement::LetElse {
+                scrutinee,
+                variants,
+                else_block,
+            } => {
+                let inner_tab = format!("{tab}{TAB_INCR}");
+                let variants: Vec<String> = variants.iter().map(|v| v.to_string()).collect();
+                write!(
+                    &mut out,
+                    "{tab}let {} = {} else {{\n{}{tab}}}",
+                    variants.join(" | "),
+                    scrutinee.fmt_with_ctx(ctx),
+                    else_block.fmt_with_ctx_and_indent(&inner_tab, ctx),
+                )
+            }
+            RawStatement::IfLetChain {
+                lets,
+                cond,
+                then_block,
+                else_block,
+            } => {
+                let inner_tab = format!("{tab}{TAB_INCR}");
+                let mut links: Vec<String> = lets
+                    .iter()
+                    .map(|binding| {
+                        let variants: Vec<String> =
+                            binding.variants.iter().map(|v| v.to_string()).collect();
+                        format!(
+                            "let {} = {} {{\n{}{tab}}}",
+                            variants.join(" | "),
+                            binding.scrutinee.fmt_with_ctx(ctx),
+                            binding.block.fmt_with_ctx_and_indent(&inner_tab, ctx),
+                        )
+                    })
+                    .collect();
+                if let Some(cond) = cond {
+                    links.push(cond.fmt_with_ctx(ctx));
+                }
+                write!(
+                    &mut out,
+                    "{tab}if {} {{\n{}{tab}}}\n{tab}else {{\n{}{tab}}}",
+                    links.join(" && "),
+                    then_block.fmt_with_ctx_and_indent(&inner_tab, ctx),
+                    else_block.fmt_with_ctx_and_indent(&inner_tab, ctx),
+                )
+            }
             RawStatement::Loop(body) => {
                 let inner_tab = format!("{tab}{TAB_INCR}");
                 write!(
@@ -1152,6 +1317,27 @@ impl<C: AstFormatter> FmtWithCtx<C> for llbc::Statement {
                     body.fmt_with_ctx_and_indent(&inner_tab, ctx),
                 )
             }
+            RawStatement::While {
+                cond_block,
+                cond,
+                body,
+            } => {
+                let inner_tab = format!("{tab}{TAB_INCR}");
+                let cond = if cond_block.statements.is_empty() {
+                    cond.fmt_with_ctx(ctx)
+                } else {
+                    format!(
+                        "{{\n{}{inner_tab}{}\n{tab}}}",
+                        cond_block.fmt_with_ctx_and_indent(&inner_tab, ctx),
+                        cond.fmt_with_ctx(ctx),
+                    )
+                };
+                write!(
+                    &mut out,
+                    "{tab}while {cond} {{\n{}{tab}}}",
+                    body.fmt_with_ctx_and_indent(&inner_tab, ctx),
+                )
+            }
             RawStatement::Error(s) => write!(&mut out, "{tab}@ERROR({})", s),
         };
         out
@@ -1169,6 +1355,9 @@ impl<C: AstFormatter> FmtWithCtx<C> for Terminator {
         for line in &self.comments_before {
             let _ = writeln!(&mut out, "{tab}// {line}");
         }
+        for annot in &self.annotations {
+            let _ = writeln!(&mut out, "{tab}{annot}");
+        }
         let _ = match &self.content {
             RawTerminator::Goto { target } => write!(&mut out, "{tab}goto bb{target}"),
             RawTerminator::Switch { discr, targets } => match targets {
@@ -1187,6 +1376,21 @@ impl<C: AstFormatter> FmtWithCtx<C> for Terminator {
                     maps.push(format!("otherwise: bb{otherwise}"));
                     let maps = maps.join(", ");
 
+                    write!(
+                        &mut out,
+                        "{tab}switch {} -> {}",
+                        discr.fmt_with_ctx(ctx),
+                        maps
+                    )
+                }
+                SwitchTargets::SwitchRange(_ty, maps, otherwise) => {
+                    let mut maps: Vec<String> = maps
+                        .iter()
+                        .map(|(range, bid)| format!("{range}: bb{bid}"))
+                        .collect();
+                    maps.push(format!("otherwise: bb{otherwise}"));
+                    let maps = maps.join(", ");
+
                     write!(
                         &mut out,
                         "{tab}switch {} -> {}",
@@ -1251,6 +1455,11 @@ impl<C: AstFormatter> FmtWithCtx<C> for TraitDecl {
                         .iter()
                         .map(|name| format!("{TAB_INCR}type {name}\n")),
                 )
+                .chain(self.generic_types.iter().map(|(name, params)| {
+                    let ctx = &ctx.push_binder(Cow::Borrowed(params));
+                    let params = params.fmt_with_ctx_single_line(ctx);
+                    format!("{TAB_INCR}type {name}{params}\n")
+                }))
                 .chain(self.methods().map(|(name, bound_fn)| {
                     let (params, fn_ref) = bound_fn.fmt_split(ctx);
                     format!("{TAB_INCR}fn {name}{params} = {fn_ref}\n",)
@@ -1315,6 +1524,10 @@ impl<C: AstFormatter> FmtWithCtx<C> for TraitImpl {
                 .chain(self.types.iter().map(|(name, ty)| {
                     format!("{TAB_INCR}type {name} = {}\n", ty.fmt_with_ctx(ctx),)
                 }))
+                .chain(self.generic_types.iter().map(|(name, bound_ty)| {
+                    let (params, ty) = bound_ty.fmt_split(ctx);
+                    format!("{TAB_INCR}type {name}{params} = {ty}\n")
+                }))
                 .chain(self.methods().map(|(name, bound_fn)| {
                     let (params, fn_ref) = bound_fn.fmt_split(ctx);
                     format!("{TAB_INCR}fn {name}{params} = {fn_ref}\n",)
@@ -1424,8 +1637,12 @@ impl<C: AstFormatter> FmtWithCtx<C> for Ty {
                 RefKind::Shared => format!("*const {}", ty.fmt_with_ctx(ctx)),
                 RefKind::Mut => format!("*mut {}", ty.fmt_with_ctx(ctx)),
             },
-            TyKind::TraitType(trait_ref, name) => {
-                format!("{}::{name}", trait_ref.fmt_with_ctx(ctx),)
+            TyKind::TraitType(trait_ref, name, args) => {
+                format!(
+                    "{}::{name}{}",
+                    trait_ref.fmt_with_ctx(ctx),
+                    args.fmt_with_ctx(ctx)
+                )
             }
             TyKind::DynTrait(pred) => format!("dyn ({})", pred.with_ctx(ctx)),
             TyKind::Arrow(io) => {
@@ -1463,6 +1680,7 @@ impl<C: AstFormatter> FmtWithCtx<C> for TypeDecl {
         let keyword = match &self.kind {
             TypeDeclKind::Struct(..) => "struct",
             TypeDeclKind::Union(..) => "union",
+            TypeDeclKind::Simd { .. } => "simd type",
             TypeDeclKind::Enum(..) => "enum",
             TypeDeclKind::Alias(..) => "type",
             TypeDeclKind::Opaque | TypeDeclKind::Error(..) => "opaque type",
@@ -1505,6 +1723,11 @@ impl<C: AstFormatter> FmtWithCtx<C> for TypeDecl {
                     .format("\n");
                 format!("{nl_or_space}=\n{variants}\n")
             }
+            TypeDeclKind::Simd { elem_ty, lanes } => {
+                let elem_ty = elem_ty.fmt_with_ctx(ctx);
+                let lanes = lanes.fmt_with_ctx(ctx);
+                format!(" = simd<{elem_ty}; {lanes}>")
+            }
             TypeDeclKind::Alias(ty) => format!(" = {}", ty.fmt_with_ctx(ctx)),
             TypeDeclKind::Opaque => format!(""),
             TypeDeclKind::Error(msg) => format!(" = ERROR({msg})"),
@@ -1524,8 +1747,11 @@ impl<C: AstFormatter> FmtWithCtx<C> for TypeId {
 }
 
 impl<C: AstFormatter> FmtWithCtx<C> for TypeVar {
-    fn fmt_with_ctx(&self, _ctx: &C) -> String {
-        self.name.to_string()
+    fn fmt_with_ctx(&self, ctx: &C) -> String {
+        match &self.default {
+            Some(default) => format!("{} = {}", self.name, default.fmt_with_ctx(ctx)),
+            None => self.name.to_string(),
+        }
     }
 }
 
@@ -1548,6 +1774,15 @@ impl<C: AstFormatter> FmtWithCtx<C> for Variant {
     }
 }
 
+impl std::fmt::Display for Annotation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::result::Result<(), std::fmt::Error> {
+        match self {
+            Annotation::Assume(cond) => write!(f, "@assume({cond})"),
+            Annotation::Assert(cond) => write!(f, "@assert({cond})"),
+        }
+    }
+}
+
 impl std::fmt::Display for AnyTransId {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::result::Result<(), std::fmt::Error> {
         let s = match self {
@@ -1581,6 +1816,9 @@ impl std::fmt::Display for BinOp {
             BinOp::CheckedAdd => write!(f, "checked.+"),
             BinOp::CheckedSub => write!(f, "checked.-"),
             BinOp::CheckedMul => write!(f, "checked.*"),
+            BinOp::WrappingAdd => write!(f, "wrapping.+"),
+            BinOp::WrappingSub => write!(f, "wrapping.-"),
+            BinOp::WrappingMul => write!(f, "wrapping.*"),
             BinOp::Shl => write!(f, "<<"),
             BinOp::Shr => write!(f, ">>"),
         }
@@ -1601,6 +1839,20 @@ impl std::fmt::Display for BuiltinFunId {
             BuiltinFunId::ArrayToSliceShared => "ArrayToSliceShared",
             BuiltinFunId::ArrayToSliceMut => "ArrayToSliceMut",
             BuiltinFunId::ArrayRepeat => "ArrayRepeat",
+            BuiltinFunId::MemSwap => "MemSwap",
+            BuiltinFunId::MemReplace => "MemReplace",
+            BuiltinFunId::MemTake => "MemTake",
+            BuiltinFunId::DerivedCmp => "DerivedCmp",
+            BuiltinFunId::DerivedPartialCmp => "DerivedPartialCmp",
+            BuiltinFunId::DerivedHash => "DerivedHash",
+            BuiltinFunId::HasherWrite(None) => "HasherWrite",
+            BuiltinFunId::HasherWrite(Some(ty)) => &format!("HasherWrite_{ty}"),
+            BuiltinFunId::HasherFinish => "HasherFinish",
+            BuiltinFunId::FormatArgs => "FormatArgs",
+            BuiltinFunId::FmtArgDisplay => "FmtArgDisplay",
+            BuiltinFunId::FmtArgDebug => "FmtArgDebug",
+            BuiltinFunId::FmtDisplay => "FmtDisplay",
+            BuiltinFunId::FmtDebug => "FmtDebug",
             BuiltinFunId::Index(BuiltinIndexOp {
                 is_array,
                 mutability,
@@ -1611,6 +1863,15 @@ impl std::fmt::Display for BuiltinFunId {
                 let mutability = mutability.variant_name();
                 &format!("{ty}{op}{mutability}")
             }
+            BuiltinFunId::PtrOffset(BuiltinPtrOffsetOp {
+                mutability,
+                kind,
+                wrapping,
+            }) => {
+                let wrapping = if wrapping { "Wrapping" } else { "" };
+                let mutability = mutability.variant_name();
+                &format!("Ptr{wrapping}{kind:?}{mutability}")
+            }
         };
         f.write_str(name)
     }
@@ -1695,7 +1956,20 @@ impl std::fmt::Display for Literal {
             Literal::Bool(v) => write!(f, "{v}"),
             Literal::Char(v) => write!(f, "{v}"),
             Literal::Str(v) => write!(f, "\"{}\"", v.replace("\\", "\\\\").replace("\n", "\\n")),
-            Literal::ByteStr(v) => write!(f, "{v:?}"),
+            Literal::ByteStr(v) => write!(f, "b\"{}\"", v.escape_ascii()),
+        }
+    }
+}
+
+impl std::fmt::Display for LiteralRange {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::result::Result<(), std::fmt::Error> {
+        if let Some(start) = &self.start {
+            write!(f, "{start}")?;
+        }
+        match &self.end {
+            Some(end) if self.end_inclusive => write!(f, "..={end}"),
+            Some(end) => write!(f, "..{end}"),
+            None => write!(f, ".."),
         }
     }
 }
@@ -1806,10 +2080,38 @@ pub fn fmt_call<C>(ctx: &C, call: &Call) -> (String, Option<String>)
 where
     C: AstFormatter,
 {
-    let args: Vec<String> = call.args.iter().map(|x| x.fmt_with_ctx(ctx)).collect();
-    let args = args.join(", ");
+    let mut args: Vec<String> = call.args.iter().map(|x| x.fmt_with_ctx(ctx)).collect();
     let f = call.func.fmt_with_ctx(ctx);
-    (format!("{f}({args})"), None)
+    if let FnOperand::DynClosure { closure, args, .. } = &call.func {
+        let closure = closure.fmt_with_ctx(ctx);
+        let args = args.fmt_with_ctx(ctx);
+        (format!("{f}({closure}, {args})"), None)
+    } else if call.is_method_call {
+        // Like in the source, we only print the name of the method after the receiver.
+        let method = match &call.func {
+            FnOperand::Regular(FnPtr {
+                func: FunIdOrTraitMethodRef::Trait(_, name, _),
+                ..
+            }) => name.0.clone(),
+            FnOperand::Regular(FnPtr {
+                func: FunIdOrTraitMethodRef::Fun(FunId::Regular(fun_id)),
+                ..
+            }) => {
+                let name = ctx.format_object(*fun_id);
+                match name.rsplit_once("::") {
+                    Some((_, method)) => method.to_owned(),
+                    None => name,
+                }
+            }
+            _ => f,
+        };
+        let receiver = args.remove(0);
+        let args = args.join(", ");
+        (format!("({receiver}).{method}({args})"), None)
+    } else {
+        let args = args.join(", ");
+        (format!("{f}({args})"), None)
+    }
 }
 
 pub(crate) fn fmt_body_blocks_with_ctx<C>(
@@ -84,6 +84,7 @@ impl<'a, 'b> SetGenerics<'a> for FmtCtx<'b> {
             translated: self.translated.as_deref(),
             generics: BindingStack::new(Cow::Borrowed(generics)),
             locals: self.locals.as_deref(),
+            max_depth: self.max_depth,
         }
     }
 }
@@ -104,6 +105,7 @@ impl<'a, 'b> SetLocals<'a> for FmtCtx<'b> {
             translated: self.translated.as_deref(),
             generics: self.generics.clone(),
             locals: Some(locals),
+            max_depth: self.max_depth,
         }
     }
 }
@@ -132,10 +134,38 @@ impl<'a, 'b> PushBinder<'a> for FmtCtx<'b> {
             translated: self.translated.as_deref(),
             generics,
             locals: self.locals.as_deref(),
+            max_depth: self.max_depth,
         }
     }
 }
 
+/// We use this trait to retrieve the syntax used to build the values of an ADT, so that we can
+/// print `P(1, 2)` for a tuple struct and `P { x: 1, y: 2 }` for a struct with named fields.
+pub trait GetCtorKind {
+    fn get_ctor_kind(&self, def_id: TypeDeclId, variant_id: Option<VariantId>) -> Option<CtorKind>;
+}
+
+impl<'a> GetCtorKind for FmtCtx<'a> {
+    fn get_ctor_kind(&self, def_id: TypeDeclId, variant_id: Option<VariantId>) -> Option<CtorKind> {
+        self.translated?
+            .type_decls
+            .get(def_id)?
+            .get_ctor_kind(variant_id)
+    }
+}
+
+/// We use this trait to bound the nesting of the statements we print, so that printing a
+/// pathological body for debugging doesn't overflow the stack.
+pub trait GetMaxDepth {
+    fn get_max_depth(&self) -> Option<usize>;
+}
+
+impl<'a> GetMaxDepth for FmtCtx<'a> {
+    fn get_max_depth(&self) -> Option<usize> {
+        self.max_depth
+    }
+}
+
 pub trait AstFormatter = Formatter<TypeDeclId>
     + Formatter<FunDeclId>
     + Formatter<GlobalDeclId>
@@ -153,6 +183,8 @@ pub trait AstFormatter = Formatter<TypeDeclId>
     + for<'a> Formatter<&'a RegionVar>
     + for<'a> Formatter<&'a Vector<ullbc_ast::BlockId, ullbc_ast::BlockData>>
     + for<'a> Formatter<&'a llbc_ast::Block>
+    + GetCtorKind
+    + GetMaxDepth
     + for<'a> SetGenerics<'a>
     + for<'a> SetLocals<'a>
     + for<'a> PushBinder<'a>;
@@ -174,6 +206,8 @@ pub struct FmtCtx<'a> {
     /// work, we keep the innermost parameters at the start of the vector.
     pub generics: BindingStack<Cow<'a, GenericParams>>,
     pub locals: Option<&'a Locals>,
+    /// The statements nested deeper than this many blocks are elided as `...`.
+    pub max_depth: Option<usize>,
 }
 
 impl<'a> FmtCtx<'a> {
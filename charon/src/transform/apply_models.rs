@@ -0,0 +1,160 @@
+//! Give a body to the functions we don't have the body of, using the models passed with
+//! `--models`. This gives semantics to e.g. `Vec::push` without re-implementing it in the crate.
+//!
+//! The models file is a JSON list of objects of the form:
+//! ```text
+//! { "name": "alloc::vec::{alloc::vec::Vec<@T, @A>}::push", "signature": .., "body": .. }
+//! ```
+//! `name` is a name pattern that selects the functions to replace. `body` is a serialized
+//! `Body::Unstructured`, and `signature` an optional serialized `FunSig`; the ids they contain refer
+//! to the items of the translated crate. We check that each model agrees with the signature of the
+//! function it replaces, and skip the ones that don't.
+use std::fs::File;
+use std::io::BufReader;
+use std::path::PathBuf;
+
+use serde::Deserialize;
+
+use crate::ast::*;
+use crate::name_matcher::NamePattern;
+use crate::register_error;
+use crate::transform::TransformCtx;
+
+use super::ctx::TransformPass;
+
+#[derive(Deserialize)]
+struct Model {
+    name: String,
+    #[serde(default)]
+    signature: Option<FunSig>,
+    body: Body,
+}
+
+/// Bodies use erased regions, unlike signatures.
+fn erase_regions(ty: &Ty) -> Ty {
+    let mut ty = ty.clone();
+    ty.dyn_visit_mut(|r: &mut Region| *r = Region::Erased);
+    ty
+}
+
+/// Check that the model can be the body of a function with this signature.
+fn check_model(sig: &FunSig, model: &Model) -> Result<(), String> {
+    if let Some(model_sig) = &model.signature
+        && model_sig != sig
+    {
+        return Err("its signature differs from the signature of the function".to_owned());
+    }
+    let Body::Unstructured(body) = &model.body else {
+        return Err("its body must be unstructured".to_owned());
+    };
+    let locals = &body.locals;
+    if locals.arg_count != sig.inputs.len() || locals.vars.elem_count() <= locals.arg_count {
+        return Err(format!(
+            "it takes {} arguments but the function takes {}",
+            locals.arg_count,
+            sig.inputs.len()
+        ));
+    }
+    let mut local_tys = locals.vars.iter().map(|var| &var.ty);
+    if local_tys.next() != Some(&erase_regions(&sig.output)) {
+        return Err("its return type differs from the one of the function".to_owned());
+    }
+    for (i, (local_ty, input)) in local_tys.zip(&sig.inputs).enumerate() {
+        if *local_ty != erase_regions(input) {
+            return Err(format!(
+                "the type of its argument {i} differs from the one of the function"
+            ));
+        }
+    }
+    Ok(())
+}
+
+pub struct Transform {
+    pub path: PathBuf,
+}
+
+impl Transform {
+    pub fn new(path: PathBuf) -> &'static Self {
+        Box::leak(Box::new(Self { path }))
+    }
+
+    fn read_models(&self) -> Result<Vec<Model>, String> {
+        let file = File::open(&self.path).map_err(|err| err.to_string())?;
+        serde_json::from_reader(BufReader::new(file)).map_err(|err| err.to_string())
+    }
+}
+
+impl TransformPass for Transform {
+    fn transform_ctx(&self, ctx: &mut TransformCtx) {
+        let models = match self.read_models() {
+            Ok(models) => models,
+            Err(err) => {
+                register_error!(
+                    ctx,
+                    Span::dummy(),
+                    "Could not read the models from `{:?}`: {err}",
+                    self.path
+                );
+                return;
+            }
+        };
+        let mut patterns = Vec::new();
+        for model in &models {
+            match NamePattern::parse(&model.name) {
+                Ok(pat) => patterns.push(Some(pat)),
+                Err(err) => {
+                    register_error!(
+                        ctx,
+                        Span::dummy(),
+                        "Failed to parse the model pattern `{}` ({err})",
+                        model.name
+                    );
+                    patterns.push(None);
+                }
+            }
+        }
+
+        // For each function without a body, find the first model that applies to it.
+        let replacements: Vec<(FunDeclId, usize)> = ctx
+            .translated
+            .fun_decls
+            .iter()
+            .filter(|decl| decl.body.is_err())
+            .filter_map(|decl| {
+                let i = patterns.iter().position(|pat| {
+                    pat.as_ref()
+                        .is_some_and(|pat| pat.matches(&ctx.translated, &decl.item_meta.name))
+                })?;
+                Some((decl.def_id, i))
+            })
+            .collect();
+
+        let mut used = vec![false; models.len()];
+        for (fun_id, i) in replacements {
+            let model = &models[i];
+            let decl = &ctx.translated.fun_decls[fun_id];
+            if let Err(reason) = check_model(&decl.signature, model) {
+                let span = decl.item_meta.span;
+                register_error!(
+                    ctx,
+                    span,
+                    "The model `{}` doesn't fit this function: {reason}",
+                    model.name
+                );
+                continue;
+            }
+            used[i] = true;
+            ctx.translated.fun_decls[fun_id].body = Ok(model.body.clone());
+        }
+        for (model, used) in models.iter().zip(used) {
+            if !used {
+                register_error!(
+                    ctx,
+                    Span::dummy(),
+                    "The model `{}` didn't apply to any function",
+                    model.name
+                );
+            }
+        }
+    }
+}
@@ -235,6 +235,22 @@ impl VisitAst for CheckGenericsVisitor<'_> {
                 let fmt = fmt2.push_binder(Cow::Borrowed(params));
                 (params, fmt)
             }
+            GenericsSource::TraitType(trait_id, type_name) => {
+                let Some(trait_decl) = self.ctx.translated.trait_decls.get(*trait_id) else {
+                    return;
+                };
+                let Some((_, params)) = trait_decl
+                    .generic_types
+                    .iter()
+                    .find(|(n, _)| n == type_name)
+                else {
+                    return;
+                };
+                fmt1 = self.ctx.into_fmt();
+                fmt2 = fmt1.push_binder(Cow::Borrowed(&trait_decl.generics));
+                let fmt = fmt2.push_binder(Cow::Borrowed(params));
+                (params, fmt)
+            }
             GenericsSource::Builtin => return,
             GenericsSource::Other => {
                 self.error("`GenericsSource::Other` should now exist in the charon AST");
@@ -280,6 +296,20 @@ impl VisitAst for CheckGenericsVisitor<'_> {
                 "The associated types supplied by the trait impl don't match the trait decl.",
             )
         }
+        let generic_types_match = timpl.generic_types.len() == tdecl.generic_types.len()
+            && tdecl
+                .generic_types
+                .iter()
+                .zip(timpl.generic_types.iter())
+                .all(|((dname, dparams), (iname, bound_ty))| {
+                    dname == iname
+                        && dparams.regions.elem_count() == bound_ty.params.regions.elem_count()
+                });
+        if !generic_types_match {
+            self.error(
+                "The generic associated types supplied by the trait impl don't match the trait decl.",
+            )
+        }
         let consts_match = timpl.consts.len() == tdecl.consts.len()
             && tdecl
                 .types
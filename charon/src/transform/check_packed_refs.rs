@@ -0,0 +1,45 @@
+//! # Micro-pass: warn about the references to the fields of a `#[repr(packed)]` struct. Such fields
+//! may not be aligned, so rustc makes MIR copy them out before using them; the places that go
+//! through them are recognized by [Place::packed_field]. Rustc rejects the references that may be
+//! misaligned, but still accepts the ones to fields whose alignment is small enough, e.g. a `u8`.
+//! We flag the ones whose alignment we can't check ourselves, since backends must not assume that
+//! such fields are addressable.
+use crate::transform::TransformCtx;
+use crate::ullbc_ast::*;
+
+use super::ctx::UllbcPass;
+
+/// Whether a value of this type is always aligned when it is packed to `pack` bytes.
+fn is_aligned_when_packed(ty: &Ty, pack: u64) -> bool {
+    let size = match ty.kind() {
+        TyKind::Literal(LiteralTy::Integer(int_ty)) => int_ty.size() as u64,
+        TyKind::Literal(LiteralTy::Bool) => 1,
+        TyKind::Literal(LiteralTy::Char) => 4,
+        _ => return false,
+    };
+    // The alignment of these types is at most their size.
+    size <= pack
+}
+
+pub struct Transform;
+impl UllbcPass for Transform {
+    fn transform_body(&self, ctx: &mut TransformCtx, b: &mut ExprBody) {
+        for block in b.body.iter() {
+            for st in &block.statements {
+                st.dyn_visit_in_body(|rvalue: &Rvalue| {
+                    let Rvalue::Ref(place, _) = rvalue else {
+                        return;
+                    };
+                    if let Some(pack) = place.packed_field(&ctx.translated)
+                        && !is_aligned_when_packed(place.ty(), pack)
+                    {
+                        ctx.span_warn(
+                            st.span,
+                            "reference to a field of a packed struct: the field may not be aligned",
+                        );
+                    }
+                });
+            }
+        }
+    }
+}
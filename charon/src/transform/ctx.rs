@@ -4,8 +4,10 @@ use crate::formatter::{FmtCtx, IntoFormatter};
 use crate::llbc_ast;
 use crate::options::TranslateOptions;
 use crate::pretty::FmtWithCtx;
+use crate::transform::emit_type_sizes::TypeLayout;
 use crate::ullbc_ast;
 use std::cell::RefCell;
+use std::collections::{HashMap, HashSet};
 use std::{fmt, mem};
 
 /// Simpler context used for rustc-independent code transformation. This only depends on rustc for
@@ -17,6 +19,31 @@ pub struct TransformCtx {
     pub translated: TranslatedCrate,
     /// Context for tracking and reporting errors.
     pub errors: RefCell<ErrorCtx>,
+    /// A copy of the crate taken just before control-flow reconstruction, if `--emit-ullbc` is
+    /// set. See [crate::transform::save_ullbc].
+    pub ullbc_snapshot: Option<TranslatedCrate>,
+    /// The rustc `DefId` of each item, as a string that is stable across compilations. Only
+    /// filled when `--emit-symbol-table` is set.
+    pub rustc_def_ids: HashMap<AnyTransId, String>,
+    /// The size and alignment of the types that rustc could lay out. Only filled when
+    /// `--emit-type-sizes` is set.
+    pub type_layouts: HashMap<TypeDeclId, TypeLayout>,
+    /// The trait impls generated by `#[derive(..)]`. Only filled when `--inline-derived-eq` is
+    /// set.
+    pub derived_impls: HashSet<TraitImplId>,
+}
+
+/// The part of [TransformCtx] that a pass over a single body can use. It only gives read access to
+/// the crate and buffers the errors, so that several bodies can be transformed at once. See
+/// [TransformCtx::par_for_each_body].
+pub struct BodyTransformCtx<'a> {
+    /// The options that control transformation.
+    pub options: &'a TranslateOptions,
+    /// The translated data. The bodies are taken out of their function declarations while we
+    /// transform them.
+    pub translated: &'a TranslatedCrate,
+    /// The errors to report once all the bodies have been transformed.
+    errors: Vec<(Span, String, Level)>,
 }
 
 /// A pass that modifies ullbc bodies.
@@ -125,6 +152,56 @@ pub trait LlbcPass: Sync {
     }
 }
 
+/// A pass that modifies each ullbc body on its own, only reading the rest of the crate. With
+/// `--max-parallel`, such passes run on several bodies at once.
+pub trait UllbcBodyPass: Sync {
+    /// Transform a body.
+    fn transform_body(&self, ctx: &mut BodyTransformCtx<'_>, body: &mut ullbc_ast::ExprBody);
+
+    /// The name of the pass, used for debug logging. The default implementation uses the type
+    /// name.
+    fn name(&self) -> &str {
+        std::any::type_name::<Self>()
+    }
+}
+
+impl<P: UllbcBodyPass> UllbcPass for P {
+    fn transform_ctx(&self, ctx: &mut TransformCtx) {
+        ctx.par_for_each_body(|ctx, body| {
+            UllbcBodyPass::transform_body(self, ctx, body.as_unstructured_mut().unwrap())
+        });
+    }
+
+    fn name(&self) -> &str {
+        UllbcBodyPass::name(self)
+    }
+}
+
+/// A pass that modifies each llbc body on its own, only reading the rest of the crate. With
+/// `--max-parallel`, such passes run on several bodies at once.
+pub trait LlbcBodyPass: Sync {
+    /// Transform a body.
+    fn transform_body(&self, ctx: &mut BodyTransformCtx<'_>, body: &mut llbc_ast::ExprBody);
+
+    /// The name of the pass, used for debug logging. The default implementation uses the type
+    /// name.
+    fn name(&self) -> &str {
+        std::any::type_name::<Self>()
+    }
+}
+
+impl<P: LlbcBodyPass> LlbcPass for P {
+    fn transform_ctx(&self, ctx: &mut TransformCtx) {
+        ctx.par_for_each_body(|ctx, body| {
+            LlbcBodyPass::transform_body(self, ctx, body.as_structured_mut().unwrap())
+        });
+    }
+
+    fn name(&self) -> &str {
+        LlbcBodyPass::name(self)
+    }
+}
+
 /// A pass that transforms the crate data.
 pub trait TransformPass: Sync {
     fn transform_ctx(&self, ctx: &mut TransformCtx);
@@ -148,6 +225,13 @@ impl<'ctx> TransformCtx {
             .span_err(&self.translated, span, msg, level)
     }
 
+    /// Report a warning that doesn't make the current item fail.
+    pub(crate) fn span_warn(&self, span: Span, msg: &str) {
+        self.errors
+            .borrow_mut()
+            .span_warn(&self.translated, span, msg)
+    }
+
     pub(crate) fn opacity_for_name(&self, name: &Name) -> ItemOpacity {
         self.options.opacity_for_name(&self.translated, name)
     }
@@ -189,6 +273,69 @@ impl<'ctx> TransformCtx {
         }
     }
 
+    /// Mutably iterate over the bodies, on up to `options.max_parallel` threads. The closure only
+    /// gets read access to the rest of the crate; its errors are reported once all the bodies have
+    /// been transformed, in the order of the functions, so the result doesn't depend on the
+    /// scheduling.
+    pub(crate) fn par_for_each_body(
+        &mut self,
+        f: impl Fn(&mut BodyTransformCtx<'_>, &mut Body) + Sync,
+    ) {
+        // Take the bodies out so that we can hand them out to threads.
+        let mut bodies: Vec<(FunDeclId, bool, Body)> = vec![];
+        for decl in self.translated.fun_decls.iter_mut() {
+            if let Ok(body) = mem::replace(&mut decl.body, Err(Opaque)) {
+                bodies.push((decl.def_id, decl.item_meta.is_local, body));
+            }
+        }
+
+        let options = &self.options;
+        let translated = &self.translated;
+        let run = |bodies: &mut [(FunDeclId, bool, Body)]| -> Vec<Vec<(Span, String, Level)>> {
+            bodies
+                .iter_mut()
+                .map(|(_, _, body)| {
+                    let mut ctx = BodyTransformCtx {
+                        options,
+                        translated,
+                        errors: vec![],
+                    };
+                    f(&mut ctx, body);
+                    ctx.errors
+                })
+                .collect()
+        };
+        let run = &run;
+        let errors = if options.max_parallel <= 1 {
+            run(&mut bodies)
+        } else {
+            let chunk_size = bodies.len().div_ceil(options.max_parallel).max(1);
+            std::thread::scope(|s| {
+                let threads: Vec<_> = bodies
+                    .chunks_mut(chunk_size)
+                    .map(|chunk| s.spawn(move || run(chunk)))
+                    .collect();
+                threads
+                    .into_iter()
+                    .flat_map(|thread| {
+                        thread
+                            .join()
+                            .unwrap_or_else(|e| std::panic::resume_unwind(e))
+                    })
+                    .collect()
+            })
+        };
+
+        for ((id, is_local, body), errors) in bodies.into_iter().zip(errors) {
+            self.with_def_id(id, is_local, |ctx| {
+                for (span, msg, level) in errors {
+                    ctx.span_err(span, &msg, level);
+                }
+            });
+            self.translated.fun_decls[id].body = Ok(body);
+        }
+    }
+
     /// Mutably iterate over the function declarations.
     /// Warning: each inspected fundecl becomes inaccessible from `ctx` during the course of this function.
     pub(crate) fn for_each_fun_decl(&mut self, mut f: impl FnMut(&mut Self, &mut FunDecl)) {
@@ -227,6 +374,17 @@ impl<'ctx> TransformCtx {
     }
 }
 
+impl BodyTransformCtx<'_> {
+    /// Register an error, to be reported once all the bodies have been transformed.
+    pub(crate) fn span_err(&mut self, span: Span, msg: &str, level: Level) -> Error {
+        self.errors.push((span, msg.to_owned(), level));
+        Error {
+            span,
+            msg: msg.to_owned(),
+        }
+    }
+}
+
 impl<'a> IntoFormatter for &'a TransformCtx {
     type C = FmtCtx<'a>;
 
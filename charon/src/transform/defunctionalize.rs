@@ -0,0 +1,436 @@
+//! # Micro-pass: translate closures as top-level functions that take their state explicitly (only
+//! with `--defunctionalize`).
+//!
+//! For each closure, we introduce a struct whose fields are the captured values, and make the
+//! closure function take (a reference to) this struct as first argument instead of the state
+//! tuple. In the functions that build and call a closure, the closure value becomes a value of the
+//! state struct, and the calls through `Fn::call`, `FnMut::call_mut` and `FnOnce::call_once`
+//! become direct calls to the closure function:
+//! ```text
+//! f = closure(&x, &y);       ~>  f = closure::State { 0: &x, 1: &y };
+//! r = Fn::call(&f, (z,));    ~>  r = closure(&f, z);
+//! ```
+//! We only do this for the closure values whose uses we fully understand, i.e. that are only
+//! moved, borrowed and called in the function that builds them. The other ones (e.g. closures
+//! passed to another function) are left untouched.
+use std::collections::{HashMap, HashSet};
+
+use crate::transform::TransformCtx;
+use crate::ullbc_ast::*;
+
+use super::ctx::TransformPass;
+
+/// What we know about a closure that we defunctionalize.
+struct ClosureData {
+    state_id: TypeDeclId,
+    kind: ClosureKind,
+    /// The number of region parameters of the closure function, which are also the region
+    /// parameters of the state struct.
+    num_regions: usize,
+}
+
+impl ClosureData {
+    /// The method of the closure traits we expect the closure to be called with.
+    fn call_method(&self) -> &'static str {
+        match self.kind {
+            ClosureKind::Fn => "call",
+            ClosureKind::FnMut => "call_mut",
+            ClosureKind::FnOnce => "call_once",
+        }
+    }
+
+    /// Reuse the arguments of a closure aggregate for the state struct or the closure function.
+    /// The regions are erased anyway, and the closure function may have more region parameters
+    /// than the aggregate has arguments since [crate::transform::update_closure_signatures].
+    fn args(&self, generics: &GenericArgs, target: GenericsSource) -> GenericArgs {
+        GenericArgs {
+            regions: (0..self.num_regions).map(|_| Region::Erased).collect(),
+            ..generics.clone().with_target(target)
+        }
+    }
+
+    fn state_ty(&self, generics: &GenericArgs) -> Ty {
+        let target = GenericsSource::item(self.state_id);
+        TyKind::Adt(TypeId::Adt(self.state_id), self.args(generics, target)).into_ty()
+    }
+}
+
+/// Add the state struct of a closure to the crate and return its id.
+fn add_state_struct(ctx: &mut TransformCtx, decl: &FunDecl, info: &ClosureInfo) -> TypeDeclId {
+    let mut item_meta = decl.item_meta.clone();
+    item_meta
+        .name
+        .name
+        .push(PathElem::Ident("State".to_string(), Disambiguator::new(0)));
+    let fields = info
+        .state
+        .iter()
+        .map(|ty| Field {
+            span: decl.item_meta.span,
+            attr_info: AttrInfo {
+                attributes: vec![],
+                inline: None,
+                rename: None,
+                public: true,
+                must_use: false,
+            },
+            name: None,
+            ty: ty.clone(),
+        })
+        .collect();
+
+    let state_id = ctx.translated.type_decls.reserve_slot();
+    ctx.translated.all_ids.insert(state_id.into());
+    ctx.translated
+        .item_names
+        .insert(state_id.into(), item_meta.name.clone());
+    ctx.translated.type_decls.set_slot(
+        state_id,
+        TypeDecl {
+            def_id: state_id,
+            item_meta,
+            generics: decl.signature.generics.clone(),
+            kind: TypeDeclKind::Struct(fields),
+            drop_impl: None,
+            interior_mut: false,
+            valid_range: None,
+            ctor_kind: Some(CtorKind::Tuple),
+            repr: None,
+        },
+    );
+    state_id
+}
+
+/// Whether the place is `state` or `*state`.
+fn is_state_place(place: &Place, state_var: VarId) -> bool {
+    match &place.kind {
+        PlaceKind::Base(var) => *var == state_var,
+        PlaceKind::Projection(sub, ProjectionElem::Deref) => sub.as_local() == Some(state_var),
+        PlaceKind::Projection(..) => false,
+    }
+}
+
+/// Make the closure take its state struct instead of the state tuple.
+fn update_closure(decl: &mut FunDecl, data: &ClosureData) {
+    let generics = &decl.signature.generics;
+    let state_ty = TyKind::Adt(
+        TypeId::Adt(data.state_id),
+        generics.identity_args(GenericsSource::item(data.state_id)),
+    )
+    .into_ty();
+    let input = &mut decl.signature.inputs[0];
+    *input = match input.kind() {
+        TyKind::Ref(r, _, kind) => TyKind::Ref(r.clone(), state_ty, *kind).into_ty(),
+        _ => state_ty,
+    };
+
+    if let Ok(body) = &mut decl.body {
+        let body = body.as_unstructured_mut().unwrap();
+        // The local 1 is the state, see [crate::transform::update_closure_signatures].
+        let state_var = VarId::new(1);
+        body.locals.vars[state_var].ty = input.clone();
+        let new_tys = HashMap::from([(state_var, input.clone())]);
+        body.body.dyn_visit_in_body_mut(|place: &mut Place| {
+            retype_place(place, &new_tys);
+            // The accesses to the state tuple become accesses to the fields of the struct.
+            if let PlaceKind::Projection(sub, ProjectionElem::Field(pk, _)) = &mut place.kind
+                && pk.is_tuple()
+                && is_state_place(sub, state_var)
+            {
+                *pk = FieldProjKind::Adt(data.state_id, None);
+            }
+        });
+    }
+}
+
+/// Update the type of a place whose local got a new type. We only need to handle the places of
+/// the form `x` and `*x`: the other places don't evaluate to a closure value.
+fn retype_place(place: &mut Place, new_tys: &HashMap<VarId, Ty>) {
+    match &place.kind {
+        PlaceKind::Base(var) => {
+            if let Some(ty) = new_tys.get(var) {
+                place.ty = ty.clone();
+            }
+        }
+        PlaceKind::Projection(sub, ProjectionElem::Deref) => {
+            if let Some(var) = sub.as_local()
+                && let Some(ty) = new_tys.get(&var)
+                && let TyKind::Ref(_, ty, _) = ty.kind()
+            {
+                place.ty = ty.clone();
+            }
+        }
+        PlaceKind::Projection(..) => {}
+    }
+}
+
+/// If this is a call to a method of the closure traits on a local, return the name of the method,
+/// the local that holds the receiver and the tuple of the arguments.
+fn closure_call<'a>(ctx: &TransformCtx, call: &'a Call) -> Option<(&'a str, VarId, &'a Operand)> {
+    let FnOperand::Regular(FnPtr {
+        func: FunIdOrTraitMethodRef::Trait(trait_ref, method, _),
+        ..
+    }) = &call.func
+    else {
+        return None;
+    };
+    let trait_name = ctx
+        .translated
+        .item_name(trait_ref.trait_decl_ref.skip_binder.trait_id)?;
+    let is_closure_trait = ["Fn", "FnMut", "FnOnce"]
+        .into_iter()
+        .any(|tr| trait_name.equals_ref_name(&["core", "ops", "function", tr]));
+    match call.args.as_slice() {
+        [Operand::Move(recv) | Operand::Copy(recv), args] if is_closure_trait => {
+            Some((method.0.as_str(), recv.as_local()?, args))
+        }
+        _ => None,
+    }
+}
+
+/// Record that the closure values mentioned in `x` are used in a way we don't understand.
+fn mark_uses<T: BodyVisitable>(
+    x: &T,
+    groups: &HashMap<VarId, VarId>,
+    invalid: &mut HashSet<VarId>,
+) {
+    x.dyn_visit_in_body(|place: &Place| {
+        if let Some(root) = groups.get(&place.var_id()) {
+            invalid.insert(*root);
+        }
+    });
+}
+
+/// If this rvalue moves, copies or borrows a closure value (or reborrows a reference to one),
+/// return the local in which that closure was built.
+fn closure_source(groups: &HashMap<VarId, VarId>, rvalue: &Rvalue) -> Option<VarId> {
+    let place = match rvalue {
+        Rvalue::Use(Operand::Copy(place) | Operand::Move(place)) => place,
+        Rvalue::Ref(place, _) => match &place.kind {
+            PlaceKind::Projection(sub, ProjectionElem::Deref) => sub,
+            _ => place,
+        },
+        _ => return None,
+    };
+    groups.get(&place.as_local()?).copied()
+}
+
+/// Turn the tupled arguments of a closure call into a list of arguments.
+fn untuple(args: Operand) -> Vec<Operand> {
+    let (place, mk_operand): (_, fn(Place) -> Operand) = match args {
+        Operand::Move(place) => (place, Operand::Move),
+        Operand::Copy(place) => (place, Operand::Copy),
+        // The unit tuple.
+        Operand::Const(_) => return vec![],
+    };
+    let TyKind::Adt(TypeId::Tuple, tys) = place.ty.kind() else {
+        unreachable!()
+    };
+    let arity = tys.types.elem_count();
+    tys.types
+        .iter()
+        .enumerate()
+        .map(|(i, ty)| {
+            let field = ProjectionElem::Field(FieldProjKind::Tuple(arity), FieldId::new(i));
+            mk_operand(place.clone().project(field, ty.clone()))
+        })
+        .collect()
+}
+
+/// Rewrite the body of a function that builds and calls defunctionalized closures.
+fn update_callers(
+    ctx: &TransformCtx,
+    closures: &HashMap<FunDeclId, ClosureData>,
+    body: &mut ExprBody,
+) {
+    // Map each local that holds a closure value, or a reference to one, to the local in which the
+    // closure was built.
+    let mut groups: HashMap<VarId, VarId> = HashMap::new();
+    // The closure (and its generics) built in each of these root locals.
+    let mut built: HashMap<VarId, (FunDeclId, GenericArgs)> = HashMap::new();
+    // The roots whose closure value we leave untouched.
+    let mut invalid: HashSet<VarId> = HashSet::new();
+
+    for block in body.body.iter() {
+        for st in &block.statements {
+            if let RawStatement::Assign(dest, rvalue) = &st.content
+                && let Rvalue::Aggregate(AggregateKind::Closure(id, generics), _) = rvalue
+                && let Some(var) = dest.as_local()
+                && closures.contains_key(id)
+            {
+                groups.insert(var, var);
+                let closure = (*id, generics.clone());
+                if built
+                    .insert(var, closure.clone())
+                    .is_some_and(|c| c != closure)
+                {
+                    invalid.insert(var);
+                }
+            }
+        }
+    }
+    if groups.is_empty() {
+        return;
+    }
+    // Follow the moves and borrows of the closure values.
+    loop {
+        let mut changed = false;
+        for block in body.body.iter() {
+            for st in &block.statements {
+                if let RawStatement::Assign(dest, rvalue) = &st.content
+                    && let Some(var) = dest.as_local()
+                    && !groups.contains_key(&var)
+                    && let Some(root) = closure_source(&groups, rvalue)
+                {
+                    groups.insert(var, root);
+                    changed = true;
+                }
+            }
+        }
+        if !changed {
+            break;
+        }
+    }
+
+    // Check that we understand all the uses of the closure values.
+    for (var, root) in &groups {
+        let ty = &body.locals.vars[*var].ty;
+        let ty = match ty.kind() {
+            TyKind::Ref(_, ty, _) => ty,
+            _ => ty,
+        };
+        if !ty.kind().is_arrow() {
+            invalid.insert(*root);
+        }
+    }
+    for block in body.body.iter() {
+        for st in &block.statements {
+            match &st.content {
+                RawStatement::Assign(dest, rvalue)
+                    if let Some(&root) = dest.as_local().and_then(|var| groups.get(&var)) =>
+                {
+                    if let Rvalue::Aggregate(AggregateKind::Closure(..), ops) = rvalue {
+                        mark_uses(ops, &groups, &mut invalid);
+                    } else if closure_source(&groups, rvalue) != Some(root) {
+                        invalid.insert(root);
+                    }
+                }
+                RawStatement::Call(call)
+                    if let Some((method, var, args, root)) =
+                        closure_call(ctx, call).and_then(|(method, var, args)| {
+                            Some((method, var, args, *groups.get(&var)?))
+                        }) =>
+                {
+                    let expected = built.get(&root).map(|(id, _)| closures[id].call_method());
+                    let by_ref = body.locals.vars[var].ty.kind().is_ref();
+                    let is_tuple = match args {
+                        Operand::Const(c) => c.ty.is_unit(),
+                        _ => matches!(args.ty().kind(), TyKind::Adt(TypeId::Tuple, _)),
+                    };
+                    if expected != Some(method) || by_ref == (method == "call_once") || !is_tuple {
+                        invalid.insert(root);
+                    }
+                    mark_uses(args, &groups, &mut invalid);
+                    mark_uses(&call.dest, &groups, &mut invalid);
+                }
+                RawStatement::StorageDead(_) => {}
+                RawStatement::Drop(place)
+                | RawStatement::FakeRead(place)
+                | RawStatement::Deinit(place)
+                    if place.is_local() => {}
+                _ => mark_uses(st, &groups, &mut invalid),
+            }
+        }
+        mark_uses(&block.terminator, &groups, &mut invalid);
+    }
+    groups.retain(|_, root| !invalid.contains(root) && built.contains_key(root));
+    if groups.is_empty() {
+        return;
+    }
+
+    // Retype the closure values.
+    let mut new_tys = HashMap::new();
+    for (var, root) in &groups {
+        let (id, generics) = &built[root];
+        let state_ty = closures[id].state_ty(generics);
+        let local = &mut body.locals.vars[*var];
+        local.ty = match local.ty.kind() {
+            TyKind::Ref(r, _, kind) => TyKind::Ref(r.clone(), state_ty, *kind).into_ty(),
+            _ => state_ty,
+        };
+        new_tys.insert(*var, local.ty.clone());
+    }
+    body.body
+        .dyn_visit_in_body_mut(|place: &mut Place| retype_place(place, &new_tys));
+
+    // Build the state structs and call the closure functions directly.
+    for block in body.body.iter_mut() {
+        for st in &mut block.statements {
+            match &mut st.content {
+                RawStatement::Assign(dest, Rvalue::Aggregate(kind, _))
+                    if dest.as_local().is_some_and(|var| groups.contains_key(&var)) =>
+                {
+                    if let AggregateKind::Closure(id, generics) = kind {
+                        let data = &closures[id];
+                        let generics = data.args(generics, GenericsSource::item(data.state_id));
+                        *kind =
+                            AggregateKind::Adt(TypeId::Adt(data.state_id), None, None, generics);
+                    }
+                }
+                RawStatement::Call(call)
+                    if let Some(&root) =
+                        closure_call(ctx, call).and_then(|(_, var, _)| groups.get(&var)) =>
+                {
+                    let (id, generics) = &built[&root];
+                    call.func = FnOperand::Regular(FnPtr {
+                        func: FunIdOrTraitMethodRef::Fun(FunId::Regular(*id)),
+                        generics: closures[id].args(generics, GenericsSource::item(*id)),
+                    });
+                    let args = call.args.pop().unwrap();
+                    call.args.extend(untuple(args));
+                }
+                _ => {}
+            }
+        }
+    }
+}
+
+pub struct Transform;
+impl TransformPass for Transform {
+    fn transform_ctx(&self, ctx: &mut TransformCtx) {
+        if !ctx.options.defunctionalize {
+            return;
+        }
+
+        // Introduce the state structs.
+        let mut closures: HashMap<FunDeclId, ClosureData> = HashMap::new();
+        for id in ctx.translated.fun_decls.all_indices() {
+            let Some(decl) = ctx.translated.fun_decls.get(id) else {
+                continue;
+            };
+            let Some(info) = &decl.signature.closure_info else {
+                continue;
+            };
+            let (decl, info) = (decl.clone(), info.clone());
+            let state_id = add_state_struct(ctx, &decl, &info);
+            let data = ClosureData {
+                state_id,
+                kind: info.kind,
+                num_regions: decl.signature.generics.regions.elem_count(),
+            };
+            closures.insert(id, data);
+        }
+        if closures.is_empty() {
+            return;
+        }
+
+        ctx.for_each_fun_decl(|ctx, decl| {
+            if let Some(data) = closures.get(&decl.def_id) {
+                update_closure(decl, data);
+            }
+            if let Ok(body) = &mut decl.body {
+                update_callers(ctx, &closures, body.as_unstructured_mut().unwrap());
+            }
+        });
+    }
+}
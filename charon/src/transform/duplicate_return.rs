@@ -35,7 +35,11 @@ use super::ctx::UllbcPass;
 
 pub struct Transform;
 impl UllbcPass for Transform {
-    fn transform_body(&self, _ctx: &mut TransformCtx, b: &mut ExprBody) {
+    fn transform_body(&self, ctx: &mut TransformCtx, b: &mut ExprBody) {
+        // We would merge the return blocks back in `single_return`.
+        if ctx.options.single_return {
+            return;
+        }
         // Find the return block id (there should be one).
         let returns: HashMap<BlockId, Span> = b
             .body
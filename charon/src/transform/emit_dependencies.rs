@@ -0,0 +1,126 @@
+//! Write to a file the list of external crates whose items appear in the translated crate, along
+//! with their versions when we can find them in the `Cargo.lock` of the translated crate. This
+//! pass doesn't modify the crate; it only runs when `--emit-dependencies` is set.
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs::File;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::ast::*;
+use crate::transform::TransformCtx;
+
+use super::ctx::TransformPass;
+
+#[derive(Debug, Serialize)]
+struct Dependency {
+    name: String,
+    /// The versions of this crate listed in `Cargo.lock`. Empty if the crate isn't listed there,
+    /// e.g. for the standard library crates.
+    versions: Vec<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct CargoLock {
+    #[serde(default)]
+    package: Vec<CargoLockPackage>,
+}
+
+#[derive(Debug, Deserialize)]
+struct CargoLockPackage {
+    name: String,
+    version: String,
+}
+
+/// Find the `Cargo.lock` that applies to the crate being translated and read the versions of the
+/// packages it lists, indexed by crate name. Returns an empty map if there is no such file.
+fn locked_versions() -> BTreeMap<String, BTreeSet<String>> {
+    // Cargo sets `CARGO_MANIFEST_DIR` when it calls us; otherwise we look from the current
+    // directory.
+    let start = match std::env::var_os("CARGO_MANIFEST_DIR") {
+        Some(dir) => PathBuf::from(dir),
+        None => match std::env::current_dir() {
+            Ok(dir) => dir,
+            Err(_) => return BTreeMap::new(),
+        },
+    };
+    let Some(lock_path) = start
+        .ancestors()
+        .map(|dir| dir.join("Cargo.lock"))
+        .find(|path| path.is_file())
+    else {
+        return BTreeMap::new();
+    };
+    let lock = match read_cargo_lock(&lock_path) {
+        Ok(lock) => lock,
+        Err(err) => {
+            warn!("Could not read `{}`: {err}", lock_path.display());
+            CargoLock::default()
+        }
+    };
+    let mut versions: BTreeMap<String, BTreeSet<String>> = BTreeMap::new();
+    for package in lock.package {
+        // Package names may contain dashes, crate names use underscores instead.
+        versions
+            .entry(package.name.replace('-', "_"))
+            .or_default()
+            .insert(package.version);
+    }
+    versions
+}
+
+fn read_cargo_lock(path: &Path) -> Result<CargoLock, String> {
+    let contents = std::fs::read_to_string(path).map_err(|err| err.to_string())?;
+    toml::from_str(&contents).map_err(|err| err.to_string())
+}
+
+pub struct Transform {
+    pub path: PathBuf,
+}
+
+impl Transform {
+    pub fn new(path: PathBuf) -> &'static Self {
+        Box::leak(Box::new(Self { path }))
+    }
+}
+
+impl TransformPass for Transform {
+    fn transform_ctx(&self, ctx: &mut TransformCtx) {
+        // The first element of a name is the crate the item comes from. This covers the opaque
+        // items as well as the impls we translated.
+        let crates: BTreeSet<&String> = ctx
+            .translated
+            .item_names
+            .values()
+            .filter_map(|name| match name.name.first() {
+                Some(PathElem::Ident(krate, _)) => Some(krate),
+                _ => None,
+            })
+            .filter(|krate| **krate != ctx.translated.crate_name)
+            .collect();
+
+        let mut versions = locked_versions();
+        let dependencies: Vec<Dependency> = crates
+            .into_iter()
+            .map(|krate| Dependency {
+                name: krate.clone(),
+                versions: versions
+                    .remove(krate)
+                    .map(|vs| vs.into_iter().collect())
+                    .unwrap_or_default(),
+            })
+            .collect();
+
+        let res = File::create(&self.path)
+            .map_err(|err| err.to_string())
+            .and_then(|file| {
+                serde_json::to_writer_pretty(file, &dependencies).map_err(|err| err.to_string())
+            });
+        if let Err(err) = res {
+            error!(
+                "Could not write the dependencies to `{:?}`: {err}",
+                self.path
+            );
+        }
+    }
+}
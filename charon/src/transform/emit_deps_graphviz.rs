@@ -0,0 +1,94 @@
+//! Write the dependency graph between items to a graphviz `.dot` file. This is the graph that
+//! [super::reorder_decls] uses to order the declarations; the groups of mutually recursive items
+//! it computed are drawn as clusters. This pass doesn't modify the crate; it only runs when
+//! `--emit-deps-graphviz` is set.
+use std::fmt::Write;
+use std::path::PathBuf;
+
+use crate::ast::*;
+use crate::transform::TransformCtx;
+
+use super::ctx::TransformPass;
+use super::reorder_decls::compute_declarations_graph;
+
+pub struct Transform {
+    pub path: PathBuf,
+}
+
+impl Transform {
+    pub fn new(path: PathBuf) -> &'static Self {
+        Box::leak(Box::new(Self { path }))
+    }
+}
+
+fn node_name(id: AnyTransId) -> String {
+    match id {
+        AnyTransId::Type(id) => format!("type_{id}"),
+        AnyTransId::Fun(id) => format!("fun_{id}"),
+        AnyTransId::Global(id) => format!("global_{id}"),
+        AnyTransId::TraitDecl(id) => format!("trait_decl_{id}"),
+        AnyTransId::TraitImpl(id) => format!("trait_impl_{id}"),
+    }
+}
+
+fn node_shape(id: AnyTransId) -> &'static str {
+    match id {
+        AnyTransId::Type(_) => "ellipse",
+        AnyTransId::Fun(_) => "box",
+        AnyTransId::Global(_) => "diamond",
+        AnyTransId::TraitDecl(_) => "hexagon",
+        AnyTransId::TraitImpl(_) => "octagon",
+    }
+}
+
+fn escape(s: &str) -> String {
+    s.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+fn to_dot(ctx: &TransformCtx) -> Result<String, std::fmt::Error> {
+    let graph = compute_declarations_graph(ctx);
+    let mut dot = String::new();
+    writeln!(dot, "digraph deps {{")?;
+    for id in graph.nodes() {
+        writeln!(
+            dot,
+            "  {} [label=\"{}\", shape={}];",
+            node_name(id),
+            escape(&id.fmt_with_ctx(ctx)),
+            node_shape(id)
+        )?;
+    }
+    let rec_groups = ctx
+        .translated
+        .ordered_decls
+        .iter()
+        .flatten()
+        .filter(|group| group.to_mixed_group().is_rec());
+    for (i, group) in rec_groups.enumerate() {
+        writeln!(dot, "  subgraph cluster_{i} {{")?;
+        writeln!(dot, "    style=dashed;")?;
+        for id in group.get_ids() {
+            writeln!(dot, "    {};", node_name(id))?;
+        }
+        writeln!(dot, "  }}")?;
+    }
+    for (src, tgt) in graph.edges() {
+        writeln!(dot, "  {} -> {};", node_name(src), node_name(tgt))?;
+    }
+    writeln!(dot, "}}")?;
+    Ok(dot)
+}
+
+impl TransformPass for Transform {
+    fn transform_ctx(&self, ctx: &mut TransformCtx) {
+        let res = to_dot(ctx)
+            .map_err(|err| err.to_string())
+            .and_then(|dot| std::fs::write(&self.path, dot).map_err(|err| err.to_string()));
+        if let Err(err) = res {
+            error!(
+                "Could not write the dependency graph to `{:?}`: {err}",
+                self.path
+            );
+        }
+    }
+}
@@ -0,0 +1,117 @@
+//! Write to a file the list of the items that we did not translate the contents of: opaque types
+//! and functions without a body. This is what a verification effort has to take on faith. This
+//! pass doesn't modify the crate; it only runs when `--emit-opaque-manifest` is set.
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::path::PathBuf;
+
+use serde::Serialize;
+
+use crate::ast::*;
+use crate::formatter::IntoFormatter;
+use crate::pretty::FmtWithCtx;
+use crate::transform::TransformCtx;
+
+use super::ctx::TransformPass;
+
+/// Why we don't have the contents of an item.
+#[derive(Debug, Clone, Copy, Serialize)]
+#[serde(rename_all = "snake_case")]
+enum OpaqueReason {
+    /// The item comes from another crate and wasn't selected for translation.
+    External,
+    /// The item was marked opaque, with `#[charon::opaque]` or `--opaque`.
+    MarkedOpaque,
+    /// We failed to translate the item.
+    Unsupported,
+}
+
+#[derive(Debug, Clone, Copy, Serialize)]
+#[serde(rename_all = "snake_case")]
+enum OpaqueItemKind {
+    Type,
+    Function,
+}
+
+#[derive(Debug, Serialize)]
+struct OpaqueItem {
+    kind: OpaqueItemKind,
+    name: String,
+    reason: OpaqueReason,
+}
+
+impl OpaqueReason {
+    fn new(item_meta: &ItemMeta) -> Self {
+        if item_meta.opacity.is_opaque() {
+            OpaqueReason::MarkedOpaque
+        } else if !item_meta.is_local {
+            OpaqueReason::External
+        } else {
+            OpaqueReason::Unsupported
+        }
+    }
+}
+
+pub struct Transform {
+    pub path: PathBuf,
+}
+
+impl Transform {
+    pub fn new(path: PathBuf) -> &'static Self {
+        Box::leak(Box::new(Self { path }))
+    }
+}
+
+impl TransformPass for Transform {
+    fn transform_ctx(&self, ctx: &mut TransformCtx) {
+        let fmt_ctx = &ctx.into_fmt();
+        let types = ctx.translated.type_decls.iter().filter_map(|decl| {
+            let reason = match &decl.kind {
+                TypeDeclKind::Opaque => OpaqueReason::new(&decl.item_meta),
+                TypeDeclKind::Error(_) => OpaqueReason::Unsupported,
+                _ => return None,
+            };
+            Some((OpaqueItemKind::Type, &decl.item_meta, reason))
+        });
+        let funs = ctx.translated.fun_decls.iter().filter_map(|decl| {
+            // Required trait methods have no body by definition.
+            if let ItemKind::TraitDecl {
+                has_default: false, ..
+            } = decl.kind
+            {
+                return None;
+            }
+            let reason = match &decl.body {
+                Ok(_) => return None,
+                Err(_) => OpaqueReason::new(&decl.item_meta),
+            };
+            Some((OpaqueItemKind::Function, &decl.item_meta, reason))
+        });
+
+        // Group the items by originating crate.
+        let mut manifest: BTreeMap<String, Vec<OpaqueItem>> = BTreeMap::new();
+        for (kind, item_meta, reason) in types.chain(funs) {
+            let krate = match item_meta.name.name.first() {
+                Some(PathElem::Ident(krate, _)) => krate.clone(),
+                _ => String::new(),
+            };
+            manifest.entry(krate).or_default().push(OpaqueItem {
+                kind,
+                name: item_meta.name.fmt_with_ctx(fmt_ctx),
+                reason,
+            });
+        }
+
+        let res = File::create(&self.path)
+            .map_err(|err| err.to_string())
+            .and_then(|file| {
+                serde_json::to_writer_pretty(file, &manifest).map_err(|err| err.to_string())
+            });
+        if let Err(err) = res {
+            error!(
+                "Could not write the opaque manifest to `{:?}`: {err}",
+                self.path
+            );
+        }
+    }
+}
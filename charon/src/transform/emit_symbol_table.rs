@@ -0,0 +1,101 @@
+//! Write to a file a table that maps the translated functions and types to their name, rustc
+//! `DefId` and source span, so that the output can be correlated with other tools. This pass
+//! doesn't modify the crate; it only runs when `--emit-symbol-table` is set.
+use std::fs::File;
+use std::path::PathBuf;
+
+use serde::Serialize;
+
+use crate::ast::*;
+use crate::formatter::IntoFormatter;
+use crate::pretty::FmtWithCtx;
+use crate::transform::TransformCtx;
+
+use super::ctx::TransformPass;
+
+#[derive(Debug, Clone, Copy, Serialize)]
+#[serde(rename_all = "snake_case")]
+enum SymbolKind {
+    Type,
+    Function,
+}
+
+#[derive(Debug, Serialize)]
+struct SymbolSpan {
+    file: String,
+    beg: Loc,
+    end: Loc,
+}
+
+#[derive(Debug, Serialize)]
+struct Symbol {
+    kind: SymbolKind,
+    /// The index of the item in `type_decls` or `fun_decls`.
+    id: usize,
+    name: String,
+    def_id: Option<String>,
+    span: SymbolSpan,
+}
+
+pub struct Transform {
+    pub path: PathBuf,
+}
+
+impl Transform {
+    pub fn new(path: PathBuf) -> &'static Self {
+        Box::leak(Box::new(Self { path }))
+    }
+}
+
+impl TransformPass for Transform {
+    fn transform_ctx(&self, ctx: &mut TransformCtx) {
+        let fmt_ctx = &ctx.into_fmt();
+        let symbol = |kind, id: AnyTransId, index: usize, item_meta: &ItemMeta| {
+            let span = item_meta.span.span;
+            let file = match ctx.translated.files.get(span.file_id) {
+                Some(file) => file.name.to_string().into_owned(),
+                None => String::new(),
+            };
+            Symbol {
+                kind,
+                id: index,
+                name: item_meta.name.fmt_with_ctx(fmt_ctx),
+                def_id: ctx.rustc_def_ids.get(&id).cloned(),
+                span: SymbolSpan {
+                    file,
+                    beg: span.beg,
+                    end: span.end,
+                },
+            }
+        };
+        let types = ctx.translated.type_decls.iter().map(|decl| {
+            symbol(
+                SymbolKind::Type,
+                decl.def_id.into(),
+                decl.def_id.index(),
+                &decl.item_meta,
+            )
+        });
+        let funs = ctx.translated.fun_decls.iter().map(|decl| {
+            symbol(
+                SymbolKind::Function,
+                decl.def_id.into(),
+                decl.def_id.index(),
+                &decl.item_meta,
+            )
+        });
+        let table: Vec<Symbol> = types.chain(funs).collect();
+
+        let res = File::create(&self.path)
+            .map_err(|err| err.to_string())
+            .and_then(|file| {
+                serde_json::to_writer_pretty(file, &table).map_err(|err| err.to_string())
+            });
+        if let Err(err) = res {
+            error!(
+                "Could not write the symbol table to `{:?}`: {err}",
+                self.path
+            );
+        }
+    }
+}
@@ -0,0 +1,50 @@
+//! Write to a file the size and alignment of the translated types, as computed by rustc during
+//! translation. This pass doesn't modify the crate; it only runs when `--emit-type-sizes` is set.
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::path::PathBuf;
+
+use serde::Serialize;
+
+use crate::transform::TransformCtx;
+
+use super::ctx::TransformPass;
+
+/// The layout of a type, in bytes.
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct TypeLayout {
+    pub size: u64,
+    pub align: u64,
+}
+
+pub struct Transform {
+    pub path: PathBuf,
+}
+
+impl Transform {
+    pub fn new(path: PathBuf) -> &'static Self {
+        Box::leak(Box::new(Self { path }))
+    }
+}
+
+impl TransformPass for Transform {
+    fn transform_ctx(&self, ctx: &mut TransformCtx) {
+        // Index by the position of the type in `type_decls`. We only have layouts for the types
+        // that are still there, e.g. not for the types rustc couldn't lay out because they are
+        // generic.
+        let layouts: BTreeMap<usize, TypeLayout> = ctx
+            .type_layouts
+            .iter()
+            .filter(|(id, _)| ctx.translated.type_decls.get(**id).is_some())
+            .map(|(id, layout)| (id.index(), *layout))
+            .collect();
+        let res = File::create(&self.path)
+            .map_err(|err| err.to_string())
+            .and_then(|file| {
+                serde_json::to_writer_pretty(file, &layouts).map_err(|err| err.to_string())
+            });
+        if let Err(err) = res {
+            error!("Could not write the type sizes to `{:?}`: {err}", self.path);
+        }
+    }
+}
@@ -1164,7 +1164,10 @@ impl VisitAstMut for UpdateItemBody<'_> {
     }
 
     fn enter_ty_kind(&mut self, kind: &mut TyKind) {
-        if let TyKind::TraitType(tref, name) = kind {
+        // Generic associated types aren't handled by this pass; we leave them as projections.
+        if let TyKind::TraitType(tref, name, args) = kind
+            && args.is_empty()
+        {
             let path = TraitRefPath::self_ref().with_assoc_type(name.clone());
             if let Some(new_ty) = self.lookup_path_on_trait_ref(&path, &tref.kind) {
                 *kind = new_ty.kind().clone();
@@ -1243,7 +1246,11 @@ impl TransformPass for Transform {
                 modifications.compute_replacements(|path| {
                     let new_type_name = TraitItemName(path.to_name());
                     tr.types.push(new_type_name.clone());
-                    TyKind::TraitType(self_tref.clone(), new_type_name).into_ty()
+                    let args = GenericArgs::empty(GenericsSource::TraitType(
+                        tr.def_id,
+                        new_type_name.clone(),
+                    ));
+                    TyKind::TraitType(self_tref.clone(), new_type_name, args).into_ty()
                 })
             } else {
                 modifications.compute_replacements(|path| {
@@ -3,14 +3,14 @@
 
 use std::collections::{HashMap, HashSet};
 
-use crate::transform::TransformCtx;
+use crate::transform::BodyTransformCtx;
 use crate::ullbc_ast::*;
 
-use super::ctx::UllbcPass;
+use super::ctx::UllbcBodyPass;
 
 pub struct Transform;
-impl UllbcPass for Transform {
-    fn transform_body(&self, _ctx: &mut TransformCtx, b: &mut ExprBody) {
+impl UllbcBodyPass for Transform {
+    fn transform_body(&self, _ctx: &mut BodyTransformCtx<'_>, b: &mut ExprBody) {
         // Perform a depth-first search to identify all the blocks reachable
         // from the first block.
         let mut explored: HashSet<BlockId> = HashSet::new();
@@ -0,0 +1,89 @@
+//! # Micro-pass: replace the `if`s on a constant boolean with a jump to the branch that is taken.
+//! Such conditions typically come from `cfg!(..)`, which expands to `true` or `false`:
+//! ```text
+//! @2 := const true;           ~>  goto bb1
+//! if move @2 { goto bb1 }
+//! else { goto bb2 }
+//! ```
+//! The branch that is not taken becomes unreachable, and is removed by
+//! [crate::transform::filter_unreachable_blocks].
+use crate::transform::BodyTransformCtx;
+use crate::ullbc_ast::*;
+
+use super::ctx::UllbcBodyPass;
+
+/// If the operand is a boolean literal, return its value.
+fn as_const_bool(op: &Operand) -> Option<bool> {
+    match op {
+        Operand::Const(ConstantExpr {
+            value: RawConstantExpr::Literal(Literal::Bool(b)),
+            ..
+        }) => Some(*b),
+        _ => None,
+    }
+}
+
+/// Count the places in the body that use the given local.
+fn count_uses(b: &ExprBody, var: VarId) -> usize {
+    let mut count = 0;
+    b.body.dyn_visit_in_body(|place: &Place| {
+        if place.as_local() == Some(var) {
+            count += 1;
+        }
+    });
+    count
+}
+
+pub struct Transform;
+impl UllbcBodyPass for Transform {
+    fn transform_body(&self, _ctx: &mut BodyTransformCtx<'_>, b: &mut ExprBody) {
+        for block_id in b.body.all_indices() {
+            let block = &b.body[block_id];
+            let RawTerminator::Switch {
+                discr,
+                targets: SwitchTargets::If(if_block, else_block),
+            } = &block.terminator.content
+            else {
+                continue;
+            };
+            let (if_block, else_block) = (*if_block, *else_block);
+
+            // The condition is either a constant, or a local that was assigned a constant in the
+            // same block.
+            let (value, assign) = if let Some(value) = as_const_bool(discr) {
+                (value, None)
+            } else if let Operand::Move(place) | Operand::Copy(place) = discr
+                && let Some(var) = place.as_local()
+                && let Some((i, st)) = block.statements.iter().enumerate().rev().find(|(_, st)| {
+                    let mut uses_var = false;
+                    st.dyn_visit_in_body(|p: &Place| uses_var |= p.var_id() == var);
+                    uses_var
+                })
+                && let RawStatement::Assign(dest, Rvalue::Use(op)) = &st.content
+                && dest.as_local() == Some(var)
+                && let Some(value) = as_const_bool(op)
+            {
+                (value, Some((i, var)))
+            } else {
+                continue;
+            };
+
+            // Remove the assignment to the local if this was its only use.
+            if let Some((i, var)) = assign
+                && count_uses(b, var) == 2
+            {
+                b.body[block_id].statements[i].content = RawStatement::Nop;
+                b.body.dyn_visit_in_body_mut(|st: &mut Statement| {
+                    if let RawStatement::StorageDead(dead) = st.content
+                        && dead == var
+                    {
+                        st.content = RawStatement::Nop;
+                    }
+                });
+            }
+
+            let target = if value { if_block } else { else_block };
+            b.body[block_id].terminator.content = RawTerminator::Goto { target };
+        }
+    }
+}
@@ -0,0 +1,95 @@
+//! # Micro-pass: evaluate the `+`, `-` and `*` operations on integer constants (only with
+//! `--fold-constants`).
+//!
+//! How an overflowing operation is folded depends on the operation, hence on `--overflow`: the
+//! wrapping operations wrap around, and the operations that fail on overflow are reported as
+//! errors and left unchanged. The checked operations are not folded.
+use crate::register_error;
+use crate::transform::TransformCtx;
+use crate::ullbc_ast::*;
+
+use super::ctx::UllbcPass;
+
+/// The result of `x op y`, or `None` if it doesn't fit in the type.
+fn exact_result(op: BinOp, x: &ScalarValue, y: &ScalarValue) -> Option<ScalarValue> {
+    let ty = x.get_integer_ty();
+    if x.is_int() {
+        let (x, y) = (x.as_int().ok()?, y.as_int().ok()?);
+        let v = match op {
+            BinOp::Add => x.checked_add(y),
+            BinOp::Sub => x.checked_sub(y),
+            BinOp::Mul => x.checked_mul(y),
+            _ => None,
+        }?;
+        ScalarValue::from_int(ty, v).ok()
+    } else {
+        let (x, y) = (x.as_uint().ok()?, y.as_uint().ok()?);
+        let v = match op {
+            BinOp::Add => x.checked_add(y),
+            BinOp::Sub => x.checked_sub(y),
+            BinOp::Mul => x.checked_mul(y),
+            _ => None,
+        }?;
+        ScalarValue::from_uint(ty, v).ok()
+    }
+}
+
+/// The result of `x op y`, wrapped around to fit in the type.
+fn wrapping_result(op: BinOp, x: &ScalarValue, y: &ScalarValue) -> Option<ScalarValue> {
+    let (x_bits, y_bits) = (x.to_bits(), y.to_bits());
+    let bits = match op {
+        BinOp::WrappingAdd => x_bits.wrapping_add(y_bits),
+        BinOp::WrappingSub => x_bits.wrapping_sub(y_bits),
+        BinOp::WrappingMul => x_bits.wrapping_mul(y_bits),
+        _ => return None,
+    };
+    // Truncating the bits gives the result modulo the size of the type.
+    Some(ScalarValue::from_bits(x.get_integer_ty(), bits))
+}
+
+pub struct Transform;
+impl UllbcPass for Transform {
+    fn transform_body(&self, ctx: &mut TransformCtx, b: &mut ExprBody) {
+        if !ctx.options.fold_constants {
+            return;
+        }
+        for block in b.body.iter_mut() {
+            for st in block.statements.iter_mut() {
+                let RawStatement::Assign(_, rvalue) = &mut st.content else {
+                    continue;
+                };
+                let Rvalue::BinaryOp(op, Operand::Const(x), Operand::Const(y)) = rvalue else {
+                    continue;
+                };
+                let (
+                    RawConstantExpr::Literal(Literal::Scalar(x)),
+                    RawConstantExpr::Literal(Literal::Scalar(y)),
+                ) = (&x.value, &y.value)
+                else {
+                    continue;
+                };
+                if x.get_integer_ty() != y.get_integer_ty() {
+                    continue;
+                }
+                let result = match op {
+                    BinOp::Add | BinOp::Sub | BinOp::Mul => match exact_result(*op, x, y) {
+                        Some(result) => result,
+                        None => {
+                            register_error!(
+                                ctx,
+                                st.span,
+                                "This arithmetic operation will overflow: `{x} {op} {y}`"
+                            );
+                            continue;
+                        }
+                    },
+                    _ => match wrapping_result(*op, x, y) {
+                        Some(result) => result,
+                        None => continue,
+                    },
+                };
+                *rvalue = Rvalue::Use(Operand::Const(result.to_constant()));
+            }
+        }
+    }
+}
@@ -0,0 +1,141 @@
+//! When both branches of an `if` start by computing the same side-effect-free rvalues, compute
+//! them once before the `if` and share the results between the branches:
+//! ```text
+//! if b {                               @3 := copy x;
+//!   @3 := copy x;                      @4 := copy y;
+//!   @4 := copy y;                      @0 := move @3 + move @4;
+//!   @0 := move @3 + move @4;           if b {
+//!   ...                         ~>       ...
+//! } else {                             } else {
+//!   @5 := copy x;                        ...
+//!   @6 := copy y;                      }
+//!   @0 := move @5 + move @6;
+//!   ...
+//! }
+//! ```
+//! The temporaries of the `else` branch are renamed to the ones of the `then` branch. This is the
+//! dual of inlining, and avoids duplicating the proof obligations related to the computations.
+//! This pass only runs when `--cse` is set.
+use std::collections::{HashMap, HashSet};
+
+use crate::llbc_ast::*;
+use crate::transform::TransformCtx;
+
+use super::ctx::LlbcPass;
+
+/// Whether evaluating this rvalue has no side-effects (borrows count as side-effects).
+fn is_pure(rv: &Rvalue) -> bool {
+    match rv {
+        Rvalue::Use(_)
+        | Rvalue::BinaryOp(..)
+        | Rvalue::UnaryOp(..)
+        | Rvalue::NullaryOp(..)
+        | Rvalue::Discriminant(..)
+        | Rvalue::Aggregate(..)
+        | Rvalue::Len(..)
+        | Rvalue::Repeat(..) => true,
+        Rvalue::Ref(..)
+        | Rvalue::RawPtr(..)
+        | Rvalue::StructUpdate(..)
+        | Rvalue::Global(..)
+        | Rvalue::GlobalRef(..)
+        | Rvalue::ShallowInitBox(..) => false,
+    }
+}
+
+/// The locals mentioned in this value.
+fn vars<T: BodyVisitable>(x: &T) -> HashSet<VarId> {
+    let mut vars = HashSet::new();
+    x.dyn_visit_in_body(|p: &Place| {
+        vars.insert(p.var_id());
+    });
+    vars
+}
+
+fn rename_vars<T: BodyVisitable>(x: &mut T, renaming: &HashMap<VarId, VarId>) {
+    x.dyn_visit_in_body_mut(|p: &mut Place| {
+        if let PlaceKind::Base(var_id) = &mut p.kind
+            && let Some(new_id) = renaming.get(var_id)
+        {
+            *var_id = *new_id;
+        }
+    });
+}
+
+pub struct Transform;
+
+impl Transform {
+    fn update_statements(seq: &mut [Statement]) -> Vec<Statement> {
+        let [Statement {
+            content: RawStatement::Switch(Switch::If(cond, then_block, else_block)),
+            ..
+        }, ..] = seq
+        else {
+            return Vec::new();
+        };
+
+        // We can't touch the locals used by the condition, which is evaluated after the hoisted
+        // statements.
+        let cond_vars = vars(cond);
+        let then_vars = vars(then_block);
+        let else_vars = vars(else_block);
+        // Maps the locals assigned in the common prefix of the `else` branch to the corresponding
+        // locals of the `then` branch.
+        let mut renaming: HashMap<VarId, VarId> = HashMap::new();
+        let mut prefix_len = 0;
+        let branches = then_block
+            .statements
+            .iter()
+            .zip(else_block.statements.iter());
+        for (then_st, else_st) in branches {
+            let (
+                RawStatement::Assign(then_dest, then_rv),
+                RawStatement::Assign(else_dest, else_rv),
+            ) = (&then_st.content, &else_st.content)
+            else {
+                break;
+            };
+            let (Some(then_var), Some(else_var)) = (then_dest.as_local(), else_dest.as_local())
+            else {
+                break;
+            };
+            let mut else_rv = else_rv.clone();
+            rename_vars(&mut else_rv, &renaming);
+            if !is_pure(then_rv) || *then_rv != else_rv || then_dest.ty != else_dest.ty {
+                break;
+            }
+            if cond_vars.contains(&then_var)
+                || cond_vars.contains(&else_var)
+                || !vars(then_rv).is_disjoint(&cond_vars)
+            {
+                break;
+            }
+            // The `else` branch must not observe the hoisted assignment to a local of the `then`
+            // branch, and vice-versa.
+            if then_var != else_var {
+                if else_vars.contains(&then_var) || then_vars.contains(&else_var) {
+                    break;
+                }
+                renaming.insert(else_var, then_var);
+            }
+            prefix_len += 1;
+        }
+        if prefix_len == 0 {
+            return Vec::new();
+        }
+
+        else_block.statements.drain(..prefix_len);
+        rename_vars(else_block, &renaming);
+        then_block.statements.drain(..prefix_len).collect()
+    }
+}
+
+impl LlbcPass for Transform {
+    fn transform_body(&self, ctx: &mut TransformCtx, b: &mut ExprBody) {
+        // This pass is opt-in.
+        if !ctx.options.cse {
+            return;
+        }
+        b.body.transform_sequences(Transform::update_statements)
+    }
+}
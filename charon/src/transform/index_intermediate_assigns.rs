@@ -33,10 +33,10 @@
 //! *dest = move tmp;
 //! ```
 
-use crate::transform::TransformCtx;
+use crate::transform::BodyTransformCtx;
 use crate::ullbc_ast::*;
 
-use super::ctx::UllbcPass;
+use super::ctx::UllbcBodyPass;
 
 fn contains_index_proj<T: BodyVisitable>(x: &T) -> bool {
     let mut contains_index = false;
@@ -63,8 +63,8 @@ impl Rvalue {
 
 pub struct Transform;
 
-impl UllbcPass for Transform {
-    fn transform_body(&self, _ctx: &mut TransformCtx, b: &mut ExprBody) {
+impl UllbcBodyPass for Transform {
+    fn transform_body(&self, _ctx: &mut BodyTransformCtx<'_>, b: &mut ExprBody) {
         for block in &mut b.body {
             block.transform(|st: &mut Statement| {
                 match &mut st.content {
@@ -1,9 +1,9 @@
 //! Desugar array/slice index operations to function calls.
-use crate::transform::TransformCtx;
+use crate::transform::BodyTransformCtx;
 use crate::ullbc_ast::*;
 use derive_generic_visitor::*;
 
-use super::ctx::UllbcPass;
+use super::ctx::UllbcBodyPass;
 
 /// We replace some place constructors with function calls. To do that, we explore all the places
 /// in a body and deconstruct a given place access into intermediate assignments.
@@ -142,6 +142,7 @@ impl<'a> IndexVisitor<'a> {
                 func: indexing_function,
                 args,
                 dest: output_var.clone(),
+                is_method_call: false,
             };
             let kind = RawStatement::Call(index_call);
             self.statements.push(Statement::new(self.span, kind));
@@ -192,7 +193,7 @@ impl VisitBodyMut for IndexVisitor<'_> {
 
     fn visit_fn_operand(&mut self, x: &mut FnOperand) -> ControlFlow<Infallible> {
         match x {
-            FnOperand::Regular(_) => self.visit_inner(x),
+            FnOperand::Regular(_) | FnOperand::DynClosure { .. } => self.visit_inner(x),
             FnOperand::Move(_) => self.visit_inner_with_mutability(x, true),
         }
     }
@@ -211,8 +212,10 @@ impl VisitBodyMut for IndexVisitor<'_> {
             | Discriminant(..)
             | Len(..) => self.visit_inner_with_mutability(x, false),
 
-            Use(_) | NullaryOp(..) | UnaryOp(..) | BinaryOp(..) | Aggregate(..) | Global(..)
-            | GlobalRef(..) | Repeat(..) | ShallowInitBox(..) => self.visit_inner(x),
+            Use(_) | NullaryOp(..) | UnaryOp(..) | BinaryOp(..) | Aggregate(..)
+            | StructUpdate(..) | Global(..) | GlobalRef(..) | Repeat(..) | ShallowInitBox(..) => {
+                self.visit_inner(x)
+            }
         }
     }
 }
@@ -277,8 +280,8 @@ pub struct Transform;
 ///   tmp1 : &mut T = ArrayIndexMut(move y, i)
 ///   *tmp1 = x
 /// ```
-impl UllbcPass for Transform {
-    fn transform_body(&self, _ctx: &mut TransformCtx, b: &mut ExprBody) {
+impl UllbcBodyPass for Transform {
+    fn transform_body(&self, _ctx: &mut BodyTransformCtx<'_>, b: &mut ExprBody) {
         for block in &mut b.body {
             // Process statements.
             block.transform(|st: &mut Statement| {
@@ -297,7 +300,8 @@ impl UllbcPass for Transform {
                     Assign(..) | SetDiscriminant(..) | Drop(..) | Deinit(..) => {
                         visitor.visit_inner_with_mutability(st, true);
                     }
-                    Nop | Error(..) | Assert(..) | Call(..) | StorageDead(..) => {
+                    Nop | EnterUnsafe | ExitUnsafe | Error(..) | Assert(..) | Call(..)
+                    | StorageDead(..) => {
                         st.drive_body_mut(&mut visitor);
                     }
                 }
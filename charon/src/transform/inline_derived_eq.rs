@@ -0,0 +1,237 @@
+//! # Micro-pass: replace the calls to the `eq` method of a derived `PartialEq` impl on a local enum
+//! with the comparison it performs (only with `--inline-derived-eq`):
+//! ```text
+//! @0 := {impl PartialEq for E}::eq(move @1, move @2)
+//! ~>
+//! match *(@1) {
+//!     E::A => match *(@2) {
+//!         E::A => @0 := (*(@1) as E::A).0 == (*(@2) as E::A).0,
+//!         _ => @0 := false,
+//!     },
+//!     E::B => match *(@2) {
+//!         E::B => @0 := true,
+//!         _ => @0 := false,
+//!     },
+//! }
+//! ```
+//! We generate the discriminant reads and switches that MIR uses for matches, which
+//! [crate::transform::remove_read_discriminant] turns into `match`es. We only do this for the enums
+//! without type parameters whose fields all have a primitive type, which we can compare with `==`.
+use crate::builtins;
+use crate::transform::TransformCtx;
+use crate::ullbc_ast::*;
+
+use super::ctx::UllbcPass;
+
+/// The operands of a call to a derived `PartialEq::eq` on a local enum.
+struct DerivedEq {
+    type_id: TypeDeclId,
+    left: Place,
+    right: Place,
+    dest: Place,
+}
+
+/// If this statement calls the `eq` method of a derived `PartialEq` impl on an enum we can inline,
+/// return the operands of the call.
+fn recognize_derived_eq(ctx: &TransformCtx, st: &Statement) -> Option<DerivedEq> {
+    let RawStatement::Call(call) = &st.content else {
+        return None;
+    };
+    let FnOperand::Regular(fn_ptr) = &call.func else {
+        return None;
+    };
+    let FunIdOrTraitMethodRef::Fun(FunId::Regular(fun_id)) = fn_ptr.func else {
+        return None;
+    };
+    let ItemKind::TraitImpl {
+        impl_ref,
+        trait_ref,
+        item_name,
+        ..
+    } = &ctx.translated.fun_decls.get(fun_id)?.kind
+    else {
+        return None;
+    };
+    if item_name.0 != "eq" || !ctx.derived_impls.contains(&impl_ref.impl_id) {
+        return None;
+    }
+    let trait_name = ctx.translated.item_name(trait_ref.trait_id)?;
+    if !trait_name.equals_ref_name(builtins::PARTIAL_EQ_NAME) {
+        return None;
+    }
+
+    let [Operand::Move(left) | Operand::Copy(left), Operand::Move(right) | Operand::Copy(right)] =
+        call.args.as_slice()
+    else {
+        return None;
+    };
+    let TyKind::Ref(_, self_ty, _) = left.ty().kind() else {
+        return None;
+    };
+    if !matches!(right.ty().kind(), TyKind::Ref(_, ty, _) if ty == self_ty) {
+        return None;
+    }
+    let TyKind::Adt(TypeId::Adt(type_id), generics) = self_ty.kind() else {
+        return None;
+    };
+    if !generics.types.is_empty() || !generics.const_generics.is_empty() {
+        return None;
+    }
+    let TypeDeclKind::Enum(variants) = &ctx.translated.type_decls.get(*type_id)?.kind else {
+        return None;
+    };
+    let comparable = !variants.is_empty()
+        && variants
+            .iter()
+            .flat_map(|variant| variant.fields.iter())
+            .all(|field| field.ty.kind().is_literal());
+    if !comparable {
+        return None;
+    }
+
+    Some(DerivedEq {
+        type_id: *type_id,
+        left: left.clone().project(ProjectionElem::Deref, self_ty.clone()),
+        right: right
+            .clone()
+            .project(ProjectionElem::Deref, self_ty.clone()),
+        dest: call.dest.clone(),
+    })
+}
+
+fn bool_ty() -> Ty {
+    TyKind::Literal(LiteralTy::Bool).into_ty()
+}
+
+fn bool_constant(b: bool) -> Operand {
+    Operand::Const(ConstantExpr {
+        value: RawConstantExpr::Literal(Literal::Bool(b)),
+        ty: bool_ty(),
+    })
+}
+
+/// Inline the first call to a derived `PartialEq::eq` in the block, if any. The statements that
+/// follow the call are moved to a new block, which we return.
+fn inline_first_call(ctx: &TransformCtx, b: &mut ExprBody, block_id: BlockId) -> Option<BlockId> {
+    let (i, eq) = b.body[block_id]
+        .statements
+        .iter()
+        .enumerate()
+        .find_map(|(i, st)| Some((i, recognize_derived_eq(ctx, st)?)))?;
+    let TypeDeclKind::Enum(variants) = &ctx.translated.type_decls[eq.type_id].kind else {
+        unreachable!()
+    };
+    let span = b.body[block_id].statements[i].span;
+    let assign =
+        |dest: &Place, rvalue| Statement::new(span, RawStatement::Assign(dest.clone(), rvalue));
+    let goto = |target| Terminator::new(span, RawTerminator::Goto { target });
+
+    // Split the block after the call, and remove the call.
+    let block = &mut b.body[block_id];
+    let rest = BlockData {
+        statements: block.statements.split_off(i + 1),
+        terminator: block.terminator.clone(),
+    };
+    block.statements.pop();
+    let rest = b.body.push(rest);
+
+    // The values have different variants.
+    let different = b.body.push(BlockData {
+        statements: vec![assign(&eq.dest, Rvalue::Use(bool_constant(false)))],
+        terminator: goto(rest),
+    });
+
+    let discr_ty = variants[VariantId::ZERO].discriminant.get_integer_ty();
+    let discr_literal_ty = LiteralTy::Integer(discr_ty);
+    let left_discr = b
+        .locals
+        .new_var(None, TyKind::Literal(discr_literal_ty).into_ty());
+    let right_discr = b
+        .locals
+        .new_var(None, TyKind::Literal(discr_literal_ty).into_ty());
+    let mut left_targets = Vec::new();
+    for (variant_id, variant) in variants.iter_indexed() {
+        // Both values have this variant: compare the fields.
+        let mut statements = Vec::new();
+        let mut rvalue = Rvalue::Use(bool_constant(true));
+        for (field_id, field) in variant.fields.iter_indexed() {
+            let proj =
+                ProjectionElem::Field(FieldProjKind::Adt(eq.type_id, Some(variant_id)), field_id);
+            let cmp = Rvalue::BinaryOp(
+                BinOp::Eq,
+                Operand::Copy(eq.left.clone().project(proj.clone(), field.ty.clone())),
+                Operand::Copy(eq.right.clone().project(proj, field.ty.clone())),
+            );
+            rvalue = if field_id == FieldId::ZERO {
+                cmp
+            } else {
+                let previous = b.locals.new_var(None, bool_ty());
+                statements.push(assign(&previous, rvalue));
+                let current = b.locals.new_var(None, bool_ty());
+                statements.push(assign(&current, cmp));
+                Rvalue::BinaryOp(
+                    BinOp::BitAnd,
+                    Operand::Move(previous),
+                    Operand::Move(current),
+                )
+            };
+        }
+        statements.push(assign(&eq.dest, rvalue));
+        let same = b.body.push(BlockData {
+            statements,
+            terminator: goto(rest),
+        });
+
+        // Check the variant of the right value.
+        let discr = Literal::Scalar(variant.discriminant);
+        let right_block = b.body.push(BlockData {
+            statements: vec![assign(
+                &right_discr,
+                Rvalue::Discriminant(eq.right.clone(), eq.type_id),
+            )],
+            terminator: Terminator::new(
+                span,
+                RawTerminator::Switch {
+                    discr: Operand::Move(right_discr.clone()),
+                    targets: SwitchTargets::SwitchInt(
+                        discr_literal_ty,
+                        vec![(discr.clone(), same)],
+                        different,
+                    ),
+                },
+            ),
+        });
+        left_targets.push((discr, right_block));
+    }
+
+    // Switch on the variant of the left value.
+    let block = &mut b.body[block_id];
+    block.statements.push(assign(
+        &left_discr,
+        Rvalue::Discriminant(eq.left.clone(), eq.type_id),
+    ));
+    block.terminator = Terminator::new(
+        span,
+        RawTerminator::Switch {
+            discr: Operand::Move(left_discr),
+            targets: SwitchTargets::SwitchInt(discr_literal_ty, left_targets, different),
+        },
+    );
+    Some(rest)
+}
+
+pub struct Transform;
+impl UllbcPass for Transform {
+    fn transform_body(&self, ctx: &mut TransformCtx, b: &mut ExprBody) {
+        if !ctx.options.inline_derived_eq {
+            return;
+        }
+        // The statements that follow an inlined call end up in a new block, which we explore too.
+        let mut to_explore = b.body.all_indices().collect::<Vec<_>>();
+        while let Some(block_id) = to_explore.pop() {
+            if let Some(rest) = inline_first_call(ctx, b, block_id) {
+                to_explore.push(rest);
+            }
+        }
+    }
+}
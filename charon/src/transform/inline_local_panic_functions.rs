@@ -24,7 +24,8 @@ impl UllbcPass for Transform {
                 if body.body.elem_count() == 1
                     && let Some(block) = body.body.iter().next()
                     && block.statements.is_empty()
-                    && let RawTerminator::Abort(AbortKind::Panic(name)) = &block.terminator.content
+                    && let RawTerminator::Abort(AbortKind::Panic(name, _)) =
+                        &block.terminator.content
                 {
                     if name.equals_ref_name(builtins::EXPLICIT_PANIC_NAME) {
                         // FIXME: also check that the name of the function is
@@ -36,7 +37,6 @@ impl UllbcPass for Transform {
         });
 
         let panic_name = Name::from_path(builtins::EXPLICIT_PANIC_NAME);
-        let panic_terminator = RawTerminator::Abort(AbortKind::Panic(panic_name));
 
         // Replace each call to one such function with a `Panic`.
         ctx.for_each_fun_decl(|_ctx, decl| {
@@ -58,8 +58,14 @@ impl UllbcPass for Transform {
                         }) = &st.content
                             && panic_fns.contains(fun_id)
                         {
+                            // These functions are `#[track_caller]`: the location of the panic
+                            // is that of the call.
+                            let location = PanicLocation::from_span(st.span);
                             block.statements.drain(i..);
-                            block.terminator.content = panic_terminator.clone();
+                            block.terminator.content = RawTerminator::Abort(AbortKind::Panic(
+                                panic_name.clone(),
+                                Some(location),
+                            ));
                             break;
                         }
                     }
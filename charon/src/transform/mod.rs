@@ -1,45 +1,74 @@
+pub mod apply_models;
 pub mod check_generics;
+pub mod check_packed_refs;
 pub mod ctx;
+pub mod defunctionalize;
 pub mod duplicate_defaulted_methods;
 pub mod duplicate_return;
+pub mod emit_dependencies;
+pub mod emit_deps_graphviz;
+pub mod emit_opaque_manifest;
+pub mod emit_symbol_table;
+pub mod emit_type_sizes;
 pub mod expand_associated_types;
 pub mod filter_invisible_trait_impls;
 pub mod filter_unreachable_blocks;
+pub mod fold_constant_ifs;
+pub mod fold_constants;
 pub mod graphs;
 pub mod hide_marker_traits;
+pub mod hoist_common_subexpressions;
 pub mod index_intermediate_assigns;
 pub mod index_to_function_calls;
+pub mod inline_derived_eq;
 pub mod inline_local_panic_functions;
 pub mod insert_assign_return_unit;
 pub mod lift_associated_item_clauses;
 pub mod merge_goto_chains;
+pub mod normalize_assoc_types;
+pub mod normalize_if_polarity;
 pub mod ops_to_function_calls;
 pub mod prettify_cfg;
+pub mod reconstruct_assert_eq;
 pub mod reconstruct_asserts;
 pub mod reconstruct_boxes;
+pub mod reconstruct_deref;
+pub mod reconstruct_let_chains;
+pub mod reconstruct_let_else;
+pub mod reconstruct_method_calls;
+pub mod reconstruct_range_matches;
+pub mod reconstruct_struct_updates;
+pub mod reconstruct_while_loops;
 pub mod recover_body_comments;
 pub mod remove_arithmetic_overflow_checks;
-pub mod remove_drop_never;
 pub mod remove_dynamic_checks;
+pub mod remove_never;
 pub mod remove_nops;
 pub mod remove_read_discriminant;
 pub mod remove_unit_locals;
 pub mod remove_unused_locals;
 pub mod remove_unused_methods;
 pub mod reorder_decls;
+pub mod save_ullbc;
 pub mod simplify_constants;
+pub mod single_return;
 pub mod skip_trait_refs_when_known;
+pub mod stable_ids;
 pub mod ullbc_to_llbc;
 pub mod unbind_item_vars;
 pub mod update_block_indices;
 pub mod update_closure_signatures;
+pub mod validate;
 
-pub use ctx::TransformCtx;
+pub use ctx::{BodyTransformCtx, TransformCtx};
 use ctx::{LlbcPass, TransformPass, UllbcPass};
 use Pass::*;
 
 /// Item and type cleanup passes.
 pub static INITIAL_CLEANUP_PASSES: &[Pass] = &[
+    // # Micro-pass: renumber the items in the order of their names, if `--stable-ids` is set. This
+    // comes first so that the other passes see the final ids.
+    NonBody(&stable_ids::Transform),
     // Remove the trait/impl methods that were not translated (because not used).
     NonBody(&remove_unused_methods::Transform),
     // Move clauses on associated types to be parent clauses
@@ -58,6 +87,10 @@ pub static INITIAL_CLEANUP_PASSES: &[Pass] = &[
     // directly instead of going via a `TraitRef`. This is done before `reorder_decls` to remove
     // some sources of mutual recursion.
     UnstructuredBody(&skip_trait_refs_when_known::Transform),
+    // # Micro-pass: replace the associated type projections on known local impls with the type
+    // the impl assigns (only with `--normalize-assoc-types`). This must come before
+    // `expand_associated_types`.
+    NonBody(&normalize_assoc_types::Transform),
     // Change trait associated types to be type parameters instead. See the module for details.
     NonBody(&expand_associated_types::Transform),
 ];
@@ -67,6 +100,13 @@ pub static ULLBC_PASSES: &[Pass] = &[
     // # Micro-pass: merge single-origin gotos into their parent. This drastically reduces the
     // graph size of the CFG.
     UnstructuredBody(&merge_goto_chains::Transform),
+    // # Micro-pass: replace the calls to a derived `PartialEq::eq` on a local enum with matches on
+    // the variants and comparisons of the fields (only with `--inline-derived-eq`). This must come
+    // after `skip_trait_refs_when_known`, which makes these calls refer to the impl method.
+    UnstructuredBody(&inline_derived_eq::Transform),
+    // # Micro-pass: warn about the references to fields of `#[repr(packed)]` structs that may not
+    // be aligned.
+    UnstructuredBody(&check_packed_refs::Transform),
     // # Micro-pass: Remove overflow/div-by-zero/bounds checks since they are already part of the
     // arithmetic/array operation in the semantics of (U)LLBC.
     // **WARNING**: this pass uses the fact that the dynamic checks introduced by Rustc use a
@@ -81,6 +121,11 @@ pub static ULLBC_PASSES: &[Pass] = &[
     // it must happen before passes that insert statements like [simplify_constants].
     // **WARNING**: this pass works across calls, hence must happen after `merge_goto_chains`,
     UnstructuredBody(&reconstruct_boxes::Transform),
+    // # Micro-pass: reconstruct struct-update syntax (`Foo { x: 1, ..base }`), if
+    // `--reconstruct-struct-updates` is set.
+    // **WARNING**: this pass relies on a precise structure of the MIR statements. Because of this,
+    // it must happen before passes that insert statements like [simplify_constants].
+    UnstructuredBody(&reconstruct_struct_updates::Transform),
     // # Micro-pass: desugar the constants to other values/operands as much
     // as possible.
     UnstructuredBody(&simplify_constants::Transform),
@@ -88,20 +133,43 @@ pub static ULLBC_PASSES: &[Pass] = &[
     // closure itself. This is not consistent with the closure signature,
     // which ignores this first variable. This micro-pass updates this.
     UnstructuredBody(&update_closure_signatures::Transform),
+    // # Micro-pass: turn the closures into top-level functions that take an explicit state struct,
+    // and call them directly (only with `--defunctionalize`). This needs the closure signatures
+    // computed by [update_closure_signatures].
+    NonBody(&defunctionalize::Transform),
     // # Micro-pass: remove the dynamic checks we couldn't remove in [`remove_dynamic_checks`].
     // **WARNING**: this pass uses the fact that the dynamic checks
     // introduced by Rustc use a special "assert" construct. Because of
     // this, it must happen *before* the [reconstruct_asserts] pass.
     UnstructuredBody(&remove_arithmetic_overflow_checks::Transform),
+    // # Micro-pass: evaluate the arithmetic operations on integer constants (only with
+    // `--fold-constants`). This must come after [remove_arithmetic_overflow_checks], which
+    // decides how overflow is modeled.
+    UnstructuredBody(&fold_constants::Transform),
+    // # Micro-pass: replace the `if`s on a constant boolean (e.g. coming from `cfg!(..)`) with a
+    // jump to the taken branch. This must come before [filter_unreachable_blocks], which removes
+    // the other branch.
+    UnstructuredBody(&fold_constant_ifs::Transform),
     // # Micro-pass: replace some unops/binops and the array aggregates with
     // function calls (introduces: ArrayToSlice, etc.)
     UnstructuredBody(&ops_to_function_calls::Transform),
     // # Micro-pass: make sure the block ids used in the ULLBC are consecutive
     UnstructuredBody(&update_block_indices::Transform),
+    // # Micro-pass: reconstruct the `assert_eq!`/`assert_ne!` asserts, recording the compared
+    // operands. This must happen before [reconstruct_asserts].
+    UnstructuredBody(&reconstruct_assert_eq::Transform),
     // # Micro-pass: reconstruct the asserts
     UnstructuredBody(&reconstruct_asserts::Transform),
+    // # Micro-pass: turn the comparisons introduced by range patterns into `switch`es over
+    // ranges (only with `--reconstruct-range-matches`). This must come before
+    // [filter_unreachable_blocks], which cleans up the comparison blocks it bypasses.
+    UnstructuredBody(&reconstruct_range_matches::Transform),
     // # Micro-pass: duplicate the return blocks
     UnstructuredBody(&duplicate_return::Transform),
+    // # Micro-pass: remove the uses of the locals whose type is `Never` (`!`), and cut the code
+    // that can't be reached because it would need such a value. This must come before
+    // [filter_unreachable_blocks], which cleans up the blocks it disconnects.
+    UnstructuredBody(&remove_never::Transform),
     // # Micro-pass: filter the "dangling" blocks. Those might have been introduced by,
     // for instance, [`reconstruct_asserts`].
     UnstructuredBody(&filter_unreachable_blocks::Transform),
@@ -115,6 +183,12 @@ pub static ULLBC_PASSES: &[Pass] = &[
     // calls.
     // (introduces: ArrayIndexShared, ArrayIndexMut, etc.)
     UnstructuredBody(&index_to_function_calls::Transform),
+    // # Micro-pass: replace the `Deref` calls on references and boxes with dereferences (only
+    // with `--reconstruct-deref`).
+    UnstructuredBody(&reconstruct_deref::Transform),
+    // # Micro-pass: mark the calls to methods whose receiver is a reference, so that we can print
+    // them with the method-call syntax (only with `--reconstruct-method-calls`).
+    UnstructuredBody(&reconstruct_method_calls::Transform),
     // # Micro-pass: add the missing assignments to the return value.
     // When the function return type is unit, the generated MIR doesn't
     // set the return value to `()`. This can be a concern: in the case
@@ -124,9 +198,10 @@ pub static ULLBC_PASSES: &[Pass] = &[
     UnstructuredBody(&insert_assign_return_unit::Transform),
     // # Micro-pass: remove locals of type `()` which show up a lot.
     UnstructuredBody(&remove_unit_locals::Transform),
-    // # Micro-pass: remove the drops of locals whose type is `Never` (`!`). This
-    // is in preparation of the next transformation.
-    UnstructuredBody(&remove_drop_never::Transform),
+    // # Micro-pass: redirect all the `return`s to a single exit block (only with
+    // `--single-return`). This must come after [insert_assign_return_unit] so that the return
+    // local is always assigned before jumping to the exit block.
+    UnstructuredBody(&single_return::Transform),
 ];
 
 /// Body cleanup passes after control flow reconstruction.
@@ -137,6 +212,23 @@ pub static LLBC_PASSES: &[Pass] = &[
     StructuredBody(&remove_read_discriminant::Transform),
     // Cleanup the cfg.
     StructuredBody(&prettify_cfg::Transform),
+    // # Micro-pass: reconstruct the `while` loops whose condition has no side effects (only with
+    // `--reconstruct-while-loops`).
+    StructuredBody(&reconstruct_while_loops::Transform),
+    // # Micro-pass: reconstruct the `if let .. && ..` chains from the nested matches they are
+    // lowered to (only with `--reconstruct-let-chains`). This must come before
+    // [reconstruct_let_else], which would otherwise turn the outer match into a `let .. else`.
+    StructuredBody(&reconstruct_let_chains::Transform),
+    // # Micro-pass: reconstruct `let .. else` statements from the matches with a diverging
+    // branch (only with `--reconstruct-let-else`).
+    StructuredBody(&reconstruct_let_else::Transform),
+    // # Micro-pass: hoist the side-effect-free computations common to both branches of an `if`
+    // (only with `--cse`).
+    StructuredBody(&hoist_common_subexpressions::Transform),
+    // # Micro-pass: turn `if !c { A } else { B }` into `if c { B } else { A }` (only with
+    // `--normalize-if-polarity`). This must come after [reconstruct_while_loops], which expects
+    // the loop exit in the `else` branch.
+    StructuredBody(&normalize_if_polarity::Transform),
 ];
 
 /// Cleanup passes useful for both llbc and ullbc.
@@ -0,0 +1,60 @@
+//! Replace the associated type projections `<T as Trait>::Type` with the type the impl assigns to
+//! them, when the impl is known, local and non-generic. E.g. with `impl Foo for S { type Bar =
+//! u32; }`, `<S as Foo>::Bar` becomes `u32`. Projections on type parameters stay abstract. This
+//! pass only runs when `--normalize-assoc-types` is set.
+use std::collections::HashMap;
+
+use derive_generic_visitor::*;
+
+use crate::ast::*;
+use crate::transform::TransformCtx;
+
+use super::ctx::TransformPass;
+
+#[derive(Visitor)]
+struct NormalizeAssocTypes<'a> {
+    /// The values of the associated types of the local non-generic impls. Plain associated types
+    /// have an empty binder.
+    impl_types: &'a HashMap<(TraitImplId, TraitItemName), Binder<Ty>>,
+}
+
+impl VisitAstMut for NormalizeAssocTypes<'_> {
+    fn enter_ty_kind(&mut self, kind: &mut TyKind) {
+        // Loop in case the new type is itself a projection; the visitor then explores its contents.
+        while let TyKind::TraitType(tref, name, args) = kind
+            && let TraitRefKind::TraitImpl(impl_id, _) = &tref.kind
+            && let Some(bound_ty) = self.impl_types.get(&(*impl_id, name.clone()))
+        {
+            *kind = bound_ty.clone().apply(args).kind().clone();
+        }
+    }
+}
+
+pub struct Transform;
+impl TransformPass for Transform {
+    fn transform_ctx(&self, ctx: &mut TransformCtx) {
+        if !ctx.options.normalize_assoc_types {
+            return;
+        }
+        let impl_types: HashMap<(TraitImplId, TraitItemName), Binder<Ty>> = ctx
+            .translated
+            .trait_impls
+            .iter()
+            .filter(|timpl| timpl.item_meta.is_local && timpl.generics.is_empty())
+            .flat_map(|timpl| {
+                let types = timpl.types.iter().map(|(name, ty)| {
+                    let ty = ty.clone().move_under_binder();
+                    let bound_ty = Binder::new(BinderKind::Other, GenericParams::empty(), ty);
+                    (name.clone(), bound_ty)
+                });
+                let generic_types = timpl.generic_types.iter().cloned();
+                types
+                    .chain(generic_types)
+                    .map(|(name, bound_ty)| ((timpl.def_id, name), bound_ty))
+            })
+            .collect();
+        ctx.translated.drive_mut(&mut NormalizeAssocTypes {
+            impl_types: &impl_types,
+        });
+    }
+}
@@ -0,0 +1,46 @@
+//! `if !c { A } else { B }` is lowered to an `if` on a temporary which holds the negation:
+//! ```text
+//! @2 := ~(copy (c))
+//! if move (@2) { A } else { B }
+//! ```
+//! This pass turns this into `if copy (c) { B } else { A }`, so that consumers only see
+//! conditions in this form. It only runs when `--normalize-if-polarity` is set.
+use crate::llbc_ast::*;
+use crate::transform::TransformCtx;
+
+use super::ctx::LlbcPass;
+
+pub struct Transform;
+
+impl Transform {
+    fn update_statements(seq: &mut [Statement]) -> Vec<Statement> {
+        let [Statement {
+            content: RawStatement::Assign(dest, Rvalue::UnaryOp(UnOp::Not, negated)),
+            ..
+        }, Statement {
+            content: RawStatement::Switch(Switch::If(cond, then_block, else_block)),
+            ..
+        }, ..] = seq
+        else {
+            return Vec::new();
+        };
+        // The temporary is moved out by the `if`, hence isn't used anywhere else.
+        if !matches!(cond, Operand::Move(p) if *p == *dest && p.as_local().is_some()) {
+            return Vec::new();
+        }
+        *cond = negated.clone();
+        std::mem::swap(then_block, else_block);
+        seq[0].content = RawStatement::Nop;
+        Vec::new()
+    }
+}
+
+impl LlbcPass for Transform {
+    fn transform_body(&self, ctx: &mut TransformCtx, b: &mut ExprBody) {
+        // This pass is opt-in.
+        if !ctx.options.normalize_if_polarity {
+            return;
+        }
+        b.body.transform_sequences(Transform::update_statements)
+    }
+}
@@ -2,10 +2,10 @@
 //! For instance, we desugar ArrayToSlice from an unop to a function call.
 //! This allows a more uniform treatment later on.
 //! TODO: actually transform all the unops and binops to function calls?
-use crate::transform::TransformCtx;
+use crate::transform::BodyTransformCtx;
 use crate::ullbc_ast::*;
 
-use super::ctx::UllbcPass;
+use super::ctx::UllbcBodyPass;
 
 fn transform_st(s: &mut Statement) {
     match &s.content {
@@ -30,6 +30,7 @@ fn transform_st(s: &mut Statement) {
                 func,
                 args: vec![op.clone()],
                 dest: p.clone(),
+                is_method_call: false,
             });
         }
         // Transform the array aggregates to function calls
@@ -50,6 +51,7 @@ fn transform_st(s: &mut Statement) {
                 func,
                 args: vec![op.clone()],
                 dest: p.clone(),
+                is_method_call: false,
             });
         }
         _ => {}
@@ -57,8 +59,8 @@ fn transform_st(s: &mut Statement) {
 }
 
 pub struct Transform;
-impl UllbcPass for Transform {
-    fn transform_body(&self, _ctx: &mut TransformCtx, b: &mut ExprBody) {
+impl UllbcBodyPass for Transform {
+    fn transform_body(&self, _ctx: &mut BodyTransformCtx<'_>, b: &mut ExprBody) {
         b.visit_statements(&mut transform_st);
     }
 }
@@ -1,7 +1,7 @@
 use crate::llbc_ast::*;
-use crate::transform::TransformCtx;
+use crate::transform::BodyTransformCtx;
 
-use super::ctx::LlbcPass;
+use super::ctx::LlbcBodyPass;
 
 pub struct Transform;
 
@@ -37,8 +37,8 @@ impl Transform {
     }
 }
 
-impl LlbcPass for Transform {
-    fn transform_body(&self, _ctx: &mut TransformCtx, b: &mut ExprBody) {
+impl LlbcBodyPass for Transform {
+    fn transform_body(&self, _ctx: &mut BodyTransformCtx<'_>, b: &mut ExprBody) {
         b.body
             .transform_sequences(|seq| Transform::update_statements(&b.locals, seq))
     }
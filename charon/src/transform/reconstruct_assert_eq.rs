@@ -0,0 +1,118 @@
+//! `assert_eq!(left, right)` expands to:
+//! ```ignore
+//! match (&left, &right) {
+//!     (left_val, right_val) => {
+//!         if !(*left_val == *right_val) {
+//!             let kind = core::panicking::AssertKind::Eq;
+//!             core::panicking::assert_failed(kind, &*left_val, &*right_val, None);
+//!         }
+//!     }
+//! }
+//! ```
+//! and `assert_ne!` expands to the same thing with `!=` and `AssertKind::Ne`. The call to
+//! `assert_failed` becomes a panic, but the statements which prepare its arguments (including the
+//! formatted message, if any) remain in the failure branch, which prevents
+//! [crate::reconstruct_asserts] from recognizing it. This pass recognizes these branches and
+//! replaces the switch on the comparison with an [Assert] which records the compared operands.
+use std::collections::{HashMap, HashSet};
+
+use crate::builtins;
+use crate::transform::TransformCtx;
+use crate::ullbc_ast::*;
+
+use super::ctx::UllbcPass;
+
+/// If this block is the failure branch of an `assert_eq!` or `assert_ne!`, return the comparison
+/// it reports.
+fn recognize_assert_failed(ctx: &TransformCtx, block: &BlockData) -> Option<AssertComparison> {
+    let RawTerminator::Abort(AbortKind::Panic(name, _)) = &block.terminator.content else {
+        return None;
+    };
+    if !name.equals_ref_name(builtins::ASSERT_FAILED_NAME) {
+        return None;
+    }
+
+    let mut statements = block.statements.iter().map(|st| &st.content);
+    // The first argument of `assert_failed` is the `AssertKind`.
+    let op = statements.find_map(|st| match st {
+        RawStatement::Assign(
+            _,
+            Rvalue::Aggregate(AggregateKind::Adt(TypeId::Adt(id), Some(variant), None, _), fields),
+        ) if fields.is_empty()
+            && ctx
+                .translated
+                .item_names
+                .get(&AnyTransId::Type(*id))
+                .is_some_and(|name| name.equals_ref_name(builtins::ASSERT_KIND_NAME)) =>
+        {
+            // `Eq` and `Ne` are the first two variants of `AssertKind`.
+            match variant.index() {
+                0 => Some(BinOp::Eq),
+                1 => Some(BinOp::Ne),
+                _ => None,
+            }
+        }
+        _ => None,
+    })?;
+    // The next two arguments are reborrows of the compared values (possibly reborrowed again).
+    // Whatever follows is the formatted message, which we ignore.
+    let mut borrows: HashSet<VarId> = HashSet::new();
+    let mut operands = statements.filter_map(|st| match st {
+        RawStatement::Assign(dest, Rvalue::Ref(place, BorrowKind::Shared)) => {
+            let is_reborrow = place.as_projection().is_some_and(|(sub, proj)| {
+                proj.is_deref() && sub.as_local().is_some_and(|var| borrows.contains(&var))
+            });
+            if let Some(dest) = dest.as_local() {
+                borrows.insert(dest);
+            }
+            (!is_reborrow).then(|| Operand::Copy(place.clone()))
+        }
+        _ => None,
+    });
+    let left = operands.next()?;
+    let right = operands.next()?;
+    Some(AssertComparison { op, left, right })
+}
+
+pub struct Transform;
+impl UllbcPass for Transform {
+    fn transform_body(&self, ctx: &mut TransformCtx, b: &mut ExprBody) {
+        let failures: HashMap<BlockId, AssertComparison> = b
+            .body
+            .iter_indexed()
+            .filter_map(|(bid, block)| Some((bid, recognize_assert_failed(ctx, block)?)))
+            .collect();
+        if failures.is_empty() {
+            return;
+        }
+
+        for block in b.body.iter_mut() {
+            let RawTerminator::Switch {
+                discr,
+                targets: SwitchTargets::If(bid0, bid1),
+            } = &block.terminator.content
+            else {
+                continue;
+            };
+            let (nbid, expected, comparison) = if let Some(cmp) = failures.get(bid0) {
+                (*bid1, false, cmp)
+            } else if let Some(cmp) = failures.get(bid1) {
+                (*bid0, true, cmp)
+            } else {
+                continue;
+            };
+            let assert = Assert {
+                cond: discr.clone(),
+                expected,
+                comparison: Some(comparison.clone()),
+                message: None,
+            };
+            block.statements.push(Statement::new(
+                block.terminator.span,
+                RawStatement::Assert(assert),
+            ));
+            // The failure branch is now unreachable: it gets filtered out later.
+            block.terminator.content = RawTerminator::Goto { target: nbid };
+        }
+    }
+}
@@ -2,28 +2,245 @@
 //! (for the binops for example). The `assert!` introduced by the user
 //! introduce `if ... then { panic!(...) } else { ...}`.
 //! This pass introduces `assert` instead in order to make the code shorter.
+//!
+//! When the `assert!` has a message, the failure branch formats it before panicking:
+//! ```text
+//! @6 := &x
+//! @5 := core::fmt::rt::{Argument}::new_display(move @6)
+//! @4 := [move @5]
+//! @3 := &@4
+//! @2 := [const ("bad: ")]
+//! @1 := &@2
+//! @0 := core::fmt::{Arguments}::new_v1(move @1, move @3)
+//! panic(core::panicking::panic_fmt)
+//! ```
+//! We recognize these branches too, and record the format string and the formatted places in the
+//! [AssertMessage] of the assertion. We only do so when the formatted values are places that are
+//! already computed before the branch, so that evaluating them at the assertion is correct.
 
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 
+use crate::builtins;
 use crate::transform::TransformCtx;
 use crate::ullbc_ast::*;
 
 use super::ctx::UllbcPass;
 
+/// The constructors of `core::fmt::Arguments` and `core::fmt::rt::Argument` that `format_args!`
+/// expands to.
+enum FmtConstructor {
+    /// `Arguments::new_const(pieces)` or `Arguments::new_v1(pieces, args)`.
+    Arguments,
+    /// `Argument::new_display(&x)` or `Argument::new_debug(&x)`, with the placeholder that
+    /// formats the value this way.
+    Argument(&'static str),
+    /// `Argument::none()`, the empty array of arguments.
+    NoArguments,
+}
+
+fn fmt_constructor(ctx: &TransformCtx, call: &Call) -> Option<FmtConstructor> {
+    let FnOperand::Regular(fn_ptr) = &call.func else {
+        return None;
+    };
+    let FunIdOrTraitMethodRef::Fun(fun_id) = &fn_ptr.func else {
+        return None;
+    };
+    let id = match fun_id {
+        // With `--abstract-formatting`.
+        FunId::Builtin(BuiltinFunId::FormatArgs) => return Some(FmtConstructor::Arguments),
+        FunId::Builtin(BuiltinFunId::FmtArgDisplay) => return Some(FmtConstructor::Argument("{}")),
+        FunId::Builtin(BuiltinFunId::FmtArgDebug) => return Some(FmtConstructor::Argument("{:?}")),
+        FunId::Builtin(_) => return None,
+        FunId::Regular(id) => *id,
+    };
+    let name = ctx.translated.item_names.get(&AnyTransId::Fun(id))?;
+    let [.., PathElem::Impl(ImplElem::Ty(self_ty), _), PathElem::Ident(method, _)] =
+        name.name.as_slice()
+    else {
+        return None;
+    };
+    let TyKind::Adt(TypeId::Adt(type_id), _) = self_ty.skip_binder.kind() else {
+        return None;
+    };
+    let type_name = ctx.translated.item_names.get(&AnyTransId::Type(*type_id))?;
+    if type_name.equals_ref_name(builtins::FMT_ARGUMENTS_NAME) {
+        matches!(method.as_str(), "new_const" | "new_v1").then_some(FmtConstructor::Arguments)
+    } else if type_name.equals_ref_name(builtins::FMT_ARGUMENT_NAME) {
+        match method.as_str() {
+            "new_display" => Some(FmtConstructor::Argument("{}")),
+            "new_debug" => Some(FmtConstructor::Argument("{:?}")),
+            "none" => Some(FmtConstructor::NoArguments),
+            _ => None,
+        }
+    } else {
+        None
+    }
+}
+
+fn operand_local(op: &Operand) -> Option<VarId> {
+    match op {
+        Operand::Move(place) | Operand::Copy(place) => place.as_local(),
+        Operand::Const(_) => None,
+    }
+}
+
+/// The values computed by the failure branch of an `assert!`.
+#[derive(Default)]
+struct FailureBranch<'a> {
+    /// The locals assigned in the branch.
+    defined: HashSet<VarId>,
+    assigns: HashMap<VarId, &'a Rvalue>,
+    arrays: HashMap<VarId, &'a [Operand]>,
+    /// The `Argument`s, with the formatted value and its placeholder.
+    arguments: HashMap<VarId, (&'a Operand, &'static str)>,
+}
+
+impl<'a> FailureBranch<'a> {
+    /// Follow the moves and copies between locals of the branch.
+    fn resolve(&self, var: VarId) -> VarId {
+        match self.assigns.get(&var).and_then(|rvalue| rvalue.as_use()) {
+            Some(op) => operand_local(op).map_or(var, |src| self.resolve(src)),
+            None => var,
+        }
+    }
+
+    /// If the local holds a shared borrow computed in the branch, return the borrowed place,
+    /// looking through the reborrows.
+    fn borrowed_place(&self, var: VarId) -> Option<&'a Place> {
+        let rvalue: &'a Rvalue = self.assigns.get(&self.resolve(var))?;
+        let Rvalue::Ref(place, BorrowKind::Shared) = rvalue else {
+            return None;
+        };
+        if let Some((sub, ProjectionElem::Deref)) = place.as_projection()
+            && let Some(sub) = sub.as_local()
+            && self.assigns.contains_key(&sub)
+        {
+            return self.borrowed_place(sub);
+        }
+        Some(place)
+    }
+
+    /// If the operand is a reference to an array built in the branch, return its elements.
+    fn array_elements(&self, op: &Operand) -> Option<&'a [Operand]> {
+        let var = self.borrowed_place(operand_local(op)?)?.as_local()?;
+        self.arrays.get(&self.resolve(var)).copied()
+    }
+
+    /// Whether the place was computed before the branch.
+    fn is_outside(&self, place: &Place) -> bool {
+        let mut outside = !self.defined.contains(&place.var_id());
+        // The operands of the index projections.
+        place.dyn_visit_in_body(|p: &Place| {
+            if p.as_local().is_some_and(|var| self.defined.contains(&var)) {
+                outside = false;
+            }
+        });
+        outside
+    }
+
+    /// Build the message from the arguments of the `Arguments` constructor.
+    fn message(&self, args: &[Operand]) -> Option<AssertMessage> {
+        let (pieces, args) = match args {
+            [pieces] => (pieces, &[] as &[Operand]),
+            [pieces, args] => (pieces, self.array_elements(args)?),
+            _ => return None,
+        };
+        let pieces = self
+            .array_elements(pieces)?
+            .iter()
+            .map(|piece| match piece {
+                Operand::Const(ConstantExpr {
+                    value: RawConstantExpr::Literal(Literal::Str(piece)),
+                    ..
+                }) => Some(piece.replace('{', "{{").replace('}', "}}")),
+                _ => None,
+            })
+            .collect::<Option<Vec<_>>>()?;
+        let args = args
+            .iter()
+            .map(|arg| {
+                let (value, placeholder) = self.arguments.get(&operand_local(arg)?)?;
+                let place = self.borrowed_place(operand_local(value)?)?;
+                self.is_outside(place)
+                    .then(|| (Operand::Copy(place.clone()), *placeholder))
+            })
+            .collect::<Option<Vec<_>>>()?;
+
+        // The pieces are the text between the placeholders.
+        let mut pieces = pieces.into_iter();
+        let mut format = String::new();
+        for (_, placeholder) in &args {
+            format.push_str(&pieces.next().unwrap_or_default());
+            format.push_str(placeholder);
+        }
+        format.extend(pieces);
+        let args = args.into_iter().map(|(arg, _)| arg).collect();
+        Some(AssertMessage { format, args })
+    }
+}
+
+/// If this block is the failure branch of an `assert!` with a message, return that message.
+fn recognize_assert_message(ctx: &TransformCtx, block: &BlockData) -> Option<AssertMessage> {
+    let RawTerminator::Abort(AbortKind::Panic(name, _)) = &block.terminator.content else {
+        return None;
+    };
+    if !name.equals_ref_name(builtins::PANIC_FMT_NAME) {
+        return None;
+    }
+
+    let mut branch = FailureBranch::default();
+    let mut message = None;
+    for st in &block.statements {
+        match &st.content {
+            RawStatement::Assign(dest, rvalue) => {
+                let dest = dest.as_local()?;
+                branch.defined.insert(dest);
+                branch.assigns.insert(dest, rvalue);
+                if let Rvalue::Aggregate(AggregateKind::Array(..), ops) = rvalue {
+                    branch.arrays.insert(dest, ops);
+                }
+            }
+            RawStatement::Call(call) => {
+                let dest = call.dest.as_local()?;
+                branch.defined.insert(dest);
+                match (fmt_constructor(ctx, call)?, call.args.as_slice()) {
+                    (FmtConstructor::Arguments, args) => message = Some(branch.message(args)?),
+                    (FmtConstructor::Argument(placeholder), [value]) => {
+                        branch.arguments.insert(dest, (value, placeholder));
+                    }
+                    (FmtConstructor::NoArguments, []) => {
+                        branch.arrays.insert(dest, &[]);
+                    }
+                    _ => return None,
+                }
+            }
+            // The temporary references to the message get dropped.
+            RawStatement::Drop(place)
+                if place
+                    .as_local()
+                    .is_some_and(|var| branch.defined.contains(&var)) => {}
+            RawStatement::StorageDead(_) | RawStatement::Nop => {}
+            _ => return None,
+        }
+    }
+    message
+}
+
 pub struct Transform;
 impl UllbcPass for Transform {
-    fn transform_body(&self, _ctx: &mut TransformCtx, b: &mut ExprBody) {
-        // Start by computing the set of blocks which are actually panics.
+    fn transform_body(&self, ctx: &mut TransformCtx, b: &mut ExprBody) {
+        // Start by computing the set of blocks which are actually panics, along with the message
+        // of the assertions that lead to them.
         // Remark: doing this in two steps because reading the blocks at random
         // while doing in-place updates is not natural to do in Rust.
-        let panics: HashSet<BlockId> = b
+        let panics: HashMap<BlockId, Option<AssertMessage>> = b
             .body
             .iter_indexed()
             .filter_map(|(bid, block)| {
                 if block.statements.is_empty() && block.terminator.content.is_abort() {
-                    Some(bid)
+                    Some((bid, None))
                 } else {
-                    None
+                    Some((bid, Some(recognize_assert_message(ctx, block)?)))
                 }
             })
             .collect();
@@ -34,10 +251,10 @@ impl UllbcPass for Transform {
                     discr: _,
                     targets: SwitchTargets::If(bid0, bid1),
                 } => {
-                    let (nbid, expected) = if panics.contains(bid0) {
-                        (*bid1, false)
-                    } else if panics.contains(bid1) {
-                        (*bid0, true)
+                    let (nbid, expected, message) = if let Some(message) = panics.get(bid0) {
+                        (*bid1, false, message)
+                    } else if let Some(message) = panics.get(bid1) {
+                        (*bid0, true, message)
                     } else {
                         continue;
                     };
@@ -52,6 +269,8 @@ impl UllbcPass for Transform {
                         RawStatement::Assert(Assert {
                             cond: discr.clone(),
                             expected,
+                            comparison: None,
+                            message: message.clone(),
                         }),
                     ));
                 }
@@ -93,6 +93,7 @@ impl Transform {
                             }),
                             args: vec![val],
                             dest,
+                            is_method_call: false,
                         });
                         return to_insert;
                     }
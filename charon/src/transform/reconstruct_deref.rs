@@ -0,0 +1,72 @@
+//! # Micro-pass: replace the calls to `Deref::deref` and `DerefMut::deref_mut` on references and
+//! boxes with the reborrow of a dereference (only with `--reconstruct-deref`).
+//!
+//! The `Deref` impls of `&T`, `&mut T` and `Box<T>` only return their contents, so we can turn:
+//! ```text
+//! @2 := <Box<u32> as Deref>::deref(move (@3))
+//! ```
+//! into:
+//! ```text
+//! @2 := &*(*(@3))
+//! ```
+//! Calls to other `Deref` impls are left untouched, since they may run arbitrary code.
+use crate::builtins;
+use crate::transform::TransformCtx;
+use crate::ullbc_ast::*;
+
+use super::ctx::UllbcPass;
+
+/// If this is a call to `deref` or `deref_mut` on a reference or a box, return the borrow kind to
+/// use for the reborrow, the type of the receiver and the target type.
+fn identity_deref(ctx: &TransformCtx, call: &Call) -> Option<(BorrowKind, Ty, Ty)> {
+    let FnOperand::Regular(FnPtr {
+        func: FunIdOrTraitMethodRef::Trait(trait_ref, method_name, _),
+        ..
+    }) = &call.func
+    else {
+        return None;
+    };
+    let decl_ref = trait_ref.trait_decl_ref.clone().erase();
+    let trait_name = ctx.translated.item_name(decl_ref.trait_id)?;
+    let kind = if trait_name.equals_ref_name(builtins::DEREF_NAME) && method_name.0 == "deref" {
+        BorrowKind::Shared
+    } else if trait_name.equals_ref_name(builtins::DEREF_MUT_NAME) && method_name.0 == "deref_mut" {
+        BorrowKind::Mut
+    } else {
+        return None;
+    };
+    let self_ty = decl_ref.generics.types.get(TypeVarId::ZERO)?.clone();
+    let target_ty = match self_ty.kind() {
+        TyKind::Ref(_, target_ty, _) => target_ty.clone(),
+        _ => self_ty.as_box()?.clone(),
+    };
+    Some((kind, self_ty, target_ty))
+}
+
+pub struct Transform;
+impl UllbcPass for Transform {
+    fn transform_body(&self, ctx: &mut TransformCtx, b: &mut ExprBody) {
+        if !ctx.options.reconstruct_deref {
+            return;
+        }
+        for block in b.body.iter_mut() {
+            for st in block.statements.iter_mut() {
+                let RawStatement::Call(call) = &st.content else {
+                    continue;
+                };
+                let Some((kind, self_ty, target_ty)) = identity_deref(ctx, call) else {
+                    continue;
+                };
+                let [Operand::Move(receiver) | Operand::Copy(receiver)] = call.args.as_slice()
+                else {
+                    continue;
+                };
+                let place = receiver
+                    .clone()
+                    .project(ProjectionElem::Deref, self_ty)
+                    .project(ProjectionElem::Deref, target_ty);
+                st.content = RawStatement::Assign(call.dest.clone(), Rvalue::Ref(place, kind));
+            }
+        }
+    }
+}
@@ -0,0 +1,114 @@
+//! A let chain like `if let Some(a) = x && a > 0 { A } else { B }` is lowered to nested matches
+//! and `if`s which all fall back to the same `else` branch:
+//! ```text
+//! match x {
+//!     1 => {
+//!         a := copy (x as variant @1).0;
+//!         @3 := copy a > const 0;
+//!         if move @3 { A } else { B }
+//!     },
+//!     _ => { B },
+//! }
+//! ```
+//! This pass turns these into a [RawStatement::IfLetChain], which keeps the `else` branch in one
+//! place. We only do so when all the `else` branches are identical: when a later link must first
+//! drop the locals bound by the earlier ones, we keep the nested matches so as not to lose these
+//! drops. It only runs when `--reconstruct-let-chains` is set.
+use crate::llbc_ast::*;
+use crate::transform::TransformCtx;
+
+use super::ctx::LlbcPass;
+
+/// A chain read from nested matches, before we put it in a statement.
+struct Chain {
+    lets: Vec<LetChainBinding>,
+    cond: Option<Operand>,
+    then_block: Block,
+    else_block: Block,
+}
+
+/// Read the chain that starts with the `match` statement `st`. `else_block` is the `else` branch
+/// of the enclosing links, if any: the `match` must then fall back to the same branch, and may be
+/// the last link of the chain. Otherwise, `st` must be followed by at least one more link.
+fn read_chain(st: &Statement, else_block: Option<&Block>) -> Option<Chain> {
+    let RawStatement::Switch(Switch::Match(scrutinee, targets, otherwise)) = &st.content else {
+        return None;
+    };
+    // The branch taken when the pattern matches, and the one taken otherwise.
+    let candidates = match (targets.as_slice(), otherwise) {
+        ([(variants, block)], Some(this_else)) => vec![(variants, block, this_else)],
+        ([(variants0, block0), (variants1, block1)], None) => {
+            vec![(variants0, block0, block1), (variants1, block1, block0)]
+        }
+        _ => return None,
+    };
+    for (variants, block, this_else) in candidates {
+        if let Some(else_block) = else_block
+            && this_else != else_block
+        {
+            continue;
+        }
+        let chain_else = else_block.unwrap_or(this_else);
+        let mk_let = |statements: &[Statement]| LetChainBinding {
+            scrutinee: scrutinee.clone(),
+            variants: variants.clone(),
+            block: Block {
+                span: block.span,
+                statements: statements.to_vec(),
+            },
+        };
+
+        // The next link is the last statement of the block.
+        if let Some((next, statements)) = block.statements.split_last() {
+            if let Some(mut chain) = read_chain(next, Some(chain_else)) {
+                chain.lets.insert(0, mk_let(statements));
+                return Some(chain);
+            }
+            if let RawStatement::Switch(Switch::If(cond, then_block, cond_else)) = &next.content
+                && cond_else == chain_else
+            {
+                return Some(Chain {
+                    lets: vec![mk_let(statements)],
+                    cond: Some(cond.clone()),
+                    then_block: then_block.clone(),
+                    else_block: chain_else.clone(),
+                });
+            }
+        }
+        // This is the last link of an enclosing chain: the block is the `then` branch.
+        if else_block.is_some() {
+            return Some(Chain {
+                lets: vec![mk_let(&[])],
+                cond: None,
+                then_block: block.clone(),
+                else_block: chain_else.clone(),
+            });
+        }
+    }
+    None
+}
+
+pub struct Transform;
+
+impl Transform {
+    fn update_statement(st: &mut Statement) {
+        if let Some(chain) = read_chain(st, None) {
+            st.content = RawStatement::IfLetChain {
+                lets: chain.lets,
+                cond: chain.cond,
+                then_block: chain.then_block,
+                else_block: chain.else_block,
+            };
+        }
+    }
+}
+
+impl LlbcPass for Transform {
+    fn transform_body(&self, ctx: &mut TransformCtx, b: &mut ExprBody) {
+        // This pass is opt-in.
+        if !ctx.options.reconstruct_let_chains {
+            return;
+        }
+        b.body.visit_statements(Transform::update_statement)
+    }
+}
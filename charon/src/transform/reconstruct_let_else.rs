@@ -0,0 +1,73 @@
+//! `let Some(x) = opt else { return };` is lowered to a match on `opt` whose failure branch
+//! diverges:
+//! ```text
+//! match opt {
+//!     1 => {
+//!         x := copy (opt as variant @1).0;
+//!         ...
+//!     },
+//!     _ => {
+//!         return
+//!     },
+//! }
+//! ```
+//! This pass recognizes such matches and turns them into a [RawStatement::LetElse] followed by
+//! the contents of the non-diverging branch, which makes it clear that the code after the `let`
+//! only runs when the pattern matched. Note that `if let Some(x) = opt { .. } else { return }`
+//! and the matches `?` is lowered to have the same shape and are therefore also turned into
+//! `let .. else`s, which is why this pass only runs with `--reconstruct-let-else`.
+use std::mem;
+
+use crate::llbc_ast::*;
+use crate::transform::TransformCtx;
+
+use super::ctx::LlbcPass;
+use super::ullbc_to_llbc::is_terminal;
+
+pub struct Transform;
+
+impl Transform {
+    fn update_statements(seq: &mut [Statement]) -> Vec<Statement> {
+        let [st, ..] = seq else {
+            return Vec::new();
+        };
+        let RawStatement::Switch(Switch::Match(scrutinee, targets, otherwise)) = &mut st.content
+        else {
+            return Vec::new();
+        };
+        // Find the branch taken when the pattern matches, and the diverging one.
+        let (variants, then_block, else_block) = match (targets.as_mut_slice(), otherwise) {
+            ([(variants, then_block)], Some(else_block)) => (variants, then_block, else_block),
+            ([(variants, then_block), (_, else_block)], None)
+            | ([(_, else_block), (variants, then_block)], None)
+                if !is_terminal(then_block) =>
+            {
+                (variants, then_block, else_block)
+            }
+            _ => return Vec::new(),
+        };
+        if !is_terminal(else_block) || is_terminal(then_block) {
+            return Vec::new();
+        }
+
+        let let_else = RawStatement::LetElse {
+            scrutinee: scrutinee.clone(),
+            variants: mem::take(variants),
+            else_block: else_block.clone(),
+        };
+        let mut new_statements = vec![Statement::new(st.span, let_else)];
+        new_statements.append(&mut then_block.statements);
+        // The match is replaced by the statements we insert before it.
+        st.content = RawStatement::Nop;
+        new_statements
+    }
+}
+
+impl LlbcPass for Transform {
+    fn transform_body(&self, ctx: &mut TransformCtx, b: &mut ExprBody) {
+        if !ctx.options.reconstruct_let_else {
+            return;
+        }
+        b.body.transform_sequences(Transform::update_statements)
+    }
+}
@@ -0,0 +1,47 @@
+//! After translation, a method call `x.method(y)` is a call `Type::method(&x, y)` (or a call to a
+//! trait method with the receiver as first argument). This pass marks the calls to methods whose
+//! first argument is a reference to a place, so that we can print them with the method-call
+//! syntax.
+use crate::transform::TransformCtx;
+use crate::ullbc_ast::*;
+
+use super::ctx::UllbcPass;
+
+/// Whether the function called by this operand takes a `self` parameter.
+fn is_method(ctx: &TransformCtx, func: &FnOperand) -> bool {
+    let FnOperand::Regular(fn_ptr) = func else {
+        return false;
+    };
+    let fun_id = match &fn_ptr.func {
+        FunIdOrTraitMethodRef::Fun(FunId::Regular(fun_id))
+        | FunIdOrTraitMethodRef::Trait(_, _, fun_id) => *fun_id,
+        FunIdOrTraitMethodRef::Fun(FunId::Builtin(_)) => return false,
+    };
+    ctx.translated
+        .fun_decls
+        .get(fun_id)
+        .is_some_and(|decl| decl.signature.has_self_param)
+}
+
+pub struct Transform;
+impl UllbcPass for Transform {
+    fn transform_body(&self, ctx: &mut TransformCtx, b: &mut ExprBody) {
+        if !ctx.options.reconstruct_method_calls {
+            return;
+        }
+        for block in b.body.iter_mut() {
+            for st in block.statements.iter_mut() {
+                let RawStatement::Call(call) = &mut st.content else {
+                    continue;
+                };
+                let receiver_is_ref = match call.args.first() {
+                    Some(Operand::Move(place) | Operand::Copy(place)) => place.ty.kind().is_ref(),
+                    _ => false,
+                };
+                if receiver_is_ref && is_method(ctx, &call.func) {
+                    call.is_method_call = true;
+                }
+            }
+        }
+    }
+}
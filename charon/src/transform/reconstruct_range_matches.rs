@@ -0,0 +1,184 @@
+//! # Micro-pass: turn the comparisons that range patterns are lowered to into `switch`es over
+//! ranges (only with `--reconstruct-range-matches`).
+//!
+//! `match x { 1..=5 => A, _ => B }` is lowered to a chain of comparisons:
+//! ```text
+//! bb0: { ...; @1 := const 1 <= copy x; if move @1 -> bb1 else bb3 }
+//! bb1: { @2 := copy x <= const 5; if move @2 -> bb2 else bb3 }
+//! bb2: { A }
+//! bb3: { B }
+//! ```
+//! which this pass turns into:
+//! ```text
+//! bb0: { ...; switch copy x -> 1..=5: bb2, otherwise: bb3 }
+//! ```
+//! The following arms of the match are tested in the `else` branches; they become the following
+//! ranges of the same `switch`. Open-ended patterns like `..=9` or `10..` only need one
+//! comparison. The blocks that only contained a comparison become unreachable and are removed by
+//! [crate::transform::filter_unreachable_blocks].
+use std::collections::HashSet;
+
+use crate::transform::TransformCtx;
+use crate::ullbc_ast::*;
+
+use super::ctx::UllbcPass;
+
+/// A bound on the scrutinee, as found in a comparison.
+enum Bound {
+    /// `c <= x`
+    Lower(Literal),
+    /// `x <= c` if the boolean is `true`, `x < c` otherwise.
+    Upper(Literal, bool),
+}
+
+/// A comparison of a place with a constant, whose result is switched on.
+struct Test<'a> {
+    scrutinee: &'a Place,
+    ty: LiteralTy,
+    bound: Bound,
+    then_tgt: BlockId,
+    else_tgt: BlockId,
+}
+
+/// A test that the scrutinee is in a range.
+struct RangeTest {
+    scrutinee: Place,
+    ty: LiteralTy,
+    range: LiteralRange,
+    then_tgt: BlockId,
+    else_tgt: BlockId,
+}
+
+/// If the block ends with a comparison between a place and an integer or `char` constant, followed
+/// by an `if` on the result, return that comparison.
+fn block_test(block: &BlockData) -> Option<Test<'_>> {
+    let RawTerminator::Switch {
+        discr: Operand::Move(cond),
+        targets: SwitchTargets::If(then_tgt, else_tgt),
+    } = &block.terminator.content
+    else {
+        return None;
+    };
+    let Some(Statement {
+        content: RawStatement::Assign(dest, Rvalue::BinaryOp(op, lhs, rhs)),
+        ..
+    }) = block.statements.last()
+    else {
+        return None;
+    };
+    if dest != cond || dest.as_local().is_none() {
+        return None;
+    }
+    let (scrutinee, constant, scrutinee_on_left) = match (lhs, rhs) {
+        (Operand::Copy(p), Operand::Const(c)) => (p, c, true),
+        (Operand::Const(c), Operand::Copy(p)) => (p, c, false),
+        _ => return None,
+    };
+    let RawConstantExpr::Literal(lit) = &constant.value else {
+        return None;
+    };
+    let ty = match constant.ty.kind() {
+        TyKind::Literal(ty @ (LiteralTy::Integer(_) | LiteralTy::Char)) => *ty,
+        _ => return None,
+    };
+    let lit = lit.clone();
+    let bound = match (op, scrutinee_on_left) {
+        (BinOp::Ge, true) | (BinOp::Le, false) => Bound::Lower(lit),
+        (BinOp::Le, true) | (BinOp::Ge, false) => Bound::Upper(lit, true),
+        (BinOp::Lt, true) | (BinOp::Gt, false) => Bound::Upper(lit, false),
+        _ => return None,
+    };
+    Some(Test {
+        scrutinee,
+        ty,
+        bound,
+        then_tgt: *then_tgt,
+        else_tgt: *else_tgt,
+    })
+}
+
+/// Read the range tested at the end of block `bid`. A lower bound may be followed by an upper
+/// bound, which is tested in the `then` branch. If `only_test` is set, the block must contain
+/// nothing but the test.
+fn range_test(body: &BodyContents, bid: BlockId, only_test: bool) -> Option<RangeTest> {
+    let block = body.get(bid)?;
+    if only_test && block.statements.len() != 1 {
+        return None;
+    }
+    let test = block_test(block)?;
+    let mut range = LiteralRange {
+        start: None,
+        end: None,
+        end_inclusive: false,
+    };
+    let mut then_tgt = test.then_tgt;
+    match test.bound {
+        Bound::Upper(end, inclusive) => {
+            range.end = Some(end);
+            range.end_inclusive = inclusive;
+        }
+        Bound::Lower(start) => {
+            range.start = Some(start);
+            if let Some(then_block) = body.get(test.then_tgt)
+                && then_block.statements.len() == 1
+                && let Some(upper) = block_test(then_block)
+                && upper.scrutinee == test.scrutinee
+                && upper.ty == test.ty
+                && upper.else_tgt == test.else_tgt
+                && let Bound::Upper(end, inclusive) = upper.bound
+            {
+                range.end = Some(end);
+                range.end_inclusive = inclusive;
+                then_tgt = upper.then_tgt;
+            }
+        }
+    }
+    Some(RangeTest {
+        scrutinee: test.scrutinee.clone(),
+        ty: test.ty,
+        range,
+        then_tgt,
+        else_tgt: test.else_tgt,
+    })
+}
+
+pub struct Transform;
+impl UllbcPass for Transform {
+    fn transform_body(&self, ctx: &mut TransformCtx, b: &mut ExprBody) {
+        if !ctx.options.reconstruct_range_matches {
+            return;
+        }
+        let mut switches = Vec::new();
+        for bid in b.body.all_indices() {
+            let Some(first) = range_test(&b.body, bid, false) else {
+                continue;
+            };
+            let mut visited: HashSet<BlockId> = HashSet::from([bid, first.then_tgt]);
+            let mut arms = vec![(first.range, first.then_tgt)];
+            let mut otherwise = first.else_tgt;
+            // The following arms are tested in the `else` branch.
+            while let Some(next) = range_test(&b.body, otherwise, true)
+                && next.scrutinee == first.scrutinee
+                && next.ty == first.ty
+                && !visited.contains(&next.then_tgt)
+                && !visited.contains(&next.else_tgt)
+            {
+                visited.insert(otherwise);
+                visited.insert(next.then_tgt);
+                arms.push((next.range, next.then_tgt));
+                otherwise = next.else_tgt;
+            }
+            switches.push((bid, first.scrutinee, first.ty, arms, otherwise));
+        }
+
+        for (bid, scrutinee, ty, arms, otherwise) in switches {
+            let block = &mut b.body[bid];
+            // Remove the comparison: we switch on the scrutinee directly.
+            block.statements.pop();
+            block.terminator.content = RawTerminator::Switch {
+                discr: Operand::Copy(scrutinee),
+                targets: SwitchTargets::SwitchRange(ty, arms, otherwise),
+            };
+        }
+    }
+}
@@ -0,0 +1,149 @@
+//! # Micro-pass: reconstruct struct-update syntax (`Foo { x: 1, ..base }`), if
+//! `--reconstruct-struct-updates` is set. MIR doesn't distinguish `Foo { x: 1, ..base }` from
+//! `Foo { x: 1, y: base.y, z: base.z }`, so the latter gets reconstructed too.
+use crate::transform::TransformCtx;
+use crate::ullbc_ast::*;
+
+use super::ctx::UllbcPass;
+
+pub struct Transform;
+
+/// If the operand reads the field `field_id` of a struct place, return that struct place.
+fn read_field_of(op: &Operand, field_id: FieldId) -> Option<&Place> {
+    let (Operand::Copy(place) | Operand::Move(place)) = op else {
+        return None;
+    };
+    match place.as_projection()? {
+        (base, ProjectionElem::Field(FieldProjKind::Adt(_, None), id)) if *id == field_id => {
+            Some(base)
+        }
+        _ => None,
+    }
+}
+
+/// Whether this statement writes to or borrows (a part of) the place.
+fn writes_or_borrows(st: &Statement, place: &Place) -> bool {
+    let var_id = place.var_id();
+    let mut found = matches!(&st.content, RawStatement::Assign(lhs, _) if lhs.var_id() == var_id);
+    st.dyn_visit_in_body(|rvalue: &Rvalue| {
+        if let Rvalue::Ref(p, _) | Rvalue::RawPtr(p, _) = rvalue
+            && p.var_id() == var_id
+        {
+            found = true;
+        }
+    });
+    found
+}
+
+impl Transform {
+    /// Struct-update syntax `Foo { x: 1, ..base }` gets lowered to an aggregate where the
+    /// non-overridden fields are read from the base place, sometimes through intermediate
+    /// temporaries:
+    ///
+    /// ```text
+    /// @2 := copy ((base).y)
+    /// @3 := move ((base).z)
+    /// @0 := Foo { x: const 1, y: move (@2), z: move (@3) }
+    /// ```
+    ///
+    /// We reconstruct this into `@0 := Foo { x: const 1, ..base }`. The statements that
+    /// initialized the temporaries are replaced with `Nop`s. We only look for these statements
+    /// right before the aggregate, and give up if `base` is modified or borrowed in between:
+    /// reading `base` at the aggregate must give the same values.
+    fn update_statement(previous: &mut [Statement], st: &mut Statement) {
+        let RawStatement::Assign(dest, rvalue) = &mut st.content else {
+            return;
+        };
+        let Rvalue::Aggregate(kind @ AggregateKind::Adt(TypeId::Adt(_), None, None, _), ops) =
+            rvalue
+        else {
+            return;
+        };
+
+        // The statements that may initialize the temporaries: the assignments to locals that
+        // immediately precede the aggregate.
+        let start = previous
+            .iter()
+            .rposition(|st| match &st.content {
+                RawStatement::Assign(lhs, Rvalue::Use(_)) => !lhs.is_local(),
+                RawStatement::StorageLive(_) | RawStatement::StorageDead(_) | RawStatement::Nop => {
+                    false
+                }
+                _ => true,
+            })
+            .map_or(0, |i| i + 1);
+        let candidates = &previous[start..];
+
+        // For every field, check whether it is read from the base place, possibly through one of
+        // these temporaries.
+        let mut base: Option<&Place> = None;
+        let mut from_base: Vec<FieldId> = Vec::new();
+        let mut tmp_assigns: Vec<usize> = Vec::new();
+        for (i, op) in ops.iter().enumerate() {
+            let field_id = FieldId::new(i);
+            let (op, tmp_assign) =
+                match op {
+                    Operand::Move(tmp) if tmp.is_local() => {
+                        let assign = candidates.iter().enumerate().rev().find_map(|(j, st)| {
+                            match &st.content {
+                                RawStatement::Assign(lhs, Rvalue::Use(src)) if lhs == tmp => {
+                                    Some((start + j, src))
+                                }
+                                _ => None,
+                            }
+                        });
+                        match assign {
+                            Some((j, src)) => (src, Some(j)),
+                            None => (op, None),
+                        }
+                    }
+                    _ => (op, None),
+                };
+            let Some(field_base) = read_field_of(op, field_id) else {
+                continue;
+            };
+            if field_base.ty() != dest.ty() || base.is_some_and(|base| base != field_base) {
+                continue;
+            }
+            base = Some(field_base);
+            from_base.push(field_id);
+            tmp_assigns.extend(tmp_assign);
+        }
+        let Some(base) = base.cloned() else {
+            return;
+        };
+        if let Some(first) = tmp_assigns.iter().min()
+            && previous[*first..]
+                .iter()
+                .any(|st| writes_or_borrows(st, &base))
+        {
+            return;
+        }
+
+        let fields: Vec<_> = ops
+            .iter()
+            .enumerate()
+            .map(|(i, op)| (FieldId::new(i), op))
+            .filter(|(field_id, _)| !from_base.contains(field_id))
+            .map(|(field_id, op)| (field_id, op.clone()))
+            .collect();
+        for j in tmp_assigns {
+            previous[j].content = RawStatement::Nop;
+        }
+        *rvalue = Rvalue::StructUpdate(kind.clone(), fields, base);
+    }
+}
+
+impl UllbcPass for Transform {
+    fn transform_body(&self, ctx: &mut TransformCtx, b: &mut ExprBody) {
+        if !ctx.options.reconstruct_struct_updates {
+            return;
+        }
+        for block in b.body.iter_mut() {
+            for i in 0..block.statements.len() {
+                let (previous, rest) = block.statements.split_at_mut(i);
+                Self::update_statement(previous, &mut rest[0]);
+            }
+        }
+    }
+}
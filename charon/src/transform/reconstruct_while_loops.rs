@@ -0,0 +1,89 @@
+//! `while i < n { .. }` is lowered to a loop that starts by evaluating the condition and exits if
+//! it is false:
+//! ```text
+//! loop {
+//!     @1 := copy (i) < copy (n)
+//!     if move (@1) {
+//!         ...
+//!         continue 0
+//!     } else {
+//!         break 0
+//!     }
+//! }
+//! ```
+//! This pass turns such loops into [RawStatement::While]. The statements that compute the
+//! condition become part of the `while` condition, hence we only do this when they don't have
+//! side effects. Otherwise (e.g. if the condition calls a method) we leave the loop as is. It only
+//! runs when `--reconstruct-while-loops` is set.
+use std::mem;
+
+use crate::llbc_ast::*;
+use crate::transform::TransformCtx;
+
+use super::ctx::LlbcPass;
+
+/// Whether this statement can be part of the condition of a `while`. Function calls are
+/// statements of their own, so assignments never have side effects.
+fn is_pure(st: &Statement) -> bool {
+    matches!(
+        st.content,
+        RawStatement::Assign(..) | RawStatement::FakeRead(_) | RawStatement::Nop
+    )
+}
+
+fn is_while_loop(loop_body: &Block) -> bool {
+    let Some((last, cond_statements)) = loop_body.statements.split_last() else {
+        return false;
+    };
+    let RawStatement::Switch(Switch::If(_, _, else_block)) = &last.content else {
+        return false;
+    };
+    matches!(
+        else_block.statements.as_slice(),
+        [Statement {
+            content: RawStatement::Break(0),
+            ..
+        }]
+    ) && cond_statements.iter().all(is_pure)
+}
+
+fn update_statement(st: &mut Statement) {
+    if !matches!(&st.content, RawStatement::Loop(loop_body) if is_while_loop(loop_body)) {
+        return;
+    }
+    let RawStatement::Loop(mut cond_block) = mem::replace(&mut st.content, RawStatement::Nop)
+    else {
+        unreachable!()
+    };
+    let Some(Statement {
+        content: RawStatement::Switch(Switch::If(cond, mut body, _)),
+        ..
+    }) = cond_block.statements.pop()
+    else {
+        unreachable!()
+    };
+    // Reaching the end of the body goes back to the condition anyway.
+    if let Some(Statement {
+        content: RawStatement::Continue(0),
+        ..
+    }) = body.statements.last()
+    {
+        body.statements.pop();
+    }
+    st.content = RawStatement::While {
+        cond_block,
+        cond,
+        body,
+    };
+}
+
+pub struct Transform;
+impl LlbcPass for Transform {
+    fn transform_body(&self, ctx: &mut TransformCtx, b: &mut ExprBody) {
+        // This pass is opt-in.
+        if !ctx.options.reconstruct_while_loops {
+            return;
+        }
+        b.body.visit_statements(update_statement)
+    }
+}
@@ -1,4 +1,5 @@
-//! Take all the comments found in the original body and assign them to statements.
+//! Take all the comments found in the original body and assign them to statements. The comments
+//! that start with the annotation prefix (`charon:` by default) are parsed as [Annotation]s.
 use std::mem;
 
 use crate::ast::*;
@@ -9,6 +10,7 @@ use super::ctx::TransformPass;
 trait IsStatement {
     fn get_span(&self) -> Span;
     fn get_comments_before(&mut self) -> &mut Vec<String>;
+    fn get_annotations(&mut self) -> &mut Vec<Annotation>;
 }
 
 impl IsStatement for llbc_ast::Statement {
@@ -18,6 +20,9 @@ impl IsStatement for llbc_ast::Statement {
     fn get_comments_before(&mut self) -> &mut Vec<String> {
         &mut self.comments_before
     }
+    fn get_annotations(&mut self) -> &mut Vec<Annotation> {
+        &mut self.annotations
+    }
 }
 impl IsStatement for ullbc_ast::Statement {
     fn get_span(&self) -> Span {
@@ -26,6 +31,9 @@ impl IsStatement for ullbc_ast::Statement {
     fn get_comments_before(&mut self) -> &mut Vec<String> {
         &mut self.comments_before
     }
+    fn get_annotations(&mut self) -> &mut Vec<Annotation> {
+        &mut self.annotations
+    }
 }
 impl IsStatement for ullbc_ast::Terminator {
     fn get_span(&self) -> Span {
@@ -34,19 +42,46 @@ impl IsStatement for ullbc_ast::Terminator {
     fn get_comments_before(&mut self) -> &mut Vec<String> {
         &mut self.comments_before
     }
+    fn get_annotations(&mut self) -> &mut Vec<Annotation> {
+        &mut self.annotations
+    }
+}
+
+/// Parse the text of a directive (what follows the prefix).
+fn parse_annotation(directive: &str) -> Option<Annotation> {
+    let (keyword, cond) = directive.trim().split_once(char::is_whitespace)?;
+    let cond = cond.trim().to_owned();
+    match keyword {
+        "assume" => Some(Annotation::Assume(cond)),
+        "assert" => Some(Annotation::Assert(cond)),
+        _ => None,
+    }
 }
 
-struct CommentsCtx {
+struct CommentsCtx<'a> {
     comments: Vec<(usize, Vec<String>)>,
+    annotation_prefix: &'a str,
+    /// Directives we couldn't parse, to be reported once we're done.
+    invalid_directives: Vec<(Span, String)>,
 }
-impl CommentsCtx {
+impl CommentsCtx<'_> {
     fn visit<St: IsStatement>(&mut self, st: &mut St) {
         let st_line = st.get_span().span.beg.line;
         self.comments = mem::take(&mut self.comments)
             .into_iter()
             .filter_map(|(line, comments)| {
                 if line <= st_line {
-                    st.get_comments_before().extend(comments);
+                    for comment in comments {
+                        if let Some(directive) = comment.strip_prefix(self.annotation_prefix) {
+                            if let Some(annot) = parse_annotation(directive) {
+                                st.get_annotations().push(annot);
+                                continue;
+                            }
+                            self.invalid_directives
+                                .push((st.get_span(), directive.trim().to_owned()));
+                        }
+                        st.get_comments_before().push(comment);
+                    }
                     None
                 } else {
                     Some((line, comments))
@@ -59,7 +94,8 @@ impl CommentsCtx {
 pub struct Transform;
 impl TransformPass for Transform {
     fn transform_ctx(&self, ctx: &mut TransformCtx) {
-        ctx.for_each_fun_decl(|_ctx, fun| {
+        let annotation_prefix = ctx.options.annotation_prefix.clone();
+        ctx.for_each_fun_decl(|ctx, fun| {
             if let Ok(body) = &mut fun.body {
                 // Constraints in the ideal case:
                 // - each comment should be assigned to exactly one statement;
@@ -67,25 +103,36 @@ impl TransformPass for Transform {
                 // - a comment should come before the statement it was applied to.
 
                 // This is a pretty simple heuristic which is good enough for now.
-                let mut ctx = CommentsCtx {
+                let mut comments_ctx = CommentsCtx {
                     comments: match body {
                         Body::Unstructured(b) => b.comments.clone(),
                         Body::Structured(b) => b.comments.clone(),
                     },
+                    annotation_prefix: &annotation_prefix,
+                    invalid_directives: Vec::new(),
                 };
                 match body {
                     Body::Unstructured(b) => {
                         for block in &mut b.body {
                             for st in &mut block.statements {
-                                ctx.visit(st);
+                                comments_ctx.visit(st);
                             }
-                            ctx.visit(&mut block.terminator);
+                            comments_ctx.visit(&mut block.terminator);
                         }
                     }
                     Body::Structured(b) => b.body.visit_statements(|st| {
-                        ctx.visit(st);
+                        comments_ctx.visit(st);
                     }),
                 }
+                for (span, directive) in comments_ctx.invalid_directives {
+                    ctx.span_warn(
+                        span,
+                        &format!(
+                            "Unknown `{annotation_prefix}` directive `{directive}`; expected \
+                            `assume <cond>` or `assert <cond>`"
+                        ),
+                    );
+                }
             }
         });
     }
@@ -1,5 +1,10 @@
 //! # Micro-pass: remove the overflow checks for arithmetic operations we couldn't remove in
 //! [`remove_dynamic_checks`]. See comments there for more details.
+//!
+//! What the checks become depends on `--overflow`: they are merged into operations that fail on
+//! overflow by default, removed in favor of wrapping operations with `--overflow wrapping`, and
+//! kept with `--overflow checked`.
+use crate::options::OverflowMode;
 use crate::transform::TransformCtx;
 use crate::ullbc_ast::*;
 
@@ -21,7 +26,8 @@ impl Transform {
     /// ```text
     /// z := x + y;
     /// ```
-    fn update_statements(seq: &mut [Statement]) {
+    /// or with `z := x wrapping.+ y` in [OverflowMode::Wrapping].
+    fn update_statements(mode: OverflowMode, seq: &mut [Statement]) {
         if let [Statement {
             content:
                 RawStatement::Assign(
@@ -38,6 +44,7 @@ impl Transform {
                 RawStatement::Assert(Assert {
                     cond: Operand::Move(assert_cond),
                     expected: false,
+                    ..
                 }),
             ..
         }, Statement {
@@ -58,7 +65,11 @@ impl Transform {
                 && *sub1 == *binop
             {
                 // Switch to the unchecked operation.
+                let wrapping = mode == OverflowMode::Wrapping;
                 *op = match op {
+                    BinOp::CheckedAdd if wrapping => BinOp::WrappingAdd,
+                    BinOp::CheckedSub if wrapping => BinOp::WrappingSub,
+                    BinOp::CheckedMul if wrapping => BinOp::WrappingMul,
                     BinOp::CheckedAdd => BinOp::Add,
                     BinOp::CheckedSub => BinOp::Sub,
                     BinOp::CheckedMul => BinOp::Mul,
@@ -75,10 +86,27 @@ impl Transform {
 }
 
 impl UllbcPass for Transform {
-    fn transform_body(&self, _ctx: &mut TransformCtx, b: &mut ExprBody) {
+    fn transform_body(&self, ctx: &mut TransformCtx, b: &mut ExprBody) {
+        let mode = ctx.options.overflow;
+        if mode == OverflowMode::Checked {
+            return;
+        }
         b.transform_sequences(|_, seq| {
-            Transform::update_statements(seq);
+            Transform::update_statements(mode, seq);
             Vec::new()
-        })
+        });
+        if mode == OverflowMode::Wrapping {
+            // The operations that rustc didn't check wrap around too.
+            b.body.dyn_visit_in_body_mut(|rvalue: &mut Rvalue| {
+                if let Rvalue::BinaryOp(op, _, _) = rvalue {
+                    *op = match op {
+                        BinOp::Add => BinOp::WrappingAdd,
+                        BinOp::Sub => BinOp::WrappingSub,
+                        BinOp::Mul => BinOp::WrappingMul,
+                        _ => *op,
+                    };
+                }
+            });
+        }
     }
 }
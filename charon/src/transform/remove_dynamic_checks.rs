@@ -34,6 +34,7 @@ fn remove_dynamic_checks(_ctx: &mut TransformCtx, statements: &mut [Statement])
                 RawStatement::Assert(Assert {
                     cond: Operand::Move(cond),
                     expected,
+                    ..
                 }),
             ..
         }, rest @ ..]
@@ -54,6 +55,7 @@ fn remove_dynamic_checks(_ctx: &mut TransformCtx, statements: &mut [Statement])
                 RawStatement::Assert(Assert {
                     cond: Operand::Move(cond),
                     expected,
+                    ..
                 }),
             ..
         }, rest @ ..]
@@ -85,6 +87,7 @@ fn remove_dynamic_checks(_ctx: &mut TransformCtx, statements: &mut [Statement])
                 RawStatement::Assert(Assert {
                     cond: Operand::Move(cond),
                     expected,
+                    ..
                 }),
             ..
         }, rest @ ..]
@@ -115,6 +118,7 @@ fn remove_dynamic_checks(_ctx: &mut TransformCtx, statements: &mut [Statement])
                 RawStatement::Assert(Assert {
                     cond: Operand::Move(cond),
                     expected,
+                    ..
                 }),
             ..
         }, rest @ ..]
@@ -137,6 +141,7 @@ fn remove_dynamic_checks(_ctx: &mut TransformCtx, statements: &mut [Statement])
                 RawStatement::Assert(Assert {
                     cond: Operand::Move(cond),
                     expected,
+                    ..
                 }),
             ..
         }, rest @ ..]
@@ -162,6 +167,7 @@ fn remove_dynamic_checks(_ctx: &mut TransformCtx, statements: &mut [Statement])
                 RawStatement::Assert(Assert {
                     cond: Operand::Move(cond),
                     expected,
+                    ..
                 }),
             ..
         }, ..]
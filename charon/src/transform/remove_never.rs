@@ -0,0 +1,81 @@
+//! # Micro-pass: remove the locals of type `!` (`Never`).
+//!
+//! The MIR code often contains variables with type `!` that come from `panic!`s, `break`s and
+//! similar diverging expressions. Such a variable is never initialized, so:
+//! - `StorageDead(x)` and `Drop(x)` are no-ops: we remove them;
+//! - a statement that reads `x`, or that writes to it, can never be reached: we replace it and the
+//!   rest of its block with `Abort(UndefinedBehavior)`;
+//! - a call whose destination has type `!` never returns: we remove the statements that follow it
+//!   and end its block with `Abort(UndefinedBehavior)`.
+//!
+//! The unused locals are then removed in [crate::transform::remove_unused_locals]. The only locals
+//! of type `!` that remain are the destinations of calls to diverging functions, since a call
+//! always needs a destination.
+use crate::transform::BodyTransformCtx;
+use crate::ullbc_ast::*;
+
+use super::ctx::UllbcBodyPass;
+
+/// Whether executing this statement requires a value of type `!`.
+fn needs_never_value(st: &Statement) -> bool {
+    let mut reads_never = false;
+    st.dyn_visit_in_body(|op: &Operand| {
+        if let Operand::Copy(p) | Operand::Move(p) = op
+            && p.ty().is_never()
+        {
+            reads_never = true;
+        }
+    });
+    reads_never || matches!(&st.content, RawStatement::Assign(dest, _) if dest.ty().is_never())
+}
+
+pub struct Transform;
+impl UllbcBodyPass for Transform {
+    fn transform_body(&self, _ctx: &mut BodyTransformCtx<'_>, b: &mut ExprBody) {
+        let locals = &b.locals;
+        for block in b.body.iter_mut() {
+            for st in block.statements.iter_mut() {
+                match &st.content {
+                    RawStatement::StorageDead(var_id) if locals[*var_id].ty.is_never() => {
+                        st.content = RawStatement::Nop;
+                    }
+                    RawStatement::Drop(place) if place.ty().is_never() => {
+                        st.content = RawStatement::Nop;
+                    }
+                    _ => {}
+                }
+            }
+
+            // Cut the block at the first statement after which execution can't continue.
+            let unreachable_from = block.statements.iter().enumerate().find_map(|(i, st)| {
+                if needs_never_value(st) {
+                    Some(i)
+                } else if let RawStatement::Call(call) = &st.content
+                    && call.dest.ty().is_never()
+                {
+                    Some(i + 1)
+                } else {
+                    None
+                }
+            });
+            let already_cut = |i| {
+                i == block.statements.len()
+                    && matches!(
+                        block.terminator.content,
+                        RawTerminator::Abort(AbortKind::UndefinedBehavior)
+                    )
+            };
+            if let Some(i) = unreachable_from
+                && !already_cut(i)
+            {
+                let span = match block.statements.get(i) {
+                    Some(st) => st.span,
+                    None => block.terminator.span,
+                };
+                block.statements.truncate(i);
+                block.terminator =
+                    Terminator::new(span, RawTerminator::Abort(AbortKind::UndefinedBehavior));
+            }
+        }
+    }
+}
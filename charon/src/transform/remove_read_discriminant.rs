@@ -107,12 +107,21 @@ impl Transform {
 
                                 let mut covered_discriminants: HashSet<ScalarValue> =
                                     HashSet::default();
-                                let targets = targets
+                                let mut targets = targets
                                     .into_iter()
                                     .map(|(v, e)| {
                                         let targets = v
                                             .into_iter()
                                             .filter_map(|discr| {
+                                                let Literal::Scalar(discr) = discr else {
+                                                    register_error!(
+                                                        ctx,
+                                                        block.span,
+                                                        "Found non-integer discriminant \
+                                                        {discr} for enum {adt_id}"
+                                                    );
+                                                    return None;
+                                                };
                                                 covered_discriminants.insert(discr);
                                                 discr_to_id.get(&discr).or_else(|| {
                                                     register_error!(
@@ -129,8 +138,33 @@ impl Transform {
                                         (targets, e)
                                     })
                                     .collect_vec();
+                                // Rustc doesn't require arms for the uninhabited variants: these
+                                // go to the otherwise branch, which can't be reached with them.
+                                // We give them an explicit unreachable arm instead.
+                                let uncovered = variants
+                                    .iter_indexed_values()
+                                    .filter(|(_, variant)| {
+                                        !covered_discriminants.contains(&variant.discriminant)
+                                    })
+                                    .collect_vec();
+                                let uninhabited = !uncovered.is_empty()
+                                    && uncovered.iter().all(|(_, variant)| {
+                                        variant.is_uninhabited(&ctx.translated)
+                                    });
+                                if uninhabited {
+                                    let unreachable = Statement::new(
+                                        *span1,
+                                        RawStatement::Abort(AbortKind::UndefinedBehavior),
+                                    );
+                                    targets.push((
+                                        uncovered.iter().map(|(id, _)| *id).collect(),
+                                        unreachable.into_block(),
+                                    ));
+                                }
+
                                 // Filter the otherwise branch if it is not necessary.
-                                let covers_all = covered_discriminants.len() == discr_to_id.len();
+                                let covers_all =
+                                    uninhabited || covered_discriminants.len() == discr_to_id.len();
                                 let otherwise = if covers_all { None } else { Some(otherwise) };
 
                                 // Replace the old switch with a match.
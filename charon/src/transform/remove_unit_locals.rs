@@ -1,14 +1,14 @@
 use derive_generic_visitor::*;
 use std::collections::HashSet;
 
-use crate::transform::TransformCtx;
+use crate::transform::BodyTransformCtx;
 use crate::ullbc_ast::*;
 
-use super::ctx::UllbcPass;
+use super::ctx::UllbcBodyPass;
 
 pub struct Transform;
-impl UllbcPass for Transform {
-    fn transform_body(&self, _ctx: &mut TransformCtx, body: &mut ExprBody) {
+impl UllbcBodyPass for Transform {
+    fn transform_body(&self, _ctx: &mut BodyTransformCtx<'_>, body: &mut ExprBody) {
         // Replace any copy/move of a unit local to a plain const assignment. Note: we don't touch
         // other `Rvalue`s as they might have side-effects (e.g. reading through a pointer).
         body.visit_statements(|st| {
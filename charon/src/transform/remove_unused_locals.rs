@@ -1,7 +1,6 @@
 //! Remove the locals (which are not used for the input arguments) which are
 //! never used in the function bodies.  This is useful to remove the locals with
-//! type `Never`. We actually check that there are no such local variables
-//! remaining afterwards.
+//! type `Never` once [crate::transform::remove_never] has removed their uses.
 use std::collections::{HashMap, HashSet};
 use std::mem;
 
@@ -39,9 +39,9 @@ pub enum DeclarationGroup {
     Fun(GDeclarationGroup<FunDeclId>),
     /// A global declaration group
     Global(GDeclarationGroup<GlobalDeclId>),
-    ///
+    /// A trait declaration group
     TraitDecl(GDeclarationGroup<TraitDeclId>),
-    ///
+    /// A trait implementation group
     TraitImpl(GDeclarationGroup<TraitImplId>),
     /// Anything that doesn't fit into these categories.
     Mixed(GDeclarationGroup<AnyTransId>),
@@ -318,7 +318,7 @@ impl VisitAst for Deps {
 }
 
 impl AnyTransId {
-    fn fmt_with_ctx(&self, ctx: &TransformCtx) -> String {
+    pub(crate) fn fmt_with_ctx(&self, ctx: &TransformCtx) -> String {
         use AnyTransId::*;
         let ctx = ctx.into_fmt();
         match self {
@@ -332,6 +332,18 @@ impl AnyTransId {
 }
 
 impl Deps {
+    /// The items of the graph, in the order in which we encountered them.
+    pub(crate) fn nodes(&self) -> impl Iterator<Item = AnyTransId> + '_ {
+        self.graph.keys().copied()
+    }
+
+    /// The dependencies of each item.
+    pub(crate) fn edges(&self) -> impl Iterator<Item = (AnyTransId, AnyTransId)> + '_ {
+        self.graph
+            .iter()
+            .flat_map(|(src, deps)| deps.iter().map(move |tgt| (*src, *tgt)))
+    }
+
     fn fmt_with_ctx(&self, ctx: &TransformCtx) -> String {
         self.dgraph
             .nodes()
@@ -350,7 +362,7 @@ impl Deps {
     }
 }
 
-fn compute_declarations_graph<'tcx>(ctx: &'tcx TransformCtx) -> Deps {
+pub(crate) fn compute_declarations_graph<'tcx>(ctx: &'tcx TransformCtx) -> Deps {
     let mut graph = Deps::new();
     for (id, item) in ctx.translated.all_items_with_ids() {
         graph.set_current_id(ctx, id);
@@ -373,6 +385,7 @@ fn compute_declarations_graph<'tcx>(ctx: &'tcx TransformCtx) -> Deps {
                     consts,
                     const_defaults,
                     types,
+                    generic_types,
                     type_defaults,
                     type_clauses,
                     methods,
@@ -387,6 +400,7 @@ fn compute_declarations_graph<'tcx>(ctx: &'tcx TransformCtx) -> Deps {
                 // Visit the items
                 consts.drive(&mut graph);
                 types.drive(&mut graph);
+                generic_types.drive(&mut graph);
                 const_defaults.drive(&mut graph);
                 type_defaults.drive(&mut graph);
 
@@ -0,0 +1,16 @@
+//! Keep a copy of the crate just before control-flow reconstruction, so that we can write the
+//! ULLBC next to the final LLBC. This pass doesn't modify the crate; it only runs when
+//! `--emit-ullbc` is set.
+use crate::transform::TransformCtx;
+
+use super::ctx::TransformPass;
+
+pub struct Transform;
+impl TransformPass for Transform {
+    fn transform_ctx(&self, ctx: &mut TransformCtx) {
+        let mut ullbc = ctx.translated.clone();
+        // Consumers look at this to know which kind of bodies to expect.
+        ullbc.options.ullbc = true;
+        ctx.ullbc_snapshot = Some(ullbc);
+    }
+}
@@ -13,10 +13,10 @@
 use itertools::Itertools;
 use std::assert_matches::assert_matches;
 
-use crate::transform::TransformCtx;
+use crate::transform::BodyTransformCtx;
 use crate::ullbc_ast::*;
 
-use super::ctx::UllbcPass;
+use super::ctx::UllbcBodyPass;
 
 /// If the constant value is a constant ADT, push `Assign::Aggregate` statements
 /// to the vector of statements, that bind new variables to the ADT parts and
@@ -122,7 +122,15 @@ fn transform_constant_expr(
     }
 }
 
-fn transform_operand(span: &Span, locals: &mut Locals, nst: &mut Vec<Statement>, op: &mut Operand) {
+/// `anon_count` counts the variables we introduced so far in this body. We use it to give them
+/// stable names (`anon_0`, `anon_1`, ...), which makes the output easier to read.
+fn transform_operand(
+    span: &Span,
+    locals: &mut Locals,
+    anon_count: &mut usize,
+    nst: &mut Vec<Statement>,
+    op: &mut Operand,
+) {
     // Transform the constant operands (otherwise do nothing)
     take_mut::take(op, |op| {
         if let Operand::Const(val) = op {
@@ -130,7 +138,9 @@ fn transform_operand(span: &Span, locals: &mut Locals, nst: &mut Vec<Statement>,
                 if let Rvalue::Use(Operand::Move(place)) = rvalue {
                     place
                 } else {
-                    let var = locals.new_var(None, ty);
+                    let name = format!("anon_{anon_count}");
+                    *anon_count += 1;
+                    let var = locals.new_var(Some(name), ty);
                     nst.push(Statement::new(
                         *span,
                         RawStatement::Assign(var.clone(), rvalue),
@@ -146,12 +156,13 @@ fn transform_operand(span: &Span, locals: &mut Locals, nst: &mut Vec<Statement>,
 }
 
 pub struct Transform;
-impl UllbcPass for Transform {
-    fn transform_body(&self, _ctx: &mut TransformCtx, body: &mut ExprBody) {
+impl UllbcBodyPass for Transform {
+    fn transform_body(&self, _ctx: &mut BodyTransformCtx<'_>, body: &mut ExprBody) {
+        let mut anon_count = 0;
         for block in body.body.iter_mut() {
             // Deconstruct some constants into series of MIR assignments.
             block.transform_operands(|span, nst, op| {
-                transform_operand(span, &mut body.locals, nst, op)
+                transform_operand(span, &mut body.locals, &mut anon_count, nst, op)
             });
 
             // Simplify array with repeated constants into array repeats.
@@ -0,0 +1,44 @@
+//! # Micro-pass: give each body a single `return`, for the backends that want a single exit point
+//! (only with `--single-return`).
+//!
+//! The returned value is always stored in the return local (`_0`) before returning, so we only
+//! need to redirect all the `return` terminators to a fresh exit block:
+//! ```text
+//! bb1: { ...; _0 := 1; return }
+//! bb2: { ...; _0 := 2; return }
+//! ```
+//! becomes:
+//! ```text
+//! bb1: { ...; _0 := 1; goto bb3 }
+//! bb2: { ...; _0 := 2; goto bb3 }
+//! bb3: { return }
+//! ```
+use crate::transform::TransformCtx;
+use crate::ullbc_ast::*;
+
+use super::ctx::UllbcPass;
+
+pub struct Transform;
+impl UllbcPass for Transform {
+    fn transform_body(&self, ctx: &mut TransformCtx, b: &mut ExprBody) {
+        if !ctx.options.single_return {
+            return;
+        }
+        let returns: Vec<BlockId> = b
+            .body
+            .iter_indexed()
+            .filter(|(_, block)| block.terminator.content.is_return())
+            .map(|(bid, _)| bid)
+            .collect();
+        if returns.len() <= 1 {
+            return;
+        }
+        let exit = b.body.push(BlockData {
+            statements: Vec::new(),
+            terminator: Terminator::new(b.span, RawTerminator::Return),
+        });
+        for bid in returns {
+            b.body[bid].terminator.content = RawTerminator::Goto { target: exit };
+        }
+    }
+}
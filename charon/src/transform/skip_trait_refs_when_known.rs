@@ -1,7 +1,44 @@
-use crate::{register_error, transform::TransformCtx, ullbc_ast::*};
+use std::sync::LazyLock;
+
+use crate::{name_matcher::NamePattern, register_error, transform::TransformCtx, ullbc_ast::*};
 
 use super::ctx::UllbcPass;
 
+/// The blanket impl `impl<T, U: From<T>> Into<U> for T`, whose `into` method just calls
+/// `U::from`.
+static INTO_VIA_FROM_IMPL: LazyLock<NamePattern> = LazyLock::new(|| {
+    NamePattern::parse("core::convert::{impl core::convert::Into<_> for _}").unwrap()
+});
+static FROM_TRAIT: LazyLock<NamePattern> =
+    LazyLock::new(|| NamePattern::parse("core::convert::From").unwrap());
+
+/// If `trait_ref` is the blanket `Into` impl and `name` is `into`, return the `U: From<T>` trait
+/// reference it forwards to, along with the name of the method to call on it. This way `.into()`
+/// resolves to the `from` method of a known `From` impl.
+fn into_via_from<'a>(
+    ctx: &TransformCtx,
+    trait_ref: &'a TraitRef,
+    name: &TraitItemName,
+) -> Option<(&'a TraitRef, TraitItemName)> {
+    let TraitRefKind::TraitImpl(impl_id, impl_generics) = &trait_ref.kind else {
+        return None;
+    };
+    if name.0 != "into" {
+        return None;
+    }
+    let trait_impl = ctx.translated.trait_impls.get(*impl_id)?;
+    if !INTO_VIA_FROM_IMPL.matches(&ctx.translated, &trait_impl.item_meta.name) {
+        return None;
+    }
+    let from_ref = impl_generics.trait_refs.iter().find(|tref| {
+        let trait_id = tref.trait_decl_ref.skip_binder.trait_id;
+        ctx.translated
+            .item_name(trait_id)
+            .is_some_and(|name| FROM_TRAIT.matches(&ctx.translated, name))
+    })?;
+    Some((from_ref, TraitItemName("from".to_owned())))
+}
+
 fn transform_call(ctx: &mut TransformCtx, span: Span, call: &mut Call) {
     // We find calls to a trait method where the impl is known; otherwise we return.
     let FnOperand::Regular(fn_ptr) = &mut call.func else {
@@ -10,6 +47,8 @@ fn transform_call(ctx: &mut TransformCtx, span: Span, call: &mut Call) {
     let FunIdOrTraitMethodRef::Trait(trait_ref, name, _) = &fn_ptr.func else {
         return;
     };
+    let (trait_ref, name) =
+        into_via_from(ctx, trait_ref, name).unwrap_or((trait_ref, name.clone()));
     let TraitRefKind::TraitImpl(impl_id, impl_generics) = &trait_ref.kind else {
         return;
     };
@@ -17,7 +56,7 @@ fn transform_call(ctx: &mut TransformCtx, span: Span, call: &mut Call) {
         return;
     };
     // Find the function declaration corresponding to this impl.
-    let Some((_, bound_fn)) = trait_impl.methods().find(|(n, _)| n == name) else {
+    let Some((_, bound_fn)) = trait_impl.methods().find(|(n, _)| *n == name) else {
         return;
     };
     let method_generics = &fn_ptr.generics;
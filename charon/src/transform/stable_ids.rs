@@ -0,0 +1,165 @@
+//! Renumber the items so that their ids only depend on the items themselves and not on the order
+//! in which rustc let us explore them. Within each kind of item, the ids are allocated in the
+//! order of the following key:
+//! 1. the name of the item, as printed (e.g. `crate::module::{impl Trait for Type}::method`);
+//! 2. the name of the file the item is defined in, then the line and column where it starts, to
+//!    distinguish items that have the same name.
+//! This makes the output of charon diffable across runs. This pass only runs when `--stable-ids`
+//! is set.
+use std::collections::HashMap;
+
+use derive_generic_visitor::*;
+
+use crate::ast::*;
+use crate::formatter::IntoFormatter;
+use crate::pretty::FmtWithCtx;
+use crate::transform::TransformCtx;
+
+use super::ctx::TransformPass;
+
+#[derive(Visitor)]
+struct RenameIds<'a> {
+    new_ids: &'a HashMap<AnyTransId, AnyTransId>,
+}
+
+impl RenameIds<'_> {
+    fn rename_any(&self, id: AnyTransId) -> AnyTransId {
+        self.new_ids.get(&id).copied().unwrap_or(id)
+    }
+    fn rename<Id: Copy + Into<AnyTransId> + TryFrom<AnyTransId>>(&self, id: &mut Id) {
+        if let Ok(new_id) = self.rename_any((*id).into()).try_into() {
+            *id = new_id;
+        }
+    }
+}
+
+impl VisitAstMut for RenameIds<'_> {
+    fn enter_type_decl_id(&mut self, id: &mut TypeDeclId) {
+        self.rename(id)
+    }
+    fn enter_fun_decl_id(&mut self, id: &mut FunDeclId) {
+        self.rename(id)
+    }
+    fn enter_global_decl_id(&mut self, id: &mut GlobalDeclId) {
+        self.rename(id)
+    }
+    fn enter_trait_decl_id(&mut self, id: &mut TraitDeclId) {
+        self.rename(id)
+    }
+    fn enter_trait_impl_id(&mut self, id: &mut TraitImplId) {
+        self.rename(id)
+    }
+    fn enter_generic_args(&mut self, args: &mut GenericArgs) {
+        // `target` isn't explored by the visitor.
+        match &mut args.target {
+            GenericsSource::Item(id) => *id = self.rename_any(*id),
+            GenericsSource::Method(id, _) | GenericsSource::TraitType(id, _) => self.rename(id),
+            GenericsSource::Builtin | GenericsSource::Other => {}
+        }
+    }
+}
+
+/// Move the items of `vector` to their new ids, and update their `def_id`.
+fn renumber<Id: Idx + Copy + Into<AnyTransId> + TryFrom<AnyTransId>, T>(
+    vector: &mut Vector<Id, T>,
+    count: usize,
+    renamer: &RenameIds<'_>,
+    def_id: impl Fn(&mut T) -> &mut Id,
+) {
+    let mut new_vector = Vector::new();
+    for _ in 0..count {
+        new_vector.reserve_slot();
+    }
+    for mut item in std::mem::take(vector) {
+        let id = def_id(&mut item);
+        renamer.rename(id);
+        let id = *id;
+        new_vector.set_slot(id, item);
+    }
+    *vector = new_vector;
+}
+
+pub struct Transform;
+impl TransformPass for Transform {
+    fn transform_ctx(&self, ctx: &mut TransformCtx) {
+        if !ctx.options.stable_ids {
+            return;
+        }
+        let krate = &ctx.translated;
+        let fmt_ctx = &ctx.into_fmt();
+        let sort_key = |id: &AnyTransId| {
+            let name = krate.item_names[id].fmt_with_ctx(fmt_ctx);
+            let span = krate.get_item(*id).map(|item| item.item_meta().span.span);
+            let location = span.map(|span| {
+                let file = krate
+                    .files
+                    .get(span.file_id)
+                    .map(|file| file.name.to_string());
+                (file, span.beg.line, span.beg.col)
+            });
+            (name, location)
+        };
+        let mut ids: Vec<AnyTransId> = krate.item_names.keys().copied().collect();
+        ids.sort_by_cached_key(sort_key);
+
+        // Allocate the new ids, kind by kind.
+        let mut counts = [0usize; 5];
+        let new_ids: HashMap<AnyTransId, AnyTransId> = ids
+            .iter()
+            .map(|&id| {
+                let count = &mut counts[id.variant_index_arity().0 as usize];
+                let new_id = match id {
+                    AnyTransId::Type(_) => AnyTransId::Type(TypeDeclId::new(*count)),
+                    AnyTransId::Fun(_) => AnyTransId::Fun(FunDeclId::new(*count)),
+                    AnyTransId::Global(_) => AnyTransId::Global(GlobalDeclId::new(*count)),
+                    AnyTransId::TraitDecl(_) => AnyTransId::TraitDecl(TraitDeclId::new(*count)),
+                    AnyTransId::TraitImpl(_) => AnyTransId::TraitImpl(TraitImplId::new(*count)),
+                };
+                *count += 1;
+                (id, new_id)
+            })
+            .collect();
+
+        let mut renamer = RenameIds { new_ids: &new_ids };
+        let krate = &mut ctx.translated;
+        krate.drive_mut(&mut renamer);
+        let [types, funs, globals, trait_decls, trait_impls] = counts;
+        renumber(&mut krate.type_decls, types, &renamer, |d| &mut d.def_id);
+        renumber(&mut krate.fun_decls, funs, &renamer, |d| &mut d.def_id);
+        renumber(&mut krate.global_decls, globals, &renamer, |d| {
+            &mut d.def_id
+        });
+        renumber(&mut krate.trait_decls, trait_decls, &renamer, |d| {
+            &mut d.def_id
+        });
+        renumber(&mut krate.trait_impls, trait_impls, &renamer, |d| {
+            &mut d.def_id
+        });
+        krate.item_names = std::mem::take(&mut krate.item_names)
+            .into_iter()
+            .map(|(id, name)| (renamer.rename_any(id), name))
+            .collect();
+        // The items are also listed in the order of the key, which `reorder_decls` then follows
+        // when it doesn't need to reorder.
+        krate.all_ids = ids
+            .into_iter()
+            .filter(|id| krate.all_ids.contains(id))
+            .map(|id| renamer.rename_any(id))
+            .collect();
+        ctx.errors
+            .borrow_mut()
+            .rename_ids(|id| renamer.rename_any(id));
+        ctx.rustc_def_ids = std::mem::take(&mut ctx.rustc_def_ids)
+            .into_iter()
+            .map(|(id, def_id)| (renamer.rename_any(id), def_id))
+            .collect();
+        ctx.type_layouts = std::mem::take(&mut ctx.type_layouts)
+            .into_iter()
+            .map(|(id, layout)| (*renamer.rename_any(id.into()).as_type().unwrap(), layout))
+            .collect();
+        ctx.derived_impls = std::mem::take(&mut ctx.derived_impls)
+            .into_iter()
+            .map(|id| *renamer.rename_any(id.into()).as_trait_impl().unwrap())
+            .collect();
+    }
+}
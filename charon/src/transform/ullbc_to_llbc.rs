@@ -1465,6 +1465,8 @@ fn translate_statement(locals: &Locals, st: &src::Statement) -> Option<tgt::Stat
         src::RawStatement::Drop(place) => tgt::RawStatement::Drop(place),
         src::RawStatement::Assert(assert) => tgt::RawStatement::Assert(assert),
         src::RawStatement::Nop => tgt::RawStatement::Nop,
+        src::RawStatement::EnterUnsafe => tgt::RawStatement::EnterUnsafe,
+        src::RawStatement::ExitUnsafe => tgt::RawStatement::ExitUnsafe,
         src::RawStatement::Error(s) => tgt::RawStatement::Error(s),
     };
     Some(tgt::Statement::new(src_span, st))
@@ -1543,7 +1545,7 @@ fn translate_terminator(
                     // We link block ids to:
                     // - vector of matched integer values
                     // - translated blocks
-                    let mut branches: IndexMap<src::BlockId, (Vec<ScalarValue>, tgt::Block)> =
+                    let mut branches: IndexMap<src::BlockId, (Vec<Literal>, tgt::Block)> =
                         IndexMap::new();
 
                     // Translate the children expressions
@@ -1554,7 +1556,7 @@ fn translate_terminator(
                             // Already translated: add the matched value to
                             // the list of values
                             let branch = branches.get_mut(bid).unwrap();
-                            branch.0.push(*v);
+                            branch.0.push(v.clone());
                         } else {
                             // Not translated: translate it
                             let block = translate_child_block(
@@ -1567,10 +1569,10 @@ fn translate_terminator(
                             // We use the terminator span information in case then
                             // then statement is `None`
                             let block = opt_block_unwrap_or_nop(terminator.span, block);
-                            branches.insert(*bid, (vec![*v], block));
+                            branches.insert(*bid, (vec![v.clone()], block));
                         }
                     }
-                    let targets_blocks: Vec<(Vec<ScalarValue>, tgt::Block)> =
+                    let targets_blocks: Vec<(Vec<Literal>, tgt::Block)> =
                         branches.into_iter().map(|(_, x)| x).collect();
 
                     let otherwise_block = translate_child_block(
@@ -1587,6 +1589,34 @@ fn translate_terminator(
                     // Translate
                     tgt::Switch::SwitchInt(discr.clone(), *int_ty, targets_blocks, otherwise_block)
                 }
+                src::SwitchTargets::SwitchRange(ty, targets, otherwise) => {
+                    // [crate::transform::reconstruct_range_matches] makes sure the branches are
+                    // distinct blocks, so we don't need to group them like above.
+                    let targets_blocks: Vec<(LiteralRange, tgt::Block)> = targets
+                        .iter()
+                        .map(|(range, bid)| {
+                            let block = translate_child_block(
+                                info,
+                                parent_loops,
+                                switch_exit_blocks,
+                                terminator.span,
+                                *bid,
+                            );
+                            let block = opt_block_unwrap_or_nop(terminator.span, block);
+                            (range.clone(), block)
+                        })
+                        .collect();
+                    let otherwise_block = translate_child_block(
+                        info,
+                        parent_loops,
+                        switch_exit_blocks,
+                        terminator.span,
+                        *otherwise,
+                    );
+                    let otherwise_block = opt_block_unwrap_or_nop(terminator.span, otherwise_block);
+
+                    tgt::Switch::SwitchRange(discr.clone(), *ty, targets_blocks, otherwise_block)
+                }
             };
 
             // Return
@@ -1603,7 +1633,7 @@ fn translate_terminator(
 /// - a panic or return
 /// - a break which goes to a loop outside the expression
 /// - a continue statement
-fn is_terminal(block: &tgt::Block) -> bool {
+pub(crate) fn is_terminal(block: &tgt::Block) -> bool {
     is_terminal_explore_block(0, block)
 }
 
@@ -1616,6 +1646,10 @@ fn is_terminal_explore(num_loops: usize, st: &tgt::Statement) -> bool {
         | tgt::RawStatement::Assert(_)
         | tgt::RawStatement::Call(_)
         | tgt::RawStatement::Nop
+        | tgt::RawStatement::EnterUnsafe
+        | tgt::RawStatement::ExitUnsafe
+        | tgt::RawStatement::LetElse { .. }
+        | tgt::RawStatement::While { .. }
         | tgt::RawStatement::Error(_) => false,
         tgt::RawStatement::Abort(..) | tgt::RawStatement::Return => true,
         tgt::RawStatement::Break(index) => *index >= num_loops,
@@ -1623,6 +1657,14 @@ fn is_terminal_explore(num_loops: usize, st: &tgt::Statement) -> bool {
         tgt::RawStatement::Switch(switch) => switch
             .iter_targets()
             .all(|tgt_st| is_terminal_explore_block(num_loops, tgt_st)),
+        tgt::RawStatement::IfLetChain {
+            then_block,
+            else_block,
+            ..
+        } => {
+            is_terminal_explore_block(num_loops, then_block)
+                && is_terminal_explore_block(num_loops, else_block)
+        }
         tgt::RawStatement::Loop(loop_st) => is_terminal_explore_block(num_loops + 1, loop_st),
     }
 }
@@ -52,6 +52,19 @@ fn transform_function(_ctx: &TransformCtx, def: &mut FunDecl) -> Result<(), Erro
             depth: 0,
         };
         inputs[0].drive_mut(&mut visitor);
+        // Keep the state types in sync with the signature, so that they mention the regions we
+        // just introduced. This matters e.g. for methods on `&mut self`, where the state contains
+        // a `&mut` borrow of (a field of) the receiver.
+        let state_ty = match info.kind {
+            ClosureKind::FnOnce => &inputs[0],
+            ClosureKind::Fn | ClosureKind::FnMut => match inputs[0].kind() {
+                TyKind::Ref(_, state_ty, _) => state_ty,
+                _ => unreachable!(),
+            },
+        };
+        if let TyKind::Adt(TypeId::Tuple, args) = state_ty.kind() {
+            info.state = args.types.clone();
+        }
 
         // Update the body.
         // We change the type of the local variable of index 1, which is a reference to the closure
@@ -0,0 +1,168 @@
+//! Check some invariants of the translated crate, to catch translation bugs early. This doesn't
+//! modify the crate; it only runs when `--validate` is set. We check that:
+//! - the items referenced anywhere in the crate exist;
+//! - the locals used in the bodies are declared;
+//! - the blocks targeted by the terminators of unstructured bodies exist.
+//!
+//! The number of generic arguments is always checked by [super::check_generics].
+use derive_generic_visitor::*;
+
+use crate::{ast::*, errors::Level, formatter::IntoFormatter, pretty::FmtWithCtx};
+
+use super::{ctx::TransformPass, TransformCtx};
+
+#[derive(Visitor)]
+struct CheckItemIds<'a> {
+    ctx: &'a TransformCtx,
+    /// The name of the item we are checking, for error reporting.
+    item_name: String,
+    /// Tracks an enclosing span for error reporting.
+    span: Span,
+}
+
+impl CheckItemIds<'_> {
+    fn check_exists(&self, id: AnyTransId) {
+        if self.ctx.translated.get_item(id).is_some()
+            // The items we failed to translate have already been reported.
+            || self.ctx.errors.borrow().ignored_failed_decls.contains(&id)
+        {
+            return;
+        }
+        let msg = format!(
+            "Validation failed: `{}` refers to the missing item `{id:?}`",
+            self.item_name
+        );
+        self.ctx.span_err(self.span, &msg, Level::Error);
+    }
+}
+
+impl VisitAst for CheckItemIds<'_> {
+    fn enter_type_decl_id(&mut self, id: &TypeDeclId) {
+        self.check_exists((*id).into())
+    }
+    fn enter_fun_decl_id(&mut self, id: &FunDeclId) {
+        self.check_exists((*id).into())
+    }
+    fn enter_global_decl_id(&mut self, id: &GlobalDeclId) {
+        self.check_exists((*id).into())
+    }
+    fn enter_trait_decl_id(&mut self, id: &TraitDeclId) {
+        self.check_exists((*id).into())
+    }
+    fn enter_trait_impl_id(&mut self, id: &TraitImplId) {
+        self.check_exists((*id).into())
+    }
+
+    fn visit_ullbc_statement(&mut self, st: &ullbc_ast::Statement) -> ControlFlow<Self::Break> {
+        // Track span for more precise error messages.
+        let old_span = self.span;
+        self.span = st.span;
+        self.visit_inner(st)?;
+        self.span = old_span;
+        Continue(())
+    }
+
+    fn visit_llbc_statement(&mut self, st: &llbc_ast::Statement) -> ControlFlow<Self::Break> {
+        // Track span for more precise error messages.
+        let old_span = self.span;
+        self.span = st.span;
+        self.visit_inner(st)?;
+        self.span = old_span;
+        Continue(())
+    }
+}
+
+#[derive(Visitor)]
+struct CheckBody<'a> {
+    ctx: &'a TransformCtx,
+    /// The name of the function we are checking, for error reporting.
+    item_name: String,
+    locals: &'a Locals,
+    /// The blocks of the body, if it is unstructured.
+    blocks: Option<&'a Vector<ullbc_ast::BlockId, ullbc_ast::BlockData>>,
+    /// Tracks an enclosing span for error reporting.
+    span: Span,
+}
+
+impl CheckBody<'_> {
+    fn error(&self, message: String) {
+        let msg = format!("Validation failed in `{}`: {message}", self.item_name);
+        self.ctx.span_err(self.span, &msg, Level::Error);
+    }
+}
+
+impl VisitBody for CheckBody<'_> {
+    fn enter_place(&mut self, place: &Place) {
+        if let PlaceKind::Base(var_id) = &place.kind
+            && self.locals.vars.get(*var_id).is_none()
+        {
+            self.error(format!("the local `{var_id}` is not declared"))
+        }
+    }
+
+    fn enter_ullbc_block_id(&mut self, block_id: &ullbc_ast::BlockId) {
+        if let Some(blocks) = self.blocks
+            && blocks.get(*block_id).is_none()
+        {
+            self.error(format!("the block `{block_id}` doesn't exist"))
+        }
+    }
+
+    fn visit_ullbc_statement(&mut self, st: &ullbc_ast::Statement) -> ControlFlow<Self::Break> {
+        let old_span = self.span;
+        self.span = st.span;
+        self.visit_inner(st)?;
+        self.span = old_span;
+        Continue(())
+    }
+
+    fn visit_ullbc_terminator(&mut self, t: &ullbc_ast::Terminator) -> ControlFlow<Self::Break> {
+        let old_span = self.span;
+        self.span = t.span;
+        self.visit_inner(t)?;
+        self.span = old_span;
+        Continue(())
+    }
+
+    fn visit_llbc_statement(&mut self, st: &llbc_ast::Statement) -> ControlFlow<Self::Break> {
+        let old_span = self.span;
+        self.span = st.span;
+        self.visit_inner(st)?;
+        self.span = old_span;
+        Continue(())
+    }
+}
+
+pub struct Check;
+impl TransformPass for Check {
+    fn transform_ctx(&self, ctx: &mut TransformCtx) {
+        let fmt_ctx = &ctx.into_fmt();
+        for item in ctx.translated.all_items() {
+            let item_meta = item.item_meta();
+            let item_name = item_meta.name.fmt_with_ctx(fmt_ctx);
+            let mut visitor = CheckItemIds {
+                ctx,
+                item_name: item_name.clone(),
+                span: item_meta.span,
+            };
+            item.drive(&mut visitor);
+
+            if let AnyTransItem::Fun(decl) = item
+                && let Ok(body) = &decl.body
+            {
+                let (locals, blocks) = match body {
+                    Body::Unstructured(body) => (&body.locals, Some(&body.body)),
+                    Body::Structured(body) => (&body.locals, None),
+                };
+                let mut visitor = CheckBody {
+                    ctx,
+                    item_name,
+                    locals,
+                    blocks,
+                    span: item_meta.span,
+                };
+                body.drive_body(&mut visitor);
+            }
+        }
+    }
+}
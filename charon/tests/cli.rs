@@ -280,3 +280,1497 @@ fn charon_input() -> Result<()> {
     ];
     charon(args, "tests/ui", |_, _| Ok(()))
 }
+
+#[test]
+fn charon_extern_flags() -> Result<()> {
+    // Build the dependency by hand, outside of cargo.
+    let dep_dir = tempfile::TempDir::new()?;
+    let rlib = dep_dir.path().join("libforeign_constant_aux.rlib");
+    let output = Command::new("rustc")
+        .args([
+            "--crate-type=rlib",
+            "--crate-name=foreign_constant_aux",
+            "-o",
+        ])
+        .arg(&rlib)
+        .arg("tests/ui/foreign-constant-aux.rs")
+        .output()?;
+    ensure!(
+        output.status.success(),
+        "building the dependency failed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let extern_arg = format!("foreign_constant_aux={}", rlib.display());
+    let lib_path = dep_dir.path().display().to_string();
+    let args = &[
+        "rustc",
+        "--print-llbc",
+        "--no-serialize",
+        "--extern",
+        &extern_arg,
+        "-L",
+        &lib_path,
+        "--",
+        "--crate-type=lib",
+        "--edition=2021",
+        "tests/ui/foreign-constant.rs",
+    ];
+    charon(args, ".", |stdout, cmd| {
+        let search = "foreign_constant_aux::CONSTANT";
+        ensure!(
+            stdout.contains(search),
+            "Output of `{cmd}` is:\n{stdout:?}\nIt doesn't contain {search:?}."
+        );
+        Ok(())
+    })
+}
+
+#[test]
+fn charon_version_check() -> Result<()> {
+    charon(&["--version-check"], ".", |stdout, cmd| {
+        ensure!(
+            stdout.contains("The toolchain matches"),
+            "Unexpected output of `{cmd}`:\n{stdout}"
+        );
+        Ok(())
+    })
+}
+
+#[test]
+fn charon_json_diagnostics() -> Result<()> {
+    let dir = tempfile::TempDir::new()?;
+    let diagnostics_file = dir.path().join("diagnostics.jsonl");
+    let args = &[
+        "--no-cargo",
+        "--rustc-flag=--crate-type=rlib",
+        "--no-serialize",
+        "--error-format=json",
+        "--error-output",
+        diagnostics_file.to_str().unwrap(),
+        "--input",
+        "tests/ui/rename_attribute_failure.rs",
+    ];
+    charon(args, ".", |_, cmd| {
+        let diagnostics = std::fs::read_to_string(&diagnostics_file)?;
+        let diagnostics: Vec<serde_json::Value> = diagnostics
+            .lines()
+            .map(serde_json::from_str)
+            .try_collect()?;
+        ensure!(!diagnostics.is_empty(), "`{cmd}` emitted no diagnostics");
+        for diag in diagnostics {
+            ensure!(diag["level"] == "warning", "unexpected diagnostic: {diag}");
+            ensure!(diag["message"].is_string(), "unexpected diagnostic: {diag}");
+            ensure!(
+                diag["span"]["file"] == "tests/ui/rename_attribute_failure.rs",
+                "unexpected diagnostic: {diag}"
+            );
+            ensure!(diag.get("code").is_some(), "unexpected diagnostic: {diag}");
+        }
+        Ok(())
+    })
+}
+
+#[test]
+fn charon_bad_error_output() -> Result<()> {
+    let dir = tempfile::TempDir::new()?;
+    let diagnostics_file = dir.path().join("missing_dir/diagnostics.jsonl");
+    let output = Command::cargo_bin("charon")?
+        .args([
+            "--no-cargo",
+            "--rustc-flag=--crate-type=rlib",
+            "--no-serialize",
+            "--error-format=json",
+            "--error-output",
+            diagnostics_file.to_str().unwrap(),
+            "--input",
+            "tests/ui/arrays.rs",
+        ])
+        .output()?;
+    let stderr = String::from_utf8(output.stderr)?;
+    // We report the bad path instead of panicking.
+    ensure!(output.status.code() == Some(1), "{stderr}");
+    ensure!(stderr.contains("Could not create"), "{stderr}");
+    ensure!(!stderr.contains("panicked"), "{stderr}");
+    Ok(())
+}
+
+#[test]
+fn charon_opaque_manifest() -> Result<()> {
+    let dir = tempfile::TempDir::new()?;
+    let manifest_file = dir.path().join("opaque.json");
+    let args = &[
+        "--no-cargo",
+        "--rustc-flag=--crate-type=rlib",
+        "--rustc-flag=--crate-name=test_crate",
+        "--no-serialize",
+        "--emit-opaque-manifest",
+        manifest_file.to_str().unwrap(),
+        "--input",
+        "tests/ui/opaque_attribute.rs",
+    ];
+    charon(args, ".", |_, cmd| {
+        let manifest = std::fs::read_to_string(&manifest_file)?;
+        let manifest: serde_json::Value = serde_json::from_str(&manifest)?;
+        let local_items = manifest["test_crate"]
+            .as_array()
+            .with_context(|| format!("`{cmd}` listed no opaque item for `test_crate`"))?;
+        let find = |name: &str| {
+            local_items
+                .iter()
+                .find(|item| item["name"] == name)
+                .with_context(|| format!("`{cmd}` didn't list `{name}`: {manifest}"))
+        };
+        let item = find("test_crate::test_bool_trait_option")?;
+        ensure!(item["kind"] == "function", "unexpected item: {item}");
+        ensure!(item["reason"] == "marked_opaque", "unexpected item: {item}");
+        let item = find("test_crate::opaque::fn_in_opaque_module")?;
+        ensure!(item["reason"] == "marked_opaque", "unexpected item: {item}");
+        // Items with a body are not listed.
+        ensure!(
+            find("test_crate::call_fn_in_opaque_module").is_err(),
+            "`{cmd}` listed a transparent function"
+        );
+        Ok(())
+    })
+}
+
+#[test]
+fn charon_symbol_table() -> Result<()> {
+    let dir = tempfile::TempDir::new()?;
+    let table_file = dir.path().join("symbols.json");
+    let args = &[
+        "--no-cargo",
+        "--rustc-flag=--crate-type=rlib",
+        "--rustc-flag=--crate-name=test_crate",
+        "--no-serialize",
+        "--emit-symbol-table",
+        table_file.to_str().unwrap(),
+        "--input",
+        "tests/ui/opaque_attribute.rs",
+    ];
+    charon(args, ".", |_, cmd| {
+        let table = std::fs::read_to_string(&table_file)?;
+        let table: serde_json::Value = serde_json::from_str(&table)?;
+        let symbol = table
+            .as_array()
+            .with_context(|| format!("`{cmd}` didn't write a list: {table}"))?
+            .iter()
+            .find(|item| item["name"] == "test_crate::call_fn_in_opaque_module")
+            .with_context(|| format!("`{cmd}` didn't list the function: {table}"))?;
+        ensure!(symbol["kind"] == "function", "unexpected item: {symbol}");
+        let def_id = symbol["def_id"].as_str().unwrap_or_default();
+        ensure!(
+            def_id.ends_with("::call_fn_in_opaque_module"),
+            "unexpected def id: {symbol}"
+        );
+        let file = symbol["span"]["file"].as_str().unwrap_or_default();
+        ensure!(
+            file.ends_with("opaque_attribute.rs"),
+            "unexpected span: {symbol}"
+        );
+        Ok(())
+    })
+}
+
+#[test]
+fn charon_symbol_table_stable_ids() -> Result<()> {
+    // The items are renumbered by `--stable-ids`; the rustc ids must follow them.
+    let dir = tempfile::TempDir::new()?;
+    let table_file = dir.path().join("symbols.json");
+    let args = &[
+        "--no-cargo",
+        "--rustc-flag=--crate-type=rlib",
+        "--rustc-flag=--crate-name=test_crate",
+        "--no-serialize",
+        "--stable-ids",
+        "--emit-symbol-table",
+        table_file.to_str().unwrap(),
+        "--input",
+        "tests/ui/opaque_attribute.rs",
+    ];
+    charon(args, ".", |_, cmd| {
+        let table = std::fs::read_to_string(&table_file)?;
+        let table: serde_json::Value = serde_json::from_str(&table)?;
+        let symbols = table
+            .as_array()
+            .with_context(|| format!("`{cmd}` didn't write a list: {table}"))?;
+        let mut checked = 0;
+        for symbol in symbols {
+            let name = symbol["name"].as_str().unwrap_or_default();
+            let Some(ident) = name.strip_prefix("test_crate::") else {
+                continue;
+            };
+            // Only look at the items whose name is a plain path.
+            if ident.contains(['{', '<']) {
+                continue;
+            }
+            let ident = ident.rsplit("::").next().unwrap();
+            let def_id = symbol["def_id"].as_str().unwrap_or_default();
+            ensure!(
+                def_id.ends_with(&format!("::{ident}")),
+                "unexpected def id: {symbol}"
+            );
+            checked += 1;
+        }
+        ensure!(checked > 1, "`{cmd}` didn't list the items: {table}");
+        Ok(())
+    })
+}
+
+#[test]
+fn charon_type_sizes() -> Result<()> {
+    let dir = tempfile::TempDir::new()?;
+    let input = dir.path().join("type_sizes.rs");
+    std::fs::write(
+        &input,
+        r#"
+        pub struct Pair {
+            x: u32,
+            y: u8,
+        }
+        pub struct Generic<T>(T);
+        "#,
+    )?;
+    let sizes_file = dir.path().join("type_sizes.json");
+    let args = &[
+        "--no-cargo",
+        "--rustc-flag=--crate-type=rlib",
+        "--no-serialize",
+        "--emit-type-sizes",
+        sizes_file.to_str().unwrap(),
+        "--input",
+        input.to_str().unwrap(),
+    ];
+    charon(args, ".", |_, cmd| {
+        let sizes = std::fs::read_to_string(&sizes_file)?;
+        let sizes: serde_json::Value = serde_json::from_str(&sizes)?;
+        let sizes = sizes
+            .as_object()
+            .with_context(|| format!("`{cmd}` didn't write a map: {sizes}"))?;
+        // `Generic` has no layout on its own.
+        let layouts = sizes.values().collect_vec();
+        ensure!(
+            layouts == [&serde_json::json!({ "size": 8, "align": 4 })],
+            "unexpected type sizes: {sizes:?}"
+        );
+        Ok(())
+    })
+}
+
+#[test]
+fn charon_dependencies() -> Result<()> {
+    let dir = tempfile::TempDir::new()?;
+    let deps_file = dir.path().join("dependencies.json");
+    let args = &[
+        "cargo",
+        "--no-serialize",
+        "--emit-dependencies",
+        deps_file.to_str().unwrap(),
+        "--",
+        "-F",
+        "test_feature",
+    ];
+    charon(args, "tests/cargo/dependencies", |_, cmd| {
+        let deps = std::fs::read_to_string(&deps_file)?;
+        let deps: serde_json::Value = serde_json::from_str(&deps)?;
+        let deps = deps
+            .as_array()
+            .with_context(|| format!("`{cmd}` didn't write a list: {deps:?}"))?;
+        let find = |name: &str| deps.iter().find(|dep| dep["name"] == name);
+        let take_mut =
+            find("take_mut").with_context(|| format!("`{cmd}` didn't list `take_mut`"))?;
+        ensure!(
+            take_mut["versions"] == serde_json::json!(["0.2.2"]),
+            "unexpected dependency: {take_mut}"
+        );
+        ensure!(
+            find("test_cargo_dependencies").is_none(),
+            "`{cmd}` listed the translated crate"
+        );
+        Ok(())
+    })
+}
+
+#[test]
+fn charon_models() -> Result<()> {
+    use charon_lib::ast::*;
+    let dir = tempfile::TempDir::new()?;
+    let input = dir.path().join("models.rs");
+    std::fs::write(
+        &input,
+        r#"
+        pub fn answer() -> u32 { 42 }
+        pub fn double(x: u32) -> u32 { 2 * x }
+        pub fn seven() -> u32 { 7 }
+        pub fn identity(x: u64) -> u64 { x }
+        "#,
+    )?;
+    let ullbc_file = dir.path().join("models.ullbc");
+    let run = |extra_args: &[&str]| -> Result<TranslatedCrate> {
+        let mut args = vec![
+            "--no-cargo",
+            "--rustc-flag=--crate-type=rlib",
+            "--rustc-flag=--crate-name=test_crate",
+            "--ullbc",
+            "--opaque=crate::answer",
+            "--opaque=crate::double",
+            "--dest-file",
+            ullbc_file.to_str().unwrap(),
+            "--input",
+            input.to_str().unwrap(),
+        ];
+        args.extend(extra_args);
+        charon(&args, ".", |_, _| charon_lib::deserialize_llbc(&ullbc_file))
+    };
+    let find = |krate: &TranslatedCrate, name: &str| -> Result<FunDecl> {
+        krate
+            .fun_decls
+            .iter()
+            .find(|decl| {
+                let last = decl.item_meta.name.name.last();
+                matches!(last, Some(PathElem::Ident(s, _)) if s == name)
+            })
+            .cloned()
+            .with_context(|| format!("`{name}` not found"))
+    };
+
+    // Use the bodies of `seven` and `identity` as models for the opaque functions.
+    let krate = run(&[])?;
+    ensure!(find(&krate, "answer")?.body.is_err());
+    let seven = find(&krate, "seven")?.body.unwrap();
+    let identity = find(&krate, "identity")?.body.unwrap();
+    let models = serde_json::json!([
+        { "name": "test_crate::answer", "body": seven },
+        // Rejected: `double` takes a `u32`, not a `u64`.
+        { "name": "test_crate::double", "body": identity },
+    ]);
+    let models_file = dir.path().join("models.json");
+    std::fs::write(&models_file, models.to_string())?;
+
+    let krate = run(&["--models", models_file.to_str().unwrap()])?;
+    let answer = find(&krate, "answer")?;
+    let Ok(Body::Unstructured(body)) = &answer.body else {
+        anyhow::bail!("the model wasn't applied to `answer`")
+    };
+    ensure!(body.locals.arg_count == 0);
+    ensure!(find(&krate, "double")?.body.is_err());
+    Ok(())
+}
+
+#[test]
+fn charon_split_by_kind() -> Result<()> {
+    let dir = tempfile::TempDir::new()?;
+    let single_file = dir.path().join("single.llbc");
+    let split_dir = dir.path().join("split");
+    let input = "tests/ui/traits.rs";
+    let args = &[
+        "--no-cargo",
+        "--rustc-flag=--crate-type=rlib",
+        "--dest-file",
+        single_file.to_str().unwrap(),
+        "--input",
+        input,
+    ];
+    charon(args, ".", |_, _| Ok(()))?;
+    let args = &[
+        "--no-cargo",
+        "--rustc-flag=--crate-type=rlib",
+        "--split-by-kind",
+        split_dir.to_str().unwrap(),
+        "--input",
+        input,
+    ];
+    charon(args, ".", |_, cmd| {
+        for kind in ["index", "types", "funs", "globals", "traits"] {
+            let file = split_dir.join(format!("{kind}.llbc"));
+            ensure!(file.exists(), "`{cmd}` didn't write {}", file.display());
+        }
+        let types = charon_lib::deserialize_llbc(&split_dir.join("types.llbc"))?;
+        ensure!(!types.type_decls.is_empty() && types.fun_decls.is_empty());
+        // Merging the parts gives back the same crate as a single-file translation.
+        let single = charon_lib::deserialize_llbc(&single_file)?;
+        let merged = charon_lib::deserialize_split_llbc(&split_dir)?;
+        ensure!(merged.to_string() == single.to_string());
+        ensure!(merged.ordered_decls.is_some());
+        Ok(())
+    })
+}
+
+#[test]
+fn charon_group_by_module() -> Result<()> {
+    let dir = tempfile::TempDir::new()?;
+    let input = dir.path().join("grouped.rs");
+    std::fs::write(
+        &input,
+        r#"
+        pub mod foo {
+            pub mod bar {
+                pub fn baz() {}
+            }
+        }
+        pub fn top() {}
+        "#,
+    )?;
+    let file = dir.path().join("grouped.llbc");
+    let args = &[
+        "--no-cargo",
+        "--rustc-flag=--crate-type=rlib",
+        "--group-by-module",
+        "--dest-file",
+        file.to_str().unwrap(),
+        "--input",
+        input.to_str().unwrap(),
+    ];
+    charon(args, ".", |_, cmd| {
+        let krate = charon_lib::deserialize_llbc(&file)?;
+        let id_of = |name: &[&str]| -> Result<serde_json::Value> {
+            let (id, _) = krate
+                .item_names
+                .iter()
+                .find(|(_, n)| n.equals_ref_name(name))
+                .with_context(|| format!("no item named `{}`", name.join("::")))?;
+            Ok(serde_json::to_value(id)?)
+        };
+        let output: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(&file)?)?;
+        // Follow the path from the root group.
+        fn find_group<'a>(
+            output: &'a serde_json::Value,
+            path: &[&str],
+        ) -> Option<&'a serde_json::Value> {
+            let mut group = &output["modules"];
+            for i in 1..=path.len() {
+                group = group["modules"]
+                    .as_array()?
+                    .iter()
+                    .find(|sub| sub["path"] == serde_json::json!(&path[..i]))?;
+            }
+            Some(group)
+        }
+        let bar = find_group(&output, &["grouped", "foo", "bar"])
+            .with_context(|| format!("`{cmd}` didn't group the items of `foo::bar`"))?;
+        ensure!(bar["items"] == serde_json::json!([id_of(&["grouped", "foo", "bar", "baz"])?]));
+        let root = find_group(&output, &["grouped"]).context("missing the crate group")?;
+        let root_items = root["items"].as_array().context("missing items")?;
+        ensure!(root_items.contains(&id_of(&["grouped", "top"])?));
+        Ok(())
+    })
+}
+
+#[test]
+fn charon_output_stdout() -> Result<()> {
+    let dir = tempfile::TempDir::new()?;
+    let file = dir.path().join("file.llbc");
+    let input = "tests/ui/traits.rs";
+    let args = &[
+        "--no-cargo",
+        "--rustc-flag=--crate-type=rlib",
+        "--dest-file",
+        file.to_str().unwrap(),
+        "--input",
+        input,
+    ];
+    charon(args, ".", |_, _| Ok(()))?;
+    let args = &[
+        "--no-cargo",
+        "--rustc-flag=--crate-type=rlib",
+        "--output-stdout",
+        "--input",
+        input,
+    ];
+    charon(args, ".", |stdout, cmd| {
+        // Nothing is written to the default location.
+        ensure!(
+            !std::path::Path::new("traits.llbc").exists(),
+            "`{cmd}` wrote a file"
+        );
+        let from_stdout: serde_json::Value = serde_json::from_str(&stdout)
+            .with_context(|| format!("`{cmd}` didn't output JSON: {stdout}"))?;
+        let from_file: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(&file)?)?;
+        ensure!(from_stdout == from_file);
+        // The output is a valid crate.
+        let stdout_file = dir.path().join("stdout.llbc");
+        std::fs::write(&stdout_file, &stdout)?;
+        let from_stdout = charon_lib::deserialize_llbc(&stdout_file)?;
+        let from_file = charon_lib::deserialize_llbc(&file)?;
+        ensure!(from_stdout.to_string() == from_file.to_string());
+        Ok(())
+    })
+}
+
+#[test]
+fn charon_deps_graphviz() -> Result<()> {
+    let dir = tempfile::TempDir::new()?;
+    let input = dir.path().join("deps.rs");
+    std::fs::write(
+        &input,
+        "pub fn is_even(n: u32) -> bool { n == 0 || is_odd(n - 1) }
+         pub fn is_odd(n: u32) -> bool { n != 0 && is_even(n - 1) }
+         pub fn check(n: u32) -> bool { is_even(n) }",
+    )?;
+    let dot_file = dir.path().join("deps.dot");
+    let args = &[
+        "--no-cargo",
+        "--rustc-flag=--crate-type=rlib",
+        "--no-serialize",
+        "--emit-deps-graphviz",
+        dot_file.to_str().unwrap(),
+        "--input",
+        input.to_str().unwrap(),
+    ];
+    charon(args, ".", |_, cmd| {
+        let dot = std::fs::read_to_string(&dot_file)?;
+        ensure!(dot.starts_with("digraph deps {"), "`{cmd}` wrote: {dot}");
+        let node = |name: &str| {
+            dot.lines()
+                .find(|line| line.contains(&format!("label=\"test_crate::{name}\"")))
+                .and_then(|line| line.split_whitespace().next())
+                .map(str::to_owned)
+                .with_context(|| format!("`{cmd}` has no node for `{name}`: {dot}"))
+        };
+        let (even, odd, check) = (node("is_even")?, node("is_odd")?, node("check")?);
+        ensure!(dot.contains(&format!("{check} -> {even};")));
+        ensure!(dot.contains(&format!("{even} -> {odd};")));
+        // The mutually recursive functions are grouped together.
+        let cluster = dot
+            .split("subgraph cluster_")
+            .nth(1)
+            .and_then(|s| s.split('}').next())
+            .with_context(|| format!("`{cmd}` drew no cluster: {dot}"))?;
+        ensure!(cluster.contains(&format!("{even};")) && cluster.contains(&format!("{odd};")));
+        ensure!(!cluster.contains(&format!("{check};")));
+        Ok(())
+    })
+}
+
+#[test]
+fn charon_normalize_assoc_types() -> Result<()> {
+    let dir = tempfile::TempDir::new()?;
+    let input = dir.path().join("assoc.rs");
+    std::fs::write(
+        &input,
+        "pub trait Foo { type Bar; }
+         pub struct S;
+         impl Foo for S { type Bar = u32; }
+         pub fn concrete(x: <S as Foo>::Bar) -> <S as Foo>::Bar { x }
+         pub fn generic<T: Foo>(x: T::Bar) -> T::Bar { x }",
+    )?;
+    let args = &[
+        "--no-cargo",
+        "--rustc-flag=--crate-type=rlib",
+        "--no-serialize",
+        "--print-llbc",
+        "--normalize-assoc-types",
+        "--input",
+        input.to_str().unwrap(),
+    ];
+    charon(args, ".", |stdout, cmd| {
+        ensure!(
+            stdout.contains("fn test_crate::concrete(@1: u32) -> u32"),
+            "`{cmd}` did not normalize the projection on `S`: {stdout}"
+        );
+        // The projection on a type parameter stays abstract.
+        let generic = stdout
+            .lines()
+            .find(|line| line.contains("fn test_crate::generic"))
+            .with_context(|| format!("`{cmd}` did not print `generic`: {stdout}"))?;
+        ensure!(generic.contains("::Bar"), "`{cmd}` printed: {generic}");
+        Ok(())
+    })
+}
+
+#[test]
+fn charon_max_parallel() -> Result<()> {
+    let print_llbc = |max_parallel: &str| {
+        let args = &[
+            "--no-cargo",
+            "--rustc-flag=--crate-type=rlib",
+            "--print-llbc",
+            "--no-serialize",
+            "--max-parallel",
+            max_parallel,
+            "--input",
+            "tests/ui/arrays.rs",
+        ];
+        charon(args, ".", |stdout, _| Ok(stdout))
+    };
+    // The output doesn't depend on the number of threads.
+    let sequential = print_llbc("1")?;
+    let parallel = print_llbc("4")?;
+    ensure!(
+        sequential == parallel,
+        "`--max-parallel 4` changed the output:\n{parallel}"
+    );
+    Ok(())
+}
+
+#[test]
+fn charon_stable_ids() -> Result<()> {
+    use charon_lib::formatter::IntoFormatter;
+    use charon_lib::pretty::FmtWithCtx;
+    let dir = tempfile::TempDir::new()?;
+    let llbc_file = dir.path().join("traits.llbc");
+    let args = &[
+        "--no-cargo",
+        "--rustc-flag=--crate-type=rlib",
+        "--stable-ids",
+        "--dest-file",
+        llbc_file.to_str().unwrap(),
+        "--input",
+        "tests/ui/traits.rs",
+    ];
+    charon(args, ".", |_, cmd| {
+        let krate = charon_lib::deserialize_llbc(&llbc_file)?;
+        let fmt_ctx = &krate.into_fmt();
+        // The ids of each kind of item follow the order of the names.
+        let check_sorted = |names: Vec<String>| {
+            ensure!(
+                names.is_sorted(),
+                "`{cmd}` didn't allocate ids by name: {names:#?}"
+            );
+            Ok(())
+        };
+        check_sorted(
+            krate
+                .type_decls
+                .iter()
+                .map(|d| d.item_meta.name.fmt_with_ctx(fmt_ctx))
+                .collect(),
+        )?;
+        check_sorted(
+            krate
+                .fun_decls
+                .iter()
+                .map(|d| d.item_meta.name.fmt_with_ctx(fmt_ctx))
+                .collect(),
+        )?;
+        check_sorted(
+            krate
+                .trait_impls
+                .iter()
+                .map(|d| d.item_meta.name.fmt_with_ctx(fmt_ctx))
+                .collect(),
+        )?;
+        Ok(())
+    })
+}
+
+#[test]
+fn charon_edition() -> Result<()> {
+    // `TryFrom` is only in the prelude from edition 2021 onwards.
+    let dir = tempfile::TempDir::new()?;
+    let input = dir.path().join("edition.rs");
+    std::fs::write(
+        &input,
+        "pub fn f(x: u32) -> bool { u8::try_from(x).is_ok() }",
+    )?;
+    let input = input.to_str().unwrap();
+    let dir_path = dir.path().to_str().unwrap();
+
+    let args = &[
+        "rustc",
+        "--no-serialize",
+        "--input",
+        input,
+        "--",
+        "--crate-type=lib",
+    ];
+    // Run from the temporary directory so that no manifest is found.
+    ensure!(charon(args, dir_path, |_, _| Ok(())).is_err());
+
+    let args = &[
+        "rustc",
+        "--no-serialize",
+        "--edition=2021",
+        "--input",
+        input,
+        "--",
+        "--crate-type=lib",
+    ];
+    charon(args, ".", |_, _| Ok(()))?;
+
+    // Without `--edition`, we read it from the manifest of the package that contains the input
+    // file, wherever we run from.
+    std::fs::write(
+        dir.path().join("Cargo.toml"),
+        "[package]\nname = \"edition\"\nedition = \"2021\"\n",
+    )?;
+    let args = &[
+        "rustc",
+        "--no-serialize",
+        "--input",
+        input,
+        "--",
+        "--crate-type=lib",
+    ];
+    charon(args, ".", |_, _| Ok(()))?;
+
+    // The manifest of the current directory is ignored if it doesn't contain the input file.
+    let other_dir = tempfile::TempDir::new()?;
+    let other_input = other_dir.path().join("edition.rs");
+    std::fs::copy(input, &other_input)?;
+    let args = &[
+        "rustc",
+        "--no-serialize",
+        "--input",
+        other_input.to_str().unwrap(),
+        "--",
+        "--crate-type=lib",
+    ];
+    ensure!(charon(args, dir_path, |_, _| Ok(())).is_err());
+    Ok(())
+}
+
+#[test]
+fn charon_crate_name() -> Result<()> {
+    let dir = tempfile::TempDir::new()?;
+    let input = dir.path().join("main.rs");
+    std::fs::write(&input, "pub fn f() {}")?;
+    let args = &[
+        "rustc",
+        "--crate-name=renamed",
+        "--dest",
+        dir.path().to_str().unwrap(),
+        "--input",
+        input.to_str().unwrap(),
+        "--",
+        "--crate-type=lib",
+    ];
+    charon(args, ".", |_, cmd| {
+        let llbc_file = dir.path().join("renamed.llbc");
+        ensure!(
+            llbc_file.exists(),
+            "`{cmd}` didn't write {}",
+            llbc_file.display()
+        );
+        ensure!(!dir.path().join("main.llbc").exists());
+        let krate = charon_lib::deserialize_llbc(&llbc_file)?;
+        ensure!(krate.crate_name == "renamed", "{}", krate.crate_name);
+        let f = krate.fun_decls.iter().next().unwrap();
+        ensure!(f.item_meta.name.equals_ref_name(&["renamed", "f"]));
+        Ok(())
+    })
+}
+
+#[test]
+fn charon_records_cfg() -> Result<()> {
+    use charon_lib::ast::PathElem;
+    let dir = tempfile::TempDir::new()?;
+    let input = dir.path().join("cfg.rs");
+    std::fs::write(
+        &input,
+        "#[cfg(feature = \"extra\")] pub fn extra() {}
+         #[cfg(not(feature = \"extra\"))] pub fn no_extra() {}",
+    )?;
+    let llbc_file = dir.path().join("cfg.llbc");
+    let args = &[
+        "--no-cargo",
+        "--rustc-flag=--crate-type=rlib",
+        "--rustc-flag=--cfg=feature=\"extra\"",
+        "--dest-file",
+        llbc_file.to_str().unwrap(),
+        "--input",
+        input.to_str().unwrap(),
+    ];
+    charon(args, ".", |_, cmd| {
+        let krate = charon_lib::deserialize_llbc(&llbc_file)?;
+        ensure!(
+            krate.cfg.iter().any(|cfg| cfg == "feature=\"extra\""),
+            "`{cmd}` recorded cfg {:?}",
+            krate.cfg
+        );
+        ensure!(krate.cfg.is_sorted());
+        // Only the items enabled by the cfg are translated.
+        let has_item = |ident: &str| {
+            krate
+                .item_names
+                .values()
+                .any(|name| matches!(name.name.last(), Some(PathElem::Ident(s, _)) if s == ident))
+        };
+        ensure!(has_item("extra") && !has_item("no_extra"));
+        Ok(())
+    })
+}
+
+#[test]
+fn charon_check() -> Result<()> {
+    let dir = tempfile::TempDir::new()?;
+    let llbc_file = dir.path().join("check.llbc");
+    let check = |input: &str| -> Result<std::process::Output> {
+        Ok(Command::cargo_bin("charon")?
+            .args([
+                "--no-cargo",
+                "--rustc-flag=--crate-type=rlib",
+                "--check",
+                "--dest-file",
+                llbc_file.to_str().unwrap(),
+                "--input",
+                input,
+            ])
+            .output()?)
+    };
+
+    let output = check("tests/ui/arrays.rs")?;
+    ensure!(
+        output.status.success(),
+        "`--check` failed on a supported crate: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    ensure!(!llbc_file.exists(), "`--check` wrote an output file");
+
+    let output = check("tests/ui/error-dependencies.rs")?;
+    let stderr = String::from_utf8(output.stderr)?;
+    ensure!(
+        !output.status.success(),
+        "`--check` succeeded on a crate with errors"
+    );
+    ensure!(
+        stderr.contains("- core::ptr::metadata::Thin"),
+        "`--check` didn't list the failed item:\n{stderr}"
+    );
+    ensure!(!llbc_file.exists(), "`--check` wrote an output file");
+
+    // Warnings alone don't make the check fail.
+    let input = dir.path().join("warnings.rs");
+    std::fs::write(
+        &input,
+        "pub fn f() {\n    // charon: frobnicate\n    let _x = 0;\n}\n",
+    )?;
+    let output = check(input.to_str().unwrap())?;
+    let stderr = String::from_utf8(output.stderr)?;
+    ensure!(
+        output.status.success(),
+        "`--check` failed on a crate with only warnings:\n{stderr}"
+    );
+    ensure!(stderr.contains("Unknown `charon:` directive"), "{stderr}");
+    ensure!(!stderr.contains("failed to translate"), "{stderr}");
+    Ok(())
+}
+
+#[test]
+fn charon_validate() -> Result<()> {
+    for extra_arg in ["--ullbc", "--no-merge-goto-chains"] {
+        // `--check` makes us exit with an error if validation fails.
+        let args = &[
+            "--no-cargo",
+            "--rustc-flag=--crate-type=rlib",
+            "--check",
+            "--validate",
+            extra_arg,
+            "--input",
+            "tests/ui/arrays.rs",
+        ];
+        charon(args, ".", |_, _| Ok(()))?;
+    }
+    Ok(())
+}
+
+#[test]
+fn charon_records_provenance() -> Result<()> {
+    let dir = tempfile::TempDir::new()?;
+    let llbc_file = dir.path().join("arrays.llbc");
+    let args = &[
+        "--no-cargo",
+        "--rustc-flag=--crate-type=rlib",
+        "--mir_optimized",
+        "--dest-file",
+        llbc_file.to_str().unwrap(),
+        "--input",
+        "tests/ui/arrays.rs",
+    ];
+    charon(args, ".", |_, cmd| {
+        let krate = charon_lib::deserialize_llbc(&llbc_file)?;
+        // Without cargo there is no manifest to take the version from.
+        ensure!(
+            krate.crate_version.is_none(),
+            "`{cmd}` recorded a crate version"
+        );
+        ensure!(!krate.rustc_commit_hash.is_empty());
+        ensure!(krate.mir_level == charon_lib::options::MirLevel::Optimized);
+        Ok(())
+    })?;
+
+    let llbc_file = dir.path().join("dependencies.llbc");
+    let args = &["cargo", "--dest-file", llbc_file.to_str().unwrap()];
+    charon(args, "tests/cargo/dependencies", |_, cmd| {
+        let krate = charon_lib::deserialize_llbc(&llbc_file)?;
+        ensure!(
+            krate.crate_version.as_deref() == Some("0.1.0"),
+            "`{cmd}` recorded crate version {:?}",
+            krate.crate_version
+        );
+        Ok(())
+    })
+}
+
+#[test]
+fn charon_merge_goto_chains() -> Result<()> {
+    use charon_lib::ast::{Body, PathElem};
+    let dir = tempfile::TempDir::new()?;
+    let input = dir.path().join("chain.rs");
+    // Each call ends a MIR block with a goto to the next one, giving a chain of blocks with a
+    // single predecessor each.
+    std::fs::write(&input, "fn g() {} pub fn f() { g(); g(); g(); }")?;
+    let ullbc_file = dir.path().join("chain.ullbc");
+    let count_blocks = |extra_args: &[&str]| -> Result<usize> {
+        let mut args = vec![
+            "--no-cargo",
+            "--rustc-flag=--crate-type=rlib",
+            "--ullbc",
+            "--dest-file",
+            ullbc_file.to_str().unwrap(),
+            "--input",
+            input.to_str().unwrap(),
+        ];
+        args.extend(extra_args);
+        charon(&args, ".", |_, cmd| {
+            let krate = charon_lib::deserialize_llbc(&ullbc_file)?;
+            let f = krate
+                .fun_decls
+                .iter()
+                .find(|decl| {
+                    let name = decl.item_meta.name.name.last();
+                    matches!(name, Some(PathElem::Ident(s, _)) if s == "f")
+                })
+                .context("`f` not found")?;
+            let Ok(Body::Unstructured(body)) = &f.body else {
+                anyhow::bail!("`{cmd}` didn't produce an unstructured body for `f`")
+            };
+            Ok(body.body.elem_count())
+        })
+    };
+    ensure!(count_blocks(&[])? == 1, "the goto chain wasn't merged");
+    ensure!(count_blocks(&["--no-merge-goto-chains"])? > 1);
+    Ok(())
+}
+
+#[test]
+fn charon_emit_ullbc() -> Result<()> {
+    use charon_lib::ast::Body;
+    let dir = tempfile::TempDir::new()?;
+    let llbc_file = dir.path().join("arrays.llbc");
+    let ullbc_file = dir.path().join("arrays.ullbc");
+    let args = &[
+        "--no-cargo",
+        "--rustc-flag=--crate-type=rlib",
+        "--emit-ullbc",
+        "--dest-file",
+        llbc_file.to_str().unwrap(),
+        "--input",
+        "tests/ui/arrays.rs",
+    ];
+    charon(args, ".", |_, cmd| {
+        let llbc = charon_lib::deserialize_llbc(&llbc_file)?;
+        let ullbc = charon_lib::deserialize_llbc(&ullbc_file)
+            .with_context(|| format!("`{cmd}` didn't write `{}`", ullbc_file.display()))?;
+        ensure!(ullbc.options.ullbc && !llbc.options.ullbc);
+        ensure!(ullbc.ordered_decls.is_some());
+        // The two crates share their ids.
+        ensure!(llbc.fun_decls.elem_count() == ullbc.fun_decls.elem_count());
+        for (llbc_fun, ullbc_fun) in llbc.fun_decls.iter().zip(ullbc.fun_decls.iter()) {
+            ensure!(llbc_fun.def_id == ullbc_fun.def_id);
+            ensure!(llbc_fun.item_meta.name == ullbc_fun.item_meta.name);
+            match (&llbc_fun.body, &ullbc_fun.body) {
+                (Ok(Body::Structured(_)), Ok(Body::Unstructured(_))) | (Err(_), Err(_)) => {}
+                _ => anyhow::bail!("unexpected bodies for `{:?}`", llbc_fun.def_id),
+            }
+        }
+        Ok(())
+    })
+}
+
+#[test]
+fn charon_single_return() -> Result<()> {
+    use charon_lib::ast::{Body, PathElem};
+    let dir = tempfile::TempDir::new()?;
+    let input = dir.path().join("returns.rs");
+    std::fs::write(
+        &input,
+        "pub fn f(x: u32) -> u32 {
+            if x == 0 { return 1; }
+            if x == 1 { return 2; }
+            if x == 2 { return 3; }
+            x
+        }",
+    )?;
+    let ullbc_file = dir.path().join("returns.ullbc");
+    let count_returns = |extra_args: &[&str]| -> Result<usize> {
+        let mut args = vec![
+            "--no-cargo",
+            "--rustc-flag=--crate-type=rlib",
+            "--ullbc",
+            "--dest-file",
+            ullbc_file.to_str().unwrap(),
+            "--input",
+            input.to_str().unwrap(),
+        ];
+        args.extend(extra_args);
+        charon(&args, ".", |_, cmd| {
+            let krate = charon_lib::deserialize_llbc(&ullbc_file)?;
+            let f = krate
+                .fun_decls
+                .iter()
+                .find(|decl| {
+                    let name = decl.item_meta.name.name.last();
+                    matches!(name, Some(PathElem::Ident(s, _)) if s == "f")
+                })
+                .context("`f` not found")?;
+            let Ok(Body::Unstructured(body)) = &f.body else {
+                anyhow::bail!("`{cmd}` didn't produce an unstructured body for `f`")
+            };
+            Ok(body
+                .body
+                .iter()
+                .filter(|block| block.terminator.content.is_return())
+                .count())
+        })
+    };
+    ensure!(count_returns(&[])? > 1);
+    ensure!(count_returns(&["--single-return"])? == 1);
+    Ok(())
+}
+
+#[test]
+fn charon_normalize_if_polarity() -> Result<()> {
+    use charon_lib::ast::*;
+    use charon_lib::llbc_ast::{RawStatement, Switch};
+    let dir = tempfile::TempDir::new()?;
+    let input = dir.path().join("negation.rs");
+    std::fs::write(
+        &input,
+        "pub fn f(x: bool) -> u32 { if !x { 1 } else { 2 } }",
+    )?;
+    let llbc_file = dir.path().join("negation.llbc");
+    // Returns whether the condition is `x` itself, and the value returned by the `then` branch.
+    let translate_if = |extra_args: &[&str]| -> Result<(bool, u32)> {
+        let mut args = vec![
+            "--no-cargo",
+            "--rustc-flag=--crate-type=rlib",
+            "--dest-file",
+            llbc_file.to_str().unwrap(),
+            "--input",
+            input.to_str().unwrap(),
+        ];
+        args.extend(extra_args);
+        charon(&args, ".", |_, cmd| {
+            let krate = charon_lib::deserialize_llbc(&llbc_file)?;
+            let f = krate
+                .fun_decls
+                .iter()
+                .find(|decl| {
+                    let name = decl.item_meta.name.name.last();
+                    matches!(name, Some(PathElem::Ident(s, _)) if s == "f")
+                })
+                .context("`f` not found")?;
+            let Ok(Body::Structured(body)) = &f.body else {
+                anyhow::bail!("`{cmd}` didn't produce a structured body for `f`")
+            };
+            let (cond, then_block) = body
+                .body
+                .statements
+                .iter()
+                .find_map(|st| match &st.content {
+                    RawStatement::Switch(Switch::If(cond, then_block, _)) => {
+                        Some((cond, then_block))
+                    }
+                    _ => None,
+                })
+                .context("no `if` in `f`")?;
+            let cond_is_x = matches!(cond, Operand::Copy(p) | Operand::Move(p)
+                if p.as_local() == Some(VarId::new(1)));
+            let then_value = then_block
+                .statements
+                .iter()
+                .find_map(|st| match &st.content {
+                    RawStatement::Assign(
+                        _,
+                        Rvalue::Use(Operand::Const(ConstantExpr {
+                            value: RawConstantExpr::Literal(Literal::Scalar(ScalarValue::U32(v))),
+                            ..
+                        })),
+                    ) => Some(*v),
+                    _ => None,
+                })
+                .context("no constant in the `then` branch")?;
+            Ok((cond_is_x, then_value))
+        })
+    };
+    ensure!(translate_if(&[])? == (false, 1));
+    ensure!(translate_if(&["--normalize-if-polarity"])? == (true, 2));
+    Ok(())
+}
+
+#[test]
+fn charon_overflow_modes() -> Result<()> {
+    use charon_lib::ast::*;
+    let dir = tempfile::TempDir::new()?;
+    let input = dir.path().join("overflow.rs");
+    std::fs::write(
+        &input,
+        r#"
+        #![allow(arithmetic_overflow)]
+        pub fn overflow() -> u8 {
+            255u8 + 1
+        }
+        "#,
+    )?;
+    let llbc_file = dir.path().join("overflow.llbc");
+    let run = |mode: &str| -> Result<std::process::Output> {
+        Ok(Command::cargo_bin("charon")?
+            .args([
+                "--no-cargo",
+                "--rustc-flag=--crate-type=rlib",
+                "--fold-constants",
+                "--overflow",
+                mode,
+                "--dest-file",
+                llbc_file.to_str().unwrap(),
+                "--input",
+                input.to_str().unwrap(),
+            ])
+            .output()?)
+    };
+
+    // Wrapping around gives `0`.
+    let output = run("wrapping")?;
+    ensure!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let krate = charon_lib::deserialize_llbc(&llbc_file)?;
+    let f = krate
+        .fun_decls
+        .iter()
+        .find(|decl| {
+            let last = decl.item_meta.name.name.last();
+            matches!(last, Some(PathElem::Ident(s, _)) if s == "overflow")
+        })
+        .context("`overflow` not found")?;
+    let body = f.body.as_ref().unwrap();
+    let mut binops = vec![];
+    let mut scalars = vec![];
+    body.dyn_visit_in_body(|rvalue: &Rvalue| {
+        if let Rvalue::BinaryOp(op, ..) = rvalue {
+            binops.push(*op);
+        }
+    });
+    body.dyn_visit_in_body(|scalar: &ScalarValue| scalars.push(*scalar));
+    ensure!(binops.is_empty(), "the operation wasn't folded: {binops:?}");
+    ensure!(
+        scalars == vec![ScalarValue::U8(0)],
+        "unexpected result: {scalars:?}"
+    );
+
+    // Failing on overflow makes it an error.
+    let output = run("panic")?;
+    let stderr = String::from_utf8(output.stderr)?;
+    ensure!(
+        stderr.contains("will overflow"),
+        "the overflow wasn't reported:\n{stderr}"
+    );
+    Ok(())
+}
+
+#[test]
+fn charon_reconstruct_deref() -> Result<()> {
+    use charon_lib::ast::*;
+    use charon_lib::ullbc_ast::{BlockData, RawStatement};
+    let dir = tempfile::TempDir::new()?;
+    let input = dir.path().join("deref.rs");
+    std::fs::write(
+        &input,
+        r#"
+        use std::ops::Deref;
+        pub struct Wrapper(u32);
+        impl Deref for Wrapper {
+            type Target = u32;
+            fn deref(&self) -> &u32 { &self.0 }
+        }
+        pub fn boxed(b: &Box<u32>) -> u32 { *b.deref() }
+        pub fn custom(w: &Wrapper) -> u32 { **w }
+        "#,
+    )?;
+    let ullbc_file = dir.path().join("deref.ullbc");
+    // Returns whether each of `boxed` and `custom` still calls a `deref` method.
+    let calls_deref = |extra_args: &[&str]| -> Result<(bool, bool)> {
+        let mut args = vec![
+            "--no-cargo",
+            "--rustc-flag=--crate-type=rlib",
+            "--ullbc",
+            "--dest-file",
+            ullbc_file.to_str().unwrap(),
+            "--input",
+            input.to_str().unwrap(),
+        ];
+        args.extend(extra_args);
+        charon(&args, ".", |_, cmd| {
+            let krate = charon_lib::deserialize_llbc(&ullbc_file)?;
+            let calls_deref = |name: &str| -> Result<bool> {
+                let f = krate
+                    .fun_decls
+                    .iter()
+                    .find(|decl| {
+                        let last = decl.item_meta.name.name.last();
+                        matches!(last, Some(PathElem::Ident(s, _)) if s == name)
+                    })
+                    .with_context(|| format!("`{name}` not found"))?;
+                let Ok(Body::Unstructured(body)) = &f.body else {
+                    anyhow::bail!("`{cmd}` didn't produce an unstructured body for `{name}`")
+                };
+                Ok(body
+                    .body
+                    .iter()
+                    .flat_map(|block: &BlockData| &block.statements)
+                    .any(|st| match &st.content {
+                        RawStatement::Call(Call {
+                            func:
+                                FnOperand::Regular(FnPtr {
+                                    func: FunIdOrTraitMethodRef::Trait(_, method_name, _),
+                                    ..
+                                }),
+                            ..
+                        }) => method_name.0 == "deref",
+                        _ => false,
+                    }))
+            };
+            Ok((calls_deref("boxed")?, calls_deref("custom")?))
+        })
+    };
+    ensure!(calls_deref(&[])? == (true, true));
+    // The `Box` deref becomes `&**b`; the custom impl may do anything, so we keep the call.
+    ensure!(calls_deref(&["--reconstruct-deref"])? == (false, true));
+    Ok(())
+}
+
+#[test]
+fn charon_reconstruct_range_matches() -> Result<()> {
+    use charon_lib::ast::*;
+    use charon_lib::llbc_ast::{RawStatement, Switch};
+    let dir = tempfile::TempDir::new()?;
+    let input = dir.path().join("ranges.rs");
+    std::fs::write(
+        &input,
+        r#"
+        pub fn digit(x: u32) -> u32 { match x { 0..=9 => 1, _ => 2 } }
+        pub fn inclusive(x: i32) -> u32 { match x { 1..=5 => 1, _ => 2 } }
+        pub fn exclusive(x: i32) -> u32 { match x { 1..5 => 1, _ => 2 } }
+        pub fn open(x: i32) -> u32 { match x { 10.. => 1, _ => 2 } }
+        "#,
+    )?;
+    let llbc_file = dir.path().join("ranges.llbc");
+    let args = &[
+        "--no-cargo",
+        "--rustc-flag=--crate-type=rlib",
+        "--reconstruct-range-matches",
+        "--dest-file",
+        llbc_file.to_str().unwrap(),
+        "--input",
+        input.to_str().unwrap(),
+    ];
+    charon(args, ".", |_, cmd| {
+        let krate = charon_lib::deserialize_llbc(&llbc_file)?;
+        // Returns the range of the range switch in the given function, which must have one arm.
+        let range_of = |name: &str| -> Result<LiteralRange> {
+            let f = krate
+                .fun_decls
+                .iter()
+                .find(|decl| {
+                    let last = decl.item_meta.name.name.last();
+                    matches!(last, Some(PathElem::Ident(s, _)) if s == name)
+                })
+                .with_context(|| format!("`{name}` not found"))?;
+            let Ok(Body::Structured(body)) = &f.body else {
+                anyhow::bail!("`{cmd}` didn't produce a structured body for `{name}`")
+            };
+            let arms = body
+                .body
+                .statements
+                .iter()
+                .find_map(|st| match &st.content {
+                    RawStatement::Switch(Switch::SwitchRange(_, _, arms, _)) => Some(arms),
+                    _ => None,
+                })
+                .with_context(|| format!("`{cmd}` produced no range switch in `{name}`"))?;
+            let [(range, _)] = arms.as_slice() else {
+                anyhow::bail!("`{cmd}` produced {} arms in `{name}`", arms.len())
+            };
+            Ok(range.clone())
+        };
+        let int = |v: i32| Literal::Scalar(ScalarValue::I32(v));
+
+        let range = range_of("digit")?;
+        ensure!(
+            range.end == Some(Literal::Scalar(ScalarValue::U32(9))) && range.end_inclusive,
+            "unexpected range: {range}"
+        );
+        ensure!(
+            matches!(
+                range.start,
+                None | Some(Literal::Scalar(ScalarValue::U32(0)))
+            ),
+            "unexpected range: {range}"
+        );
+
+        let range = range_of("inclusive")?;
+        ensure!(range.start == Some(int(1)), "unexpected range: {range}");
+        ensure!(
+            range.end == Some(int(5)) && range.end_inclusive,
+            "unexpected range: {range}"
+        );
+
+        let range = range_of("exclusive")?;
+        ensure!(range.start == Some(int(1)), "unexpected range: {range}");
+        ensure!(
+            range.end == Some(int(5)) && !range.end_inclusive,
+            "unexpected range: {range}"
+        );
+
+        let range = range_of("open")?;
+        ensure!(range.start == Some(int(10)), "unexpected range: {range}");
+        ensure!(range.end.is_none(), "unexpected range: {range}");
+        Ok(())
+    })
+}
+
+#[test]
+fn charon_remap_path_prefix() -> Result<()> {
+    let dir = tempfile::TempDir::new()?;
+    let input = dir.path().join("remapped.rs");
+    std::fs::write(&input, "pub fn foo() {}")?;
+    let llbc = dir.path().join("remapped.llbc");
+    let mapping = format!("--remap-path-prefix={}=/src", dir.path().display());
+    let args = &[
+        "--no-cargo",
+        "--rustc-flag=--crate-type=rlib",
+        "--input",
+        input.to_str().unwrap(),
+        "--dest-file",
+        llbc.to_str().unwrap(),
+        &mapping,
+    ];
+    charon(args, ".", |_, _| {
+        let json = std::fs::read_to_string(&llbc)?;
+        ensure!(json.contains(r#""/src/remapped.rs""#), "{json}");
+        Ok(())
+    })
+}
+
+#[test]
+fn charon_on_unsupported() -> Result<()> {
+    let dir = tempfile::TempDir::new()?;
+    let input = dir.path().join("inline_asm.rs");
+    std::fs::write(
+        &input,
+        "pub fn with_asm() { unsafe { core::arch::asm!(\"nop\") } }\n\
+         pub fn other() -> u32 { 42 }\n",
+    )?;
+    let diagnostics = dir.path().join("diagnostics.json");
+    let run = |policy: &str| {
+        let policy = format!("--on-unsupported={policy}");
+        let error_output = format!("--error-output={}", diagnostics.display());
+        let args = &[
+            "--no-cargo",
+            "--rustc-flag=--crate-type=rlib",
+            "--input",
+            input.to_str().unwrap(),
+            "--print-llbc",
+            "--no-serialize",
+            "--error-format=json",
+            &error_output,
+            &policy,
+        ];
+        let res = charon(args, ".", |stdout, _| Ok(stdout));
+        let diagnostics = std::fs::read_to_string(&diagnostics)?;
+        anyhow::Ok((res, diagnostics))
+    };
+    let msg = "Inline assembly is not supported";
+
+    // By default we warn, and the function with inline assembly gets an opaque body.
+    let (res, diagnostics) = run("asm=warn")?;
+    let stdout = res?;
+    ensure!(diagnostics.contains(msg), "{diagnostics}");
+    ensure!(
+        stdout.contains("pub fn inline_asm::other() -> u32\n{"),
+        "{stdout}"
+    );
+    ensure!(
+        stdout.contains("pub fn inline_asm::with_asm()\n\n"),
+        "{stdout}"
+    );
+
+    // Same without the warning.
+    let (res, diagnostics) = run("asm=opaque")?;
+    let stdout = res?;
+    ensure!(!diagnostics.contains(msg), "{diagnostics}");
+    ensure!(
+        stdout.contains("pub fn inline_asm::with_asm()\n\n"),
+        "{stdout}"
+    );
+
+    // The policy for the other categories doesn't apply to inline assembly.
+    let (res, diagnostics) = run("simd=error")?;
+    res?;
+    ensure!(diagnostics.contains(msg), "{diagnostics}");
+
+    // With `error`, charon fails.
+    let (res, diagnostics) = run("error")?;
+    ensure!(res.is_err());
+    ensure!(diagnostics.contains(msg), "{diagnostics}");
+    Ok(())
+}
+
+#[test]
+fn charon_max_errors() -> Result<()> {
+    let dir = tempfile::TempDir::new()?;
+    let input = dir.path().join("inline_asm.rs");
+    std::fs::write(
+        &input,
+        "pub fn first() { unsafe { core::arch::asm!(\"nop\") } }\n\
+         pub fn second() { unsafe { core::arch::asm!(\"nop\") } }\n",
+    )?;
+    let llbc_file = dir.path().join("inline_asm.llbc");
+    let run = |max_errors: &str| -> Result<std::process::Output> {
+        Ok(Command::cargo_bin("charon")?
+            .args([
+                "--no-cargo",
+                "--rustc-flag=--crate-type=rlib",
+                "--max-errors",
+                max_errors,
+                "--dest-file",
+                llbc_file.to_str().unwrap(),
+                "--input",
+                input.to_str().unwrap(),
+            ])
+            .output()?)
+    };
+    let summary = "aborting after 1 item(s) failed to translate";
+
+    // Both functions fail, so we stop after the first one.
+    let output = run("1")?;
+    let stderr = String::from_utf8(output.stderr)?;
+    ensure!(!output.status.success(), "`--max-errors 1` didn't fail");
+    ensure!(stderr.contains(summary), "{stderr}");
+    ensure!(stderr.contains("  - inline_asm::"), "{stderr}");
+    ensure!(!llbc_file.exists(), "`--max-errors` wrote an output file");
+
+    // Below the limit, the failures are reported as usual.
+    let output = run("3")?;
+    let stderr = String::from_utf8(output.stderr)?;
+    ensure!(output.status.success(), "{stderr}");
+    ensure!(!stderr.contains(summary), "{stderr}");
+    ensure!(llbc_file.exists());
+
+    // Warnings don't count as failed items.
+    std::fs::write(
+        &input,
+        "pub fn first() {\n    // charon: frobnicate\n    let _x = 0;\n}\n\
+         pub fn second() {\n    // charon: frobnicate\n    let _x = 0;\n}\n",
+    )?;
+    let output = run("1")?;
+    let stderr = String::from_utf8(output.stderr)?;
+    ensure!(output.status.success(), "{stderr}");
+    ensure!(stderr.contains("Unknown `charon:` directive"), "{stderr}");
+    ensure!(!stderr.contains(summary), "{stderr}");
+    Ok(())
+}
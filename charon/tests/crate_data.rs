@@ -1,6 +1,9 @@
 use charon_lib::ast::{AnyTransItem, TranslatedCrate};
+use charon_lib::formatter::IntoFormatter;
+use charon_lib::pretty::FmtWithCtx;
+use charon_lib::reorder_decls::DeclarationGroup;
 use itertools::Itertools;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::error::Error;
 
 use charon_lib::llbc_ast::*;
@@ -49,7 +52,6 @@ struct Item<'c> {
     name_str: String,
     // Not a ref because we do a little hack.
     generics: GenericParams,
-    #[expect(dead_code)]
     kind: AnyTransItem<'c>,
 }
 
@@ -273,6 +275,42 @@ fn predicate_origins() -> anyhow::Result<()> {
     Ok(())
 }
 
+#[test]
+fn impl_trait_in_argument_position() -> anyhow::Result<()> {
+    let crate_data = translate(
+        r#"
+        fn f(x: impl Iterator<Item = u32>) {}
+        fn g<ImplClone: Clone>(x: ImplClone, y: impl Clone) {}
+        "#,
+    )?;
+    let items_by_name = items_by_name(&crate_data);
+
+    let generics = &items_by_name["test_crate::f"].generics;
+    let type_vars = generics.types.iter().map(|v| v.name.as_str()).collect_vec();
+    assert_eq!(type_vars, vec!["ImplIterator"]);
+    let bounds = generics
+        .trait_clauses
+        .iter()
+        .map(|clause| trait_name(&crate_data, clause.trait_.skip_binder.trait_id))
+        .filter(|name| *name != "Sized")
+        .collect_vec();
+    assert_eq!(bounds, vec!["Iterator"]);
+    // The clause applies to the synthetic parameter.
+    let iterator_clause = generics
+        .trait_clauses
+        .iter()
+        .find(|clause| trait_name(&crate_data, clause.trait_.skip_binder.trait_id) == "Iterator")
+        .unwrap();
+    let self_ty = &iterator_clause.trait_.skip_binder.generics.types[0];
+    assert!(matches!(self_ty.kind(), TyKind::TypeVar(_)));
+
+    // The generated names don't clash with the declared ones.
+    let generics = &items_by_name["test_crate::g"].generics;
+    let type_vars = generics.types.iter().map(|v| v.name.as_str()).collect_vec();
+    assert_eq!(type_vars, vec!["ImplClone", "ImplClone1"]);
+    Ok(())
+}
+
 #[test]
 fn attributes() -> anyhow::Result<()> {
     // Use the `clippy::` prefix because it's ignored by rustc.
@@ -423,6 +461,57 @@ fn discriminants() -> anyhow::Result<()> {
     Ok(())
 }
 
+#[test]
+fn computed_discriminants() -> anyhow::Result<()> {
+    let crate_data = translate(
+        r#"
+        const fn compute() -> u8 {
+            2 * 5
+        }
+        #[repr(u8)]
+        enum Flags {
+            A = 1 << 3,
+            B = compute(),
+            C,
+        }
+        #[repr(i8)]
+        enum Signed {
+            Neg = -2,
+            Next,
+            Pos = 5,
+            Last,
+        }
+        "#,
+    )?;
+    let discriminants = |name: &str| {
+        let ty = crate_data
+            .type_decls
+            .iter()
+            .find(|ty| repr_name(&crate_data, &ty.item_meta.name) == name)
+            .unwrap();
+        ty.kind
+            .as_enum()
+            .unwrap()
+            .iter()
+            .map(|v| v.discriminant)
+            .collect_vec()
+    };
+    assert_eq!(
+        discriminants("test_crate::Flags"),
+        vec![ScalarValue::U8(8), ScalarValue::U8(10), ScalarValue::U8(11)]
+    );
+    assert_eq!(
+        discriminants("test_crate::Signed"),
+        vec![
+            ScalarValue::I8(-2),
+            ScalarValue::I8(-1),
+            ScalarValue::I8(5),
+            ScalarValue::I8(6)
+        ]
+    );
+    Ok(())
+}
+
 #[test]
 fn rename_attribute() -> anyhow::Result<()> {
     let crate_data = translate(
@@ -627,6 +716,56 @@ fn declaration_groups() -> anyhow::Result<()> {
     Ok(())
 }
 
+#[test]
+fn recursive_declaration_groups() -> anyhow::Result<()> {
+    let crate_data = translate(
+        r#"
+        enum Tree {
+            Node(Forest),
+        }
+        enum Forest {
+            Nil,
+            Cons(Box<Tree>, Box<Forest>),
+        }
+        fn is_even(n: u32) -> bool {
+            if n == 0 { true } else { is_odd(n - 1) }
+        }
+        fn is_odd(n: u32) -> bool {
+            if n == 0 { false } else { is_even(n - 1) }
+        }
+        fn not_rec() {}
+        "#,
+    )?;
+    let decl_groups = crate_data.ordered_decls.as_ref().unwrap();
+    let group_names = |group: &DeclarationGroup| {
+        group
+            .get_ids()
+            .into_iter()
+            .map(|id| repr_name(&crate_data, crate_data.item_name(id).unwrap()))
+            .sorted()
+            .collect_vec()
+    };
+    let rec_groups = decl_groups
+        .iter()
+        .filter(|group| group.to_mixed_group().is_rec())
+        .map(group_names)
+        .collect_vec();
+    assert_eq!(
+        rec_groups,
+        vec![
+            vec!["test_crate::Forest", "test_crate::Tree"],
+            vec!["test_crate::is_even", "test_crate::is_odd"],
+        ]
+    );
+    let not_rec = decl_groups
+        .iter()
+        .find(|group| group_names(group) == vec!["test_crate::not_rec"])
+        .unwrap();
+    assert!(not_rec.is_fun() && not_rec.to_mixed_group().is_non_rec());
+
+    Ok(())
+}
+
 #[test]
 fn source_text() -> anyhow::Result<()> {
     let crate_data = translate(
@@ -708,3 +847,3053 @@ fn known_trait_method_call() -> Result<(), Box<dyn Error>> {
     };
     Ok(())
 }
+
+#[test]
+fn thread_local_statics() -> anyhow::Result<()> {
+    let crate_data = translate(
+        r#"
+        #![feature(thread_local)]
+        #[thread_local]
+        static COUNTER: u32 = 0;
+        static REGULAR: u32 = 0;
+        const CONST: u32 = 0;
+        fn read() -> u32 {
+            COUNTER
+        }
+        "#,
+    )?;
+    let global_kinds: HashMap<String, GlobalKind> = crate_data
+        .global_decls
+        .iter()
+        .map(|g| (repr_name(&crate_data, &g.item_meta.name), g.global_kind))
+        .collect();
+    assert_eq!(global_kinds["test_crate::COUNTER"], GlobalKind::ThreadLocal);
+    assert_eq!(global_kinds["test_crate::REGULAR"], GlobalKind::Static);
+    assert_eq!(global_kinds["test_crate::CONST"], GlobalKind::NamedConst);
+
+    // The initializer body is kept.
+    let counter = crate_data
+        .global_decls
+        .iter()
+        .find(|g| g.global_kind.is_thread_local())
+        .unwrap();
+    assert!(crate_data.fun_decls[counter.init].body.is_ok());
+    Ok(())
+}
+
+#[test]
+fn static_mut_accesses() -> anyhow::Result<()> {
+    let crate_data = translate(
+        r#"
+        static mut COUNTER: u32 = 0;
+        fn read() -> u32 {
+            unsafe { COUNTER }
+        }
+        fn write(x: u32) {
+            unsafe { COUNTER = x }
+        }
+        "#,
+    )?;
+    let counter = crate_data
+        .global_decls
+        .iter()
+        .find(|g| repr_name(&crate_data, &g.item_meta.name) == "test_crate::COUNTER")
+        .unwrap();
+    assert_eq!(counter.global_kind, GlobalKind::StaticMut);
+
+    for fun_name in ["test_crate::read", "test_crate::write"] {
+        let body = crate_data
+            .fun_decls
+            .iter()
+            .find(|f| repr_name(&crate_data, &f.item_meta.name) == fun_name)
+            .unwrap()
+            .body
+            .as_ref()
+            .unwrap();
+        // Both accesses go through a mutable raw pointer to the static; its value is never
+        // copied directly.
+        let mut ref_kinds = vec![];
+        body.dyn_visit_in_body(|rv: &Rvalue| match rv {
+            Rvalue::GlobalRef(global_ref, kind) if global_ref.id == counter.def_id => {
+                ref_kinds.push(*kind)
+            }
+            Rvalue::Global(global_ref) if global_ref.id == counter.def_id => {
+                panic!("`{fun_name}` copies the `static mut`")
+            }
+            _ => {}
+        });
+        assert_eq!(ref_kinds, vec![RefKind::Mut], "in `{fun_name}`");
+    }
+
+    // The write goes through a dereference of that pointer.
+    let write = crate_data
+        .fun_decls
+        .iter()
+        .find(|f| repr_name(&crate_data, &f.item_meta.name) == "test_crate::write")
+        .unwrap();
+    let mut deref_writes = 0;
+    write
+        .body
+        .as_ref()
+        .unwrap()
+        .dyn_visit_in_body(|st: &Statement| {
+            if let RawStatement::Assign(dest, _) = &st.content {
+                if let Some((_, ProjectionElem::Deref)) = dest.as_projection() {
+                    deref_writes += 1;
+                }
+            }
+        });
+    assert_eq!(deref_writes, 1);
+    Ok(())
+}
+
+#[test]
+fn visit_types() -> anyhow::Result<()> {
+    let crate_data = translate(
+        r#"
+        struct Wrapper(bool);
+        type Alias = char;
+        static GLOBAL: u8 = 0;
+        fn foo(x: u16) -> u32 {
+            let y: u64 = 0;
+            x as u32
+        }
+        "#,
+    )?;
+    let mut contexts: HashMap<String, Vec<TyContext>> = HashMap::new();
+    let fmt_ctx = crate_data.into_fmt();
+    crate_data.visit_types(|ty, ctx| {
+        contexts
+            .entry(ty.fmt_with_ctx(&fmt_ctx))
+            .or_default()
+            .push(ctx)
+    });
+    assert!(contexts["bool"].contains(&TyContext::Field));
+    assert!(contexts["char"].contains(&TyContext::Alias));
+    assert!(contexts["u8"].contains(&TyContext::Global));
+    assert!(contexts["u16"].contains(&TyContext::Param));
+    assert!(contexts["u16"].contains(&TyContext::Local));
+    assert!(contexts["u32"].contains(&TyContext::Return));
+    assert!(contexts["u64"].contains(&TyContext::Local));
+    assert!(!contexts["u64"].contains(&TyContext::Param));
+    Ok(())
+}
+
+#[test]
+fn simd_types() -> anyhow::Result<()> {
+    let crate_data = translate(
+        r#"
+        #![feature(repr_simd)]
+        #[repr(simd)]
+        struct F32x4([f32; 4]);
+        fn id(x: F32x4) -> F32x4 {
+            x
+        }
+        "#,
+    )?;
+    let tdecl = &crate_data.type_decls[0];
+    assert_eq!(
+        repr_name(&crate_data, &tdecl.item_meta.name),
+        "test_crate::F32x4"
+    );
+    let TypeDeclKind::Simd { elem_ty, lanes } = &tdecl.kind else {
+        panic!("{:?}", tdecl.kind)
+    };
+    assert_eq!(
+        elem_ty.kind(),
+        &TyKind::Literal(LiteralTy::Float(FloatTy::F32))
+    );
+    assert_eq!(
+        lanes,
+        &ConstGeneric::Value(Literal::Scalar(ScalarValue::Usize(4)))
+    );
+    Ok(())
+}
+
+#[test]
+fn visit_simd_types() -> anyhow::Result<()> {
+    let crate_data = translate(
+        r#"
+        #![feature(repr_simd)]
+        #[repr(simd)]
+        struct U16x8([u16; 8]);
+        "#,
+    )?;
+    let mut contexts: HashMap<String, Vec<TyContext>> = HashMap::new();
+    let fmt_ctx = crate_data.into_fmt();
+    crate_data.visit_types(|ty, ctx| {
+        contexts
+            .entry(ty.fmt_with_ctx(&fmt_ctx))
+            .or_default()
+            .push(ctx)
+    });
+    // The type of the lanes is in field position.
+    assert_eq!(contexts["u16"], vec![TyContext::Field]);
+    Ok(())
+}
+
+#[test]
+fn struct_update() -> anyhow::Result<()> {
+    let crate_data = util::translate_rust_text_with_args(
+        r#"
+        struct Foo {
+            a: u32,
+            b: u32,
+            c: u32,
+            d: u32,
+            e: u32,
+        }
+        fn update(base: Foo) -> Foo {
+            Foo { b: 1, d: 2, ..base }
+        }
+        "#,
+        &["--reconstruct-struct-updates"],
+    )?;
+    let function = &crate_data.fun_decls[0];
+    let body = function.body.as_ref().unwrap();
+    let body = &body.as_structured().unwrap().body;
+    let rvalue = body
+        .statements
+        .iter()
+        .find_map(|st| match &st.content {
+            RawStatement::Assign(_, rvalue @ Rvalue::StructUpdate(..)) => Some(rvalue),
+            _ => None,
+        })
+        .unwrap();
+    let Rvalue::StructUpdate(_, fields, base) = rvalue else {
+        unreachable!()
+    };
+    // Only the overridden fields are kept; the others come from `base`.
+    let field_ids = fields.iter().map(|(id, _)| id.index()).collect_vec();
+    assert_eq!(field_ids, vec![1, 3]);
+    assert_eq!(base.as_local(), Some(VarId::new(1)));
+
+    let fmt_ctx = crate_data.into_fmt();
+    let repr = rvalue.fmt_with_ctx(&fmt_ctx);
+    assert!(repr.starts_with("test_crate::Foo { b: "), "{repr}");
+    assert!(repr.ends_with(", ..base@1 }"), "{repr}");
+    Ok(())
+}
+
+#[test]
+fn struct_update_modified_base() -> anyhow::Result<()> {
+    let crate_data = util::translate_rust_text_with_args(
+        r#"
+        struct Foo {
+            a: u32,
+            b: u32,
+            c: u32,
+        }
+        fn update(mut base: Foo) -> Foo {
+            Foo {
+                a: base.a,
+                b: {
+                    base.a = 3;
+                    1
+                },
+                ..base
+            }
+        }
+        "#,
+        &["--reconstruct-struct-updates"],
+    )?;
+    let function = &crate_data.fun_decls[0];
+    let mut updated_fields = vec![];
+    function
+        .body
+        .as_ref()
+        .unwrap()
+        .dyn_visit_in_body(|rvalue: &Rvalue| {
+            if let Rvalue::StructUpdate(_, fields, _) = rvalue {
+                updated_fields.push(fields.iter().map(|(id, _)| id.index()).collect_vec());
+            }
+        });
+    // `a` was read before `base` got modified, so it must not be read from `base` at the end.
+    assert_eq!(updated_fields, vec![vec![0, 1]]);
+    Ok(())
+}
+
+#[test]
+fn char_switch() -> anyhow::Result<()> {
+    let crate_data = translate(
+        r#"
+        fn classify(c: char) -> u32 {
+            match c {
+                'a' => 1,
+                'b' | 'c' => 2,
+                _ => 0,
+            }
+        }
+        "#,
+    )?;
+    let function = &crate_data.fun_decls[0];
+    let body = function.body.as_ref().unwrap();
+    let body = &body.as_structured().unwrap().body;
+    let (ty, targets) = body
+        .statements
+        .iter()
+        .find_map(|st| match &st.content {
+            RawStatement::Switch(Switch::SwitchInt(_, ty, targets, _)) => Some((ty, targets)),
+            _ => None,
+        })
+        .unwrap();
+    assert_eq!(ty, &LiteralTy::Char);
+    let values = targets
+        .iter()
+        .flat_map(|(values, _)| values)
+        .cloned()
+        .sorted()
+        .collect_vec();
+    assert_eq!(
+        values,
+        vec![Literal::Char('a'), Literal::Char('b'), Literal::Char('c')]
+    );
+    Ok(())
+}
+
+#[test]
+fn drop_impl() -> anyhow::Result<()> {
+    let crate_data = translate(
+        r#"
+        struct WithDrop;
+        impl Drop for WithDrop {
+            fn drop(&mut self) {}
+        }
+        struct WithoutDrop;
+        "#,
+    )?;
+    let items = items_by_name(&crate_data);
+    let AnyTransItem::Type(with_drop) = items["test_crate::WithDrop"].kind else {
+        panic!()
+    };
+    let AnyTransItem::Type(without_drop) = items["test_crate::WithoutDrop"].kind else {
+        panic!()
+    };
+    assert!(without_drop.drop_impl.is_none());
+    let drop_fn = &crate_data.fun_decls[with_drop.drop_impl.unwrap()];
+    assert_eq!(
+        repr_name(&crate_data, &drop_fn.item_meta.name),
+        "test_crate::<impl Drop for ??>::drop"
+    );
+    Ok(())
+}
+
+#[test]
+fn assert_eq_ne() -> anyhow::Result<()> {
+    let crate_data = translate(
+        r#"
+        fn check(x: u32, y: u32) {
+            assert_eq!(x, 0);
+            assert_ne!(x, y, "x and y must differ: {}", y);
+        }
+        "#,
+    )?;
+    let function = &crate_data.fun_decls[0];
+    let body = function.body.as_ref().unwrap();
+    let body = &body.as_structured().unwrap().body;
+    let asserts = body
+        .statements
+        .iter()
+        .filter_map(|st| st.content.as_assert())
+        .collect_vec();
+    assert_eq!(asserts.len(), 2);
+    let cmps = asserts
+        .iter()
+        .map(|assert| assert.comparison.as_ref().unwrap())
+        .collect_vec();
+    assert_eq!(cmps[0].op, BinOp::Eq);
+    assert!(asserts[0].expected);
+    assert_eq!(cmps[1].op, BinOp::Ne);
+    assert!(!asserts[1].expected);
+
+    let fmt_ctx = crate_data.into_fmt();
+    let repr = asserts[1].fmt_with_ctx(&fmt_ctx);
+    assert!(repr.starts_with("assert_ne(copy (*(left_val@"), "{repr}");
+    Ok(())
+}
+
+#[test]
+fn interior_mutability() -> anyhow::Result<()> {
+    let crate_data = translate(
+        r#"
+        use std::cell::{Cell, UnsafeCell};
+        struct Counter {
+            count: Cell<u32>,
+        }
+        struct Wrapper<T>(T);
+        struct Pair {
+            a: Wrapper<u32>,
+            b: Wrapper<Cell<u32>>,
+        }
+        fn get(c: &UnsafeCell<u32>) -> *mut u32 {
+            c.get()
+        }
+        "#,
+    )?;
+    let items = items_by_name(&crate_data);
+    let type_decl = |name: &str| {
+        let AnyTransItem::Type(decl) = items[name].kind else {
+            panic!()
+        };
+        decl
+    };
+    assert!(type_decl("test_crate::Counter").interior_mut);
+    assert!(type_decl("core::cell::Cell").interior_mut);
+    // A type parameter may or may not have interior mutability; we only look at the type itself.
+    assert!(!type_decl("test_crate::Wrapper").interior_mut);
+    // The instantiations of the same type must be looked at separately.
+    assert!(type_decl("test_crate::Pair").interior_mut);
+
+    let AnyTransItem::Fun(get) = items["test_crate::get"].kind else {
+        panic!()
+    };
+    let TyKind::Ref(_, inner_ty, RefKind::Shared) = get.signature.inputs[0].kind() else {
+        panic!()
+    };
+    let TyKind::Adt(TypeId::Builtin(BuiltinTy::UnsafeCell), generics) = inner_ty.kind() else {
+        panic!("{inner_ty:?}")
+    };
+    assert!(matches!(
+        generics.types[0].kind(),
+        TyKind::Literal(LiteralTy::Integer(IntegerTy::U32))
+    ));
+    Ok(())
+}
+
+#[test]
+fn unsafe_cell_aggregate() -> anyhow::Result<()> {
+    let crate_data = util::translate_rust_text_with_args(
+        r#"
+        fn new_cell() -> std::cell::Cell<u32> {
+            std::cell::Cell::new(0)
+        }
+        "#,
+        &["--extract-opaque-bodies"],
+    )?;
+    // `UnsafeCell::new` builds the cell directly, which must not be an error.
+    let mut cell_aggregates = 0;
+    for f in &crate_data.fun_decls {
+        let body = f.body.as_ref().unwrap();
+        body.dyn_visit_in_body(|rvalue: &Rvalue| {
+            if let Rvalue::Aggregate(
+                AggregateKind::Adt(TypeId::Builtin(BuiltinTy::UnsafeCell), ..),
+                _,
+            ) = rvalue
+            {
+                cell_aggregates += 1;
+            }
+        });
+    }
+    assert_eq!(cell_aggregates, 1);
+    Ok(())
+}
+
+#[test]
+fn raw_borrows() -> anyhow::Result<()> {
+    let crate_data = translate(
+        r#"
+        use std::ptr::{addr_of, addr_of_mut};
+        fn raw_syntax() {
+            let mut x = 0u32;
+            let _p = &raw const x;
+            let _q = &raw mut x;
+        }
+        fn raw_macros() {
+            let mut x = 0u32;
+            let _p = addr_of!(x);
+            let _q = addr_of_mut!(x);
+        }
+        "#,
+    )?;
+    let bodies = crate_data
+        .fun_decls
+        .iter()
+        .map(|f| &f.body.as_ref().unwrap().as_structured().unwrap().body)
+        .collect_vec();
+    let [syntax_body, macros_body] = bodies.as_slice() else {
+        panic!()
+    };
+    let raw_ptrs = syntax_body
+        .statements
+        .iter()
+        .filter_map(|st| match &st.content {
+            RawStatement::Assign(_, Rvalue::RawPtr(_, kind)) => Some(*kind),
+            _ => None,
+        })
+        .collect_vec();
+    assert_eq!(raw_ptrs, vec![RefKind::Shared, RefKind::Mut]);
+
+    let fmt_ctx = crate_data.into_fmt();
+    assert_eq!(
+        syntax_body.fmt_with_ctx(&fmt_ctx),
+        macros_body.fmt_with_ctx(&fmt_ctx)
+    );
+    Ok(())
+}
+
+#[test]
+fn simplify_constants_names() -> anyhow::Result<()> {
+    let code = r#"
+        const ONE: u32 = 1;
+        const PAIR: (u32, u32) = (2, 3);
+        fn sum() -> u32 {
+            ONE + PAIR.0 + PAIR.1
+        }
+    "#;
+    let local_names = |crate_data: &TranslatedCrate| {
+        let sum = crate_data
+            .fun_decls
+            .iter()
+            .find(|f| repr_name(crate_data, &f.item_meta.name) == "test_crate::sum")
+            .unwrap();
+        sum.body
+            .as_ref()
+            .unwrap()
+            .as_structured()
+            .unwrap()
+            .locals
+            .vars
+            .iter()
+            .filter_map(|var| var.name.clone())
+            .collect_vec()
+    };
+    let names = local_names(&translate(code)?);
+    assert!(names.contains(&"anon_0".to_string()), "{names:?}");
+    assert!(names.contains(&"anon_1".to_string()), "{names:?}");
+    // The names don't depend on the run.
+    assert_eq!(names, local_names(&translate(code)?));
+    Ok(())
+}
+
+#[test]
+fn scalar_valid_range() -> anyhow::Result<()> {
+    let crate_data = translate(
+        r#"
+        fn f(_x: std::num::NonZeroU8) {}
+        "#,
+    )?;
+    let non_zero = crate_data
+        .type_decls
+        .iter()
+        .find(|decl| repr_name(&crate_data, &decl.item_meta.name).contains("NonZero"))
+        .unwrap();
+    assert_eq!(
+        non_zero.valid_range,
+        Some(ScalarValidRange {
+            start: Some(1),
+            end: None
+        })
+    );
+    Ok(())
+}
+
+#[test]
+fn method_calls() -> anyhow::Result<()> {
+    let crate_data = util::translate_rust_text_with_args(
+        r#"
+        struct Foo;
+        impl Foo {
+            fn new() -> Self {
+                Foo
+            }
+            fn get(&self, x: u32) -> u32 {
+                x
+            }
+        }
+        trait Trait {
+            fn method(&mut self);
+        }
+        impl Trait for Foo {
+            fn method(&mut self) {}
+        }
+        fn f() -> u32 {
+            let mut foo = Foo::new();
+            foo.method();
+            foo.get(1)
+        }
+        "#,
+        &["--reconstruct-method-calls"],
+    )?;
+    let f = crate_data
+        .fun_decls
+        .iter()
+        .find(|f| repr_name(&crate_data, &f.item_meta.name) == "test_crate::f")
+        .unwrap();
+    assert!(!f.signature.has_self_param);
+    let ctx = &crate_data.into_fmt();
+    let mut calls = vec![];
+    f.body.as_ref().unwrap().dyn_visit_in_body(|call: &Call| {
+        let name = call.func.fmt_with_ctx(ctx);
+        let name = name.rsplit("::").next().unwrap().to_owned();
+        calls.push((name, call.is_method_call));
+    });
+    assert_eq!(
+        calls,
+        vec![
+            ("new".to_owned(), false),
+            ("method".to_owned(), true),
+            ("get".to_owned(), true),
+        ]
+    );
+    // Only the name of the method is printed after the receiver.
+    let body = f.body.as_ref().unwrap().as_structured().unwrap();
+    let repr = body.body.fmt_with_ctx(ctx);
+    assert!(repr.contains(").method()"), "{repr}");
+    assert!(repr.contains(").get(const (1 : u32))"), "{repr}");
+    Ok(())
+}
+
+#[test]
+fn generic_defaults() -> anyhow::Result<()> {
+    let crate_data = translate(
+        r#"
+        struct S<const N: usize = 4, T = u32> {
+            x: [T; N],
+        }
+        fn f(_s: S::<>) {}
+        "#,
+    )?;
+    let four = ConstGeneric::Value(Literal::Scalar(ScalarValue::Usize(4)));
+    let u32_ty = TyKind::Literal(LiteralTy::Integer(IntegerTy::U32));
+    let s = &crate_data.type_decls[0];
+    assert_eq!(repr_name(&crate_data, &s.item_meta.name), "test_crate::S");
+    assert_eq!(s.generics.const_generics[0].default.as_ref(), Some(&four));
+    assert_eq!(
+        s.generics.types[0].default.as_ref().map(|ty| ty.kind()),
+        Some(&u32_ty)
+    );
+
+    // The defaults are substituted when the parameters are not specified.
+    let f = crate_data
+        .fun_decls
+        .iter()
+        .find(|f| repr_name(&crate_data, &f.item_meta.name) == "test_crate::f")
+        .unwrap();
+    let TyKind::Adt(TypeId::Adt(_), args) = f.signature.inputs[0].kind() else {
+        panic!()
+    };
+    assert_eq!(args.const_generics[0], four);
+    assert_eq!(args.types[0].kind(), &u32_ty);
+    Ok(())
+}
+
+#[test]
+fn dyn_closure_calls() -> anyhow::Result<()> {
+    let crate_data = translate(
+        r#"
+        fn call_boxed(f: Box<dyn Fn(u32) -> u32>) -> u32 {
+            f(1)
+        }
+        fn call_mut(f: &mut dyn FnMut(u32)) {
+            f(1)
+        }
+        fn call_once(f: Box<dyn FnOnce() -> u32>) -> u32 {
+            f()
+        }
+        "#,
+    )?;
+    let call_kinds = |name: &str| {
+        let f = crate_data
+            .fun_decls
+            .iter()
+            .find(|f| repr_name(&crate_data, &f.item_meta.name) == name)
+            .unwrap();
+        let mut kinds = vec![];
+        f.body.as_ref().unwrap().dyn_visit_in_body(|call: &Call| {
+            if let FnOperand::DynClosure { kind, .. } = &call.func {
+                assert!(call.args.is_empty());
+                kinds.push(*kind);
+            }
+        });
+        kinds
+    };
+    assert_eq!(call_kinds("test_crate::call_boxed"), vec![ClosureKind::Fn]);
+    assert_eq!(call_kinds("test_crate::call_mut"), vec![ClosureKind::FnMut]);
+    assert_eq!(
+        call_kinds("test_crate::call_once"),
+        vec![ClosureKind::FnOnce]
+    );
+    Ok(())
+}
+
+#[test]
+fn ctor_kinds() -> anyhow::Result<()> {
+    let crate_data = translate(
+        r#"
+        struct Point(u32, u32);
+        struct Named { x: u32 }
+        struct Marker;
+        enum E { A(u32), B { x: u32 }, C }
+        "#,
+    )?;
+    let decl = |name: &str| {
+        crate_data
+            .type_decls
+            .iter()
+            .find(|decl| repr_name(&crate_data, &decl.item_meta.name) == name)
+            .unwrap()
+    };
+    assert_eq!(decl("test_crate::Point").ctor_kind, Some(CtorKind::Tuple));
+    assert_eq!(decl("test_crate::Named").ctor_kind, Some(CtorKind::Braced));
+    assert_eq!(decl("test_crate::Marker").ctor_kind, Some(CtorKind::Unit));
+    let e = decl("test_crate::E");
+    assert_eq!(e.ctor_kind, None);
+    let ctor_kinds = e
+        .kind
+        .as_enum()
+        .unwrap()
+        .iter()
+        .map(|variant| variant.ctor_kind)
+        .collect_vec();
+    assert_eq!(
+        ctor_kinds,
+        vec![CtorKind::Tuple, CtorKind::Braced, CtorKind::Unit]
+    );
+    Ok(())
+}
+
+#[test]
+fn repr_attributes() -> anyhow::Result<()> {
+    let crate_data = translate(
+        r#"
+        #[repr(u16, align(8))]
+        enum E { A, B }
+        #[repr(C, packed)]
+        struct P { x: u8, y: u32 }
+        #[repr(transparent)]
+        struct W(u32);
+        struct Plain(u32);
+        "#,
+    )?;
+    let decl = |name: &str| {
+        crate_data
+            .type_decls
+            .iter()
+            .find(|decl| repr_name(&crate_data, &decl.item_meta.name) == name)
+            .unwrap()
+    };
+    assert_eq!(
+        decl("test_crate::E").repr,
+        Some(ReprInfo {
+            int: Some(IntegerTy::U16),
+            align: Some(8),
+            ..ReprInfo::default()
+        })
+    );
+    assert_eq!(
+        decl("test_crate::P").repr,
+        Some(ReprInfo {
+            c: true,
+            pack: Some(1),
+            ..ReprInfo::default()
+        })
+    );
+    assert_eq!(
+        decl("test_crate::W").repr,
+        Some(ReprInfo {
+            transparent: true,
+            ..ReprInfo::default()
+        })
+    );
+    assert_eq!(decl("test_crate::Plain").repr, None);
+    Ok(())
+}
+
+#[test]
+fn must_use() -> anyhow::Result<()> {
+    let crate_data = translate(
+        r#"
+        #[must_use]
+        struct Token;
+        #[must_use = "the result must be checked"]
+        fn check() -> bool { true }
+        fn unchecked() -> bool { true }
+        "#,
+    )?;
+    assert_eq!(
+        repr_name(&crate_data, &crate_data.type_decls[0].item_meta.name),
+        "test_crate::Token"
+    );
+    assert!(crate_data.type_decls[0].item_meta.attr_info.must_use);
+    let fun = |name: &str| {
+        crate_data
+            .fun_decls
+            .iter()
+            .find(|f| repr_name(&crate_data, &f.item_meta.name) == name)
+            .unwrap()
+    };
+    assert!(fun("test_crate::check").item_meta.attr_info.must_use);
+    assert!(!fun("test_crate::unchecked").item_meta.attr_info.must_use);
+    Ok(())
+}
+
+#[test]
+fn let_else() -> anyhow::Result<()> {
+    let crate_data = util::translate_rust_text_with_args(
+        r#"
+        fn returns(opt: Option<u32>) -> u32 {
+            let Some(x) = opt else { return 0 };
+            x
+        }
+        fn panics(opt: Option<u32>) -> u32 {
+            let Some(x) = opt else { panic!() };
+            x
+        }
+        "#,
+        &["--reconstruct-let-else"],
+    )?;
+    for name in ["test_crate::returns", "test_crate::panics"] {
+        let f = crate_data
+            .fun_decls
+            .iter()
+            .find(|f| repr_name(&crate_data, &f.item_meta.name) == name)
+            .unwrap();
+        let body = f.body.as_ref().unwrap();
+        let body = &body.as_structured().unwrap().body;
+        let (variants, else_block) = body
+            .statements
+            .iter()
+            .find_map(|st| match &st.content {
+                RawStatement::LetElse {
+                    variants,
+                    else_block,
+                    ..
+                } => Some((variants, else_block)),
+                _ => None,
+            })
+            .unwrap_or_else(|| panic!("no `let .. else` in {name}"));
+        // `Some` is the second variant of `Option`.
+        assert_eq!(variants, &vec![VariantId::new(1)]);
+        let last = &else_block.statements.last().unwrap().content;
+        if name == "test_crate::returns" {
+            assert!(matches!(last, RawStatement::Return), "{last:?}");
+        } else {
+            assert!(matches!(last, RawStatement::Abort(_)), "{last:?}");
+        }
+        // No match remains.
+        assert!(!body
+            .statements
+            .iter()
+            .any(|st| matches!(st.content, RawStatement::Switch(_))));
+    }
+    Ok(())
+}
+
+#[test]
+fn while_loops() -> anyhow::Result<()> {
+    let crate_data = util::translate_rust_text_with_args(
+        r#"
+        fn count(n: u32) -> u32 {
+            let mut i = 0;
+            while i < n {
+                i += 1;
+            }
+            i
+        }
+        fn drain(v: &mut Vec<u32>) {
+            while v.len() > 0 {
+                v.pop();
+            }
+        }
+        "#,
+        &["--reconstruct-while-loops"],
+    )?;
+    let body = |name: &str| {
+        let f = crate_data
+            .fun_decls
+            .iter()
+            .find(|f| repr_name(&crate_data, &f.item_meta.name) == name)
+            .unwrap();
+        f.body
+            .as_ref()
+            .unwrap()
+            .as_structured()
+            .unwrap()
+            .body
+            .clone()
+    };
+
+    let count = body("test_crate::count");
+    let (cond_block, body_block) = count
+        .statements
+        .iter()
+        .find_map(|st| match &st.content {
+            RawStatement::While {
+                cond_block, body, ..
+            } => Some((cond_block, body)),
+            _ => None,
+        })
+        .expect("no `while` in `count`");
+    // The condition computes `i < n`.
+    assert!(matches!(
+        cond_block.statements.last().unwrap().content,
+        RawStatement::Assign(_, Rvalue::BinaryOp(BinOp::Lt, ..))
+    ));
+    assert!(!body_block
+        .statements
+        .iter()
+        .any(|st| matches!(st.content, RawStatement::Continue(0))));
+    assert!(!count
+        .statements
+        .iter()
+        .any(|st| matches!(st.content, RawStatement::Loop(_))));
+
+    // The condition of this loop calls a method: we keep the loop.
+    let drain = body("test_crate::drain");
+    assert!(drain
+        .statements
+        .iter()
+        .any(|st| matches!(st.content, RawStatement::Loop(_))));
+    assert!(!drain
+        .statements
+        .iter()
+        .any(|st| matches!(st.content, RawStatement::While { .. })));
+    Ok(())
+}
+
+#[test]
+fn loop_break_values() -> anyhow::Result<()> {
+    let crate_data = translate(
+        r#"
+        fn immediate() -> i32 {
+            let x = loop {
+                break 5;
+            };
+            x
+        }
+        fn search(mut i: u32) -> u32 {
+            let x = loop {
+                i += 1;
+                if i > 10 {
+                    break i * 2;
+                }
+            };
+            x
+        }
+        "#,
+    )?;
+    let body = |name: &str| {
+        let f = crate_data
+            .fun_decls
+            .iter()
+            .find(|f| repr_name(&crate_data, &f.item_meta.name) == name)
+            .unwrap();
+        f.body.as_ref().unwrap().as_structured().unwrap().clone()
+    };
+    let local_named = |body: &ExprBody, name: &str| {
+        body.locals
+            .vars
+            .iter()
+            .find(|var| var.name.as_deref() == Some(name))
+            .unwrap()
+            .index
+    };
+
+    // `x = 5`.
+    let immediate = body("test_crate::immediate");
+    let x = local_named(&immediate, "x");
+    let mut x_values = vec![];
+    immediate.body.dyn_visit_in_body(|st: &Statement| {
+        if let RawStatement::Assign(dest, Rvalue::Use(Operand::Const(c))) = &st.content {
+            if dest.as_local() == Some(x) {
+                x_values.push(c.value.clone());
+            }
+        }
+    });
+    assert_eq!(
+        x_values,
+        vec![RawConstantExpr::Literal(Literal::Scalar(ScalarValue::I32(
+            5
+        )))]
+    );
+
+    // The break value is assigned to `x`, and the loop is exited with a `Break`.
+    let search = body("test_crate::search");
+    let x = local_named(&search, "x");
+    let loop_body = search
+        .body
+        .statements
+        .iter()
+        .find_map(|st| match &st.content {
+            RawStatement::Loop(body) => Some(body),
+            _ => None,
+        })
+        .expect("no loop in `search`");
+    let mut breaks = false;
+    loop_body.dyn_visit_in_body(|st: &Statement| {
+        if let RawStatement::Break(_) = st.content {
+            breaks = true;
+        }
+    });
+    assert!(breaks);
+    let mut assigns_x = false;
+    search.body.dyn_visit_in_body(|st: &Statement| {
+        if let RawStatement::Assign(dest, Rvalue::BinaryOp(BinOp::Mul, ..)) = &st.content {
+            assigns_x |= dest.as_local() == Some(x);
+        }
+    });
+    assert!(assigns_x);
+    Ok(())
+}
+
+#[test]
+fn print_depth() -> anyhow::Result<()> {
+    let crate_data = translate(
+        r#"
+        fn nested(a: bool, b: bool) -> u32 {
+            if a {
+                if b {
+                    42
+                } else {
+                    1
+                }
+            } else {
+                0
+            }
+        }
+        "#,
+    )?;
+    let full = crate_data.to_string_with_max_depth(None);
+    assert!(full.contains("42"), "{full}");
+    assert!(!full.contains("..."), "{full}");
+    // The inner `if` is elided.
+    let elided = crate_data.to_string_with_max_depth(Some(2));
+    assert!(!elided.contains("42"), "{elided}");
+    assert!(elided.contains("..."), "{elided}");
+    Ok(())
+}
+
+#[test]
+fn transmute() -> anyhow::Result<()> {
+    let crate_data = translate(
+        r#"
+        fn to_bytes(x: u32) -> [u8; 4] {
+            unsafe { std::mem::transmute::<u32, [u8; 4]>(x) }
+        }
+        "#,
+    )?;
+    let function = &crate_data.fun_decls[0];
+    let body = function.body.as_ref().unwrap();
+    let body = &body.as_structured().unwrap().body;
+    let (src, tgt) = body
+        .statements
+        .iter()
+        .find_map(|st| match &st.content {
+            RawStatement::Assign(
+                _,
+                Rvalue::UnaryOp(UnOp::Cast(CastKind::Transmute(src, tgt)), _),
+            ) => Some((src, tgt)),
+            _ => None,
+        })
+        .unwrap();
+    assert_eq!(
+        src.kind(),
+        &TyKind::Literal(LiteralTy::Integer(IntegerTy::U32))
+    );
+    let TyKind::Adt(TypeId::Builtin(BuiltinTy::Array), generics) = tgt.kind() else {
+        panic!("unexpected target type: {tgt:?}")
+    };
+    assert_eq!(
+        generics.types[0].kind(),
+        &TyKind::Literal(LiteralTy::Integer(IntegerTy::U8))
+    );
+    // No call to the intrinsic remains.
+    assert!(!body
+        .statements
+        .iter()
+        .any(|st| matches!(st.content, RawStatement::Call(_))));
+    Ok(())
+}
+
+#[test]
+fn opaque_function() -> anyhow::Result<()> {
+    let crate_data = translate(
+        r#"
+        #![feature(register_tool)]
+        #![register_tool(charon)]
+        #[charon::opaque]
+        fn specified_separately(x: u32) -> u32 {
+            x + 1
+        }
+        fn caller() -> u32 {
+            specified_separately(0)
+        }
+        "#,
+    )?;
+    let fun = |name: &str| {
+        crate_data
+            .fun_decls
+            .iter()
+            .find(|f| repr_name(&crate_data, &f.item_meta.name) == name)
+            .unwrap()
+    };
+    let opaque = fun("test_crate::specified_separately");
+    // The signature is translated but not the body.
+    assert_eq!(opaque.signature.inputs.len(), 1);
+    assert!(opaque.body.is_err());
+    assert!(opaque.item_meta.opacity.is_opaque());
+    assert!(fun("test_crate::caller").body.is_ok());
+    Ok(())
+}
+
+#[test]
+fn opaque_file() -> anyhow::Result<()> {
+    let dir = tempfile::TempDir::new()?;
+    let opaque_file = dir.path().join("opaque.txt");
+    std::fs::write(
+        &opaque_file,
+        "# Keep the helpers opaque, except for one.\ncrate::helpers\n\n!crate::helpers::kept\n",
+    )?;
+    let crate_data = util::translate_rust_text_with_args(
+        r#"
+        mod helpers {
+            pub fn hidden(x: u32) -> u32 {
+                x + 1
+            }
+            pub fn kept(x: u32) -> u32 {
+                x + 2
+            }
+        }
+        fn caller() -> u32 {
+            helpers::hidden(0) + helpers::kept(0)
+        }
+        "#,
+        &["--opaque-file", opaque_file.to_str().unwrap()],
+    )?;
+    let fun = |name: &str| {
+        crate_data
+            .fun_decls
+            .iter()
+            .find(|f| repr_name(&crate_data, &f.item_meta.name) == name)
+            .unwrap()
+    };
+    let hidden = fun("test_crate::helpers::hidden");
+    assert_eq!(hidden.signature.inputs.len(), 1);
+    assert!(hidden.body.is_err());
+    assert!(fun("test_crate::helpers::kept").body.is_ok());
+    assert!(fun("test_crate::caller").body.is_ok());
+    Ok(())
+}
+
+#[test]
+fn from_conversions() -> anyhow::Result<()> {
+    let crate_data = translate(
+        r#"
+        struct MyErr;
+        struct BoxErr(u32);
+        impl From<MyErr> for BoxErr {
+            fn from(_: MyErr) -> Self {
+                BoxErr(42)
+            }
+        }
+        fn via_from(e: MyErr) -> BoxErr {
+            BoxErr::from(e)
+        }
+        fn via_into(e: MyErr) -> BoxErr {
+            e.into()
+        }
+        "#,
+    )?;
+    let fun = |name: &str| {
+        crate_data
+            .fun_decls
+            .iter()
+            .find(|f| repr_name(&crate_data, &f.item_meta.name) == name)
+            .unwrap()
+    };
+    let from_impl = crate_data
+        .fun_decls
+        .iter()
+        .find(|f| {
+            let name = repr_name(&crate_data, &f.item_meta.name);
+            name.starts_with("test_crate::<impl") && name.ends_with("::from")
+        })
+        .unwrap();
+    assert!(from_impl.body.is_ok());
+    for caller in ["test_crate::via_from", "test_crate::via_into"] {
+        let body = fun(caller).body.as_ref().unwrap();
+        let body = &body.as_structured().unwrap().body;
+        let fn_ptr = body
+            .statements
+            .iter()
+            .find_map(|st| match &st.content {
+                RawStatement::Call(Call {
+                    func: FnOperand::Regular(fn_ptr),
+                    ..
+                }) => Some(fn_ptr),
+                _ => None,
+            })
+            .unwrap();
+        // The call refers to the local `from` method directly, without going through a trait ref.
+        let FunIdOrTraitMethodRef::Fun(FunId::Regular(id)) = &fn_ptr.func else {
+            panic!("unexpected callee in `{caller}`: {:?}", fn_ptr.func)
+        };
+        assert_eq!(*id, from_impl.def_id);
+    }
+    Ok(())
+}
+
+#[test]
+fn closure_arguments() -> anyhow::Result<()> {
+    let crate_data = translate(
+        r#"
+        fn double_all() -> Vec<i32> {
+            [1, 2, 3].iter().map(|x| x * 2).collect()
+        }
+        "#,
+    )?;
+    let fun = |name: &str| {
+        crate_data
+            .fun_decls
+            .iter()
+            .find(|f| repr_name(&crate_data, &f.item_meta.name) == name)
+            .unwrap()
+    };
+    let closure = fun("test_crate::double_all::closure");
+    let body = fun("test_crate::double_all").body.as_ref().unwrap();
+    // The closure passed to `map` refers to the function that implements it, whether it is built
+    // as an aggregate or passed as a zero-sized constant.
+    let mut closure_ids = vec![];
+    body.dyn_visit_in_body(|op: &Operand| {
+        if let Operand::Const(ConstantExpr {
+            value:
+                RawConstantExpr::FnPtr(FnPtr {
+                    func: FunIdOrTraitMethodRef::Fun(FunId::Regular(id)),
+                    ..
+                }),
+            ..
+        }) = op
+        {
+            closure_ids.push(*id)
+        }
+    });
+    body.dyn_visit_in_body(|kind: &AggregateKind| {
+        if let AggregateKind::Closure(id, _) = kind {
+            closure_ids.push(*id)
+        }
+    });
+    assert_eq!(closure_ids, vec![closure.def_id]);
+    Ok(())
+}
+
+#[test]
+fn closure_capturing_mut_self() -> anyhow::Result<()> {
+    let crate_data = translate(
+        r#"
+        struct S<T> { x: u32, y: T }
+        impl<T> S<T> {
+            fn m(&mut self) {
+                let mut f = || self.x += 1;
+                f();
+            }
+        }
+        "#,
+    )?;
+    let closure = crate_data
+        .fun_decls
+        .iter()
+        .find(|f| {
+            repr_name(&crate_data, &f.item_meta.name) == "test_crate::<inherent impl>::m::closure"
+        })
+        .unwrap();
+    let sig = &closure.signature;
+    // The closure inherits the generics of the impl.
+    assert!(sig.is_closure);
+    assert_eq!(sig.generics.types.elem_count(), 1);
+    let info = sig.closure_info.as_ref().unwrap();
+    assert_eq!(info.kind, ClosureKind::FnMut);
+    // The closure captures `self.x` by mutable borrow.
+    assert_eq!(info.state.elem_count(), 1);
+    let state_ty = info.state.iter().next().unwrap();
+    let TyKind::Ref(region, ty, RefKind::Mut) = state_ty.kind() else {
+        panic!("unexpected state type: {state_ty:?}")
+    };
+    assert_eq!(
+        ty.kind(),
+        &TyKind::Literal(LiteralTy::Integer(IntegerTy::U32))
+    );
+    assert_ne!(*region, Region::Erased);
+    // The state types are those of the tuple the closure receives.
+    let TyKind::Ref(_, input_state, RefKind::Mut) = sig.inputs[0].kind() else {
+        panic!()
+    };
+    let TyKind::Adt(TypeId::Tuple, args) = input_state.kind() else {
+        panic!()
+    };
+    assert_eq!(args.types, info.state);
+    Ok(())
+}
+
+#[test]
+fn binding_modes() -> anyhow::Result<()> {
+    let crate_data = translate(
+        r#"
+        fn by_default_ref(opt: &Option<u32>) -> u32 {
+            match opt { Some(x) => *x, None => 0 }
+        }
+        fn by_move(opt: Option<u32>) -> u32 {
+            match opt { Some(y) => y, None => 0 }
+        }
+        fn by_mut_ref(mut opt: Option<u32>) {
+            if let Some(ref mut z) = opt { *z += 1 }
+        }
+        "#,
+    )?;
+    let binding_mode = |fun_name: &str, var_name: &str| {
+        let fun = crate_data
+            .fun_decls
+            .iter()
+            .find(|f| repr_name(&crate_data, &f.item_meta.name) == fun_name)
+            .unwrap();
+        let body = fun.body.as_ref().unwrap().as_structured().unwrap();
+        body.locals
+            .vars
+            .iter()
+            .find(|var| var.name.as_deref() == Some(var_name))
+            .unwrap()
+            .binding_mode
+    };
+    assert_eq!(
+        binding_mode("test_crate::by_default_ref", "x"),
+        Some(BindingMode::ByRef)
+    );
+    assert_eq!(
+        binding_mode("test_crate::by_move", "y"),
+        Some(BindingMode::ByMove)
+    );
+    assert_eq!(
+        binding_mode("test_crate::by_mut_ref", "z"),
+        Some(BindingMode::ByMutRef)
+    );
+    // Function parameters are bindings too; temporaries aren't.
+    assert_eq!(
+        binding_mode("test_crate::by_move", "opt"),
+        Some(BindingMode::ByMove)
+    );
+    let fun = crate_data
+        .fun_decls
+        .iter()
+        .find(|f| repr_name(&crate_data, &f.item_meta.name) == "test_crate::by_move")
+        .unwrap();
+    let locals = &fun.body.as_ref().unwrap().as_structured().unwrap().locals;
+    assert!(locals.vars.iter().any(|var| var.binding_mode.is_none()));
+    Ok(())
+}
+
+#[test]
+fn string_literals() -> anyhow::Result<()> {
+    let crate_data = translate(
+        r#"
+        const S: &str = "hello";
+        const B: &[u8] = b"hi\n";
+        "#,
+    )?;
+    let literals = |name: &str| {
+        let global = crate_data
+            .global_decls
+            .iter()
+            .find(|g| repr_name(&crate_data, &g.item_meta.name) == name)
+            .unwrap();
+        let body = crate_data.fun_decls[global.init].body.as_ref().unwrap();
+        let mut literals = vec![];
+        body.dyn_visit_in_body(|c: &ConstantExpr| {
+            if let RawConstantExpr::Literal(lit) = &c.value {
+                literals.push(lit.clone())
+            }
+        });
+        literals
+    };
+    assert_eq!(
+        literals("test_crate::S"),
+        vec![Literal::Str("hello".to_owned())]
+    );
+    // rustc gives us byte strings as arrays of bytes, which we can't tell apart from other `u8`
+    // arrays; we keep the bytes in order.
+    assert_eq!(
+        literals("test_crate::B"),
+        b"hi\n"
+            .iter()
+            .map(|b| Literal::Scalar(ScalarValue::U8(*b)))
+            .collect::<Vec<_>>()
+    );
+    Ok(())
+}
+
+#[test]
+fn std_collections() -> anyhow::Result<()> {
+    let crate_data = translate(
+        r#"
+        use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
+        fn counts(_map: &HashMap<String, u32>) {}
+        fn others(_: VecDeque<u8>, _: BTreeMap<u8, bool>, _: HashSet<char>) {}
+        "#,
+    )?;
+    let fun = |name: &str| {
+        crate_data
+            .fun_decls
+            .iter()
+            .find(|f| repr_name(&crate_data, &f.item_meta.name) == name)
+            .unwrap()
+    };
+    let counts = fun("test_crate::counts");
+    let TyKind::Ref(_, map_ty, _) = counts.signature.inputs[0].kind() else {
+        panic!()
+    };
+    // The hasher parameter is dropped but the key and value types are kept.
+    let TyKind::Adt(TypeId::Builtin(BuiltinTy::HashMap), generics) = map_ty.kind() else {
+        panic!("{map_ty:?}")
+    };
+    assert_eq!(generics.types.elem_count(), 2);
+    assert!(matches!(
+        generics.types[0].kind(),
+        TyKind::Adt(TypeId::Adt(_), _)
+    ));
+    assert!(matches!(
+        generics.types[1].kind(),
+        TyKind::Literal(LiteralTy::Integer(IntegerTy::U32))
+    ));
+
+    let others = fun("test_crate::others");
+    let builtins: Vec<_> = others
+        .signature
+        .inputs
+        .iter()
+        .map(|ty| match ty.kind() {
+            TyKind::Adt(TypeId::Builtin(builtin_ty), generics) => {
+                (*builtin_ty, generics.types.elem_count())
+            }
+            _ => panic!("{ty:?}"),
+        })
+        .collect();
+    assert_eq!(
+        builtins,
+        vec![
+            (BuiltinTy::VecDeque, 1),
+            (BuiltinTy::BTreeMap, 2),
+            (BuiltinTy::HashSet, 1)
+        ]
+    );
+    Ok(())
+}
+
+#[test]
+fn panic_location() -> anyhow::Result<()> {
+    let crate_data = translate(
+        r#"
+        fn fail(x: u32) -> u32 {
+            if x == 0 {
+                panic!()
+            }
+            x
+        }
+        "#,
+    )?;
+    let function = crate_data
+        .fun_decls
+        .iter()
+        .find(|f| repr_name(&crate_data, &f.item_meta.name) == "test_crate::fail")
+        .unwrap();
+    let mut locations = vec![];
+    function
+        .body
+        .as_ref()
+        .unwrap()
+        .dyn_visit_in_body(|st: &Statement| {
+            if let RawStatement::Abort(AbortKind::Panic(_, location)) = &st.content {
+                locations.push(*location);
+            }
+        });
+    let [Some(location)] = locations.as_slice() else {
+        panic!("unexpected panic locations: {locations:?}")
+    };
+    // The location of the `panic!()` call, with a 1-based column like `Location::column`.
+    assert_eq!((location.line, location.col), (4, 17));
+    let file = &crate_data.files[location.file_id];
+    assert!(file.name.to_string().ends_with("test_crate.rs"));
+    Ok(())
+}
+
+#[test]
+fn never_locals() -> anyhow::Result<()> {
+    let crate_data = translate(
+        r#"
+        fn sum_until_none(v: &[Option<u32>]) -> u32 {
+            let mut sum = 0;
+            let mut i = 0;
+            loop {
+                let n = match v[i] {
+                    Some(n) => n,
+                    None => break,
+                };
+                sum += n;
+                i += 1;
+            }
+            sum
+        }
+        fn diverge() -> ! {
+            loop {}
+        }
+        fn call_diverge() -> u32 {
+            diverge();
+        }
+        "#,
+    )?;
+    let never_locals = |fun_name: &str| {
+        let f = crate_data
+            .fun_decls
+            .iter()
+            .find(|f| repr_name(&crate_data, &f.item_meta.name) == fun_name)
+            .unwrap();
+        let body = f.body.as_ref().unwrap().as_structured().unwrap();
+        body.locals
+            .vars
+            .iter()
+            .filter(|var| var.ty.is_never())
+            .map(|var| var.index)
+            .collect_vec()
+    };
+    // The `!`-typed local introduced for the `break` arm is removed.
+    assert_eq!(never_locals("test_crate::sum_until_none"), vec![]);
+    // The destination of a call to a diverging function remains.
+    let [dest] = never_locals("test_crate::call_diverge")[..] else {
+        panic!()
+    };
+    let f = crate_data
+        .fun_decls
+        .iter()
+        .find(|f| repr_name(&crate_data, &f.item_meta.name) == "test_crate::call_diverge")
+        .unwrap();
+    let mut call_dests = vec![];
+    f.body
+        .as_ref()
+        .unwrap()
+        .dyn_visit_in_body(|call: &Call| call_dests.push(call.dest.clone()));
+    assert_eq!(call_dests.len(), 1);
+    assert_eq!(call_dests[0].as_local(), Some(dest));
+    Ok(())
+}
+
+#[test]
+fn extern_functions() -> anyhow::Result<()> {
+    let crate_data = translate(
+        r#"
+        extern "C" {
+            fn abs(x: i32) -> i32;
+        }
+        extern "C" fn callback(x: i32) -> i32 {
+            x
+        }
+        fn call_abs(x: i32) -> i32 {
+            unsafe { abs(x) }
+        }
+        "#,
+    )?;
+    let fun = |name: &str| {
+        crate_data
+            .fun_decls
+            .iter()
+            .find(|f| repr_name(&crate_data, &f.item_meta.name) == name)
+            .unwrap()
+    };
+    let abs = fun("test_crate::abs");
+    assert!(abs.item_meta.is_foreign);
+    assert!(abs.body.is_err());
+    assert!(abs.signature.is_unsafe);
+    assert_eq!(abs.signature.abi.as_deref(), Some("C"));
+    assert_eq!(abs.signature.inputs.len(), 1);
+
+    let callback = fun("test_crate::callback");
+    assert!(!callback.item_meta.is_foreign);
+    assert_eq!(callback.signature.abi.as_deref(), Some("C"));
+
+    let call_abs = fun("test_crate::call_abs");
+    assert!(!call_abs.item_meta.is_foreign);
+    assert_eq!(call_abs.signature.abi, None);
+    let mut callees = vec![];
+    call_abs
+        .body
+        .as_ref()
+        .unwrap()
+        .dyn_visit_in_body(|fn_ptr: &FnPtr| {
+            if let FunIdOrTraitMethodRef::Fun(FunId::Regular(id)) = fn_ptr.func {
+                callees.push(id)
+            }
+        });
+    assert_eq!(callees, vec![abs.def_id]);
+    Ok(())
+}
+
+#[test]
+fn lifetime_generic_associated_types() -> anyhow::Result<()> {
+    let crate_data = translate(
+        r#"
+        trait Lending {
+            type Item<'a> where Self: 'a;
+            fn next<'a>(&'a mut self) -> Option<Self::Item<'a>>;
+        }
+        struct Buffer(Vec<u32>);
+        impl Lending for Buffer {
+            type Item<'a> = &'a [u32];
+            fn next<'a>(&'a mut self) -> Option<Self::Item<'a>> {
+                Some(&self.0)
+            }
+        }
+        fn first<L: Lending>(l: &mut L) -> Option<L::Item<'_>> {
+            l.next()
+        }
+        "#,
+    )?;
+    let tdecl = crate_data
+        .trait_decls
+        .iter()
+        .find(|t| repr_name(&crate_data, &t.item_meta.name) == "test_crate::Lending")
+        .unwrap();
+    assert!(tdecl.types.is_empty());
+    let [(name, params)] = tdecl.generic_types.as_slice() else {
+        panic!()
+    };
+    assert_eq!(name.0, "Item");
+    assert_eq!(params.regions.elem_count(), 1);
+
+    let timpl = crate_data
+        .trait_impls
+        .iter()
+        .find(|timpl| timpl.impl_trait.trait_id == tdecl.def_id)
+        .unwrap();
+    assert!(timpl.types.is_empty());
+    let [(name, bound_ty)] = timpl.generic_types.as_slice() else {
+        panic!()
+    };
+    assert_eq!(name.0, "Item");
+    assert_eq!(bound_ty.params.regions.elem_count(), 1);
+    let TyKind::Ref(Region::Var(var), _, RefKind::Shared) = bound_ty.skip_binder.kind() else {
+        panic!()
+    };
+    assert_eq!(*var, DeBruijnVar::new_at_zero(RegionId::new(0)));
+
+    // The projection carries the arguments of the GAT.
+    let first = crate_data
+        .fun_decls
+        .iter()
+        .find(|f| repr_name(&crate_data, &f.item_meta.name) == "test_crate::first")
+        .unwrap();
+    let TyKind::Adt(_, option_args) = first.signature.output.kind() else {
+        panic!()
+    };
+    let TyKind::TraitType(_, name, args) = option_args.types[0].kind() else {
+        panic!()
+    };
+    assert_eq!(name.0, "Item");
+    assert_eq!(args.regions.elem_count(), 1);
+    assert!(args.types.is_empty());
+    Ok(())
+}
+
+#[test]
+fn custom_try_type() -> anyhow::Result<()> {
+    let crate_data = translate(
+        r#"
+        #![feature(try_trait_v2)]
+        use std::ops::{ControlFlow, FromResidual, Try};
+        enum Maybe {
+            Just(u32),
+            Nothing,
+        }
+        struct NothingResidual;
+        impl Try for Maybe {
+            type Output = u32;
+            type Residual = NothingResidual;
+            fn from_output(x: u32) -> Self {
+                Maybe::Just(x)
+            }
+            fn branch(self) -> ControlFlow<NothingResidual, u32> {
+                match self {
+                    Maybe::Just(x) => ControlFlow::Continue(x),
+                    Maybe::Nothing => ControlFlow::Break(NothingResidual),
+                }
+            }
+        }
+        impl FromResidual<NothingResidual> for Maybe {
+            fn from_residual(_: NothingResidual) -> Self {
+                Maybe::Nothing
+            }
+        }
+        fn halve(x: Maybe) -> Maybe {
+            Maybe::Just(x? / 2)
+        }
+        "#,
+    )?;
+
+    // `ControlFlow` is translated as a normal enum.
+    let control_flow = crate_data
+        .type_decls
+        .iter()
+        .find(|ty| {
+            repr_name(&crate_data, &ty.item_meta.name) == "core::ops::control_flow::ControlFlow"
+        })
+        .unwrap();
+    let TypeDeclKind::Enum(variants) = &control_flow.kind else {
+        panic!()
+    };
+    let variants = variants.iter().map(|v| v.name.as_str()).collect_vec();
+    assert_eq!(variants, vec!["Continue", "Break"]);
+
+    // The `?` desugaring calls the methods of the local impls directly.
+    let halve = crate_data
+        .fun_decls
+        .iter()
+        .find(|f| repr_name(&crate_data, &f.item_meta.name) == "test_crate::halve")
+        .unwrap();
+    let mut callees = vec![];
+    halve
+        .body
+        .as_ref()
+        .unwrap()
+        .dyn_visit_in_body(|fn_ptr: &FnPtr| match &fn_ptr.func {
+            FunIdOrTraitMethodRef::Fun(FunId::Regular(id)) => {
+                let name = &crate_data.fun_decls[*id].item_meta.name;
+                callees.push(repr_name(&crate_data, name))
+            }
+            FunIdOrTraitMethodRef::Trait(_, name, _) => panic!("unresolved call to `{name}`"),
+            FunIdOrTraitMethodRef::Fun(FunId::Builtin(_)) => {}
+        });
+    assert_eq!(
+        callees,
+        vec![
+            "test_crate::<impl Try for ??>::branch",
+            "test_crate::<impl FromResidual for ??>::from_residual",
+        ]
+    );
+    Ok(())
+}
+
+#[test]
+fn inherent_method_generics() -> anyhow::Result<()> {
+    let crate_data = translate(
+        r#"
+        struct Foo<T>(T);
+        impl<T: Clone> Foo<T> {
+            fn m<U: Default>(&self) -> (T, U) {
+                (self.0.clone(), U::default())
+            }
+        }
+        fn call<T: Clone>(x: &Foo<T>) -> (T, u8) {
+            x.m::<u8>()
+        }
+        "#,
+    )?;
+    let fun = |name: &str| {
+        crate_data
+            .fun_decls
+            .iter()
+            .find(|f| repr_name(&crate_data, &f.item_meta.name) == name)
+            .unwrap()
+    };
+
+    // The method has the parameters of the impl block followed by its own.
+    let m = fun("test_crate::<inherent impl>::m");
+    let generics = &m.signature.generics;
+    let type_names = generics
+        .types
+        .iter()
+        .map(|ty| ty.name.as_str())
+        .collect_vec();
+    assert_eq!(type_names, vec!["T", "U"]);
+    let clause_traits = generics
+        .trait_clauses
+        .iter()
+        .map(|clause| trait_name(&crate_data, clause.trait_.skip_binder.trait_id))
+        .collect_vec();
+    assert_eq!(clause_traits, vec!["Clone", "Default"]);
+
+    // The impl-level parameters are also recorded in the name of the method.
+    let Some(PathElem::Impl(ImplElem::Ty(impl_ty), _)) = m.item_meta.name.name.iter().rev().nth(1)
+    else {
+        panic!()
+    };
+    assert_eq!(impl_ty.params.types.elem_count(), 1);
+    assert_eq!(impl_ty.params.trait_clauses.elem_count(), 1);
+
+    // Calls supply the arguments for both levels.
+    let call = fun("test_crate::call");
+    let mut calls = vec![];
+    call.body
+        .as_ref()
+        .unwrap()
+        .dyn_visit_in_body(|fn_ptr: &FnPtr| calls.push(fn_ptr.clone()));
+    let [fn_ptr] = calls.as_slice() else { panic!() };
+    assert_eq!(
+        fn_ptr.func,
+        FunIdOrTraitMethodRef::Fun(FunId::Regular(m.def_id))
+    );
+    assert_eq!(fn_ptr.generics.types.elem_count(), 2);
+    assert!(matches!(
+        fn_ptr.generics.types[0].kind(),
+        TyKind::TypeVar(_)
+    ));
+    assert!(matches!(
+        fn_ptr.generics.types[1].kind(),
+        TyKind::Literal(LiteralTy::Integer(IntegerTy::U8))
+    ));
+    assert_eq!(fn_ptr.generics.trait_refs.elem_count(), 2);
+    Ok(())
+}
+
+#[test]
+fn comment_annotations() -> anyhow::Result<()> {
+    let crate_data = translate(
+        r#"
+        fn decr(x: u32) -> u32 {
+            // charon: assume x > 0
+            let y = x - 1;
+            // Not a directive.
+            // charon: assert y < x
+            y
+        }
+        "#,
+    )?;
+    let function = &crate_data.fun_decls[0];
+    let body = function.body.as_ref().unwrap();
+    let body = &body.as_structured().unwrap().body;
+    let annotated = body
+        .statements
+        .iter()
+        .filter(|st| !st.annotations.is_empty())
+        .collect_vec();
+    assert_eq!(annotated.len(), 2);
+    assert_eq!(
+        annotated[0].annotations,
+        vec![Annotation::Assume("x > 0".to_owned())]
+    );
+    assert_eq!(
+        annotated[1].annotations,
+        vec![Annotation::Assert("y < x".to_owned())]
+    );
+    // Directives are removed from the plain comments; the other comments are kept.
+    assert_eq!(annotated[0].comments_before, Vec::<String>::new());
+    assert_eq!(annotated[1].comments_before, vec!["Not a directive."]);
+    Ok(())
+}
+
+#[test]
+fn dyn_upcasting() -> anyhow::Result<()> {
+    let crate_data = translate(
+        r#"
+        trait Super {
+            fn name(&self) -> u32;
+        }
+        trait Sub: Super {}
+        fn upcast(x: &dyn Sub) -> &dyn Super {
+            x
+        }
+        fn add_auto_trait(x: &(dyn Sub + Send)) -> &dyn Sub {
+            x
+        }
+        fn upcast_box(x: Box<dyn Sub>) -> Box<dyn Super> {
+            x
+        }
+        "#,
+    )?;
+    let casts_in = |name: &str| {
+        let function = crate_data
+            .fun_decls
+            .iter()
+            .find(|f| repr_name(&crate_data, &f.item_meta.name) == format!("test_crate::{name}"))
+            .unwrap();
+        let body = function.body.as_ref().unwrap();
+        let body = &body.as_structured().unwrap().body;
+        body.statements
+            .iter()
+            .filter_map(|st| match &st.content {
+                RawStatement::Assign(_, Rvalue::UnaryOp(UnOp::Cast(cast), _)) => Some(cast.clone()),
+                _ => None,
+            })
+            .collect_vec()
+    };
+
+    let casts = casts_in("upcast");
+    let [CastKind::DynUpcast(_, _, src_trait, tgt_trait)] = casts.as_slice() else {
+        panic!("expected a single upcast, found {casts:?}")
+    };
+    assert_eq!(trait_name(&crate_data, src_trait.trait_id), "Sub");
+    assert_eq!(trait_name(&crate_data, tgt_trait.trait_id), "Super");
+    // `Self` is the trait object type.
+    assert!(tgt_trait.generics.types[0].kind().is_dyn_trait());
+
+    // Smart pointers can be upcast too.
+    let casts = casts_in("upcast_box");
+    assert!(
+        casts
+            .iter()
+            .any(|cast| matches!(cast, CastKind::DynUpcast(..))),
+        "{casts:?}"
+    );
+
+    // Changing only the auto traits doesn't change the principal trait.
+    let casts = casts_in("add_auto_trait");
+    assert!(
+        casts
+            .iter()
+            .all(|cast| !matches!(cast, CastKind::DynUpcast(..))),
+        "{casts:?}"
+    );
+    Ok(())
+}
+
+#[test]
+fn lang_item_roles() -> anyhow::Result<()> {
+    let crate_data = translate(
+        r#"
+        #![no_std]
+        use core::alloc::{GlobalAlloc, Layout};
+        struct Null;
+        unsafe impl GlobalAlloc for Null {
+            unsafe fn alloc(&self, _: Layout) -> *mut u8 {
+                core::ptr::null_mut()
+            }
+            unsafe fn dealloc(&self, _: *mut u8, _: Layout) {}
+        }
+        #[global_allocator]
+        static ALLOC: Null = Null;
+        #[panic_handler]
+        fn panic(_: &core::panic::PanicInfo) -> ! {
+            loop {}
+        }
+        fn not_a_hook() {}
+        "#,
+    )?;
+    let role = |name: &str| {
+        crate_data
+            .all_items()
+            .find(|item| repr_name(&crate_data, &item.item_meta().name) == name)
+            .unwrap()
+            .item_meta()
+            .lang_item_role
+    };
+    assert_eq!(role("test_crate::panic"), Some(LangItemRole::PanicHandler));
+    assert_eq!(
+        role("test_crate::ALLOC"),
+        Some(LangItemRole::GlobalAllocator)
+    );
+    assert_eq!(role("test_crate::not_a_hook"), None);
+    Ok(())
+}
+
+#[test]
+fn const_generic_call_args() -> anyhow::Result<()> {
+    let crate_data = translate(
+        r#"
+        fn foo<T: Copy, const N: usize>(x: T) -> [T; N] {
+            [x; N]
+        }
+        fn with_var<const M: usize>() -> [u8; M] {
+            foo::<u8, M>(0)
+        }
+        fn with_value() -> [u32; 4] {
+            foo::<u32, { 2 + 2 }>(1)
+        }
+        "#,
+    )?;
+    let call_generics = |name: &str| {
+        let f = crate_data
+            .fun_decls
+            .iter()
+            .find(|f| repr_name(&crate_data, &f.item_meta.name) == name)
+            .unwrap();
+        let mut generics = vec![];
+        f.body.as_ref().unwrap().dyn_visit_in_body(|call: &Call| {
+            if let FnOperand::Regular(fn_ptr) = &call.func {
+                generics.push(fn_ptr.generics.clone());
+            }
+        });
+        assert_eq!(generics.len(), 1);
+        generics.pop().unwrap()
+    };
+    let u8_ty = TyKind::Literal(LiteralTy::Integer(IntegerTy::U8));
+    let u32_ty = TyKind::Literal(LiteralTy::Integer(IntegerTy::U32));
+
+    let generics = call_generics("test_crate::with_var");
+    assert_eq!(generics.types.elem_count(), 1);
+    assert_eq!(generics.types[0].kind(), &u8_ty);
+    assert_eq!(generics.const_generics.elem_count(), 1);
+    assert!(matches!(generics.const_generics[0], ConstGeneric::Var(_)));
+
+    let generics = call_generics("test_crate::with_value");
+    assert_eq!(generics.types.elem_count(), 1);
+    assert_eq!(generics.types[0].kind(), &u32_ty);
+    assert_eq!(generics.const_generics.elem_count(), 1);
+    assert_eq!(
+        generics.const_generics[0],
+        ConstGeneric::Value(Literal::Scalar(ScalarValue::Usize(4)))
+    );
+    Ok(())
+}
+
+#[test]
+fn ptr_offset() -> anyhow::Result<()> {
+    let crate_data = translate(
+        r#"
+        fn next(p: *const u32) -> *const u32 {
+            unsafe { p.add(1) }
+        }
+        fn back(p: *mut u8, n: isize) -> *mut u8 {
+            p.wrapping_offset(n)
+        }
+        "#,
+    )?;
+    let call_fn_ptr = |name: &str| {
+        let f = crate_data
+            .fun_decls
+            .iter()
+            .find(|f| repr_name(&crate_data, &f.item_meta.name) == name)
+            .unwrap();
+        let mut fn_ptrs = vec![];
+        f.body
+            .as_ref()
+            .unwrap()
+            .dyn_visit_in_body(|fn_ptr: &FnPtr| fn_ptrs.push(fn_ptr.clone()));
+        assert_eq!(fn_ptrs.len(), 1);
+        fn_ptrs.pop().unwrap()
+    };
+
+    let fn_ptr = call_fn_ptr("test_crate::next");
+    assert_eq!(
+        fn_ptr.func,
+        FunIdOrTraitMethodRef::Fun(FunId::Builtin(BuiltinFunId::PtrOffset(
+            BuiltinPtrOffsetOp {
+                mutability: RefKind::Shared,
+                kind: PtrOffsetKind::Add,
+                wrapping: false,
+            }
+        )))
+    );
+    assert_eq!(fn_ptr.generics.types.elem_count(), 1);
+    assert_eq!(
+        fn_ptr.generics.types[0].kind(),
+        &TyKind::Literal(LiteralTy::Integer(IntegerTy::U32))
+    );
+
+    let fn_ptr = call_fn_ptr("test_crate::back");
+    assert_eq!(
+        fn_ptr.func,
+        FunIdOrTraitMethodRef::Fun(FunId::Builtin(BuiltinFunId::PtrOffset(
+            BuiltinPtrOffsetOp {
+                mutability: RefKind::Mut,
+                kind: PtrOffsetKind::Offset,
+                wrapping: true,
+            }
+        )))
+    );
+    assert_eq!(
+        fn_ptr.generics.types[0].kind(),
+        &TyKind::Literal(LiteralTy::Integer(IntegerTy::U8))
+    );
+    Ok(())
+}
+
+#[test]
+fn custom_residual_type() -> anyhow::Result<()> {
+    let crate_data = translate(
+        r#"
+        #![feature(try_trait_v2)]
+        use std::ops::{ControlFlow, FromResidual, Try};
+        struct Failure(u32);
+        enum Checked {
+            Passed(u32),
+            Failed(Failure),
+        }
+        enum Report {
+            Success(u32),
+            Error(u32),
+        }
+        impl Try for Checked {
+            type Output = u32;
+            type Residual = Failure;
+            fn from_output(x: u32) -> Self {
+                Checked::Passed(x)
+            }
+            fn branch(self) -> ControlFlow<Failure, u32> {
+                match self {
+                    Checked::Passed(x) => ControlFlow::Continue(x),
+                    Checked::Failed(f) => ControlFlow::Break(f),
+                }
+            }
+        }
+        impl FromResidual<Failure> for Checked {
+            fn from_residual(f: Failure) -> Self {
+                Checked::Failed(f)
+            }
+        }
+        impl FromResidual<Failure> for Report {
+            fn from_residual(f: Failure) -> Self {
+                Report::Error(f.0)
+            }
+        }
+        fn check(x: Checked) -> Report {
+            Report::Success(x?)
+        }
+        "#,
+    )?;
+    let report_id = crate_data
+        .type_decls
+        .iter()
+        .find(|ty| repr_name(&crate_data, &ty.item_meta.name) == "test_crate::Report")
+        .unwrap()
+        .def_id;
+
+    // The error path of `?` calls the `FromResidual` impl of the return type, which converts the
+    // residual of `Checked` into a `Report`.
+    let check = crate_data
+        .fun_decls
+        .iter()
+        .find(|f| repr_name(&crate_data, &f.item_meta.name) == "test_crate::check")
+        .unwrap();
+    let mut callees = vec![];
+    check
+        .body
+        .as_ref()
+        .unwrap()
+        .dyn_visit_in_body(|fn_ptr: &FnPtr| match &fn_ptr.func {
+            FunIdOrTraitMethodRef::Fun(FunId::Regular(id)) => callees.push(*id),
+            FunIdOrTraitMethodRef::Trait(_, name, _) => panic!("unresolved call to `{name}`"),
+            FunIdOrTraitMethodRef::Fun(FunId::Builtin(_)) => {}
+        });
+    let callee_names = callees
+        .iter()
+        .map(|id| repr_name(&crate_data, &crate_data.fun_decls[*id].item_meta.name))
+        .collect_vec();
+    assert_eq!(
+        callee_names,
+        vec![
+            "test_crate::<impl Try for ??>::branch",
+            "test_crate::<impl FromResidual for ??>::from_residual",
+        ]
+    );
+    let from_residual = &crate_data.fun_decls[callees[1]];
+    assert!(matches!(
+        from_residual.signature.output.kind(),
+        TyKind::Adt(TypeId::Adt(id), _) if *id == report_id
+    ));
+    // The body of the conversion is available.
+    assert!(from_residual.body.is_ok());
+    Ok(())
+}
+
+#[test]
+fn relative_source_paths() -> anyhow::Result<()> {
+    // `translate` writes the code to a new temporary directory every time.
+    let source_path = |krate: &TranslatedCrate| {
+        let foo = krate
+            .fun_decls
+            .iter()
+            .find(|f| repr_name(krate, &f.item_meta.name) == "test_crate::foo")
+            .unwrap();
+        krate.files[foo.item_meta.span.span.file_id].name.clone()
+    };
+    let first = source_path(&translate("fn foo() {}")?);
+    let second = source_path(&translate("fn foo() {}")?);
+    assert_eq!(first, FileName::Local("test_crate.rs".into()));
+    assert_eq!(first, second);
+    Ok(())
+}
+
+#[test]
+fn ref_binding_of_enum_field() -> anyhow::Result<()> {
+    let crate_data = translate(
+        r#"
+        fn use_it<T>(_: &T) {}
+        fn foo<T>(opt: Option<T>) {
+            match &opt {
+                Some(ref x) => use_it(x),
+                None => {}
+            }
+        }
+        "#,
+    )?;
+    let foo = crate_data
+        .fun_decls
+        .iter()
+        .find(|f| repr_name(&crate_data, &f.item_meta.name) == "test_crate::foo")
+        .unwrap();
+    let body = foo.body.as_ref().unwrap().as_structured().unwrap();
+    let x = body
+        .locals
+        .vars
+        .iter()
+        .find(|var| var.name.as_deref() == Some("x"))
+        .unwrap();
+    let TyKind::Ref(_, x_inner_ty, RefKind::Shared) = x.ty.kind() else {
+        panic!("unexpected type for `x`: {:?}", x.ty)
+    };
+    assert!(x_inner_ty.kind().is_type_var());
+
+    // `x` borrows `(*@2 as Some).0`: the field of the `Some` variant, behind a dereference.
+    let mut borrowed = vec![];
+    body.body.dyn_visit_in_body(|rv: &Rvalue| {
+        if let Rvalue::Ref(place, _) = rv {
+            if let Some((sub, ProjectionElem::Field(FieldProjKind::Adt(_, variant), field))) =
+                place.as_projection()
+            {
+                borrowed.push((sub.clone(), *variant, *field, place.ty().clone()));
+            }
+        }
+    });
+    let [(sub, variant, field, ty)] = borrowed.as_slice() else {
+        panic!("expected a single borrow of an enum field: {borrowed:?}")
+    };
+    assert_eq!(*variant, Some(VariantId::new(1)));
+    assert_eq!(*field, FieldId::ZERO);
+    assert_eq!(ty, x_inner_ty);
+    let Some((base, ProjectionElem::Deref)) = sub.as_projection() else {
+        panic!("expected a dereference: {sub:?}")
+    };
+    assert!(matches!(base.ty().kind(), TyKind::Ref(_, opt_ty, _) if opt_ty == sub.ty()));
+    Ok(())
+}
+
+#[test]
+fn devirtualize_method_calls() -> anyhow::Result<()> {
+    let crate_data = translate(
+        r#"
+        trait Foo {
+            fn foo(&self) -> u32;
+        }
+        struct Concrete;
+        impl Foo for Concrete {
+            fn foo(&self) -> u32 {
+                0
+            }
+        }
+        fn concrete(x: Concrete) -> u32 {
+            x.foo()
+        }
+        fn generic<T: Foo>(x: T) -> u32 {
+            x.foo()
+        }
+        "#,
+    )?;
+    let items = items_by_name(&crate_data);
+    let called_fn = |name: &str| {
+        let AnyTransItem::Fun(fun) = items[name].kind else {
+            panic!()
+        };
+        let body = &fun.body.as_ref().unwrap().as_structured().unwrap().body;
+        body.statements
+            .iter()
+            .find_map(|st| match &st.content {
+                RawStatement::Call(Call {
+                    func: FnOperand::Regular(fn_ptr),
+                    ..
+                }) => Some(fn_ptr.func.clone()),
+                _ => None,
+            })
+            .unwrap()
+    };
+
+    // The call on a concrete type refers to the impl method directly.
+    let FunIdOrTraitMethodRef::Fun(FunId::Regular(id)) = called_fn("test_crate::concrete") else {
+        panic!()
+    };
+    assert_eq!(
+        repr_name(&crate_data, &crate_data.fun_decls[id].item_meta.name),
+        "test_crate::<impl Foo for ??>::foo"
+    );
+    // The call on a type parameter still goes through the trait clause.
+    let FunIdOrTraitMethodRef::Trait(trait_ref, name, _) = called_fn("test_crate::generic") else {
+        panic!()
+    };
+    assert!(matches!(trait_ref.kind, TraitRefKind::Clause(..)));
+    assert_eq!(name.0, "foo");
+    Ok(())
+}
+
+#[test]
+fn let_chains() -> anyhow::Result<()> {
+    let crate_data = util::translate_rust_text_with_args(
+        r#"
+        #![feature(let_chains)]
+        fn positive(x: Option<u32>) -> bool {
+            if let Some(a) = x && a > 0 {
+                true
+            } else {
+                false
+            }
+        }
+        "#,
+        &["--reconstruct-let-chains"],
+    )?;
+    let function = &crate_data.fun_decls[0];
+    let body = &function
+        .body
+        .as_ref()
+        .unwrap()
+        .as_structured()
+        .unwrap()
+        .body;
+    let Some(RawStatement::IfLetChain {
+        lets,
+        cond,
+        then_block,
+        else_block,
+    }) = body
+        .statements
+        .iter()
+        .map(|st| &st.content)
+        .find(|content| content.is_if_let_chain())
+    else {
+        panic!("{:?}", body.statements)
+    };
+    // A single refutable binding, that checks the `Some` variant, followed by the condition.
+    let [binding] = lets.as_slice() else { panic!() };
+    assert_eq!(binding.variants, vec![VariantId::new(1)]);
+    assert!(binding
+        .block
+        .statements
+        .iter()
+        .any(|st| st.content.is_assign()));
+    assert!(cond.is_some());
+    // The `else` branch isn't nested in the binding anymore.
+    let assigned_bools = |block: &Block| {
+        block
+            .statements
+            .iter()
+            .filter_map(|st| match &st.content {
+                RawStatement::Assign(_, Rvalue::Use(Operand::Const(c))) => match &c.value {
+                    RawConstantExpr::Literal(Literal::Bool(b)) => Some(*b),
+                    _ => None,
+                },
+                _ => None,
+            })
+            .collect_vec()
+    };
+    assert_eq!(assigned_bools(then_block), vec![true]);
+    assert_eq!(assigned_bools(else_block), vec![false]);
+
+    // The inner `else` branch drops `a` first, so the branches differ and we keep the matches.
+    let crate_data = util::translate_rust_text_with_args(
+        r#"
+        #![feature(let_chains)]
+        fn non_empty(x: Option<String>) -> bool {
+            if let Some(a) = x && !a.is_empty() {
+                true
+            } else {
+                false
+            }
+        }
+        "#,
+        &["--reconstruct-let-chains"],
+    )?;
+    let items = items_by_name(&crate_data);
+    let AnyTransItem::Fun(function) = items["test_crate::non_empty"].kind else {
+        panic!()
+    };
+    let body = function.body.as_ref().unwrap();
+    let mut has_chain = false;
+    body.dyn_visit_in_body(|st: &Statement| has_chain |= st.content.is_if_let_chain());
+    assert!(!has_chain);
+    Ok(())
+}
+
+#[test]
+fn derived_cmp_builtins() -> anyhow::Result<()> {
+    let code = r#"
+        use std::cmp::Ordering;
+        #[derive(PartialEq, Eq, PartialOrd, Ord)]
+        struct Pair {
+            x: u32,
+            y: u32,
+        }
+        fn compare(a: &Pair, b: &Pair) -> Ordering {
+            a.cmp(b)
+        }
+        "#;
+    let called_fn = |crate_data: &TranslatedCrate| {
+        let items = items_by_name(crate_data);
+        let AnyTransItem::Fun(fun) = items["test_crate::compare"].kind else {
+            panic!()
+        };
+        let body = &fun.body.as_ref().unwrap().as_structured().unwrap().body;
+        body.statements
+            .iter()
+            .find_map(|st| match &st.content {
+                RawStatement::Call(Call {
+                    func: FnOperand::Regular(fn_ptr),
+                    ..
+                }) => Some(fn_ptr.clone()),
+                _ => None,
+            })
+            .unwrap()
+    };
+
+    // With the flag, the derived comparison is a builtin on the compared type.
+    let crate_data = util::translate_rust_text_with_args(code, &["--derived-cmp-builtins"])?;
+    let fn_ptr = called_fn(&crate_data);
+    assert!(matches!(
+        fn_ptr.func,
+        FunIdOrTraitMethodRef::Fun(FunId::Builtin(BuiltinFunId::DerivedCmp))
+    ));
+    let [ty] = fn_ptr.generics.types.as_slice() else {
+        panic!()
+    };
+    let TyKind::Adt(TypeId::Adt(type_id), _) = ty.kind() else {
+        panic!()
+    };
+    assert_eq!(
+        repr_name(&crate_data, &crate_data.type_decls[*type_id].item_meta.name),
+        "test_crate::Pair"
+    );
+
+    // Without it, we call the method of the derived impl.
+    let crate_data = translate(code)?;
+    let fn_ptr = called_fn(&crate_data);
+    assert!(!matches!(
+        fn_ptr.func,
+        FunIdOrTraitMethodRef::Fun(FunId::Builtin(_))
+    ));
+    Ok(())
+}
+
+#[test]
+fn integer_literal_types() -> anyhow::Result<()> {
+    let crate_data = translate(
+        r#"
+        fn literals() -> (u8, i64) {
+            let x: u8 = 5;
+            let y = 5i64;
+            (x, y)
+        }
+        "#,
+    )?;
+    let function = &crate_data.fun_decls[0];
+    let body = &function
+        .body
+        .as_ref()
+        .unwrap()
+        .as_structured()
+        .unwrap()
+        .body;
+    let constants = body
+        .statements
+        .iter()
+        .filter_map(|st| match &st.content {
+            RawStatement::Assign(_, Rvalue::Use(Operand::Const(c))) => Some(c),
+            _ => None,
+        })
+        .collect_vec();
+    // Both the unsuffixed and the suffixed literal get the concrete type, and the literal agrees
+    // with the type of the constant.
+    let [x, y] = constants.as_slice() else {
+        panic!("{constants:?}")
+    };
+    assert_eq!(
+        x.value,
+        RawConstantExpr::Literal(Literal::Scalar(ScalarValue::U8(5)))
+    );
+    assert_eq!(
+        x.ty.kind(),
+        &TyKind::Literal(LiteralTy::Integer(IntegerTy::U8))
+    );
+    assert_eq!(
+        y.value,
+        RawConstantExpr::Literal(Literal::Scalar(ScalarValue::I64(5)))
+    );
+    assert_eq!(
+        y.ty.kind(),
+        &TyKind::Literal(LiteralTy::Integer(IntegerTy::I64))
+    );
+    Ok(())
+}
+
+#[test]
+fn box_new_builtin() -> anyhow::Result<()> {
+    let crate_data = translate(
+        r#"
+        fn make_box() -> Box<u32> {
+            Box::new(5u32)
+        }
+        "#,
+    )?;
+    let function = &crate_data.fun_decls[0];
+    let body = &function
+        .body
+        .as_ref()
+        .unwrap()
+        .as_structured()
+        .unwrap()
+        .body;
+    // At the default (built) MIR level, `Box::new` is a call to the builtin with the inner type,
+    // not to the allocator.
+    let Some(Call {
+        func: FnOperand::Regular(fn_ptr),
+        args,
+        ..
+    }) = body.statements.iter().find_map(|st| st.content.as_call())
+    else {
+        panic!("{:?}", body.statements)
+    };
+    assert!(matches!(
+        fn_ptr.func,
+        FunIdOrTraitMethodRef::Fun(FunId::Builtin(BuiltinFunId::BoxNew))
+    ));
+    assert_eq!(
+        fn_ptr.generics.types.as_slice(),
+        &[TyKind::Literal(LiteralTy::Integer(IntegerTy::U32)).into_ty()]
+    );
+    let [Operand::Const(arg)] = args.as_slice() else {
+        panic!("{args:?}")
+    };
+    assert_eq!(
+        arg.value,
+        RawConstantExpr::Literal(Literal::Scalar(ScalarValue::U32(5)))
+    );
+    Ok(())
+}
+
+#[test]
+fn hash_builtins() -> anyhow::Result<()> {
+    let crate_data = util::translate_rust_text_with_args(
+        r#"
+        use std::hash::{Hash, Hasher};
+        #[derive(Hash)]
+        struct Pair {
+            x: u32,
+            y: u32,
+        }
+        fn hash_pair<H: Hasher>(p: &Pair, state: &mut H) -> u64 {
+            p.hash(state);
+            state.write_u8(0);
+            state.finish()
+        }
+        "#,
+        &["--hash-builtins"],
+    )?;
+    let items = items_by_name(&crate_data);
+    let AnyTransItem::Fun(fun) = items["test_crate::hash_pair"].kind else {
+        panic!()
+    };
+    let body = &fun.body.as_ref().unwrap().as_structured().unwrap().body;
+    let called = body
+        .statements
+        .iter()
+        .filter_map(|st| match &st.content {
+            RawStatement::Call(Call {
+                func: FnOperand::Regular(fn_ptr),
+                ..
+            }) => Some(fn_ptr.func.clone()),
+            _ => None,
+        })
+        .collect_vec();
+    let builtin = |id| FunIdOrTraitMethodRef::Fun(FunId::Builtin(id));
+    assert_eq!(
+        called,
+        vec![
+            builtin(BuiltinFunId::DerivedHash),
+            builtin(BuiltinFunId::HasherWrite(Some(IntegerTy::U8))),
+            builtin(BuiltinFunId::HasherFinish),
+        ]
+    );
+    Ok(())
+}
+
+#[test]
+fn defunctionalize() -> anyhow::Result<()> {
+    let crate_data = util::translate_rust_text_with_args(
+        r#"
+        fn capture(x: u32, y: u32) -> u32 {
+            let f = |z| x + y + z;
+            f(0)
+        }
+        "#,
+        &["--defunctionalize"],
+    )?;
+    let state = crate_data
+        .type_decls
+        .iter()
+        .find(|decl| repr_name(&crate_data, &decl.item_meta.name).ends_with("::State"))
+        .unwrap();
+    assert_eq!(state.kind.as_struct().unwrap().elem_count(), 2);
+
+    let closure = crate_data
+        .fun_decls
+        .iter()
+        .find(|decl| decl.signature.closure_info.is_some())
+        .unwrap();
+    let TyKind::Ref(_, state_ty, _) = closure.signature.inputs[0].kind() else {
+        panic!()
+    };
+    assert_eq!(state_ty.as_adt().unwrap().0, TypeId::Adt(state.def_id));
+
+    let items = items_by_name(&crate_data);
+    let AnyTransItem::Fun(fun) = items["test_crate::capture"].kind else {
+        panic!()
+    };
+    let body = &fun.body.as_ref().unwrap().as_structured().unwrap().body;
+    let called = body
+        .statements
+        .iter()
+        .filter_map(|st| match &st.content {
+            RawStatement::Call(Call {
+                func: FnOperand::Regular(fn_ptr),
+                args,
+                ..
+            }) => Some((fn_ptr.func.clone(), args.len())),
+            _ => None,
+        })
+        .collect_vec();
+    assert_eq!(
+        called,
+        vec![(
+            FunIdOrTraitMethodRef::Fun(FunId::Regular(closure.def_id)),
+            2
+        )]
+    );
+    Ok(())
+}
+
+#[test]
+fn abstract_formatting() -> anyhow::Result<()> {
+    let crate_data = util::translate_rust_text_with_args(
+        r#"
+        use std::fmt;
+        struct Wrapper(u32);
+        impl fmt::Display for Wrapper {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                fmt::Display::fmt(&self.0, f)
+            }
+        }
+        fn show(x: u32) -> String {
+            format!("{}", x)
+        }
+        "#,
+        &["--abstract-formatting"],
+    )?;
+    let items = items_by_name(&crate_data);
+    let called_builtins = |name: &str| {
+        let AnyTransItem::Fun(fun) = items[name].kind else {
+            panic!()
+        };
+        let mut called = vec![];
+        fun.body
+            .as_ref()
+            .unwrap()
+            .dyn_visit_in_body(|fn_ptr: &FnPtr| {
+                if let FunIdOrTraitMethodRef::Fun(FunId::Builtin(id)) = fn_ptr.func {
+                    called.push(id);
+                }
+            });
+        called
+    };
+    let called = called_builtins("test_crate::show");
+    assert!(called.contains(&BuiltinFunId::FmtArgDisplay));
+    assert!(called.contains(&BuiltinFunId::FormatArgs));
+    let called = called_builtins("test_crate::<impl Display for ??>::fmt");
+    assert_eq!(called, vec![BuiltinFunId::FmtDisplay]);
+    Ok(())
+}
+
+#[test]
+fn constant_if_conditions() -> anyhow::Result<()> {
+    let crate_data = translate(
+        r#"
+        fn pick(a: u32, b: u32) -> u32 {
+            if cfg!(debug_assertions) {
+                a
+            } else {
+                b
+            }
+        }
+        "#,
+    )?;
+    let function = &crate_data.fun_decls[0];
+    let body = &function
+        .body
+        .as_ref()
+        .unwrap()
+        .as_structured()
+        .unwrap()
+        .body;
+    // The condition is gone, and only the taken branch remains.
+    let mut returned = vec![];
+    body.dyn_visit_in_body(|st: &Statement| match &st.content {
+        RawStatement::Switch(..) => panic!("the `if` wasn't folded"),
+        RawStatement::Assign(dest, Rvalue::Use(Operand::Move(src) | Operand::Copy(src)))
+            if dest.as_local() == Some(VarId::new(0)) =>
+        {
+            returned.push(src.as_local().unwrap())
+        }
+        _ => {}
+    });
+    let [var] = returned.as_slice() else {
+        panic!("{returned:?}")
+    };
+    assert!([VarId::new(1), VarId::new(2)].contains(var));
+    Ok(())
+}
+
+#[test]
+fn array_and_slice_impls() -> anyhow::Result<()> {
+    let crate_data = translate(
+        r#"
+        trait MyTrait {
+            fn total(&self) -> usize;
+        }
+        impl<const N: usize> MyTrait for [u8; N] {
+            fn total(&self) -> usize {
+                N
+            }
+        }
+        impl MyTrait for [u8] {
+            fn total(&self) -> usize {
+                self.len()
+            }
+        }
+        fn use_array(a: [u8; 4]) -> usize {
+            a.total()
+        }
+        "#,
+    )?;
+    let impl_for = |builtin: BuiltinTy| {
+        crate_data
+            .trait_impls
+            .iter()
+            .find(|timpl| {
+                matches!(
+                    timpl.impl_trait.generics.types[0].kind(),
+                    TyKind::Adt(TypeId::Builtin(ty), _) if *ty == builtin
+                )
+            })
+            .unwrap()
+    };
+    let array_impl = impl_for(BuiltinTy::Array);
+    // The slice impl is registered too.
+    impl_for(BuiltinTy::Slice);
+
+    // The call resolves to the array impl, with the length of the array as const generic.
+    let items = items_by_name(&crate_data);
+    let AnyTransItem::Fun(fun) = items["test_crate::use_array"].kind else {
+        panic!()
+    };
+    let mut impl_calls = vec![];
+    fun.body
+        .as_ref()
+        .unwrap()
+        .dyn_visit_in_body(|fn_ptr: &FnPtr| {
+            if let FunIdOrTraitMethodRef::Trait(trait_ref, name, _) = &fn_ptr.func {
+                if let TraitRefKind::TraitImpl(impl_id, generics) = &trait_ref.kind {
+                    impl_calls.push((*impl_id, name.0.clone(), generics.const_generics.clone()));
+                }
+            }
+        });
+    let [(impl_id, name, const_generics)] = impl_calls.as_slice() else {
+        panic!("{impl_calls:?}")
+    };
+    assert_eq!(*impl_id, array_impl.def_id);
+    assert_eq!(name, "total");
+    assert_eq!(
+        const_generics.as_slice(),
+        &[ConstGeneric::Value(Literal::Scalar(ScalarValue::Usize(4)))]
+    );
+    Ok(())
+}
+
+#[test]
+fn assoc_const_array_length() -> anyhow::Result<()> {
+    let crate_data = translate(
+        r#"
+        trait HasLen {
+            const LEN: usize;
+            fn count_zeros(&self) -> usize;
+        }
+        struct Eight;
+        impl HasLen for Eight {
+            const LEN: usize = 8;
+            fn count_zeros(&self) -> usize {
+                let buf: [u8; Self::LEN] = [0; Self::LEN];
+                buf.len()
+            }
+        }
+        "#,
+    )?;
+    let items = items_by_name(&crate_data);
+    let AnyTransItem::Fun(fun) = items["test_crate::<impl HasLen for ??>::count_zeros"].kind else {
+        panic!()
+    };
+    let body = fun.body.as_ref().unwrap().as_structured().unwrap();
+    // The length of the array is the value the impl gives to `LEN`.
+    let lengths = body
+        .locals
+        .vars
+        .iter()
+        .filter_map(|var| match var.ty.kind() {
+            TyKind::Adt(TypeId::Builtin(BuiltinTy::Array), generics) => {
+                Some(generics.const_generics[0].clone())
+            }
+            _ => None,
+        })
+        .collect_vec();
+    assert!(!lengths.is_empty());
+    for len in lengths {
+        assert_eq!(
+            len,
+            ConstGeneric::Value(Literal::Scalar(ScalarValue::Usize(8)))
+        );
+    }
+    Ok(())
+}
+
+#[test]
+fn unreachable_blocks() -> anyhow::Result<()> {
+    use charon_lib::ullbc_ast as ullbc;
+    let crate_data = util::translate_rust_text_with_args(
+        r#"
+        fn pick(x: u32) -> u32 {
+            if cfg!(test) {
+                x + 1
+            } else {
+                x
+            }
+        }
+        "#,
+        &["--ullbc"],
+    )?;
+    let function = &crate_data.fun_decls[0];
+    let body = &function
+        .body
+        .as_ref()
+        .unwrap()
+        .as_unstructured()
+        .unwrap()
+        .body;
+    // The `then` branch can't be reached once the condition is folded: it is removed, and the
+    // remaining blocks are renumbered.
+    let mut reachable = HashSet::new();
+    let mut to_explore = vec![ullbc::BlockId::new(0)];
+    while let Some(id) = to_explore.pop() {
+        if reachable.insert(id) {
+            to_explore.extend(body[id].targets());
+        }
+    }
+    assert_eq!(reachable.len(), body.elem_count());
+    let mut has_addition = false;
+    body.dyn_visit_in_body(|rvalue: &Rvalue| {
+        has_addition |= matches!(rvalue, Rvalue::BinaryOp(BinOp::Add | BinOp::CheckedAdd, ..))
+    });
+    assert!(!has_addition);
+    Ok(())
+}
+
+#[test]
+fn assert_message_arguments() -> anyhow::Result<()> {
+    let crate_data = translate(
+        r#"
+        fn check(x: i32) {
+            assert!(x > 0, "bad: {}", x);
+        }
+        "#,
+    )?;
+    let function = &crate_data.fun_decls[0];
+    let mut messages = vec![];
+    function
+        .body
+        .as_ref()
+        .unwrap()
+        .dyn_visit_in_body(|st: &Statement| {
+            if let RawStatement::Assert(assert) = &st.content {
+                messages.push(assert.message.clone());
+            }
+        });
+    let [Some(message)] = messages.as_slice() else {
+        panic!("{messages:?}")
+    };
+    assert_eq!(message.format, "bad: {}");
+    // The message formats the argument `x` directly, which is available before the assertion.
+    let [Operand::Copy(arg)] = message.args.as_slice() else {
+        panic!("{:?}", message.args)
+    };
+    assert_eq!(arg.as_local(), Some(VarId::new(1)));
+    Ok(())
+}
+
+#[test]
+fn inline_derived_eq() -> anyhow::Result<()> {
+    let crate_data = util::translate_rust_text_with_args(
+        r#"
+        #[derive(PartialEq)]
+        enum E {
+            A(u32),
+            B,
+        }
+        fn same(x: E, y: E) -> bool {
+            x == y
+        }
+        "#,
+        &["--inline-derived-eq"],
+    )?;
+    let items = items_by_name(&crate_data);
+    let AnyTransItem::Fun(fun) = items["test_crate::same"].kind else {
+        panic!()
+    };
+    let body = fun.body.as_ref().unwrap();
+    let mut calls = 0;
+    let mut matches = 0;
+    body.dyn_visit_in_body(|st: &Statement| match &st.content {
+        RawStatement::Call(_) => calls += 1,
+        RawStatement::Switch(Switch::Match(..)) => matches += 1,
+        _ => {}
+    });
+    let mut comparisons = 0;
+    body.dyn_visit_in_body(|rvalue: &Rvalue| {
+        if let Rvalue::BinaryOp(BinOp::Eq, ..) = rvalue {
+            comparisons += 1
+        }
+    });
+    assert_eq!(calls, 0);
+    // We match on the variant of `x`, then on the variant of `y` in each branch.
+    assert_eq!(matches, 3);
+    // Only `A` has a field to compare.
+    assert_eq!(comparisons, 1);
+    Ok(())
+}
+
+#[test]
+fn packed_field_read() -> anyhow::Result<()> {
+    let crate_data = translate(
+        r#"
+        #[repr(packed)]
+        struct Packed {
+            a: u8,
+            b: u32,
+        }
+        fn get(p: &Packed) -> u32 {
+            p.b
+        }
+        fn first(p: &Packed) -> &u8 {
+            &p.a
+        }
+        "#,
+    )?;
+    let items = items_by_name(&crate_data);
+    let AnyTransItem::Type(ty) = items["test_crate::Packed"].kind else {
+        panic!()
+    };
+    assert_eq!(ty.repr.and_then(|repr| repr.pack), Some(1));
+
+    // The field is copied out of the struct.
+    let AnyTransItem::Fun(get) = items["test_crate::get"].kind else {
+        panic!()
+    };
+    let body = get.body.as_ref().unwrap();
+    let mut packed_reads = Vec::new();
+    body.dyn_visit_in_body(|op: &Operand| {
+        if let Operand::Copy(place) = op {
+            packed_reads.push(place.packed_field(&crate_data));
+        }
+    });
+    assert_eq!(packed_reads, vec![Some(1)]);
+
+    // A `u8` field is always aligned, so this reference is accepted.
+    let AnyTransItem::Fun(first) = items["test_crate::first"].kind else {
+        panic!()
+    };
+    let body = first.body.as_ref().unwrap();
+    let mut packed_refs = Vec::new();
+    body.dyn_visit_in_body(|rvalue: &Rvalue| {
+        if let Rvalue::Ref(place, _) = rvalue {
+            packed_refs.push(place.packed_field(&crate_data));
+        }
+    });
+    assert_eq!(packed_refs, vec![Some(1)]);
+    Ok(())
+}
+
+#[test]
+fn match_uninhabited_variant() -> anyhow::Result<()> {
+    let crate_data = translate(
+        r#"
+        use std::convert::Infallible;
+        enum E {
+            A(Infallible),
+            B,
+        }
+        fn f(x: E) -> u32 {
+            match x {
+                E::B => 1,
+            }
+        }
+        "#,
+    )?;
+    let items = items_by_name(&crate_data);
+    let AnyTransItem::Fun(fun) = items["test_crate::f"].kind else {
+        panic!()
+    };
+    let body = fun.body.as_ref().unwrap();
+    let mut switches = Vec::new();
+    body.dyn_visit_in_body(|st: &Statement| {
+        if let RawStatement::Switch(Switch::Match(_, targets, otherwise)) = &st.content {
+            switches.push((targets.clone(), otherwise.clone()));
+        }
+    });
+    let [(targets, otherwise)] = switches.as_slice() else {
+        panic!("expected a single match, found {}", switches.len())
+    };
+    // The arm for `A` is unreachable, and there is no `_` arm.
+    assert!(otherwise.is_none());
+    let (a_ids, a_block) = targets
+        .iter()
+        .find(|(ids, _)| ids.contains(&VariantId::ZERO))
+        .unwrap();
+    assert_eq!(a_ids, &vec![VariantId::ZERO]);
+    assert!(matches!(
+        a_block.statements.as_slice(),
+        [Statement {
+            content: RawStatement::Abort(AbortKind::UndefinedBehavior),
+            ..
+        }]
+    ));
+    Ok(())
+}
+
+#[test]
+fn question_mark_span() -> anyhow::Result<()> {
+    let crate_data = translate(
+        r#"
+        fn incr(x: Option<u32>) -> Option<u32> {
+            let y = x?;
+            Some(y + 1)
+        }
+        "#,
+    )?;
+    let items = items_by_name(&crate_data);
+    let AnyTransItem::Fun(fun) = items["test_crate::incr"].kind else {
+        panic!()
+    };
+    // The error path of `?` calls `FromResidual::from_residual`, with the span of the `?` itself.
+    let mut residual_spans = vec![];
+    fun.body
+        .as_ref()
+        .unwrap()
+        .dyn_visit_in_body(|st: &Statement| {
+            let RawStatement::Call(call) = &st.content else {
+                return;
+            };
+            let FnOperand::Regular(fn_ptr) = &call.func else {
+                return;
+            };
+            let method_name = match &fn_ptr.func {
+                FunIdOrTraitMethodRef::Fun(FunId::Regular(id)) => {
+                    let name = crate_data.item_name(*id).unwrap();
+                    let Some(PathElem::Ident(name, _)) = name.name.last() else {
+                        return;
+                    };
+                    name.clone()
+                }
+                FunIdOrTraitMethodRef::Trait(_, name, _) => name.0.clone(),
+                FunIdOrTraitMethodRef::Fun(FunId::Builtin(_)) => return,
+            };
+            if method_name == "from_residual" {
+                residual_spans.push(repr_span(st.span));
+            }
+        });
+    assert_eq!(residual_spans, vec!["3:21-3:22"]);
+    Ok(())
+}
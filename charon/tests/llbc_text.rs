@@ -0,0 +1,229 @@
+//! Tests for the textual LLBC syntax, see [charon_lib::llbc_text].
+use charon_lib::ast::*;
+use charon_lib::llbc_text::{parse_crate, print_crate};
+
+mod util;
+
+/// Print the crate, then check that parsing the text back and printing it again gives the same
+/// text.
+fn check_roundtrip(krate: &TranslatedCrate) -> anyhow::Result<String> {
+    let text = print_crate(krate)?;
+    let reparsed = parse_crate(&text)?;
+    assert_eq!(print_crate(&reparsed)?, text);
+    Ok(text)
+}
+
+/// Translate the code and print the crate, returning the printer error.
+fn print_error(code: &str) -> anyhow::Result<String> {
+    let krate = util::translate_rust_text(code)?;
+    match print_crate(&krate) {
+        Ok(text) => panic!("the printer accepted:\n{text}"),
+        Err(err) => Ok(format!("{err:#}")),
+    }
+}
+
+#[test]
+fn roundtrip_text() -> anyhow::Result<()> {
+    let text = indoc::indoc!(
+        r#"
+        crate test_crate;
+
+        pub struct test_crate::Pair<T> {
+            pub a: T,
+            b: u32,
+        }
+
+        struct test_crate::Wrapper<'a, T, const N: usize>(pub &'a [T; N], (u8,));
+
+        enum test_crate::E {
+            A = 0isize,
+            B(u32, bool) = 1isize,
+            C { x: char } = 2isize,
+        }
+
+        opaque type test_crate::Abstract;
+
+        type test_crate::Alias = (u32, alloc::boxed::Box<i8>);
+
+        pub fn test_crate::first<'_0, T>(&'_0 test_crate::Pair<T>) -> &'_0 T {
+            let @0: &'_ T;
+            let p@1: &'_ test_crate::Pair<T>;
+
+            @0 := &(*p@1).0;
+            return;
+        }
+
+        fn test_crate::make<T>(T) -> test_crate::Pair<T> {
+            let @0: test_crate::Pair<T>;
+            let x@1: T;
+            let @2: &'_ test_crate::Pair<T>;
+            let @3: &'_ T;
+
+            @0 := test_crate::Pair<T> { move x@1, const 0u32 };
+            @2 := &@0;
+            @3 := call test_crate::first<'_, T>(move @2);
+            drop @3;
+            return;
+        }
+
+        fn test_crate::get(test_crate::E) -> u32 {
+            let @0: u32;
+            let e@1: test_crate::E;
+
+            match e@1 {
+                B => {
+                    @0 := copy (e@1 as B).0;
+                }
+                A | C => {
+                    @0 := const 0u32;
+                }
+            }
+            return;
+        }
+
+        unsafe fn test_crate::compute(u32, i32) -> (u32, bool) {
+            let @0: (u32, bool);
+            let x@1: u32;
+            let y@2: i32;
+            let @3: u32;
+            let @4: bool;
+            let @5: test_crate::E;
+
+            @3 := copy x@1 wrapping.+ const 1u32;
+            @4 := copy y@2 < const -1i32;
+            @5 := test_crate::E::B { copy @3, copy @4 };
+            switch copy y@2 {
+                0i32 | 1i32 => {
+                    @3 := cast<i32, u32>(copy y@2);
+                }
+                _ => {
+                    loop {
+                        if copy @4 {
+                            break 0;
+                        } else {
+                            continue 0;
+                        }
+                    }
+                }
+            }
+            @3 := call test_crate::get(move @5);
+            assert !copy @4;
+            @0 := (move @3, const true);
+            return;
+        }
+
+        fn test_crate::external(char) -> ();
+        "#
+    );
+    let krate = parse_crate(text)?;
+    assert_eq!(print_crate(&krate)?, text);
+    Ok(())
+}
+
+#[test]
+fn roundtrip_ui_crates() -> anyhow::Result<()> {
+    for name in [
+        "bitwise",
+        "disambiguator",
+        "issue-92-nonpositive-variant-indices",
+    ] {
+        let code = std::fs::read_to_string(format!("tests/ui/{name}.rs"))?;
+        let krate = util::translate_rust_text(code)?;
+        let text = check_roundtrip(&krate)?;
+        // All the items of the crate are printed.
+        for decl in &krate.fun_decls {
+            let name = decl.item_meta.name.name.last().unwrap();
+            let PathElem::Ident(name, _) = name else {
+                panic!()
+            };
+            assert!(
+                text.contains(&format!("{name}(")),
+                "{name} is missing:\n{text}"
+            );
+        }
+    }
+    Ok(())
+}
+
+#[test]
+fn rejects_trait_clauses() -> anyhow::Result<()> {
+    let err = print_error("fn id<T: Clone>(x: T) -> T { x }")?;
+    assert!(err.contains("trait"), "{err}");
+    assert!(err.contains("not supported"), "{err}");
+    Ok(())
+}
+
+#[test]
+fn rejects_globals() -> anyhow::Result<()> {
+    let err = print_error("static S: u32 = 0; fn get() -> u32 { S }")?;
+    assert!(err.contains("globals are not supported"), "{err}");
+    Ok(())
+}
+
+#[test]
+fn rejects_array_projections() -> anyhow::Result<()> {
+    // Charon turns indexing into calls to builtins, so we write the projection by hand.
+    let mut krate = parse_crate(indoc::indoc!(
+        r#"
+        crate test_crate;
+
+        fn test_crate::first([u32; 2]) -> u32 {
+            let @0: u32;
+            let a@1: [u32; 2];
+
+            @0 := copy a@1;
+            return;
+        }
+        "#
+    ))?;
+    let fun = krate.fun_decls.iter_mut().next().unwrap();
+    fun.body
+        .as_mut()
+        .unwrap()
+        .dyn_visit_in_body_mut(|op: &mut Operand| {
+            if let Operand::Copy(place) = op {
+                let index = ProjectionElem::Index {
+                    offset: Box::new(Operand::Move(place.clone())),
+                    from_end: false,
+                };
+                *place = place.clone().project(index, place.ty.clone());
+            }
+        });
+    let err = format!("{:#}", print_crate(&krate).unwrap_err());
+    assert!(
+        err.contains("index and subslice projections are not supported"),
+        "{err}"
+    );
+    Ok(())
+}
+
+#[test]
+fn parse_errors() {
+    let parse_error = |text: &str| format!("{:#}", parse_crate(text).unwrap_err());
+
+    // Items must be declared.
+    let err = parse_error("crate c;\nfn c::f() -> c::T;");
+    assert!(err.contains("unknown type `c::T`"), "{err}");
+    let err = parse_error(
+        "crate c;\nfn c::f() -> () {\n    let @0: ();\n\n    @0 := call c::g();\n    return;\n}",
+    );
+    assert!(err.contains("unknown function `c::g`"), "{err}");
+    // Items are defined once.
+    let err = parse_error("crate c;\nfn c::f() -> ();\nfn c::f() -> ();");
+    assert!(err.contains("`c::f` is defined twice"), "{err}");
+    // Locals must be declared, in order.
+    let err = parse_error(
+        "crate c;\nfn c::f() -> u32 {\n    let @0: u32;\n\n    @0 := copy @1;\n    return;\n}",
+    );
+    assert!(err.contains("unknown local 1"), "{err}");
+    let err = parse_error("crate c;\nfn c::f() -> u32 {\n    let @1: u32;\n    return;\n}");
+    assert!(err.contains("locals must be declared in order"), "{err}");
+    // Plain syntax errors.
+    let err = parse_error("crate c;\nfn c::f( -> ();");
+    assert!(err.contains("Failed to parse textual LLBC"), "{err}");
+    let err = parse_error("crate c;\nopaque type c::S; opaque type c::T;");
+    assert!(
+        err.contains("this item should start on a new line"),
+        "{err}"
+    );
+}
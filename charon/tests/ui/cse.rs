@@ -0,0 +1,9 @@
+//@ charon-args=--cse
+//! Test that the computations common to both branches of an `if` are hoisted out of it.
+fn both_arms(c: bool, a: u32, b: u32) -> u32 {
+    if c {
+        a + b
+    } else {
+        a + b
+    }
+}
@@ -0,0 +1,6 @@
+//@ charon-args=--mark-unsafe
+//! Test the markers around the statements of an `unsafe` block.
+fn deref(p: *const u32) -> u32 {
+    let x = unsafe { *p };
+    x
+}
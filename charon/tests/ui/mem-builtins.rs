@@ -0,0 +1,6 @@
+//! Calls to `core::mem::swap` are translated to the `MemSwap` builtin function.
+pub fn swap_ints() {
+    let mut a = 0u32;
+    let mut b = 1u32;
+    std::mem::swap(&mut a, &mut b);
+}
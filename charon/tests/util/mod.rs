@@ -62,6 +62,14 @@ fn expect_file_contents(path: &Path, actual: snapbox::Data) -> snapbox::assert::
 
 /// Given a string that contains rust code, this calls charon on it and returns the result.
 pub fn translate_rust_text(code: impl Display) -> anyhow::Result<TranslatedCrate> {
+    translate_rust_text_with_args(code, &[])
+}
+
+/// Like [translate_rust_text], passing the extra arguments `args` to charon.
+pub fn translate_rust_text_with_args(
+    code: impl Display,
+    args: &[&str],
+) -> anyhow::Result<TranslatedCrate> {
     // Initialize the logger
     logger::initialize_logger();
 
@@ -85,6 +93,7 @@ pub fn translate_rust_text(code: impl Display) -> anyhow::Result<TranslatedCrate
         .arg(input_path)
         .arg("--dest-file")
         .arg(&output_path)
+        .args(args)
         .assert()
         .try_success()?;
 